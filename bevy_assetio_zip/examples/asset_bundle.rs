@@ -7,11 +7,13 @@ fn main() {
     // Config must be inserted before adding plugins
     #[cfg(feature = "bevy-unstable")]
     builder.insert_resource(AssetIoZipConfig {
-        file_name: "assets".into(), // The default
+        file_names: vec!["assets".into()], // The default
+        ..Default::default()
     });
     #[cfg(not(feature = "bevy-unstable"))]
     builder.add_resource(AssetIoZipConfig {
-        file_name: "assets".into(), // The default
+        file_names: vec!["assets".into()], // The default
+        ..Default::default()
     });
 
     // Add the default plugins
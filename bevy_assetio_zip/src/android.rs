@@ -0,0 +1,67 @@
+//! Support for loading asset bundles from Android APK expansion ( `.obb` ) files
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+/// Get the name of the app's package, which is the name of the process on Android
+///
+/// Processes that are started from an executable path, such as test runners, use the file name.
+fn package_name() -> Option<String> {
+    let cmdline = fs::read("/proc/self/cmdline").ok()?;
+    let name = cmdline.split(|&x| x == 0).next()?;
+    let name = String::from_utf8(name.to_vec()).ok()?;
+    let name = name.rsplit('/').next()?.to_owned();
+
+    if name.is_empty() {
+        None
+    } else {
+        Some(name)
+    }
+}
+
+/// Get the directory that the Play Store places the app's expansion files in
+fn obb_dir(package: &str) -> PathBuf {
+    let storage = std::env::var("EXTERNAL_STORAGE").unwrap_or_else(|_| "/sdcard".into());
+    Path::new(&storage).join("Android/obb").join(package)
+}
+
+/// Get the paths of the app's expansion files of the given kind ( `main` or `patch` ), newest
+/// version first
+fn expansion_files(dir: &Path, kind: &str, package: &str) -> Vec<PathBuf> {
+    let suffix = format!(".{}.obb", package);
+    let mut files = fs::read_dir(dir)
+        .into_iter()
+        .flatten()
+        .filter_map(|entry| {
+            let file_name = entry.ok()?.file_name().into_string().ok()?;
+            // Expansion files are named `[main|patch].<version-code>.<package-name>.obb`
+            let version = file_name
+                .strip_prefix(kind)?
+                .strip_prefix('.')?
+                .strip_suffix(&suffix)?
+                .parse::<u64>()
+                .ok()?;
+            Some((version, dir.join(file_name)))
+        })
+        .collect::<Vec<_>>();
+    files.sort_by(|a, b| b.0.cmp(&a.0));
+
+    files.into_iter().map(|(_, path)| path).collect()
+}
+
+/// Get the paths of the app's expansion files, which are plain zip files, with the main expansion
+/// files first followed by the patch expansion files
+pub(crate) fn obb_candidates() -> Vec<PathBuf> {
+    let package = match package_name() {
+        Some(package) => package,
+        None => return Vec::new(),
+    };
+    let dir = obb_dir(&package);
+
+    let mut candidates = expansion_files(&dir, "main", &package);
+    candidates.extend(expansion_files(&dir, "patch", &package));
+
+    candidates
+}
@@ -0,0 +1,54 @@
+//! Finding bundles appended to executables by the bundler
+//!
+//! The bundle is followed by a footer at the end of the file, which locates it. All values in the
+//! footer are little-endian:
+//!
+//! | Offset from the end | Size | Field                            |
+//! | ------------------- | ---- | -------------------------------- |
+//! | 24                  | 8    | Offset of the bundle in the file |
+//! | 16                  | 8    | Length of the bundle             |
+//! | 8                   | 8    | Magic bytes, `BAZBAPND`          |
+
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{Read, Seek, SeekFrom},
+    path::Path,
+};
+
+use crate::BundleRegion;
+
+/// The magic bytes at the end of the footer of files with an appended bundle
+const APPENDED_BUNDLE_MAGIC: &[u8; 8] = b"BAZBAPND";
+
+/// The length of the footer of files with an appended bundle
+const APPENDED_FOOTER_LEN: u64 = 24;
+
+/// Get the region of the bundle appended to the file at the given path, returning `None` if the
+/// file can't be read or doesn't end with a footer
+pub(crate) fn appended_region(path: &Path) -> Option<BundleRegion> {
+    let mut file = File::open(path).ok()?;
+    let len = file.metadata().ok()?.len();
+    if len < APPENDED_FOOTER_LEN {
+        return None;
+    }
+
+    let mut footer = [0; APPENDED_FOOTER_LEN as usize];
+    file.seek(SeekFrom::End(-(APPENDED_FOOTER_LEN as i64)))
+        .ok()?;
+    file.read_exact(&mut footer).ok()?;
+    if &footer[16..] != APPENDED_BUNDLE_MAGIC {
+        return None;
+    }
+
+    let region = BundleRegion {
+        offset: u64::from_le_bytes(footer[..8].try_into().unwrap()),
+        length: u64::from_le_bytes(footer[8..16].try_into().unwrap()),
+    };
+    // Ignore footers that don't describe the data right before them
+    if region.offset.checked_add(region.length) != Some(len - APPENDED_FOOTER_LEN) {
+        return None;
+    }
+
+    Some(region)
+}
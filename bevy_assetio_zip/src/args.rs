@@ -0,0 +1,65 @@
+//! Overriding the bundle config with command-line arguments
+
+use std::path::PathBuf;
+
+use bevy::log::warn;
+
+use crate::AssetIoZipConfig;
+
+/// The argument that sets [`AssetIoZipConfig::bundle_path`]
+const ASSET_BUNDLE_ARG: &str = "--asset-bundle";
+/// The argument that sets [`AssetIoZipConfig::asset_dir`]
+const ASSET_DIR_ARG: &str = "--asset-dir";
+
+impl AssetIoZipConfig {
+    /// Override the config with the `--asset-bundle <path>` and `--asset-dir <path>` arguments
+    /// that the game was run with
+    ///
+    /// This lets launchers and development scripts redirect asset loading for a single run. See
+    /// [`apply_args`][Self::apply_args].
+    pub fn with_args(mut self) -> Self {
+        self.apply_args(std::env::args().skip(1));
+        self
+    }
+
+    /// Override the config with the `--asset-bundle <path>` and `--asset-dir <path>` arguments in
+    /// the given list of arguments
+    ///
+    /// `--asset-bundle` sets [`bundle_path`][Self::bundle_path] and `--asset-dir` sets
+    /// [`asset_dir`][Self::asset_dir]. The values may also be given as `--asset-bundle=<path>`.
+    /// Relative paths are relative to the working directory. Other arguments are ignored so that
+    /// the game can parse them itself.
+    pub fn apply_args<I, S>(&mut self, args: I)
+    where
+        I: IntoIterator<Item = S>,
+        S: AsRef<str>,
+    {
+        let mut args = args.into_iter();
+        while let Some(arg) = args.next() {
+            let arg = arg.as_ref();
+            let (name, value) = match arg.find('=') {
+                Some(i) => (&arg[..i], Some(arg[i + 1..].to_owned())),
+                None => (arg, None),
+            };
+            if name != ASSET_BUNDLE_ARG && name != ASSET_DIR_ARG {
+                continue;
+            }
+
+            let value = match value.or_else(|| args.next().map(|x| x.as_ref().to_owned())) {
+                Some(value) => PathBuf::from(value),
+                None => {
+                    warn!("Ignoring `{}` argument without a path", name);
+                    continue;
+                }
+            };
+            let path = std::env::current_dir()
+                .map(|dir| dir.join(&value))
+                .unwrap_or(value);
+            if name == ASSET_BUNDLE_ARG {
+                self.bundle_path = Some(path);
+            } else {
+                self.asset_dir = Some(path);
+            }
+        }
+    }
+}
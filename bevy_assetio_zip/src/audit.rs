@@ -0,0 +1,57 @@
+//! Recording every loaded asset in an audit log file
+//!
+//! Each loaded asset appends one line to the file, with the fields separated by tabs:
+//!
+//! ```text
+//! <seconds since the unix epoch>\t<source>\t<CRC-32 of the data as hex>\t<path>
+//! ```
+//!
+//! The checksum is the same one that the bundler records when `asset-metadata` is enabled, so the
+//! log can be checked against the bundles that were approved.
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+    sync::Mutex,
+    time::SystemTime,
+};
+
+use crate::AssetSource;
+
+/// An audit log file that loaded assets are appended to
+#[derive(Debug)]
+pub(crate) struct AuditLog {
+    file: Mutex<File>,
+}
+
+impl AuditLog {
+    /// Open the audit log at the given path, creating it if it doesn't exist and keeping the
+    /// entries that are already in it
+    pub(crate) fn open(path: &Path) -> io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+        Ok(Self {
+            file: Mutex::new(file),
+        })
+    }
+
+    /// Append a loaded asset to the log
+    pub(crate) fn record(&self, path: &Path, source: &AssetSource, data: &[u8]) -> io::Result<()> {
+        let time = SystemTime::now()
+            .duration_since(SystemTime::UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        let line = format!(
+            "{}\t{}\t{:08x}\t{}\n",
+            time,
+            source,
+            crc32fast::hash(data),
+            path.display()
+        );
+
+        // Write the whole line at once so that lines from concurrent loads aren't interleaved
+        let mut file = self.file.lock().unwrap();
+        file.write_all(line.as_bytes())?;
+        file.flush()
+    }
+}
@@ -0,0 +1,936 @@
+//! Asset bundles mounted by the [`AssetIoZip`][crate::AssetIoZip]
+
+#[cfg(feature = "mmap")]
+use std::fs::OpenOptions;
+#[cfg(not(target_arch = "wasm32"))]
+use std::path::PathBuf;
+use std::{
+    io::{self, Read, Seek, SeekFrom},
+    ops::{Deref, DerefMut},
+    path::Path,
+    sync::{Arc, Mutex, RwLock},
+};
+
+use bevy::asset::AssetIoError;
+#[cfg(feature = "obfuscation")]
+use bevy::log::warn;
+#[cfg(feature = "mmap")]
+use memmap2::Mmap;
+use zip::{
+    result::{ZipError, ZipResult},
+    CompressionMethod, ZipArchive,
+};
+
+#[cfg(feature = "sqlite")]
+use crate::sqlite::{self, SqliteArchive};
+use crate::{
+    entry::EntryReader,
+    filter::PathFilter,
+    groups::GROUPS_ENTRY,
+    metadata::{MetadataIndex, RecordedMetadata, METADATA_ENTRY},
+    pack::{self, PackBlockCache, PackIndex, PACK_INDEX_ENTRY},
+    progress::ProgressReader,
+    read_ahead::{ReadAheadCache, ReadAheadReader},
+    AssetIoZipConfig, AssetMetadata, BundleSource, ReadSeekSend, RetryPolicy,
+};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    index::{IndexEntry, IndexedArchive, SidecarIndex},
+    raw::RawEntry,
+};
+#[cfg(feature = "obfuscation")]
+use crate::{keys::EntryObfuscation, KeyProvider};
+
+/// A zip entry that marks that the data of every other entry in the bundle is obfuscated, which
+/// contains the salt that the entry keys are derived from
+#[cfg(feature = "obfuscation")]
+pub(crate) const OBFUSCATED_ENTRIES_MARKER: &str = ".obfuscated-entries";
+
+/// The entry table and data of an opened asset bundle, whatever the container format of the bundle
+///
+/// Each archive has its own reader over the bundle. Bundles are zip archives, found through their
+/// zip central directory or their sidecar index, or SQLite databases if the `sqlite` feature is
+/// enabled.
+pub(crate) trait ArchiveBackend: Send {
+    /// The number of entries in the archive
+    fn len(&self) -> usize;
+
+    /// The total uncompressed size of the entries in the archive, as recorded in the archive
+    fn total_size(&mut self) -> u64;
+
+    /// Get the names of the entries in the archive
+    fn entry_names(&self) -> Vec<&str>;
+
+    /// Whether or not the archive contains an entry with the given name
+    fn has_entry(&mut self, name: &str) -> bool;
+
+    /// Get the uncompressed size of the given entry, returning `None` if the archive does not
+    /// contain the entry
+    fn entry_size(&mut self, name: &str) -> Option<u64>;
+
+    /// Get the offset of the data of the given entry in the bundle data and its size, returning
+    /// `None` if the entry is missing, compressed, or not stored in the bundle data as it is
+    fn stored_entry(&mut self, name: &str) -> Option<(u64, u64)>;
+
+    /// Get the location and encoding of the data of the given entry, returning `None` if the
+    /// archive does not contain the entry or its data is not stored in the bundle data as it is
+    #[cfg(not(target_arch = "wasm32"))]
+    fn entry_location(&mut self, name: &str) -> Option<IndexEntry>;
+
+    /// Read and decompress the data of the given entry, returning `None` if the archive does not
+    /// contain the entry
+    ///
+    /// `progress` is called with the number of bytes decompressed so far and the size of the entry.
+    fn read_entry_data(
+        &mut self,
+        name: &str,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<io::Result<Vec<u8>>>;
+}
+
+/// An opened asset bundle archive
+pub(crate) type Archive = Box<dyn ArchiveBackend>;
+
+/// An archive that has been opened by parsing its zip central directory
+impl ArchiveBackend for ZipArchive<Box<dyn ReadSeekSend>> {
+    fn len(&self) -> usize {
+        ZipArchive::len(self)
+    }
+
+    fn total_size(&mut self) -> u64 {
+        (0..ZipArchive::len(self))
+            .filter_map(|i| self.by_index_raw(i).ok().map(|file| file.size()))
+            .sum()
+    }
+
+    fn entry_names(&self) -> Vec<&str> {
+        self.file_names().collect()
+    }
+
+    fn has_entry(&mut self, name: &str) -> bool {
+        self.by_name(name).is_ok()
+    }
+
+    fn entry_size(&mut self, name: &str) -> Option<u64> {
+        self.by_name(name).ok().map(|file| file.size())
+    }
+
+    fn stored_entry(&mut self, name: &str) -> Option<(u64, u64)> {
+        let file = self.by_name(name).ok()?;
+        if file.compression() != CompressionMethod::Stored {
+            return None;
+        }
+        Some((file.data_start(), file.size()))
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn entry_location(&mut self, name: &str) -> Option<IndexEntry> {
+        let file = self.by_name(name).ok()?;
+        let compression = match file.compression() {
+            CompressionMethod::STORE => "stored".to_owned(),
+            CompressionMethod::DEFLATE => "deflated".to_owned(),
+            CompressionMethod::BZIP2 => "bzip2".to_owned(),
+            other => other.to_string(),
+        };
+        Some(IndexEntry {
+            data_start: file.data_start(),
+            compressed_size: file.compressed_size(),
+            size: file.size(),
+            crc32: file.crc32(),
+            compression,
+        })
+    }
+
+    fn read_entry_data(
+        &mut self,
+        name: &str,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<io::Result<Vec<u8>>> {
+        // Entries that are listed but can't be opened are corrupt rather than missing
+        let file = match self.by_name(name) {
+            Ok(file) => file,
+            Err(ZipError::FileNotFound) => return None,
+            Err(ZipError::Io(e)) => return Some(Err(e)),
+            Err(e) => return Some(Err(io::Error::new(io::ErrorKind::InvalidData, e))),
+        };
+        let size = file.size();
+        let mut buf = Vec::with_capacity(size as usize);
+        let mut report = |read: u64| progress(read, size);
+        let result = ProgressReader::new(file, &mut report).read_to_end(&mut buf);
+        if result.is_ok() {
+            progress(size, size);
+        }
+        Some(result.map(|_| buf))
+    }
+}
+
+/// An asset bundle that has been mounted by the [`AssetIoZip`][crate::AssetIoZip]
+pub(crate) struct Bundle {
+    /// The name used to identify the bundle
+    pub name: String,
+    /// Whether or not this bundle is a mod
+    pub is_mod: bool,
+    source: Arc<dyn BundleSource>,
+    /// Restricts which assets the bundle may provide
+    filter: Option<PathFilter>,
+    /// Index of the small files stored in packed blocks, loaded the first time it is needed
+    pack_index: RwLock<Option<PackIndex>>,
+    /// Packed blocks that have been decompressed and still have files that haven't been read
+    pack_blocks: Mutex<PackBlockCache>,
+    /// Index of the recorded asset metadata, loaded the first time it is needed
+    metadata_index: RwLock<Option<MetadataIndex>>,
+    /// The sidecar index of the bundle, used instead of its zip central directory if present
+    #[cfg(not(target_arch = "wasm32"))]
+    index: Option<Arc<SidecarIndex>>,
+    /// The directory that the assets of the bundle have been extracted to, if any
+    #[cfg(not(target_arch = "wasm32"))]
+    extracted_dir: Option<PathBuf>,
+    /// Archives that have been opened and are not currently being read from
+    pool: Mutex<Vec<Archive>>,
+    /// The maximum number of archives to keep in the pool
+    pool_size: usize,
+    /// The regions of the bundle that have been read ahead, if reading ahead is enabled
+    read_ahead: Option<Arc<ReadAheadCache>>,
+    /// How to retry opening the bundle and reading entries that fail with transient errors
+    retry: RetryPolicy,
+    /// Memory map of the bundle used to serve uncompressed entries
+    #[cfg(feature = "mmap")]
+    map: RwLock<Option<Arc<Mmap>>>,
+    /// Supplies or overrides the salt that the keys of obfuscated entries are derived from
+    #[cfg(feature = "obfuscation")]
+    key_provider: Option<Arc<dyn KeyProvider>>,
+}
+
+impl Bundle {
+    /// Create a bundle that reads from the given source, keeping as many opened archives around
+    /// to be reused by later reads as the config allows
+    pub fn new(
+        name: String,
+        is_mod: bool,
+        source: Arc<dyn BundleSource>,
+        config: &AssetIoZipConfig,
+    ) -> Self {
+        // The default is filled in by the `ZipAssetIo`, which knows the size of the IO task pool
+        let pool_size = config.max_concurrent_reads.unwrap_or(1);
+
+        #[cfg(feature = "obfuscation")]
+        if let Some(slot) = source.key_slot() {
+            if EntryObfuscation::from_key_slot(&slot, Vec::new()).is_none() {
+                warn!(
+                    "Ignoring unsupported key slot of asset bundle {}, algorithm {} and key \
+                     derivation {}",
+                    name, slot.algorithm, slot.kdf
+                );
+            }
+        }
+
+        Self {
+            name,
+            is_mod,
+            #[cfg(not(target_arch = "wasm32"))]
+            index: SidecarIndex::load(&*source).map(Arc::new),
+            #[cfg(not(target_arch = "wasm32"))]
+            extracted_dir: None,
+            source,
+            filter: None,
+            pack_index: Default::default(),
+            pack_blocks: Default::default(),
+            metadata_index: Default::default(),
+            pool: Default::default(),
+            pool_size,
+            read_ahead: match config.read_ahead {
+                0 => None,
+                size => Some(Arc::new(ReadAheadCache::new(size, pool_size))),
+            },
+            retry: config.io_retry,
+            #[cfg(feature = "mmap")]
+            map: Default::default(),
+            #[cfg(feature = "obfuscation")]
+            key_provider: config.key_provider.clone(),
+        }
+    }
+
+    /// Restrict the assets that the bundle may provide to those allowed by the given filter
+    pub fn with_filter(mut self, filter: PathFilter) -> Self {
+        self.filter = Some(filter);
+        self
+    }
+
+    /// Serve the assets of the bundle from the loose files that they have been extracted to in the
+    /// given directory
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn with_extracted_dir(mut self, dir: PathBuf) -> Self {
+        self.extracted_dir = Some(dir);
+        self
+    }
+
+    /// Whether or not the bundle is allowed to provide the given asset
+    fn allows(&self, path: &Path) -> bool {
+        self.filter
+            .as_ref()
+            .map(|filter| filter.allows(path))
+            .unwrap_or(true)
+    }
+
+    /// The path of the bundle file, if it is on the filesystem
+    pub fn path(&self) -> Option<&Path> {
+        self.source.path()
+    }
+
+    /// Get a description of where the bundle is mounted from for log messages
+    pub fn location(&self) -> String {
+        self.path()
+            .map(|x| x.display().to_string())
+            .unwrap_or_else(|| self.name.clone())
+    }
+
+    /// Whether or not the whole bundle file is obfuscated
+    pub fn obfuscated(&self) -> bool {
+        self.source.obfuscated()
+    }
+
+    /// Wait for the bundle data to be available
+    pub async fn wait_ready(&self) {
+        self.source.ready().await
+    }
+
+    /// Get an opened archive of the bundle from the pool, or open a new one if they are all in use
+    ///
+    /// Each archive has its own reader over the bundle, so assets can be decompressed from
+    /// different archives on different threads at the same time. The archive is returned to the
+    /// pool when it is dropped.
+    pub fn open(&self) -> Option<PooledArchive<'_>> {
+        let pooled = self.pool.lock().unwrap().pop();
+        let archive = match pooled {
+            Some(archive) => archive,
+            None => self.open_new()?,
+        };
+
+        Some(PooledArchive {
+            bundle: self,
+            archive: Some(archive),
+        })
+    }
+
+    /// Open a new archive of the bundle
+    fn open_new(&self) -> Option<Archive> {
+        self.retry
+            .run(
+                || format!("open asset bundle {}", self.location()),
+                || {
+                    let reader = self.source.open()?;
+                    self.open_archive(reader).map_err(|e| match e {
+                        ZipError::Io(e) => e,
+                        e => io::Error::new(io::ErrorKind::InvalidData, e),
+                    })
+                },
+            )
+            .ok()
+    }
+
+    /// Open an archive of the bundle over the given reader of the bundle source
+    fn open_archive(&self, reader: Box<dyn ReadSeekSend>) -> ZipResult<Archive> {
+        #[cfg_attr(not(feature = "sqlite"), allow(unused_mut))]
+        let mut reader: Box<dyn ReadSeekSend> = match &self.read_ahead {
+            Some(cache) => Box::new(ReadAheadReader::new(reader, cache.clone())),
+            None => reader,
+        };
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(index) = &self.index {
+            return Ok(Box::new(IndexedArchive::new(reader, index.clone())));
+        }
+
+        #[cfg(feature = "sqlite")]
+        if sqlite::is_sqlite(&mut *reader)? {
+            return Ok(Box::new(SqliteArchive::open(&*self.source)?));
+        }
+
+        Ok(Box::new(ZipArchive::new(reader)?))
+    }
+
+    /// Read the archive comment of the bundle, returning `None` if it has no comment or it can't be
+    /// read
+    ///
+    /// The zip central directory is always parsed, since the sidecar index doesn't record the
+    /// comment.
+    pub fn comment(&self) -> Option<String> {
+        let archive = ZipArchive::new(self.source.open().ok()?).ok()?;
+        if archive.comment().is_empty() {
+            return None;
+        }
+        Some(String::from_utf8_lossy(archive.comment()).into_owned())
+    }
+
+    /// Return an archive to the pool to be reused by later reads, unless the pool is full
+    fn release(&self, archive: Archive) {
+        let mut pool = self.pool.lock().unwrap();
+        if pool.len() < self.pool_size {
+            pool.push(archive);
+        }
+    }
+
+    /// Check that the bundle can be opened as a zip archive, returning a detailed description of
+    /// the problem if it can't
+    ///
+    /// Bundles that are not available yet, such as bundles that are still being downloaded, are
+    /// not checked. Bundles with a sidecar index have already been checked against the index when
+    /// it was loaded. The archive opened by the check is kept in the pool, so the bundle isn't
+    /// opened again for the first read.
+    pub fn check(&self) -> Result<(), String> {
+        let open_error =
+            |e: io::Error| format!("Could not open asset bundle {}: {}", self.location(), e);
+
+        let opened = self.retry.run(
+            || format!("open asset bundle {}", self.location()),
+            || self.source.open(),
+        );
+        let reader = match opened {
+            Ok(reader) => reader,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+            Err(e) => return Err(open_error(e)),
+        };
+
+        let error = match self.open_archive(reader) {
+            Ok(archive) => {
+                self.release(archive);
+                return Ok(());
+            }
+            Err(e) => e,
+        };
+        let mut reader = self.source.open().map_err(open_error)?;
+
+        // Collect the size and the first bytes of the bundle to help diagnose truncated downloads
+        // and bundles that have been mislabeled as obfuscated or plain
+        let size = reader.seek(SeekFrom::End(0)).ok();
+        let mut magic = [0; 4];
+        let magic_len = reader
+            .seek(SeekFrom::Start(0))
+            .and_then(|_| reader.read(&mut magic))
+            .unwrap_or(0);
+
+        Err(format!(
+            "Asset bundle {} is corrupted and could not be opened as a zip archive: {} ( size: {}, \
+            magic bytes: {:02x?}, obfuscated: {} )",
+            self.location(),
+            error,
+            size.map(|x| format!("{} bytes", x))
+                .unwrap_or_else(|| "unknown".into()),
+            &magic[..magic_len],
+            self.obfuscated()
+        ))
+    }
+
+    /// Check that the bundle has no more than `max_entries` entries, and that their total
+    /// uncompressed size is no more than `max_size`, returning a description of the problem if it
+    /// is over a limit
+    ///
+    /// The sizes recorded in the zip data are checked without decompressing anything. Bundles that
+    /// can't be opened are not checked.
+    pub fn check_limits(
+        &self,
+        max_entries: Option<usize>,
+        max_size: Option<u64>,
+    ) -> Result<(), String> {
+        let mut archive = match self.open() {
+            Some(archive) => archive,
+            None => return Ok(()),
+        };
+
+        let entries = archive.len();
+        if let Some(max_entries) = max_entries.filter(|x| entries > *x) {
+            return Err(format!(
+                "Asset bundle {} has {} entries, more than the limit of {}",
+                self.location(),
+                entries,
+                max_entries
+            ));
+        }
+
+        if let Some(max_size) = max_size {
+            let size = archive.total_size();
+            if size > max_size {
+                return Err(format!(
+                    "Asset bundle {} has {} bytes of uncompressed entries, more than the limit of \
+                    {} bytes",
+                    self.location(),
+                    size,
+                    max_size
+                ));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Whether or not the given archive of this bundle contains the given asset
+    pub fn contains(&self, archive: &mut Archive, path: &Path) -> bool {
+        if !self.allows(path) {
+            return false;
+        }
+
+        let in_archive = path
+            .to_str()
+            .map(|name| archive.has_entry(name))
+            .unwrap_or(false);
+
+        in_archive || self.packed_file(archive, path).is_some()
+    }
+
+    /// Get the size of the given asset in the given archive of this bundle, returning `None` if the
+    /// archive does not contain the asset
+    pub fn asset_size(&self, archive: &mut Archive, path: &Path) -> Option<u64> {
+        if !self.allows(path) {
+            return None;
+        }
+
+        path.to_str()
+            .and_then(|name| archive.entry_size(name))
+            .or_else(|| Some(self.packed_file(archive, path)?.length as u64))
+    }
+
+    /// Get the metadata of the given asset in the given archive of this bundle, returning `None` if
+    /// the archive does not contain the asset
+    pub fn asset_metadata(&self, archive: &mut Archive, path: &Path) -> Option<AssetMetadata> {
+        if !self.contains(archive, path) {
+            return None;
+        }
+        let recorded = self.recorded_metadata(archive, path);
+
+        Some(AssetMetadata {
+            bundle: self.name.clone(),
+            modified: recorded.map(|x| x.modified),
+            hash: recorded.map(|x| x.hash),
+        })
+    }
+
+    /// Get the metadata recorded by the bundler for the given asset in the given archive of this
+    /// bundle, returning `None` if the bundle was built without `asset-metadata`
+    fn recorded_metadata(&self, archive: &mut Archive, path: &Path) -> Option<RecordedMetadata> {
+        // Load the metadata index if we haven't yet. Bundles without metadata get an empty index.
+        if self.metadata_index.read().unwrap().is_none() {
+            let index = match self.read_entry(archive, Path::new(METADATA_ENTRY)) {
+                Some(Ok(bytes)) => MetadataIndex::parse(&String::from_utf8_lossy(&bytes)),
+                _ => MetadataIndex::default(),
+            };
+            *self.metadata_index.write().unwrap() = Some(index);
+        }
+
+        self.metadata_index.read().unwrap().as_ref()?.get(path)
+    }
+
+    /// Get the content hashes recorded by the bundler for all of the assets in the given archive of
+    /// this bundle, with `None` for the assets whose hash wasn't recorded
+    pub fn asset_hashes(&self, archive: &mut Archive) -> Vec<(String, Option<u32>)> {
+        self.asset_paths(archive)
+            .into_iter()
+            .map(|path| {
+                let hash = self
+                    .recorded_metadata(archive, Path::new(&path))
+                    .map(|x| x.hash);
+                (path, hash)
+            })
+            .collect()
+    }
+
+    /// Get the paths of all of the assets in the given archive of this bundle, including packed
+    /// assets but excluding directories and the entries used internally by the bundler
+    pub fn asset_paths(&self, archive: &mut Archive) -> Vec<String> {
+        let mut paths = archive
+            .entry_names()
+            .into_iter()
+            .filter(|name| !name.ends_with('/') && !is_internal_entry(name))
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+
+        // Make sure the pack index has been loaded
+        self.packed_file(archive, Path::new(PACK_INDEX_ENTRY));
+        if let Some(index) = self.pack_index.read().unwrap().as_ref() {
+            paths.extend(index.paths().map(ToOwned::to_owned));
+        }
+
+        paths.retain(|path| self.allows(Path::new(path)));
+
+        paths
+    }
+
+    /// Read an asset from the given archive of this bundle, either from its own entry or from a
+    /// packed block, returning `None` if the archive does not contain the asset
+    pub fn read_asset(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        self.read_asset_with_progress(archive, path, &mut |_, _| ())
+    }
+
+    /// [`Read`][Self::read_asset] an asset from the given archive of this bundle, calling
+    /// `progress` with the number of bytes decompressed so far and the size of the asset while its
+    /// own entry is decompressed
+    pub fn read_asset_with_progress(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        if !self.allows(path) {
+            return None;
+        }
+
+        // Only assets in the bundle have been extracted, so reading the file is all we need to do
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(dir) = &self.extracted_dir {
+            if let Ok(data) = std::fs::read(dir.join(path)) {
+                return Some(Ok(data));
+            }
+        }
+
+        #[cfg(feature = "mmap")]
+        if let Some(result) = self.read_mapped(archive, path) {
+            return Some(result);
+        }
+
+        self.read_entry_with_progress(archive, path, progress)
+            .or_else(|| self.read_packed(archive, path))
+    }
+
+    /// Open a streaming reader over an uncompressed asset in the given archive of this bundle,
+    /// returning `None` if the archive does not contain the asset
+    pub fn open_entry(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+    ) -> Option<io::Result<EntryReader>> {
+        if !self.allows(path) {
+            return None;
+        }
+
+        let name = path.to_str()?;
+        let (start, len) = match archive.stored_entry(name) {
+            Some(entry) => entry,
+            None if archive.has_entry(name) || self.packed_file(archive, path).is_some() => {
+                return Some(Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    format!(
+                        "Asset {} is not stored uncompressed in the data of bundle {} and cannot \
+                        be streamed",
+                        name, self.name
+                    ),
+                )))
+            }
+            None => return None,
+        };
+
+        #[cfg(feature = "obfuscation")]
+        let obfuscation = self.entry_obfuscation(archive);
+
+        let reader = match self.source.open() {
+            Ok(reader) => reader,
+            Err(e) => return Some(Err(e)),
+        };
+
+        #[cfg(feature = "obfuscation")]
+        if let Some(obfuscation) = obfuscation {
+            let keystream = obfuscation.keystream(name);
+            return Some(EntryReader::new(reader, start, len).map(|x| x.with_keystream(keystream)));
+        }
+
+        Some(EntryReader::new(reader, start, len))
+    }
+
+    /// Read the compressed data of an asset in the given archive of this bundle without
+    /// decompressing it, returning `None` if the archive does not contain the asset
+    ///
+    /// Packed and extracted assets, and assets in SQLite bundles, are returned uncompressed. Assets
+    /// in bundles with obfuscated entries can't be passed through, since their data is obfuscated
+    /// before it is compressed.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn raw_entry(&self, archive: &mut Archive, path: &Path) -> Option<io::Result<RawEntry>> {
+        if !self.allows(path) {
+            return None;
+        }
+
+        if let Some(dir) = &self.extracted_dir {
+            if let Ok(data) = std::fs::read(dir.join(path)) {
+                return Some(Ok(RawEntry::stored(path.to_owned(), data)));
+            }
+        }
+
+        let name = path.to_str()?;
+        let entry = match archive.entry_location(name) {
+            Some(entry) => entry,
+            None => {
+                let read = if archive.has_entry(name) {
+                    self.read_entry(archive, path)
+                } else {
+                    self.read_packed(archive, path)
+                };
+                return read.map(|result| {
+                    result
+                        .map(|data| RawEntry::stored(path.to_owned(), data))
+                        .map_err(|e| match e {
+                            AssetIoError::Io(e) => e,
+                            e => io::Error::new(io::ErrorKind::InvalidData, e),
+                        })
+                });
+            }
+        };
+
+        #[cfg(feature = "obfuscation")]
+        if entries_obfuscated(archive) {
+            return Some(Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!(
+                    "Asset {} is obfuscated in bundle {} and cannot be passed through",
+                    name, self.name
+                ),
+            )));
+        }
+
+        let read = || {
+            let mut reader = self.source.open()?;
+            reader.seek(SeekFrom::Start(entry.data_start))?;
+            let mut data = Vec::with_capacity(entry.compressed_size as usize);
+            reader.take(entry.compressed_size).read_to_end(&mut data)?;
+            Ok(RawEntry {
+                path: path.to_owned(),
+                compression: entry.compression.clone(),
+                data,
+                size: entry.size,
+                crc32: entry.crc32,
+            })
+        };
+        Some(read())
+    }
+
+    /// Get the memory map of the bundle, mapping it if it hasn't been already
+    ///
+    /// Obfuscated bundles cannot be memory mapped.
+    #[cfg(feature = "mmap")]
+    fn map(&self) -> Option<Arc<Mmap>> {
+        if let Some(map) = self.map.read().unwrap().as_ref() {
+            return Some(map.clone());
+        }
+
+        if self.source.obfuscated() || self.source.solid() {
+            return None;
+        }
+        let path = self.source.path()?;
+
+        let file = OpenOptions::new().read(true).open(path).ok()?;
+        // SAFETY: The bundle is opened read-only and is expected not to be modified while the game
+        // is running.
+        let map = Arc::new(unsafe { Mmap::map(&file) }.ok()?);
+        *self.map.write().unwrap() = Some(map.clone());
+
+        Some(map)
+    }
+
+    /// Read an uncompressed entry directly out of the memory mapped bundle, returning `None` if
+    /// the entry is compressed, missing, or the bundle cannot be mapped
+    #[cfg(feature = "mmap")]
+    fn read_mapped(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        #[cfg(feature = "obfuscation")]
+        let obfuscation = self.entry_obfuscation(archive);
+
+        let name = path.to_str()?;
+        let (data_start, size) = archive.stored_entry(name)?;
+        let data_start = (data_start + self.source.data_offset()) as usize;
+        let size = size as usize;
+
+        let map = self.map()?;
+        let bytes = map.get(data_start..data_start + size)?;
+
+        #[cfg(feature = "obfuscation")]
+        if let Some(obfuscation) = obfuscation {
+            let mut buf = bytes.to_vec();
+            obfuscation.keystream(name).apply(0, &mut buf);
+            return Some(Ok(buf));
+        }
+
+        Some(Ok(bytes.to_vec()))
+    }
+
+    /// Read and decompress an entry from the given archive of this bundle, returning `None` if the
+    /// archive does not contain the entry
+    pub fn read_entry(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        self.read_entry_with_progress(archive, path, &mut |_, _| ())
+    }
+
+    /// [`Read`][Self::read_entry] an entry from the given archive of this bundle, calling
+    /// `progress` with the number of bytes decompressed so far and the size of the entry
+    fn read_entry_with_progress(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        #[cfg(feature = "obfuscation")]
+        let obfuscation = self.entry_obfuscation(archive);
+
+        let name = path.to_str().expect("non-unicode filename");
+        let data = self
+            .retry
+            .run(
+                || format!("read {} from asset bundle {}", name, self.location()),
+                || archive.read_entry_data(name, &mut *progress).transpose(),
+            )
+            .transpose()?;
+
+        #[cfg(feature = "obfuscation")]
+        if let Some(obfuscation) = obfuscation {
+            let keystream = obfuscation.keystream(name);
+            return Some(
+                data.map(|mut data| {
+                    keystream.apply(0, &mut data);
+                    data
+                })
+                .map_err(Into::into),
+            );
+        }
+
+        Some(data.map_err(Into::into))
+    }
+
+    /// Get how the data of each entry in the given archive of this bundle has been obfuscated by
+    /// the bundler, returning `None` if the entries aren't obfuscated
+    ///
+    /// The salt stored in the bundle is replaced by the one from the
+    /// [`KeyProvider`][crate::KeyProvider], if it supplies one.
+    ///
+    /// The [key slot][crate::KeySlot] of the bundle header is used if it has one that is supported,
+    /// otherwise the obfuscated entries marker is.
+    #[cfg(feature = "obfuscation")]
+    fn entry_obfuscation(&self, archive: &mut Archive) -> Option<EntryObfuscation> {
+        if let Some(slot) = self.source.key_slot() {
+            let obfuscation = EntryObfuscation::from_key_slot(&slot, self.salt(slot.salt.clone()));
+            if obfuscation.is_some() {
+                return obfuscation;
+            }
+        }
+
+        let marker = archive
+            .read_entry_data(OBFUSCATED_ENTRIES_MARKER, &mut |_, _| ())?
+            .unwrap_or_default();
+        Some(EntryObfuscation::from_marker(self.salt(marker)))
+    }
+
+    /// Get the salt to derive the entry keys from, given the salt stored in the bundle
+    #[cfg(feature = "obfuscation")]
+    fn salt(&self, stored: Vec<u8>) -> Vec<u8> {
+        match &self.key_provider {
+            Some(provider) => provider.salt(&self.name, &stored).unwrap_or(stored),
+            None => stored,
+        }
+    }
+
+    /// Get the location of the given asset in the packed blocks of the given archive
+    fn packed_file(&self, archive: &mut Archive, path: &Path) -> Option<pack::PackedFile> {
+        // Load the pack index if we haven't yet. Archives without packed blocks get an empty index.
+        if self.pack_index.read().unwrap().is_none() {
+            let index = match self.read_entry(archive, Path::new(PACK_INDEX_ENTRY)) {
+                Some(Ok(bytes)) => PackIndex::parse(&String::from_utf8_lossy(&bytes)),
+                Some(Err(_)) => return None,
+                None => PackIndex::default(),
+            };
+            *self.pack_index.write().unwrap() = Some(index);
+        }
+
+        self.pack_index.read().unwrap().as_ref()?.get(path)
+    }
+
+    /// Read an asset from the packed blocks in the given archive
+    fn read_packed(
+        &self,
+        archive: &mut Archive,
+        path: &Path,
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        let packed = self.packed_file(archive, path)?;
+
+        // Decompress the whole block the first time one of its files is read, and keep it until
+        // the rest of its files have been read too
+        let name = path.to_str()?;
+        let cached_block = self.pack_blocks.lock().unwrap().read(name, packed);
+        let block = match cached_block {
+            Some(block) => block,
+            None => {
+                let block_name = pack::block_entry_name(packed.block);
+                let block = match self.read_entry(archive, Path::new(&block_name))? {
+                    Ok(block) => Arc::new(block),
+                    Err(e) => return Some(Err(e)),
+                };
+                if let Some(index) = self.pack_index.read().unwrap().as_ref() {
+                    self.pack_blocks
+                        .lock()
+                        .unwrap()
+                        .insert(index, name, packed, block.clone());
+                }
+                block
+            }
+        };
+
+        Some(
+            block
+                .get(packed.offset..packed.offset + packed.length)
+                .map(|bytes| bytes.to_vec())
+                .ok_or_else(|| {
+                    AssetIoError::Io(std::io::Error::new(
+                        std::io::ErrorKind::InvalidData,
+                        "Packed file is out of the bounds of its block",
+                    ))
+                }),
+        )
+    }
+}
+
+/// An archive borrowed from the pool of a [`Bundle`], which is returned to the pool when dropped
+pub(crate) struct PooledArchive<'a> {
+    bundle: &'a Bundle,
+    archive: Option<Archive>,
+}
+
+impl Deref for PooledArchive<'_> {
+    type Target = Archive;
+
+    fn deref(&self) -> &Archive {
+        self.archive.as_ref().unwrap()
+    }
+}
+
+impl DerefMut for PooledArchive<'_> {
+    fn deref_mut(&mut self) -> &mut Archive {
+        self.archive.as_mut().unwrap()
+    }
+}
+
+impl Drop for PooledArchive<'_> {
+    fn drop(&mut self) {
+        if let Some(archive) = self.archive.take() {
+            self.bundle.release(archive);
+        }
+    }
+}
+
+/// Whether or not the given entry is used internally by the bundler rather than being an asset
+fn is_internal_entry(name: &str) -> bool {
+    #[cfg(feature = "obfuscation")]
+    if name == OBFUSCATED_ENTRIES_MARKER {
+        return true;
+    }
+
+    name == METADATA_ENTRY || name == GROUPS_ENTRY || name.starts_with(pack::PACK_DIR)
+}
+
+/// Whether or not the data of each entry in the archive has been obfuscated by the bundler
+#[cfg(feature = "obfuscation")]
+pub(crate) fn entries_obfuscated(archive: &mut Archive) -> bool {
+    archive.has_entry(OBFUSCATED_ENTRIES_MARKER)
+}
@@ -0,0 +1,34 @@
+//! Cancelling asset loads that are no longer needed
+
+use std::sync::{
+    atomic::{AtomicBool, Ordering},
+    Arc,
+};
+
+/// A handle that cancels the asset loads and prefetches it is bound to
+///
+/// Bind a token to the assets of a level with
+/// [`prefetch_cancellable`][crate::ZipAssetIo::prefetch_cancellable] or
+/// [`bind_cancellation`][crate::ZipAssetIo::bind_cancellation], and cancel it when the level load
+/// is aborted. Clones of the token share the same cancellation state.
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    /// Create a token that hasn't been cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the loads bound to the token
+    ///
+    /// Reads that are already decompressing are finished, but their data is dropped.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether or not the token has been cancelled
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
@@ -0,0 +1,168 @@
+//! Validation of the [`AssetIoZipConfig`]
+
+use glob::Pattern;
+
+use crate::{AssetIoZipConfig, FallbackAssetIo};
+
+/// The extensions of asset bundle files
+#[cfg(not(feature = "sqlite"))]
+const BUNDLE_EXTENSIONS: &[&str] = &["zip", "bin"];
+#[cfg(feature = "sqlite")]
+const BUNDLE_EXTENSIONS: &[&str] = &["zip", "bin", "sqlite"];
+
+impl AssetIoZipConfig {
+    /// Check the config for settings that would keep bundles or mods from being loaded
+    ///
+    /// Each problem is described along with how to fix it. The [`AssetIoZipPlugin`] logs these as
+    /// errors when it is built, so that a misconfiguration doesn't just look like missing assets.
+    ///
+    /// [`AssetIoZipPlugin`]: crate::AssetIoZipPlugin
+    pub fn validate(&self) -> Vec<String> {
+        let mut problems = Vec::new();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(path) = &self.asset_dir {
+            if !path.is_dir() {
+                problems.push(format!(
+                    "`asset_dir` {} is not a directory, so no loose assets will be loaded from it. \
+                    Check that the path is correct.",
+                    path.display()
+                ));
+            }
+        }
+
+        if let Some(path) = &self.bundle_path {
+            // There is no filesystem to check in the browser
+            #[cfg(not(target_arch = "wasm32"))]
+            if path.is_dir() {
+                problems.push(format!(
+                    "`bundle_path` {} is a directory. Set it to the path of the bundle file, \
+                    including the extension.",
+                    path.display()
+                ));
+            } else if !path.exists() {
+                problems.push(format!(
+                    "`bundle_path` {} does not exist, so no asset bundle will be loaded. Check \
+                    that the path is correct; relative paths are relative to the working \
+                    directory.",
+                    path.display()
+                ));
+            }
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let (Some(region), Ok(metadata)) = (self.bundle_region, path.metadata()) {
+                if region.offset.saturating_add(region.length) > metadata.len() {
+                    problems.push(format!(
+                        "`bundle_region` ends at byte {} but `bundle_path` {} is only {} bytes \
+                        long, so the bundle can't be read. Check the offset and length of the \
+                        region.",
+                        region.offset.saturating_add(region.length),
+                        path.display(),
+                        metadata.len()
+                    ));
+                }
+            }
+
+            // Bundles in a region of a larger file, such as the executable, are detected from
+            // their first bytes instead of the extension
+            let extension = path.extension().and_then(|x| x.to_str()).unwrap_or("");
+            if self.bundle_region.is_none() {
+                if !BUNDLE_EXTENSIONS.contains(&extension) {
+                    problems.push(format!(
+                        "`bundle_path` {} does not have a `.zip` or `.bin` extension, so it will \
+                        be read as a plain zip. Rename it if it is an obfuscated bundle.",
+                        path.display()
+                    ));
+                } else if extension == "bin" && !cfg!(feature = "obfuscation") {
+                    problems.push(format!(
+                        "`bundle_path` {} is an obfuscated bundle, but the `obfuscation` feature \
+                        of `bevy_assetio_zip` is disabled. Enable the feature to load it.",
+                        path.display()
+                    ));
+                }
+            }
+        } else if self.file_names.is_empty() {
+            problems.push(
+                "`file_names` is empty, so no asset bundle will be searched for. Add the name of \
+                the bundle, such as \"assets\", or set `bundle_path`."
+                    .into(),
+            );
+        }
+
+        if self.bundle_region.is_some() && self.bundle_path.is_none() {
+            problems.push(
+                "`bundle_region` is set without `bundle_path`, so it will be ignored. Set \
+                `bundle_path` to the file that contains the bundle."
+                    .into(),
+            );
+        }
+
+        for file_name in &self.file_names {
+            if file_name.is_empty() {
+                problems.push(
+                    "`file_names` contains an empty name. Remove it or set it to the name of the \
+                    bundle, such as \"assets\"."
+                        .into(),
+                );
+            } else if file_name.contains(&['/', '\\'][..]) {
+                problems.push(format!(
+                    "`file_names` entry \"{}\" is a path, but bundles are only searched for by \
+                    name. Set `bundle_path` to load a bundle from a specific path.",
+                    file_name
+                ));
+            } else if let Some(stem) = BUNDLE_EXTENSIONS
+                .iter()
+                .find_map(|x| file_name.strip_suffix(&format!(".{}", x)))
+            {
+                problems.push(format!(
+                    "`file_names` entry \"{}\" includes the extension, so \"{}.zip\" will be \
+                    searched for. Use \"{}\" instead.",
+                    file_name, file_name, stem
+                ));
+            }
+        }
+
+        if let Some(hash) = &self.expected_bundle_hash {
+            if hash.len() != 64 || !hash.chars().all(|x| x.is_ascii_hexdigit()) {
+                problems.push(format!(
+                    "`expected_bundle_hash` \"{}\" is not a hex BLAKE3 hash, so the bundle will \
+                    never match it. Set it with the `bundle_hash!` macro.",
+                    hash
+                ));
+            }
+        }
+
+        if self.decompression_threads == Some(0) {
+            problems.push(
+                "`decompression_threads` is 0, so assets will never be decompressed. Set it to \
+                the number of threads to decompress on, or to `None` to use the IO task pool."
+                    .into(),
+            );
+        }
+
+        if self.prefer_filesystem && self.fallback == FallbackAssetIo::Noop {
+            problems.push(
+                "`prefer_filesystem` is set but `fallback` is `Noop`, so there are no loose files \
+                to load in place of the bundled assets. Set `fallback` to `PlatformDefault`."
+                    .into(),
+            );
+        }
+
+        for (field, patterns) in &[
+            ("mount_patterns", &self.mount_patterns),
+            ("mod_allow", &self.mod_allow),
+            ("mod_deny", &self.mod_deny),
+        ] {
+            for pattern in patterns.iter() {
+                if let Err(e) = Pattern::new(pattern) {
+                    problems.push(format!(
+                        "`{}` pattern `{}` is invalid and will be ignored: {}",
+                        field, pattern, e
+                    ));
+                }
+            }
+        }
+
+        problems
+    }
+}
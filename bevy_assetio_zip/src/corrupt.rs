@@ -0,0 +1,58 @@
+//! The error of assets that are in a bundle but can't be read from it
+
+use std::{error::Error, fmt, io, path::PathBuf};
+
+use bevy::asset::AssetIoError;
+
+/// The error of an asset that was found in a bundle but failed to decompress or de-obfuscate,
+/// such as an asset whose data is corrupt
+///
+/// Loading such an asset fails with an [`AssetIoError::Io`] error of the
+/// [`InvalidData`][io::ErrorKind::InvalidData] kind that wraps this error, which tells it apart
+/// from an asset that isn't in any bundle, which fails with [`AssetIoError::NotFound`]. Get it from
+/// the error with [`from_asset_io_error`][Self::from_asset_io_error]. The asset isn't loaded from
+/// the fallback asset IO instead unless
+/// [`corrupt_asset_fallback`][crate::AssetIoZipConfig::corrupt_asset_fallback] is set, because
+/// falling back would hide bugs in the pipeline that built the bundle.
+#[derive(Debug)]
+pub struct CorruptAssetError {
+    /// The path of the asset
+    pub path: PathBuf,
+    /// The name of the bundle that the asset was found in
+    pub bundle: String,
+    /// The error that reading the asset failed with
+    pub source: io::Error,
+}
+
+impl CorruptAssetError {
+    /// Get the corrupt asset error that the given asset IO error wraps, if it wraps one
+    pub fn from_asset_io_error(error: &AssetIoError) -> Option<&Self> {
+        match error {
+            AssetIoError::Io(e) => e.get_ref()?.downcast_ref(),
+            _ => None,
+        }
+    }
+
+    /// Wrap the error in an asset IO error
+    pub(crate) fn into_asset_io_error(self) -> AssetIoError {
+        AssetIoError::Io(io::Error::new(io::ErrorKind::InvalidData, self))
+    }
+}
+
+impl fmt::Display for CorruptAssetError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Asset {} was found in asset bundle {} but could not be read: {}",
+            self.path.display(),
+            self.bundle,
+            self.source
+        )
+    }
+}
+
+impl Error for CorruptAssetError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.source)
+    }
+}
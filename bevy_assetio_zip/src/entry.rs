@@ -0,0 +1,103 @@
+//! Streaming readers over the entries of asset bundles
+
+use std::io::{self, Read, Seek, SeekFrom};
+
+#[cfg(feature = "obfuscation")]
+use crate::keys::Keystream;
+use crate::ReadSeekSend;
+
+/// A reader that streams an uncompressed asset directly out of an asset bundle
+///
+/// This is returned by [`AssetIoZip::open_entry`][crate::ZipAssetIo::open_entry] and has its own
+/// reader over the bundle, so it can be read from another thread, such as by an audio or video
+/// decoder, without loading the whole asset into memory.
+pub struct EntryReader {
+    inner: Box<dyn ReadSeekSend>,
+    /// The offset of the entry data in the inner reader
+    start: u64,
+    /// The length of the entry data
+    len: u64,
+    /// The current position relative to the start of the entry data
+    pos: u64,
+    /// The keystream the entry data is obfuscated with
+    #[cfg(feature = "obfuscation")]
+    keystream: Option<Keystream>,
+}
+
+impl EntryReader {
+    pub(crate) fn new(inner: Box<dyn ReadSeekSend>, start: u64, len: u64) -> io::Result<Self> {
+        let mut reader = Self {
+            inner,
+            start,
+            len,
+            pos: 0,
+            #[cfg(feature = "obfuscation")]
+            keystream: None,
+        };
+        reader.inner.seek(SeekFrom::Start(start))?;
+
+        Ok(reader)
+    }
+
+    /// Decode the entry data with the given keystream as it is read
+    #[cfg(feature = "obfuscation")]
+    pub(crate) fn with_keystream(mut self, keystream: Keystream) -> Self {
+        self.keystream = Some(keystream);
+        self
+    }
+
+    /// The size of the asset in bytes
+    pub fn len(&self) -> u64 {
+        self.len
+    }
+
+    /// Whether or not the asset is empty
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+}
+
+impl Read for EntryReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.len.saturating_sub(self.pos);
+        let max = remaining.min(buf.len() as u64) as usize;
+        let read = self.inner.read(&mut buf[..max])?;
+        #[cfg(feature = "obfuscation")]
+        if let Some(keystream) = &self.keystream {
+            keystream.apply(self.pos, &mut buf[..read]);
+        }
+        self.pos += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl Seek for EntryReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => checked_offset(self.len, offset),
+            SeekFrom::Current(offset) => checked_offset(self.pos, offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek to before the start of the asset",
+            )
+        })?;
+
+        self.inner.seek(SeekFrom::Start(self.start + new_pos))?;
+        self.pos = new_pos;
+
+        Ok(new_pos)
+    }
+}
+
+/// Add a signed offset to a position, returning `None` if the result would be negative
+pub(crate) fn checked_offset(pos: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        pos.checked_sub(offset.wrapping_neg() as u64)
+    } else {
+        pos.checked_add(offset as u64)
+    }
+}
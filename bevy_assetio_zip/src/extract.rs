@@ -0,0 +1,132 @@
+//! Extracting the asset bundle to loose files in a scratch directory
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use bevy::{log::info, tasks::TaskPool};
+
+use crate::bundle::Bundle;
+
+/// The file written to the extraction directory once every asset has been extracted into it
+const COMPLETE_MARKER: &str = ".extracted";
+
+/// The number of characters of the bundle hash used in the name of the extraction directory
+const HASH_PREFIX_LEN: usize = 16;
+
+/// Extract every asset of the bundle with the given hex hash into a directory inside of
+/// `extract_dir`, returning the directory that the assets were extracted to
+///
+/// The directory is named after the bundle and its hash, so the assets are only extracted the
+/// first time a version of the bundle is mounted, and the directories extracted from other
+/// versions of the bundle are removed. Assets are extracted in parallel on the given task pool and
+/// checked against the content hashes recorded by the bundler, if the bundle has any.
+pub(crate) fn extract_bundle(
+    bundle: &Bundle,
+    hash: &str,
+    extract_dir: &Path,
+    task_pool: &TaskPool,
+) -> Result<PathBuf, String> {
+    let prefix = format!("{}-", bundle.name);
+    let dir = extract_dir.join(format!("{}{}", prefix, &hash[..HASH_PREFIX_LEN]));
+    if dir.join(COMPLETE_MARKER).is_file() {
+        return Ok(dir);
+    }
+
+    // This also removes what was left of an extraction that was interrupted
+    remove_extracted(extract_dir, &prefix)
+        .map_err(|e| format!("Could not clean up {}: {}", extract_dir.display(), e))?;
+
+    let paths = bundle
+        .open()
+        .map(|mut archive| bundle.asset_paths(&mut archive))
+        .ok_or_else(|| format!("Could not open asset bundle {}", bundle.location()))?;
+    info!(
+        "Extracting {} assets from bundle {} to {}",
+        paths.len(),
+        bundle.location(),
+        dir.display()
+    );
+
+    let chunk_size = (paths.len() / task_pool.thread_num().max(1)).max(1);
+    let results = task_pool.scope(|scope| {
+        for chunk in paths.chunks(chunk_size) {
+            let dir = &dir;
+            scope.spawn(async move { extract_assets(bundle, chunk, dir) });
+        }
+    });
+    results.into_iter().collect::<Result<(), _>>()?;
+
+    fs::write(dir.join(COMPLETE_MARKER), hash)
+        .map_err(|e| format!("Could not finish extracting to {}: {}", dir.display(), e))?;
+
+    Ok(dir)
+}
+
+/// Extract the given assets of the bundle into `dir`, checking them against their recorded hashes
+fn extract_assets(bundle: &Bundle, paths: &[String], dir: &Path) -> Result<(), String> {
+    let mut archive = bundle
+        .open()
+        .ok_or_else(|| format!("Could not open asset bundle {}", bundle.location()))?;
+
+    for path in paths {
+        let path = Path::new(path);
+        let data = match bundle.read_asset(&mut archive, path) {
+            Some(Ok(data)) => data,
+            Some(Err(e)) => return Err(format!("Could not read asset {}: {}", path.display(), e)),
+            None => continue,
+        };
+
+        if let Some(expected) = bundle
+            .asset_metadata(&mut archive, path)
+            .and_then(|x| x.hash)
+        {
+            let hash = crc32fast::hash(&data);
+            if hash != expected {
+                return Err(format!(
+                    "Asset {} in bundle {} has hash {:08x}, but the bundler recorded {:08x}",
+                    path.display(),
+                    bundle.location(),
+                    hash,
+                    expected
+                ));
+            }
+        }
+
+        let target = dir.join(path);
+        if let Some(parent) = target.parent() {
+            fs::create_dir_all(parent)
+                .map_err(|e| format!("Could not create {}: {}", parent.display(), e))?;
+        }
+        fs::write(&target, data)
+            .map_err(|e| format!("Could not write {}: {}", target.display(), e))?;
+    }
+
+    Ok(())
+}
+
+/// Remove the directories in `extract_dir` that were extracted from any version of a bundle, which
+/// are named with `prefix` followed by part of the bundle hash
+fn remove_extracted(extract_dir: &Path, prefix: &str) -> io::Result<()> {
+    let entries = match fs::read_dir(extract_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(()),
+        Err(e) => return Err(e),
+    };
+
+    for entry in entries {
+        let entry = entry?;
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let is_extracted = name
+            .strip_prefix(prefix)
+            .map(|x| x.len() == HASH_PREFIX_LEN && x.chars().all(|x| x.is_ascii_hexdigit()))
+            .unwrap_or(false)
+            && entry.file_type()?.is_dir();
+        if is_extracted {
+            fs::remove_dir_all(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,142 @@
+//! Built-in [`AssetIo`] implementations that can be used as the fallback of the
+//! [`AssetIoZip`][crate::AssetIoZip]
+
+use std::{
+    collections::{BTreeSet, HashMap},
+    iter::FromIterator,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    utils::BoxedFuture,
+};
+
+/// An [`AssetIo`] that doesn't contain any assets
+///
+/// This is used as the fallback for bundle-only games that set
+/// [`AssetIoZipConfig::fallback`][crate::AssetIoZipConfig::fallback] to
+/// [`FallbackAssetIo::Noop`][crate::FallbackAssetIo::Noop], so that the platform default asset IO
+/// is never created.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopAssetIo;
+
+impl AssetIo for NoopAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move { Err(AssetIoError::NotFound(path.to_owned())) })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        Err(AssetIoError::NotFound(path.to_owned()))
+    }
+
+    fn is_directory(&self, _path: &Path) -> bool {
+        false
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
+
+/// An [`AssetIo`] that loads assets from an in-memory map of asset paths to their data
+///
+/// This can be used as the fallback asset IO by setting
+/// [`AssetIoZipConfig::fallback`][crate::AssetIoZipConfig::fallback] to
+/// [`FallbackAssetIo::Memory`][crate::FallbackAssetIo::Memory], which is useful for testing
+/// which assets are loaded from bundles without creating any files.
+///
+/// ```
+/// # use bevy_assetio_zip::MemoryAssetIo;
+/// let mut fallback = MemoryAssetIo::default();
+/// fallback
+///     .insert("config/settings.ron", b"()".to_vec())
+///     .insert("textures/grass.png", Vec::new());
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct MemoryAssetIo {
+    assets: HashMap<PathBuf, Vec<u8>>,
+}
+
+impl MemoryAssetIo {
+    /// Create an empty asset IO
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add an asset at the given path, replacing any existing asset at that path
+    pub fn insert<P: Into<PathBuf>, B: Into<Vec<u8>>>(&mut self, path: P, bytes: B) -> &mut Self {
+        self.assets.insert(path.into(), bytes.into());
+        self
+    }
+
+    /// Remove the asset at the given path, returning its data if it existed
+    pub fn remove<P: AsRef<Path>>(&mut self, path: P) -> Option<Vec<u8>> {
+        self.assets.remove(path.as_ref())
+    }
+
+    /// Get the paths of the files and directories directly inside of the given directory
+    fn dir_children(&self, dir: &Path) -> BTreeSet<PathBuf> {
+        self.assets
+            .keys()
+            .filter_map(|path| {
+                let rest = path.strip_prefix(dir).ok()?;
+                let child = rest.components().next()?;
+                Some(dir.join(child))
+            })
+            .collect()
+    }
+}
+
+impl<P: Into<PathBuf>, B: Into<Vec<u8>>> FromIterator<(P, B)> for MemoryAssetIo {
+    fn from_iter<I: IntoIterator<Item = (P, B)>>(iter: I) -> Self {
+        Self {
+            assets: iter
+                .into_iter()
+                .map(|(path, bytes)| (path.into(), bytes.into()))
+                .collect(),
+        }
+    }
+}
+
+impl AssetIo for MemoryAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            self.assets
+                .get(path)
+                .cloned()
+                .ok_or_else(|| AssetIoError::NotFound(path.to_owned()))
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let children = self.dir_children(path);
+        if children.is_empty() {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+
+        Ok(Box::new(children.into_iter()))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        !self.dir_children(path).is_empty()
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
@@ -0,0 +1,188 @@
+//! Support for browsing the mounted bundles with the [`vfs`] crate
+
+use std::{
+    fmt,
+    io::{self, Cursor, Write},
+    path::Path,
+};
+
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+use bevy::asset::{AssetServerSettings, FileAssetIo};
+use vfs::{FileSystem, SeekAndRead, VfsError, VfsFileType, VfsMetadata, VfsResult};
+
+use crate::ZipAssetIo;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{AssetIoZipConfig, BundleSources};
+
+/// A read-only [`vfs::FileSystem`] over the assets in the bundles mounted by a [`ZipAssetIo`]
+///
+/// Assets are resolved exactly like the game resolves them, so the assets in higher priority
+/// bundles such as mods hide the assets that they override. Assets that are only available from
+/// the fallback asset IO are not included. This lets tools such as editors and validators browse
+/// the assets that the game will see.
+///
+/// ```no_run
+/// # use bevy_assetio_zip::{AssetIoZipConfig, BundleFileSystem, BundleSources};
+/// use vfs::VfsPath;
+///
+/// let root = VfsPath::new(BundleFileSystem::from_config(
+///     AssetIoZipConfig {
+///         bundle_path: Some("target/assets.zip".into()),
+///         ..Default::default()
+///     },
+///     BundleSources::default(),
+/// ));
+/// for path in root.join("textures").unwrap().read_dir().unwrap() {
+///     println!("{}", path.as_str());
+/// }
+/// ```
+#[derive(Clone)]
+pub struct BundleFileSystem(ZipAssetIo);
+
+impl BundleFileSystem {
+    /// Create a file system over the bundles mounted by the given asset IO
+    ///
+    /// The [`AssetIoZip`][crate::AssetIoZip] resource inserted by the
+    /// [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] dereferences to its [`ZipAssetIo`], so a file
+    /// system over it can be created with `BundleFileSystem::new((*asset_io).clone())`.
+    pub fn new(zip_io: ZipAssetIo) -> Self {
+        Self(zip_io)
+    }
+
+    /// Mount the bundles that the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] would mount with
+    /// the given config and custom bundle sources, without creating a Bevy app
+    ///
+    /// Bundles are searched for in the default Bevy asset folder.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn from_config(config: AssetIoZipConfig, sources: BundleSources) -> Self {
+        #[cfg(not(target_os = "android"))]
+        let asset_dir =
+            Some(FileAssetIo::get_root_path().join(AssetServerSettings::default().asset_folder));
+        #[cfg(target_os = "android")]
+        let asset_dir = None;
+
+        Self(ZipAssetIo::new(
+            config,
+            sources,
+            asset_dir,
+            Default::default(),
+        ))
+    }
+
+    /// Get the size of the asset at the given path, if it is in one of the bundles
+    fn asset_size(&self, path: &Path) -> Option<u64> {
+        self.0.bundles().iter().find_map(|bundle| {
+            let mut archive = bundle.open()?;
+            bundle.asset_size(&mut archive, path)
+        })
+    }
+
+    /// Whether or not the given path is a directory in one of the bundles
+    fn is_dir(&self, path: &str) -> bool {
+        path.is_empty() || !self.0.bundle_dir_children(Path::new(path)).is_empty()
+    }
+}
+
+impl fmt::Debug for BundleFileSystem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("BundleFileSystem")
+            .field(&self.0.mounted_bundles())
+            .finish()
+    }
+}
+
+impl FileSystem for BundleFileSystem {
+    fn read_dir(&self, path: &str) -> VfsResult<Box<dyn Iterator<Item = String>>> {
+        let path = normalize(path);
+        let children = self.0.bundle_dir_children(Path::new(path));
+        if children.is_empty() && !path.is_empty() {
+            return Err(not_found(path));
+        }
+
+        Ok(Box::new(children.into_iter().map(|child| {
+            child
+                .rsplit('/')
+                .next()
+                .map(ToOwned::to_owned)
+                .unwrap_or(child)
+        })))
+    }
+
+    fn create_dir(&self, _path: &str) -> VfsResult<()> {
+        Err(VfsError::NotSupported)
+    }
+
+    fn open_file(&self, path: &str) -> VfsResult<Box<dyn SeekAndRead>> {
+        let path = Path::new(normalize(path));
+
+        // Stream uncompressed assets and read compressed assets into memory
+        match self.0.open_entry(path) {
+            Ok(reader) => Ok(Box::new(reader)),
+            Err(e) if e.kind() == io::ErrorKind::InvalidInput => {
+                let zip_io = &self.0;
+                let result = zip_io
+                    .task_pool
+                    .scope(|scope| scope.spawn(zip_io.read_from_bundles(path)))
+                    .pop()
+                    .flatten();
+                match result {
+                    Some(Ok((bytes, _))) => Ok(Box::new(Cursor::new(bytes))),
+                    Some(Err(e)) => Err(e.to_string().into()),
+                    None => Err(not_found(&path.to_string_lossy())),
+                }
+            }
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                Err(not_found(&path.to_string_lossy()))
+            }
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    fn create_file(&self, _path: &str) -> VfsResult<Box<dyn Write>> {
+        Err(VfsError::NotSupported)
+    }
+
+    fn append_file(&self, _path: &str) -> VfsResult<Box<dyn Write>> {
+        Err(VfsError::NotSupported)
+    }
+
+    fn metadata(&self, path: &str) -> VfsResult<VfsMetadata> {
+        let path = normalize(path);
+        if let Some(len) = self.asset_size(Path::new(path)) {
+            Ok(VfsMetadata {
+                file_type: VfsFileType::File,
+                len,
+            })
+        } else if self.is_dir(path) {
+            Ok(VfsMetadata {
+                file_type: VfsFileType::Directory,
+                len: 0,
+            })
+        } else {
+            Err(not_found(path))
+        }
+    }
+
+    fn exists(&self, path: &str) -> VfsResult<bool> {
+        let path = normalize(path);
+        Ok(self.asset_size(Path::new(path)).is_some() || self.is_dir(path))
+    }
+
+    fn remove_file(&self, _path: &str) -> VfsResult<()> {
+        Err(VfsError::NotSupported)
+    }
+
+    fn remove_dir(&self, _path: &str) -> VfsResult<()> {
+        Err(VfsError::NotSupported)
+    }
+}
+
+/// Convert a [`vfs`] path, which is either empty for the root or starts with a `/`, into an asset
+/// path
+fn normalize(path: &str) -> &str {
+    path.trim_start_matches('/')
+}
+
+fn not_found(path: &str) -> VfsError {
+    VfsError::FileNotFound { path: path.into() }
+}
@@ -0,0 +1,55 @@
+//! Filtering of the asset paths that a bundle is allowed to provide
+
+use std::path::Path;
+
+use glob::{MatchOptions, Pattern};
+
+/// The options used to match path patterns, where `*` does not match across directories but `**`
+/// does
+pub(crate) const MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A filter restricting which asset paths a bundle may provide
+#[derive(Debug, Clone, Default)]
+pub(crate) struct PathFilter {
+    allow: Vec<Pattern>,
+    deny: Vec<Pattern>,
+}
+
+impl PathFilter {
+    /// Create a filter from lists of glob patterns
+    ///
+    /// Invalid patterns are skipped. They are reported by [`AssetIoZipConfig::validate`].
+    ///
+    /// [`AssetIoZipConfig::validate`]: crate::AssetIoZipConfig::validate
+    pub fn new(allow: &[String], deny: &[String]) -> Self {
+        Self {
+            allow: compile_patterns(allow),
+            deny: compile_patterns(deny),
+        }
+    }
+
+    /// Whether or not the filter allows the given asset path
+    ///
+    /// A path is allowed if it matches any of the allow patterns, or if there are no allow
+    /// patterns, and it does not match any of the deny patterns.
+    pub fn allows(&self, path: &Path) -> bool {
+        let path = match path.to_str() {
+            Some(path) => path.replace('\\', "/"),
+            None => return false,
+        };
+        let matches = |pattern: &Pattern| pattern.matches_with(&path, MATCH_OPTIONS);
+
+        (self.allow.is_empty() || self.allow.iter().any(matches)) && !self.deny.iter().any(matches)
+    }
+}
+
+fn compile_patterns(patterns: &[String]) -> Vec<Pattern> {
+    patterns
+        .iter()
+        .filter_map(|pattern| Pattern::new(pattern).ok())
+        .collect()
+}
@@ -0,0 +1,241 @@
+//! Detection of the layout of bundle files
+//!
+//! Bundles are either legacy plain or obfuscated zip files, or v2 bundles which start with a small
+//! header describing the zip data that follows it. All values in the header are little-endian:
+//!
+//! | Offset | Size | Field                                          |
+//! | ------ | ---- | ---------------------------------------------- |
+//! | 0      | 4    | Magic bytes, `BAZB`                            |
+//! | 4      | 2    | Format version, currently `2`                  |
+//! | 6      | 2    | Flags: `1` obfuscated, `2` encrypted, `4` compressed, `8` solid |
+//! | 8      | 4    | Header length, the offset of the zip data      |
+//! | 12     | 8    | Offset of the zip central directory in the zip data |
+//!
+//! When the `16` key slot flag is set, the header is followed by a key slot describing how the data
+//! of the entries is obfuscated:
+//!
+//! | Offset | Size | Field                                          |
+//! | ------ | ---- | ---------------------------------------------- |
+//! | 20     | 1    | Algorithm id                                   |
+//! | 21     | 1    | Key derivation id                              |
+//! | 22     | 2    | Salt length                                    |
+//! | 24     | ...  | Salt, which is empty when the game supplies it |
+
+use std::{
+    convert::TryInto,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+/// The byte that obfuscated bundles have been XOR-ed with
+pub(crate) const OBFUSCATION_KEY: u8 = 0b01010101;
+
+/// The magic bytes at the start of v2 bundles
+pub(crate) const HEADER_MAGIC: &[u8; 4] = b"BAZB";
+/// The newest bundle format version that can be read
+pub(crate) const FORMAT_VERSION: u16 = 2;
+/// The length of the v2 header fields that we know how to parse
+pub(crate) const HEADER_LEN: usize = 20;
+
+/// Flag set when the zip data is obfuscated
+pub(crate) const FLAG_OBFUSCATED: u16 = 1;
+/// Flag set when the zip data is encrypted
+pub(crate) const FLAG_ENCRYPTED: u16 = 1 << 1;
+/// Flag set when the zip data is wrapped in a seekable zstd stream
+pub(crate) const FLAG_SOLID: u16 = 1 << 3;
+/// Flag set when the header is followed by a key slot
+pub(crate) const FLAG_KEY_SLOT: u16 = 1 << 4;
+
+/// The header of a v2 bundle
+///
+/// The compression flag and the index offset are informational and are not needed to read the
+/// bundle, because the zip data describes them itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct BundleHeader {
+    pub version: u16,
+    pub flags: u16,
+    /// The length of the header, which may be longer than [`HEADER_LEN`] in future versions
+    pub header_len: u32,
+}
+
+impl BundleHeader {
+    /// Parse the header from the first bytes of a bundle, returning `None` if the bundle doesn't
+    /// start with a v2 header
+    pub fn parse(bytes: &[u8]) -> Option<Self> {
+        let bytes = bytes.get(..HEADER_LEN)?;
+        if &bytes[..4] != HEADER_MAGIC {
+            return None;
+        }
+
+        Some(Self {
+            version: u16::from_le_bytes(bytes[4..6].try_into().ok()?),
+            flags: u16::from_le_bytes(bytes[6..8].try_into().ok()?),
+            header_len: u32::from_le_bytes(bytes[8..12].try_into().ok()?),
+        })
+    }
+}
+
+/// The parameters of the obfuscation of the entries in a bundle, stored in the key slot of its v2
+/// header
+///
+/// The runtime uses these instead of the obfuscated entries marker, so that bundles describe how to
+/// read them themselves.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeySlot {
+    /// The id of the algorithm that the data of the entries is obfuscated with
+    pub algorithm: u8,
+    /// The id of the key derivation that the entry keys are derived from the salt with
+    pub kdf: u8,
+    /// The salt that the entry keys are derived from, which is empty when the game supplies it
+    /// with a [`KeyProvider`][crate::KeyProvider]
+    pub salt: Vec<u8>,
+}
+
+impl KeySlot {
+    /// Parse the key slot from the whole header at the start of a bundle, returning `None` if the
+    /// bundle doesn't have one
+    pub(crate) fn parse(header: &[u8]) -> Option<Self> {
+        let parsed = BundleHeader::parse(header)?;
+        if parsed.flags & FLAG_KEY_SLOT == 0 {
+            return None;
+        }
+
+        let slot = header.get(HEADER_LEN..parsed.header_len as usize)?;
+        let salt_len = u16::from_le_bytes(slot.get(2..4)?.try_into().ok()?) as usize;
+        Some(Self {
+            algorithm: slot[0],
+            kdf: slot[1],
+            salt: slot.get(4..4 + salt_len)?.to_vec(),
+        })
+    }
+}
+
+/// Where the zip data is in a bundle and how it is encoded
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub(crate) struct BundleLayout {
+    /// The format version of the bundle, which is `0` for legacy zip bundles
+    pub version: u16,
+    /// The offset of the zip data in the bundle
+    pub offset: u64,
+    pub obfuscated: bool,
+    pub encrypted: bool,
+    /// Whether or not the zip data is compressed as a whole with solid zstd compression
+    pub solid: bool,
+}
+
+impl BundleLayout {
+    /// Detect the layout of a bundle from its first [`HEADER_LEN`] bytes, returning `None` if it
+    /// doesn't look like either a v2 bundle or a plain or obfuscated zip archive
+    ///
+    /// Zip archives start with `PK`, so if XOR-ing the first bytes by the obfuscation key yields
+    /// `PK` the bundle is obfuscated.
+    pub fn detect(magic: &[u8]) -> Option<Self> {
+        if let Some(header) = BundleHeader::parse(magic) {
+            return Some(Self {
+                version: header.version,
+                offset: header.header_len as u64,
+                obfuscated: header.flags & FLAG_OBFUSCATED != 0,
+                encrypted: header.flags & FLAG_ENCRYPTED != 0,
+                solid: header.flags & FLAG_SOLID != 0,
+            });
+        }
+
+        let magic = magic.get(..2)?;
+        let obfuscated = if magic == b"PK" {
+            false
+        } else if magic
+            .iter()
+            .map(|x| x ^ OBFUSCATION_KEY)
+            .eq(b"PK".iter().copied())
+        {
+            true
+        } else {
+            return None;
+        };
+
+        Some(Self {
+            obfuscated,
+            ..Default::default()
+        })
+    }
+
+    /// Wrap a reader over the whole bundle in a reader over its decoded zip data
+    pub fn open<R: Read + Seek + Send + 'static>(
+        &self,
+        reader: R,
+    ) -> io::Result<Box<dyn crate::ReadSeekSend>> {
+        if self.version > FORMAT_VERSION {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!(
+                    "Asset bundle format version {} is newer than the supported version {}",
+                    self.version, FORMAT_VERSION
+                ),
+            ));
+        }
+        if self.encrypted {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Encrypted asset bundles are not supported",
+            ));
+        }
+
+        if self.solid && cfg!(not(feature = "solid")) {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "Asset bundles with solid compression require the `solid` feature",
+            ));
+        }
+
+        let reader = OffsetReader::new(reader, self.offset)?;
+
+        #[cfg(feature = "obfuscation")]
+        let reader: Box<dyn crate::ReadSeekSend> = if self.obfuscated {
+            Box::new(xorio::Xor::new(reader))
+        } else {
+            Box::new(reader)
+        };
+
+        #[cfg(feature = "solid")]
+        if self.solid {
+            return Ok(Box::new(crate::solid::SolidReader::new(reader)?));
+        }
+
+        Ok(Box::new(reader))
+    }
+}
+
+/// A reader over the data in the inner reader after the given offset
+pub(crate) struct OffsetReader<R> {
+    inner: R,
+    offset: u64,
+}
+
+impl<R: Seek> OffsetReader<R> {
+    pub fn new(mut inner: R, offset: u64) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(offset))?;
+        Ok(Self { inner, offset })
+    }
+}
+
+impl<R: Read> Read for OffsetReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<R: Seek> Seek for OffsetReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => SeekFrom::Start(pos + self.offset),
+            pos => pos,
+        };
+        let new_pos = self.inner.seek(pos)?;
+
+        new_pos.checked_sub(self.offset).ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek to before the start of the bundle data",
+            )
+        })
+    }
+}
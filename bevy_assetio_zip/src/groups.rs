@@ -0,0 +1,111 @@
+//! Named groups of assets recorded by the bundler, which can be preloaded together
+
+use std::{
+    collections::{BTreeMap, BTreeSet},
+    path::{Path, PathBuf},
+};
+
+use glob::Pattern;
+
+use crate::{filter::MATCH_OPTIONS, CancellationToken, LoadPriority, ZipAssetIo};
+
+/// The zip entry that contains the asset groups of a bundle
+///
+/// Each line of the entry has the form `<group>\t<pattern>`, with one line for every glob pattern
+/// of the group.
+pub(crate) const GROUPS_ENTRY: &str = ".groups";
+
+/// Resource for preloading named groups of assets, such as all of the assets needed by a level
+///
+/// Groups are defined in a `groups.toml` file next to the `asset_config.toml`, which maps each
+/// group name to glob patterns of the asset paths in the group, and are recorded in the bundle by
+/// the bundler. The groups of all of the mounted bundles are merged, and the patterns are resolved
+/// against the assets in all of the mounted bundles, so mods can add assets to a group.
+#[derive(Clone)]
+pub struct AssetGroups {
+    zip_io: ZipAssetIo,
+}
+
+impl AssetGroups {
+    /// Create a handle to the groups of the bundles mounted by the given asset IO
+    pub fn new(zip_io: ZipAssetIo) -> Self {
+        Self { zip_io }
+    }
+
+    /// Get the names of the groups recorded in the mounted bundles
+    ///
+    /// In browser builds this is empty until the bundle has been downloaded.
+    pub fn names(&self) -> BTreeSet<String> {
+        self.groups().into_iter().map(|(name, _)| name).collect()
+    }
+
+    /// Get the paths of the assets in the given group, which is empty if there is no such group
+    pub fn paths(&self, group: &str) -> Vec<PathBuf> {
+        let patterns = match self.groups().remove(group) {
+            Some(patterns) => patterns,
+            None => return Vec::new(),
+        };
+
+        self.zip_io
+            .bundle_asset_paths()
+            .into_iter()
+            .filter(|path| patterns.iter().any(|x| x.matches_with(path, MATCH_OPTIONS)))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// Decompress every asset in the given group in the background with
+    /// [`prefetch`][ZipAssetIo::prefetch], returning the paths of the assets
+    ///
+    /// Load the returned paths with the `AssetServer` while showing a loading screen to get the
+    /// handles of the assets once they are ready.
+    pub fn load(&self, group: &str) -> Vec<PathBuf> {
+        let paths = self.paths(group);
+        self.zip_io.prefetch(paths.clone());
+        paths
+    }
+
+    /// [`Load`][Self::load] every asset in the given group until the given token is cancelled, with
+    /// [`prefetch_cancellable`][ZipAssetIo::prefetch_cancellable], returning the paths of the
+    /// assets
+    pub fn load_cancellable(&self, group: &str, token: &CancellationToken) -> Vec<PathBuf> {
+        let paths = self.paths(group);
+        self.zip_io.prefetch_cancellable(paths.clone(), token);
+        paths
+    }
+
+    /// [`Load`][Self::load] every asset in the given group with the given priority, with
+    /// [`prefetch_with_priority`][ZipAssetIo::prefetch_with_priority], returning the paths of the
+    /// assets
+    pub fn load_with_priority(&self, group: &str, priority: LoadPriority) -> Vec<PathBuf> {
+        let paths = self.paths(group);
+        self.zip_io.prefetch_with_priority(paths.clone(), priority);
+        paths
+    }
+
+    /// Read and merge the groups of all of the mounted bundles
+    fn groups(&self) -> BTreeMap<String, Vec<Pattern>> {
+        let mut groups = BTreeMap::<_, Vec<_>>::new();
+        for bundle in self.zip_io.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => continue,
+            };
+            let entry = match bundle.read_entry(&mut archive, Path::new(GROUPS_ENTRY)) {
+                Some(Ok(entry)) => entry,
+                _ => continue,
+            };
+
+            for line in String::from_utf8_lossy(&entry).lines() {
+                let mut fields = line.splitn(2, '\t');
+                if let (Some(group), Some(Ok(pattern))) =
+                    (fields.next(), fields.next().map(Pattern::new))
+                {
+                    groups.entry(group.to_owned()).or_default().push(pattern);
+                }
+            }
+        }
+
+        groups
+    }
+}
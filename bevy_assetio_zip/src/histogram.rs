@@ -0,0 +1,106 @@
+//! Histograms of asset load times by asset type
+
+use std::{collections::BTreeMap, fmt};
+
+use crate::AssetLoadEvent;
+
+/// The upper bounds in microseconds of the buckets of the [`LoadTimeHistogram`]
+///
+/// Load times above the last bound are counted in a final overflow bucket.
+pub const LOAD_TIME_BUCKETS: [u128; 8] = [100, 500, 1_000, 5_000, 10_000, 50_000, 100_000, 500_000];
+
+/// The load times of the assets with one file extension
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtensionLoadTimes {
+    /// The number of loads in each of the [`LOAD_TIME_BUCKETS`], followed by the number of loads
+    /// that took longer than the last bucket
+    pub buckets: [u64; LOAD_TIME_BUCKETS.len() + 1],
+    /// The number of assets loaded
+    pub count: u64,
+    /// The total number of bytes loaded
+    pub bytes: u64,
+    /// The total time spent loading in microseconds
+    pub total_micros: u128,
+    /// The longest time it took to load a single asset in microseconds
+    pub max_micros: u128,
+}
+
+impl ExtensionLoadTimes {
+    /// The average time it took to load an asset in microseconds
+    pub fn mean_micros(&self) -> u128 {
+        self.total_micros / self.count.max(1) as u128
+    }
+}
+
+/// Resource with a histogram of how long it took to read and decompress assets, grouped by file
+/// extension
+///
+/// This is inserted by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] and updated from the
+/// [`AssetLoadEvent`]s, so it can be used to find out which asset formats are slowing down level
+/// loads. The [`Display`][fmt::Display] implementation renders a summary with one line per
+/// extension.
+#[derive(Debug, Clone, Default)]
+pub struct LoadTimeHistogram {
+    /// The load times for each lowercase file extension, where assets without an extension are
+    /// listed under an empty extension
+    pub extensions: BTreeMap<String, ExtensionLoadTimes>,
+}
+
+impl LoadTimeHistogram {
+    /// Add the load time of an asset to the histogram
+    pub fn record(&mut self, event: &AssetLoadEvent) {
+        let extension = event
+            .path
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let times = self.extensions.entry(extension).or_default();
+
+        let bucket = LOAD_TIME_BUCKETS
+            .iter()
+            .position(|bound| event.micros <= *bound)
+            .unwrap_or(LOAD_TIME_BUCKETS.len());
+        times.buckets[bucket] += 1;
+        times.count += 1;
+        times.bytes += event.bytes as u64;
+        times.total_micros += event.micros;
+        times.max_micros = times.max_micros.max(event.micros);
+    }
+
+    /// Forget all of the recorded load times, such as at the start of a level
+    pub fn clear(&mut self) {
+        self.extensions.clear();
+    }
+}
+
+impl fmt::Display for LoadTimeHistogram {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (extension, times) in &self.extensions {
+            let extension = if extension.is_empty() {
+                "(none)"
+            } else {
+                extension
+            };
+            write!(
+                f,
+                "{}: {} loads, {} bytes, mean {} us, max {} us, histogram",
+                extension,
+                times.count,
+                times.bytes,
+                times.mean_micros(),
+                times.max_micros,
+            )?;
+            for (bound, count) in LOAD_TIME_BUCKETS.iter().zip(&times.buckets) {
+                write!(f, " <={}us:{}", bound, count)?;
+            }
+            writeln!(
+                f,
+                " >{}us:{}",
+                LOAD_TIME_BUCKETS[LOAD_TIME_BUCKETS.len() - 1],
+                times.buckets[LOAD_TIME_BUCKETS.len()]
+            )?;
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,143 @@
+//! Reloading the loose assets that shadow the bundles when they change
+
+use std::{
+    collections::HashSet,
+    path::{Path, PathBuf},
+    sync::Mutex,
+};
+
+use bevy::{
+    asset::{AssetIoError, AssetPath},
+    prelude::{AssetServer, Res},
+};
+use crossbeam_channel::Receiver;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+
+use crate::AssetIoZip;
+
+/// The label that changed assets are loaded with to make the asset server load them again
+///
+/// Bevy 0.4 has no public way to reload an asset. No asset is ever loaded with this label, so
+/// loading a path with it always reads the file again and replaces the assets loaded from it.
+const RELOAD_LABEL: &str = "bevy_assetio_zip-reload";
+
+/// Watches the loose files of the loaded assets when
+/// [`prefer_filesystem`][crate::AssetIoZipConfig::prefer_filesystem] is set
+///
+/// Bevy 0.4 only reloads changed files when its asset IO is a bare `FileAssetIo`, so the
+/// [`AssetIoZip`] watches the files itself and the [`reload_changed_loose_assets`] system reloads
+/// them.
+pub(crate) struct LooseAssetWatcher {
+    asset_dir: PathBuf,
+    /// The watcher, once watching has been started with [`start`][Self::start]
+    state: Mutex<Option<WatcherState>>,
+}
+
+struct WatcherState {
+    watcher: RecommendedWatcher,
+    events: Receiver<notify::Result<Event>>,
+    /// The loose files of the loaded assets
+    files: HashSet<PathBuf>,
+    /// The dirs of the files, which are watched instead of the files because editors often
+    /// replace a file instead of writing to it
+    dirs: HashSet<PathBuf>,
+}
+
+impl LooseAssetWatcher {
+    /// Create a watcher for the loose assets in the given asset dir
+    pub fn new(asset_dir: PathBuf) -> Self {
+        Self {
+            asset_dir,
+            state: Mutex::new(None),
+        }
+    }
+
+    /// Start watching the loose files of the assets that are loaded from now on
+    pub fn start(&self) -> Result<(), AssetIoError> {
+        let mut state = self.state.lock().unwrap();
+        if state.is_some() {
+            return Ok(());
+        }
+
+        let (sender, events) = crossbeam_channel::unbounded();
+        let watcher = RecommendedWatcher::new_immediate(move |event| {
+            // The receiver is owned by the watcher state, which also owns the watcher
+            sender.send(event).ok();
+        })
+        .map_err(|_| AssetIoError::PathWatchError(self.asset_dir.clone()))?;
+        *state = Some(WatcherState {
+            watcher,
+            events,
+            files: HashSet::new(),
+            dirs: HashSet::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Watch the loose file of the asset at the given path, if watching has been started and the
+    /// file exists
+    pub fn watch(&self, path: &Path) -> Result<(), AssetIoError> {
+        let mut state = self.state.lock().unwrap();
+        let state = match &mut *state {
+            Some(state) => state,
+            None => return Ok(()),
+        };
+
+        let file = self.asset_dir.join(path);
+        let dir = match file.parent() {
+            Some(dir) if file.is_file() => dir.to_owned(),
+            _ => return Ok(()),
+        };
+        if !state.dirs.contains(&dir) {
+            state
+                .watcher
+                .watch(&dir, RecursiveMode::NonRecursive)
+                .map_err(|_| AssetIoError::PathWatchError(dir.clone()))?;
+            state.dirs.insert(dir);
+        }
+        state.files.insert(file);
+
+        Ok(())
+    }
+
+    /// Get the paths of the assets whose loose files were written or replaced since the last call
+    pub fn changed(&self) -> Vec<PathBuf> {
+        let state = self.state.lock().unwrap();
+        let state = match &*state {
+            Some(state) => state,
+            None => return Vec::new(),
+        };
+
+        let mut changed = Vec::new();
+        for event in state.events.try_iter().filter_map(Result::ok) {
+            if !matches!(event.kind, EventKind::Create(_) | EventKind::Modify(_)) {
+                continue;
+            }
+
+            for file in event.paths {
+                if !state.files.contains(&file) {
+                    continue;
+                }
+                let path = file.strip_prefix(&self.asset_dir).unwrap().to_owned();
+                if !changed.contains(&path) {
+                    changed.push(path);
+                }
+            }
+        }
+
+        changed
+    }
+}
+
+/// System that reloads the assets whose loose files changed
+pub(crate) fn reload_changed_loose_assets(
+    asset_server: Res<AssetServer>,
+    asset_io: Res<AssetIoZip>,
+) {
+    for path in asset_io.changed_loose_assets() {
+        // The handle of the label is dropped right away, which never frees anything because no
+        // asset has the label
+        asset_server.load_untyped(AssetPath::new(path, Some(RELOAD_LABEL.into())));
+    }
+}
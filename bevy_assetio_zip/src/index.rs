@@ -0,0 +1,210 @@
+//! Support for the sidecar index files written next to bundles by the bundler
+//!
+//! The index lets us find the entries of huge bundles without parsing the zip central directory.
+//! The first line of the index has the form `bevy_assetio_zip-index\t<version>\t<size>\t<crc32>`,
+//! where `size` is the size of the zip data and `crc32` is the checksum of its last 22 bytes, which
+//! hold its end of central directory record and change whenever the archive is rebuilt. Each
+//! following line has the form
+//! `<data start>\t<compressed size>\t<size>\t<crc32>\t<compression>\t<name>`.
+
+use std::{
+    collections::HashMap,
+    io::{self, Read, SeekFrom},
+    sync::Arc,
+};
+
+use bevy::log::warn;
+
+use crate::{
+    bundle::ArchiveBackend, progress::ProgressReader, raw::decompress, BundleSource, ReadSeekSend,
+};
+
+/// The extension of the index file written next to a bundle
+pub(crate) const INDEX_EXTENSION: &str = "idx";
+
+/// The identifier at the start of the first line of the index
+const INDEX_MAGIC: &str = "bevy_assetio_zip-index";
+
+/// The version of the index format that we can read
+const INDEX_VERSION: u32 = 1;
+
+/// The length of the zip end of central directory record, which is the length of the tail of the
+/// zip data that the index checksums, even if the bundle has a comment after the record
+const EOCD_LEN: u64 = 22;
+
+/// The location and encoding of an entry in the zip data of a bundle
+#[derive(Debug, Clone)]
+pub(crate) struct IndexEntry {
+    pub data_start: u64,
+    pub compressed_size: u64,
+    pub size: u64,
+    pub crc32: u32,
+    pub compression: String,
+}
+
+impl IndexEntry {
+    /// Read and decompress the entry from a reader over the zip data of the bundle
+    ///
+    /// `progress` is called with the estimated number of bytes decompressed so far and the size of
+    /// the entry, which is estimated from the amount of compressed data that has been read.
+    pub fn read(
+        &self,
+        reader: &mut dyn ReadSeekSend,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> io::Result<Vec<u8>> {
+        reader.seek(SeekFrom::Start(self.data_start))?;
+        let (size, compressed_size) = (self.size, self.compressed_size.max(1));
+        let mut report = |read: u64| progress(read * size / compressed_size, size);
+        let data = ProgressReader::new(reader.take(self.compressed_size), &mut report);
+        let data = decompress(&self.compression, data, self.size, self.crc32)?;
+        progress(size, size);
+
+        Ok(data)
+    }
+}
+
+/// The entry table of a bundle loaded from its sidecar index file
+#[derive(Debug, Default)]
+pub(crate) struct SidecarIndex {
+    entries: HashMap<String, IndexEntry>,
+}
+
+impl SidecarIndex {
+    /// Load the index file next to the given bundle source, returning `None` if there is no index
+    /// or if it doesn't match the bundle
+    pub fn load(source: &dyn BundleSource) -> Option<Self> {
+        let index_path = source.path()?.with_extension(INDEX_EXTENSION);
+        let text = std::fs::read_to_string(&index_path).ok()?;
+        let mut lines = text.lines();
+
+        let (size, crc32) = match parse_header(lines.next()?) {
+            Some(header) => header,
+            None => {
+                warn!("Ignoring unsupported asset index {}", index_path.display());
+                return None;
+            }
+        };
+        if !matches_bundle(source, size, crc32).unwrap_or(false) {
+            warn!(
+                "Ignoring asset index {} because it doesn't match its bundle",
+                index_path.display()
+            );
+            return None;
+        }
+
+        let entries = lines
+            .filter_map(|line| {
+                let mut fields = line.splitn(6, '\t');
+                let entry = IndexEntry {
+                    data_start: fields.next()?.parse().ok()?,
+                    compressed_size: fields.next()?.parse().ok()?,
+                    size: fields.next()?.parse().ok()?,
+                    crc32: fields.next()?.parse().ok()?,
+                    compression: fields.next()?.to_owned(),
+                };
+                Some((fields.next()?.to_owned(), entry))
+            })
+            .collect();
+
+        Some(Self { entries })
+    }
+
+    /// The number of entries in the bundle
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    /// The names of the entries in the bundle
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Get the entry with the given name
+    pub fn get(&self, name: &str) -> Option<&IndexEntry> {
+        self.entries.get(name)
+    }
+}
+
+/// An archive whose entries are found using its sidecar index
+pub(crate) struct IndexedArchive {
+    reader: Box<dyn ReadSeekSend>,
+    index: Arc<SidecarIndex>,
+}
+
+impl IndexedArchive {
+    /// Create an archive that reads the entries listed in the index from the given reader over the
+    /// zip data of the bundle
+    pub fn new(reader: Box<dyn ReadSeekSend>, index: Arc<SidecarIndex>) -> Self {
+        Self { reader, index }
+    }
+}
+
+impl ArchiveBackend for IndexedArchive {
+    fn len(&self) -> usize {
+        self.index.len()
+    }
+
+    fn total_size(&mut self) -> u64 {
+        self.index
+            .names()
+            .filter_map(|name| self.index.get(name).map(|entry| entry.size))
+            .sum()
+    }
+
+    fn entry_names(&self) -> Vec<&str> {
+        self.index.names().collect()
+    }
+
+    fn has_entry(&mut self, name: &str) -> bool {
+        self.index.get(name).is_some()
+    }
+
+    fn entry_size(&mut self, name: &str) -> Option<u64> {
+        self.index.get(name).map(|entry| entry.size)
+    }
+
+    fn stored_entry(&mut self, name: &str) -> Option<(u64, u64)> {
+        let entry = self.index.get(name)?;
+        if entry.compression != "stored" {
+            return None;
+        }
+        Some((entry.data_start, entry.size))
+    }
+
+    fn entry_location(&mut self, name: &str) -> Option<IndexEntry> {
+        self.index.get(name).cloned()
+    }
+
+    fn read_entry_data(
+        &mut self,
+        name: &str,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<io::Result<Vec<u8>>> {
+        Some(self.index.get(name)?.read(&mut *self.reader, progress))
+    }
+}
+
+/// Parse the first line of the index, returning the size and checksum of the bundle it was
+/// written for
+fn parse_header(line: &str) -> Option<(u64, u32)> {
+    let mut fields = line.split('\t');
+    if fields.next()? != INDEX_MAGIC || fields.next()?.parse::<u32>().ok()? != INDEX_VERSION {
+        return None;
+    }
+
+    Some((fields.next()?.parse().ok()?, fields.next()?.parse().ok()?))
+}
+
+/// Check that the zip data of the bundle has the given size and checksum of its last bytes
+fn matches_bundle(source: &dyn BundleSource, size: u64, crc32: u32) -> io::Result<bool> {
+    let mut reader = source.open()?;
+    if reader.seek(SeekFrom::End(0))? != size || size < EOCD_LEN {
+        return Ok(false);
+    }
+
+    let mut eocd = [0; EOCD_LEN as usize];
+    reader.seek(SeekFrom::Start(size - EOCD_LEN))?;
+    reader.read_exact(&mut eocd)?;
+
+    Ok(crc32fast::hash(&eocd) == crc32)
+}
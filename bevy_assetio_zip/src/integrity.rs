@@ -0,0 +1,102 @@
+//! Checking that the asset bundle hasn't been tampered with
+//!
+//! Bundles are hashed with BLAKE3, the same as the bundler does when it embeds the hash in the
+//! game.
+
+use std::{
+    fs::{self, File},
+    io::{self, Read, Seek, SeekFrom},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use bevy::log::warn;
+
+use crate::BundleRegion;
+
+/// The file in the hash cache dir that the hashes of the bundles are cached in
+///
+/// Each line of the file has the form `<size>\t<modified>\t<hash>\t<path>`, where `modified` is the
+/// modification time of the bundle in nanoseconds since the Unix epoch.
+const HASH_CACHE_FILE: &str = "bundle-hashes";
+
+/// Get the hex BLAKE3 hash of the bundle in the given region of the file at the given path, or of
+/// the whole file if there is no region
+pub(crate) fn bundle_hash(path: &Path, region: Option<BundleRegion>) -> io::Result<String> {
+    let mut file = File::open(path)?;
+    let mut file: Box<dyn Read> = match region {
+        Some(region) => {
+            file.seek(SeekFrom::Start(region.offset))?;
+            Box::new(file.take(region.length))
+        }
+        None => Box::new(file),
+    };
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut file, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
+
+/// Get the hex BLAKE3 hash of the bundle in the given region of the file at the given path,
+/// reusing the hash cached in `cache_dir` if the size and modification time of the file haven't
+/// changed since it was hashed
+///
+/// The bundle is hashed and the cache is updated otherwise. Failing to update the cache is only
+/// logged, since the hash is still correct.
+pub(crate) fn cached_bundle_hash(
+    path: &Path,
+    region: Option<BundleRegion>,
+    cache_dir: Option<&Path>,
+) -> io::Result<String> {
+    let cache_dir = match cache_dir {
+        Some(cache_dir) => cache_dir,
+        None => return bundle_hash(path, region),
+    };
+
+    let metadata = fs::metadata(path)?;
+    let modified = metadata
+        .modified()?
+        .duration_since(UNIX_EPOCH)
+        .map(|x| x.as_nanos())
+        .unwrap_or_default();
+    let stamp = format!("{}\t{}", metadata.len(), modified);
+    let mut bundle_path = path
+        .canonicalize()
+        .unwrap_or_else(|_| path.to_owned())
+        .to_string_lossy()
+        .into_owned();
+    // Bundles in different regions of the same file are cached separately
+    if let Some(region) = region {
+        bundle_path += &format!("@{}+{}", region.offset, region.length);
+    }
+
+    let cache_path = cache_dir.join(HASH_CACHE_FILE);
+    let cache = fs::read_to_string(&cache_path).unwrap_or_default();
+    let mut lines = Vec::new();
+    for line in cache.lines() {
+        let fields = line.splitn(4, '\t').collect::<Vec<_>>();
+        if fields.len() != 4 {
+            continue;
+        }
+
+        if fields[3] != bundle_path {
+            lines.push(line.to_owned());
+        } else if format!("{}\t{}", fields[0], fields[1]) == stamp {
+            return Ok(fields[2].to_owned());
+        }
+    }
+
+    let hash = bundle_hash(path, region)?;
+    lines.push(format!("{}\t{}\t{}", stamp, hash, bundle_path));
+    let written =
+        fs::create_dir_all(cache_dir).and_then(|_| fs::write(&cache_path, lines.join("\n") + "\n"));
+    if let Err(e) = written {
+        warn!(
+            "Could not cache the hash of asset bundle {} in {}: {}",
+            path.display(),
+            cache_path.display(),
+            e
+        );
+    }
+
+    Ok(hash)
+}
@@ -0,0 +1,25 @@
+//! Support for loading asset bundles from the resources of an iOS application bundle
+
+use std::path::{Path, PathBuf};
+
+use crate::ZipAssetIo;
+
+/// Get the application bundle ( `.app` ) directory that contains the executable
+fn app_bundle_dir() -> Option<PathBuf> {
+    ZipAssetIo::exe_dir()
+        .ancestors()
+        .find(|dir| dir.extension().map(|x| x == "app").unwrap_or(false))
+        .map(Path::to_owned)
+}
+
+/// Get the directories of the application bundle that resources are copied to, in the order that
+/// they should be searched
+///
+/// Xcode copies loose resources to the root of the bundle, while `cargo-mobile` and Xcode folder
+/// references keep the `assets` folder.
+pub(crate) fn resource_dirs() -> Vec<PathBuf> {
+    match app_bundle_dir() {
+        Some(dir) => vec![dir.join("assets"), dir],
+        None => Vec::new(),
+    }
+}
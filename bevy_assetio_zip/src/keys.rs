@@ -0,0 +1,112 @@
+//! Keys that the data of the entries in obfuscated bundles is scrambled with
+//!
+//! Bundles store a random salt in their obfuscated entries marker. The data of each entry is XOR-ed
+//! with a keystream seeded from the salt and the name of the entry, so recovering the keystream of
+//! one file does not decode the other files in the bundle. Bundles with an empty marker were
+//! written before entries had their own keys and XOR every entry with the same byte. V2 bundles
+//! also describe their obfuscation in the [key slot][crate::KeySlot] of their header.
+
+use crate::KeySlot;
+
+/// The byte that entries of bundles without a salt are XOR-ed with, the default of `xorio`
+const LEGACY_XOR_BYTE: u8 = 0b0101_0101;
+/// The FNV-1a offset basis used to hash the salt and entry name into a seed
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a prime used to hash the salt and entry name into a seed
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+/// The increment of the SplitMix64 generator that produces the keystream
+const SPLITMIX_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Key slot algorithm id of entries that are XOR-ed with the same byte
+pub(crate) const KEY_ALGORITHM_XOR: u8 = 1;
+/// Key slot algorithm id of entries that are XOR-ed with a keystream derived from the salt and the
+/// entry name
+pub(crate) const KEY_ALGORITHM_KEYSTREAM: u8 = 2;
+/// Key slot id of the key derivation that hashes the salt and entry name with FNV-1a and expands
+/// the hash with SplitMix64
+pub(crate) const KDF_FNV1A_SPLITMIX: u8 = 1;
+
+/// How the data of the entries in a bundle is obfuscated
+#[derive(Debug, Clone)]
+pub(crate) enum EntryObfuscation {
+    /// Every entry is XOR-ed with the same byte
+    Xor,
+    /// Every entry is XOR-ed with a keystream derived from this bundle salt and the entry name
+    Keyed(Vec<u8>),
+}
+
+impl EntryObfuscation {
+    /// Get the obfuscation described by the data of the obfuscated entries marker
+    pub fn from_marker(data: Vec<u8>) -> Self {
+        if data.is_empty() {
+            EntryObfuscation::Xor
+        } else {
+            EntryObfuscation::Keyed(data)
+        }
+    }
+
+    /// Get the obfuscation described by the given key slot with the given salt, returning `None` if
+    /// the algorithm or key derivation isn't supported
+    pub fn from_key_slot(slot: &KeySlot, salt: Vec<u8>) -> Option<Self> {
+        match (slot.algorithm, slot.kdf) {
+            (KEY_ALGORITHM_XOR, _) => Some(EntryObfuscation::Xor),
+            (KEY_ALGORITHM_KEYSTREAM, KDF_FNV1A_SPLITMIX) => Some(EntryObfuscation::Keyed(salt)),
+            _ => None,
+        }
+    }
+
+    /// Get the keystream that the entry with the given name is obfuscated with
+    pub fn keystream(&self, name: &str) -> Keystream {
+        match self {
+            EntryObfuscation::Xor => Keystream::Xor,
+            EntryObfuscation::Keyed(salt) => {
+                let hash = salt
+                    .iter()
+                    .chain(&[0])
+                    .chain(name.as_bytes())
+                    .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                        (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+                    });
+                Keystream::Keyed(splitmix(hash))
+            }
+        }
+    }
+}
+
+/// The keystream that the data of a single entry is XOR-ed with
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Keystream {
+    Xor,
+    Keyed(u64),
+}
+
+impl Keystream {
+    /// Decode the given data, which starts at `offset` in the entry data
+    pub fn apply(&self, offset: u64, data: &mut [u8]) {
+        match *self {
+            Keystream::Xor => data.iter_mut().for_each(|byte| *byte ^= LEGACY_XOR_BYTE),
+            Keystream::Keyed(seed) => {
+                // Each word of the keystream is computed from its position, so we can start
+                // anywhere in the entry
+                let mut word = None;
+                for (pos, byte) in (offset..).zip(data.iter_mut()) {
+                    if word.is_none() || pos % 8 == 0 {
+                        let counter = pos / 8 + 1;
+                        word = Some(splitmix(
+                            seed.wrapping_add(counter.wrapping_mul(SPLITMIX_GAMMA)),
+                        ));
+                    }
+                    *byte ^= word.unwrap().to_le_bytes()[(pos % 8) as usize];
+                }
+            }
+        }
+    }
+}
+
+/// The SplitMix64 output function
+fn splitmix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
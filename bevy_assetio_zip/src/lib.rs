@@ -15,8 +15,9 @@
 //! App::build()
 //!     // Any config must be inserted before adding plugins. This is optional.
 //!     .add_resource(AssetIoZipConfig {
-//!         // The name of the asset bundle file, excluding the extension, to load
-//!         file_name: "assets".into(), // This is the default
+//!         // The names of the asset bundle files, excluding the extension, to try to load
+//!         file_names: vec!["assets".into()], // This is the default
+//!         ..Default::default()
 //!     })
 //!     // Add the default plugins
 //!     .add_plugins_with(DefaultPlugins, |group| {
@@ -27,9 +28,394 @@
 //! ```
 //!
 //! Once enabling the plugin, the game will now search for `assets.zip` and `assets.bin` files
-//! adjacent to the executable when attempting to load assets. If an asset is not found in the zip
-//! file, it will attempt to load the asset using the default Bevy asset loader for the target
-//! platform.
+//! adjacent to the executable, and then in the Bevy asset folder, when attempting to load assets.
+//! If an asset is not found in the zip file, it will attempt to load the asset using the default
+//! Bevy asset loader for the target platform.
+//!
+//! Directories in the bundle can be listed too, so [`AssetServer::load_folder`] will load the
+//! assets in a folder of the bundle even if there are no loose asset files. The listing of a folder
+//! combines the assets in it from every mounted bundle, including mods and DLC, and from the
+//! fallback asset IO, with each path listed once.
+//!
+//! To load everything from the fallback asset IO instead, such as to check whether a bug only
+//! happens with the bundle, run the game with the `ASSETIO_ZIP_DISABLE=1` environment variable or
+//! set [`AssetIoZipConfig::disable_bundles`].
+//!
+//! To edit assets while a bundle is mounted, set [`AssetIoZipConfig::prefer_filesystem`] so that
+//! loose asset files are loaded in place of the assets in the bundles. With the `hot-reload`
+//! feature the assets are reloaded when their loose files change, once
+//! [`AssetServer::watch_for_changes`] has been called.
+//!
+//! Games that are only ever distributed with a bundle can set [`AssetIoZipConfig::fallback`] to
+//! [`FallbackAssetIo::Noop`] so that the default asset loader is never created and assets are only
+//! loaded from bundles.
+//!
+//! # Prefetching Assets
+//!
+//! The plugin adds the [`AssetIoZip`] as a resource which can be used to decompress assets from
+//! the bundle in the background before they are loaded. This lets loading screens warm exactly the
+//! assets that the next level is going to need.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZip;
+//! fn prefetch_level_assets(asset_io: Res<AssetIoZip>) {
+//!     asset_io.prefetch(vec!["level_2/map.png", "level_2/music.ogg"]);
+//! }
+//! ```
+//!
+//! Prefetched assets are kept in memory until they are loaded. Assets that end up not being loaded
+//! can be dropped with [`clear_prefetched`][ZipAssetIo::clear_prefetched] or
+//! [`evict_prefetched`][ZipAssetIo::evict_prefetched], and
+//! [`AssetIoZipConfig::max_prefetched_bytes`] bounds the memory that they can take up.
+//!
+//! Prefetches can be cancelled with a [`CancellationToken`] when the player backs out of loading
+//! a level, which also makes the pending loads of the prefetched assets through the asset server
+//! fail instead of reading them:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{AssetIoZip, CancellationToken};
+//! fn prefetch_level_assets(asset_io: Res<AssetIoZip>) -> CancellationToken {
+//!     let token = CancellationToken::new();
+//!     asset_io.prefetch_cancellable(vec!["level_2/map.png", "level_2/music.ogg"], &token);
+//!     // Call `token.cancel()` if the level load is aborted
+//!     token
+//! }
+//! ```
+//!
+//! Assets can also be given a [`LoadPriority`], so that the assets needed first are decompressed
+//! before the rest when a level loads:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{AssetIoZip, LoadPriority};
+//! fn prefetch_level_assets(asset_io: Res<AssetIoZip>) {
+//!     asset_io.prefetch_with_priority(vec!["level_2/hero.png"], LoadPriority::Critical);
+//!     asset_io.prefetch_with_priority(vec!["level_2/far_lod.png"], LoadPriority::Background);
+//! }
+//! ```
+//!
+//! # Asset Groups
+//!
+//! The assets needed by each level can be listed in a `groups.toml` file next to the
+//! `asset_config.toml`, which maps group names to glob patterns of asset paths:
+//!
+//! ```toml
+//! level_1 = ["levels/1/**", "music/forest.ogg"]
+//! level_2 = ["levels/2/**", "music/boss.ogg"]
+//! ```
+//!
+//! The bundler records the groups in the bundle, and the [`AssetGroups`] resource prefetches every
+//! asset of a group at once:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetGroups;
+//! fn start_loading_level_2(asset_groups: Res<AssetGroups>) {
+//!     // Decompress the assets of the level while the loading screen is shown
+//!     asset_groups.load("level_2");
+//! }
+//! ```
+//!
+//! To load the next level during gameplay without hitches, queue its group in the
+//! [`LevelStreamer`] resource instead. A system added by the plugin starts the reads of a few
+//! of its assets at a time on the IO threads, within a per-frame budget of bytes, without ever
+//! waiting for them:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::LevelStreamer;
+//! fn approach_level_2(mut streamer: ResMut<LevelStreamer>) {
+//!     streamer.bytes_per_frame = Some(4 * 1024 * 1024);
+//!     streamer.stream_group("level_2");
+//! }
+//! ```
+//!
+//! # Streaming Assets
+//!
+//! Assets that are stored uncompressed in the bundle can be streamed directly out of it with
+//! [`AssetIoZip::open_entry`][ZipAssetIo::open_entry], which returns a reader that can be handed
+//! to streaming audio or video decoders instead of loading the whole file into memory.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZip;
+//! fn play_music(asset_io: Res<AssetIoZip>) {
+//!     let reader = asset_io.open_entry("music/theme.ogg").unwrap();
+//!     // Pass the reader to your streaming decoder
+//! }
+//! ```
+//!
+//! # Sending Assets Over the Network
+//!
+//! A game server can send assets to its clients without recompressing them by getting their
+//! compressed data out of the bundle with
+//! [`AssetIoZip::raw_entry`][ZipAssetIo::raw_entry]. The returned [`RawEntry`] carries the
+//! compression method, size, and checksum of the asset, so the client only needs to send those
+//! fields along with the data and call [`RawEntry::decompress`] on the other end. This is not
+//! available in browser builds.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{AssetIoZip, RawEntry};
+//! fn send_level(asset_io: Res<AssetIoZip>) {
+//!     let entry = asset_io.raw_entry("levels/1.level").unwrap();
+//!     // Send the entry to the client, which decompresses it when it arrives
+//!     let data = entry.decompress().unwrap();
+//! }
+//! ```
+//!
+//! # Browsing Bundles
+//!
+//! Systems can query the mounted bundles through the `AssetServer` with the `BundleAssetServerExt`
+//! trait, which adds `bundle_contains`, `bundle_metadata`, and `bundle_list` to it:
+//!
+//! ```no_run
+//! use bevy::prelude::*;
+//! use bevy_assetio_zip::BundleAssetServerExt;
+//!
+//! fn load_levels(asset_server: Res<AssetServer>) {
+//!     for path in asset_server.bundle_list("levels/") {
+//!         let _level: HandleUntyped = asset_server.load_untyped(path.as_path());
+//!     }
+//! }
+//! ```
+//!
+//! With the `vfs` feature enabled, `BundleFileSystem` implements the `FileSystem` trait of the
+//! [`vfs`](https://docs.rs/vfs) crate over the mounted bundles. Tools such as editors and asset
+//! validators can use it to browse the assets exactly as the game would see them, without creating
+//! a Bevy app.
+//!
+//! # Load Events
+//!
+//! An [`AssetLoadEvent`] is sent for every asset that is loaded, containing the asset path, whether
+//! it was loaded from the bundle or the fallback asset IO, its size, and how long it took to load.
+//! You can read these events to build your own loading profilers.
+//!
+//! The load times are also collected into the [`LoadTimeHistogram`] resource, grouped by file
+//! extension, which shows which asset formats are slowing down level loads. A summary of the
+//! histogram can be logged periodically by setting
+//! [`AssetIoZipConfig::load_time_summary_interval`].
+//!
+//! When [`AssetIoZipConfig::record_asset_sources`] is enabled, the source that each asset was last
+//! loaded from is also recorded in the [`AssetSourceLog`] resource. QA builds can dump it to find
+//! out which bundle, if any, an unexpected version of an asset came from.
+//!
+//! Compliance builds that must prove that no unapproved content is loaded can set
+//! [`AssetIoZipConfig::audit_log`] to the path of a file that every loaded asset is appended to,
+//! one line per load with the load time, the source, the CRC-32 of the data, and the path,
+//! separated by tabs. Problems writing the file are logged as warnings and don't stop assets from
+//! loading.
+//!
+//! # Mods
+//!
+//! Mod bundles can be loaded from any number of mod directories, such as a Steam Workshop content
+//! directory, by setting [`AssetIoZipConfig::mod_dirs`]. Every `.zip` or `.bin` file in a mod
+//! directory, or in one of its immediate sub-directories, is mounted as a mod, and assets in mods
+//! override the assets in the main bundle. The priority of the mods can be set with
+//! [`AssetIoZipConfig::mod_order`], and the assets that mods may replace can be restricted with
+//! glob patterns in [`AssetIoZipConfig::mod_allow`] and [`AssetIoZipConfig::mod_deny`].
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZipConfig;
+//! let config = AssetIoZipConfig {
+//!     mod_dirs: vec!["mods".into(), "../../workshop/content/123456".into()],
+//!     // Mods not listed here have a lower priority and are ordered by name
+//!     mod_order: vec!["hd_textures".into()],
+//!     // Only let mods replace textures and audio
+//!     mod_allow: vec!["textures/**".into(), "audio/**".into()],
+//!     ..Default::default()
+//! };
+//! ```
+//!
+//! You can find out which bundles provide an asset, highest priority first, with
+//! [`AssetIoZip::override_chain`][ZipAssetIo::override_chain]:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZip;
+//! fn log_overrides(asset_io: Res<AssetIoZip>) {
+//!     for bundle in asset_io.override_chain("textures/grass.png") {
+//!         info!("textures/grass.png is provided by {}", bundle.name);
+//!     }
+//! }
+//! ```
+//!
+//! When more than one mod provides the same asset, the conflict is listed in the
+//! [`ModConflictReport`] resource along with which mod won. How conflicts are resolved can be
+//! changed with [`AssetIoZipConfig::mod_conflict_policy`], which can also be set to
+//! [`ModConflictPolicy::Error`] to make conflicting assets fail to load instead.
+//!
+//! Mods downloaded from third parties can be limited with [`AssetIoZipConfig::mod_max_entries`]
+//! and [`AssetIoZipConfig::mod_max_uncompressed_size`], so that suspiciously huge mods, such as zip
+//! bombs, are refused instead of being mounted.
+//!
+//! # Runtime Config
+//!
+//! With the `runtime-config` feature, which is enabled by default, the plugin reads an optional
+//! `assetio.toml` file from the directory containing the executable. Its settings override the
+//! [`AssetIoZipConfig`], which lets players and server admins relocate asset packs without
+//! rebuilding the game. Relative paths are relative to the executable directory.
+//!
+//! ```toml
+//! # The names of the asset bundle files to search for, excluding the extension
+//! file-names = ["assets"]
+//! # The path to a specific asset bundle to load, which disables the search by name
+//! bundle-path = "../shared/assets.zip"
+//! # Whether or not to also search for the bundle in the Bevy asset folder
+//! search-asset-dir = true
+//! # The directories to load mod bundles from
+//! mod-dirs = ["mods"]
+//! # The number of reads from each bundle that can run at once, which can be tuned for the disk
+//! max-concurrent-reads = 2
+//!
+//! # Asset paths to substitute with variants on each platform, or on every platform with `all`
+//! [variants.macos]
+//! "shaders/foo.wgsl" = "shaders/foo.metal.wgsl"
+//! ```
+//!
+//! Every setting is optional. Problems reading the file are logged and the file is ignored.
+//!
+//! Launchers and development scripts can also redirect asset loading for a single run by building
+//! the config with [`AssetIoZipConfig::with_args`], which reads the `--asset-bundle <path>` and
+//! `--asset-dir <path>` command-line arguments. Since the runtime config file is read when the
+//! plugin is built, its settings take precedence over the arguments.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZipConfig;
+//! App::build().add_resource(AssetIoZipConfig::default().with_args());
+//! ```
+//!
+//! # Platform Variants
+//!
+//! One bundle can carry the variants of an asset for every platform. Set
+//! [`AssetIoZipConfig::asset_variants`] to an [`AssetVariants`] table that maps asset paths to the
+//! paths of their variants, and loading `shaders/foo.wgsl` loads `shaders/foo.metal.wgsl` instead
+//! on macOS. `AssetVariants::from_toml` reads the table for the current platform from TOML, and
+//! `ZipAssetIo::set_asset_variants` replaces the table at runtime.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{AssetIoZipConfig, AssetVariants};
+//! let mut variants = AssetVariants::new();
+//! if cfg!(target_os = "macos") {
+//!     variants.insert("shaders/foo.wgsl", "shaders/foo.metal.wgsl");
+//! }
+//! App::build().add_resource(AssetIoZipConfig {
+//!     asset_variants: variants,
+//!     ..Default::default()
+//! });
+//! ```
+//!
+//! # Custom Bundle Sources
+//!
+//! Bundles don't have to be files next to the executable. Anything that implements
+//! [`BundleSource`] can be mounted by adding it to the [`BundleSources`] resource before adding
+//! the plugin, which lets bundles come from memory, network streams, platform archives, or custom
+//! DRM wrappers.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{BundleSources, MemoryBundleSource};
+//! # let bundle_bytes: &'static [u8] = &[];
+//! // Such as `include_bytes!("../dlc.zip")`
+//! let mut sources = BundleSources::default();
+//! sources.add("dlc", MemoryBundleSource::new(bundle_bytes));
+//!
+//! App::build().add_resource(sources);
+//! ```
+//!
+//! Bundles can be unmounted by name with [`AssetIoZip::unmount`][ZipAssetIo::unmount], which
+//! releases the bundle file and the memory used by its decompressed data. This is needed on
+//! platforms where files must be closed before an updater can replace them.
+//!
+//! # Writable Overlay
+//!
+//! Assets downloaded at runtime or created by the player can be written into a writable overlay
+//! bundle, set with [`AssetIoZipConfig::overlay_bundle`], which is mounted above every other
+//! bundle. Assets written with [`AssetIoZip::write_overlay_asset`][ZipAssetIo::write_overlay_asset]
+//! override the shipped assets and are kept between runs.
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::AssetIoZip;
+//! fn save_skin(asset_io: Res<AssetIoZip>) {
+//!     # let png_bytes = Vec::new();
+//!     asset_io
+//!         .write_overlay_asset("textures/player.png", &png_bytes)
+//!         .expect("Could not save skin");
+//! }
+//! ```
+//!
+//! # User Overrides
+//!
+//! Players can override assets without touching the install directory by placing loose files in
+//! the [`AssetIoZipConfig::override_dir`], such as `%APPDATA%/MyGame/overrides/textures/grass.png`.
+//! The override directory is consulted before any of the bundles, and can be switched off for
+//! shipping builds with [`AssetIoZipConfig::use_override_dir`].
+//!
+//! # Extracting the Bundle
+//!
+//! On platforms where decompressing assets again on every run is slower than reading loose files
+//! through the OS file cache, [`AssetIoZipConfig::extract_bundle`] extracts the main asset bundle
+//! to the [`AssetIoZipConfig::extract_dir`] the first time that a version of it is mounted. The
+//! assets are decompressed in parallel and checked against the hashes recorded by the bundler with
+//! `asset-metadata`, then loaded from the extracted files from then on.
+//!
+//! # Single-File Distributions
+//!
+//! The bundler's `append` command attaches a bundle to a copy of the built executable, followed by
+//! a small footer that locates it, so that the game and its assets can be shipped as a single file:
+//!
+//! ```bash
+//! bevy_assetio_zip_bundler append target/release/my_game target/release/assets.bin my_game
+//! ```
+//!
+//! The plugin looks for a bundle appended to the executable before searching next to it, which can
+//! be switched off with [`AssetIoZipConfig::search_exe`]. Bundles that live inside a larger file
+//! without a footer, such as a platform container, can be loaded by setting
+//! [`AssetIoZipConfig::bundle_path`] to the file and [`AssetIoZipConfig::bundle_region`] to the
+//! offset and length of the bundle in it.
+//!
+//! # Tamper Checks
+//!
+//! With `embed-bundle-hash` enabled in the `asset_config.toml`, the bundler passes the BLAKE3 hash
+//! of the bundle to the game crate, where it can be embedded with the [`bundle_hash!`] macro. The
+//! plugin then hashes the bundle at startup and warns, or panics, if it has been modified:
+//!
+//! ```no_run
+//! # use bevy::prelude::*;
+//! # use bevy_assetio_zip::{bundle_hash, AssetIoZipConfig, TamperResponse};
+//! App::build().add_resource(AssetIoZipConfig {
+//!     expected_bundle_hash: bundle_hash!().map(Into::into),
+//!     tamper_response: TamperResponse::Error,
+//!     ..Default::default()
+//! });
+//! ```
+//!
+//! Hashing a large bundle on every launch adds to the startup time, so the hash can be cached in
+//! the [`AssetIoZipConfig::hash_cache_dir`] along with the size and modification time of the
+//! bundle. The bundle is then only hashed again when it changes.
+//!
+//! # Layering Asset IO
+//!
+//! The [`AssetIoZip`] inserted by the plugin is a [`ZipAssetIo`], which only loads assets from the
+//! mounted bundles, composed with a fallback asset IO. To layer the bundles with other asset IO
+//! implementations in a different order, such as trying a network asset IO before the bundles and
+//! the filesystem after them, create a [`ZipAssetIo`] yourself and wrap it in your own [`AssetIo`]
+//! instead of adding the plugin.
+//!
+//! # Browser Builds
+//!
+//! In browser builds there is no executable directory, so the plugin downloads the bundle from the
+//! Bevy asset folder on the web server when the app starts. Assets loaded before the download has
+//! finished wait for it to complete. The download progress is sent as `BundleDownloadProgress`
+//! events which can be used to display a progress bar, and the bundle can optionally be cached in
+//! IndexedDB by setting [`AssetIoZipConfig::cache_bundle`] so that returning players don't have to
+//! download it again if it hasn't changed.
 //!
 //! # Types of Asset Bundles
 //!
@@ -39,10 +425,43 @@
 //! [`bevy_assetio_zip_bundler`] and are simply a normal zip file that has had the bytes XOR-ed by
 //! `0b01010101`.
 //!
+//! Whether a bundle is obfuscated is detected from its first bytes, so bundles that have been
+//! renamed or mislabeled still load. The extension is only used when the bundle doesn't start with
+//! a recognizable zip header.
+//!
+//! The bundler can also write v2 bundles, which start with a small header made of the magic bytes
+//! `BAZB`, a format version, flags saying whether the zip data is obfuscated or compressed, and the
+//! offset of the zip data. The header is never obfuscated, so v2 bundles are read correctly
+//! whatever their extension, and bundles written in a newer format version fail with a clear error
+//! instead of being misread. Plain and obfuscated zip bundles without a header are still supported.
+//!
+//! The bundler can also obfuscate only the data of each file in the bundle while leaving the zip
+//! structure intact. These bundles are plain `.zip` files that standard zip tools can list, but the
+//! file contents are scrambled and are decoded per entry when they are loaded. Each file is
+//! scrambled with its own key, derived from its name and a random salt for the bundle, so
+//! recovering the key of one file doesn't decode the rest of the bundle.
+//!
+//! The salt can also be kept out of the bundle with the bundler's `entry-salt` setting and supplied
+//! by the game with a [`KeyProvider`], which can fetch it from wherever the game keeps its keys.
+//!
 //! > **⚠️ WARNING:** Obfuscated zip files provide no real security or protection for your assets.
 //! > It is trivial to decript the asset bundle even if it is obfuscated. Obfuscation of the zip is
 //! > only a measure to prevent casual users from being able to immediately introspect the data.
 //!
+//! Support for obfuscated bundles is enabled by the `obfuscation` feature, which is on by default.
+//! Each compression method has its own feature too: `deflate` and `bzip2`, which are on by default,
+//! and `solid` for zstd compressed solid bundles. Bzip2 needs a C compiler, so if you only ship
+//! plain `.zip` files that are stored or deflate compressed you can disable default features to
+//! drop the `.bin` lookup and the `bzip2` and `xorio` dependencies:
+//!
+//! ```toml
+//! [dependencies]
+//! bevy_assetio_zip = { version = "0.1", default-features = false, features = ["deflate"] }
+//! ```
+//!
+//! The bundler has the same `bzip2`, `deflate`, and `solid` features, so that build scripts only
+//! compile the codecs that they use.
+//!
 //! # Bundling Assets
 //!
 //! To bundle your bevy assets you can use the [`bevy_assetio_zip_bundler`] crate. The easiest way
@@ -54,10 +473,10 @@
 //! }
 //! ```
 //!
-//! This will automatically zip up your crate's `assets` folder and put it in your `target/` dir
-//! when compiling release builds. When distributing your application simply take your asset bundle
-//! and place it adjacent to the executable and Bevy will attempt to load assets from the bundle
-//! before falling back to the `assets` dir.
+//! This will automatically zip up your crate's `assets` folder and put it next to the executable in
+//! your `target/release` dir when compiling release builds. When distributing your application
+//! simply take your asset bundle and place it adjacent to the executable and Bevy will attempt to
+//! load assets from the bundle before falling back to the `assets` dir.
 //!
 //! You can configure the name, obfuscation, and compression of the bundle by creating and
 //! `asset_config.toml` file next to your `Cargo.toml` file:
@@ -70,17 +489,206 @@
 //! # for the average user to read them.
 //! obfuscate = true # Default: false
 //!
-//! # Compress the asset bundle using Bzip2 compression. Other options are "deflate" and "none".
-//! compression = "bzip2" # Default: "bzip2"
+//! # Obfuscate the data of each file while leaving the zip structure intact, so that standard zip
+//! # tools can still list the bundle contents. Each file gets its own key, derived from its name
+//! # and a random salt for the bundle. This can be combined with `obfuscate`.
+//! obfuscate-entries = false # Default: false
+//!
+//! # Derive the keys of obfuscated entries from this salt instead of a random one, and leave it out
+//! # of the bundle. The game then has to supply the salt with a `KeyProvider` to read the bundle.
+//! # entry-salt = "some salt" # Default: not set
+//!
+//! # Compress the asset bundle using Bzip2 compression. Other options are "deflate" and "none". The
+//! # default is the best codec enabled by the `bzip2` and `deflate` features of the bundler.
+//! # compression = "bzip2" # Default: "bzip2", then "deflate", then "none"
 //!
 //! # The name of the file, not counting the exention, which will be different based on the `obfuscate`
 //! # setting. Obfuscated bundles will end in `.bin` and non-obfuscated bundles will end in `.zip`.
 //! file-name = "assets" # Default: "assets"
 //!
-//! # Set the directory that asset bundle should be placed.
-//! out-dir = "../target" # Default "./target"
+//! # The dir to bundle the assets from.
+//! asset-dir = "assets" # Default: "assets"
+//!
+//! # What the `asset-dir`, `extra-asset-dirs`, and `out-dir` paths are relative to. "crate" is the
+//! # crate root, and "workspace" is the root of the workspace that the crate belongs to, so that a
+//! # game organized as many crates can keep its assets anywhere in the workspace.
+//! paths-relative-to = "crate" # Default: "crate"
+//!
+//! # Set the directory that asset bundle should be placed. By default the bundle is written to the
+//! # build script's `OUT_DIR` and then linked next to the executable in `target/release` or
+//! # `target/debug`, so that the build script doesn't write outside of its `OUT_DIR`.
+//! # out-dir = "dist" # Default: none
+//!
+//! # Aggregate files smaller than this many bytes into packed blocks that are compressed
+//! # together. This greatly improves load times for scenes made of many tiny files. Set to 0 to
+//! # disable packing.
+//! pack-threshold = 0 # Default: 0
+//!
+//! # The target size in bytes of each packed block. At runtime, a few decompressed blocks are kept
+//! # in memory until all of their files have been loaded.
+//! pack-block-size = 1048576 # Default: 1048576
+//!
+//! # Align the data of each file to a multiple of this many bytes. Only applies when compression
+//! # is "none" and allows the `mmap` feature of `bevy_assetio_zip` to serve the files from a
+//! # memory map.
+//! align = 0 # Default: 0
+//!
+//! # The file format of the bundle. "zip" writes a plain or obfuscated zip file. "v2" writes the
+//! # zip data after a small header that records the format version and how the data is encoded,
+//! # so that future versions of the format can be read without guessing from the file extension.
+//! format = "zip" # Default: "zip"
+//!
+//! # Write a sidecar index of the bundle entries next to the bundle, named like the bundle but with
+//! # an `.idx` extension. Ship it alongside the bundle to skip parsing the zip central directory at
+//! # startup, which is slow for bundles with a huge number of files.
+//! write-index = false # Default: false
+//!
+//! # Collect the license and credits files in the assets dir into a single `credits.txt` file in
+//! # the bundle, which can be read at runtime with `ZipAssetIo::credits`.
+//! collect-credits = false # Default: false
+//!
+//! # Glob patterns of the files to collect when `collect-credits` is enabled. Patterns are matched
+//! # case-insensitively against the paths relative to the assets dir.
+//! credits-patterns = ["**/LICENSE*", "**/CREDITS*"] # Default: ["**/LICENSE*", "**/CREDITS*"]
+//!
+//! # Write a `pub const` with the path of every asset to `asset_paths.rs` in the build script's
+//! # `OUT_DIR`, so that asset paths can be checked at compile time. The file is written even when
+//! # the assets are not bundled.
+//! asset-paths = false # Default: false
+//!
+//! # Check that the asset paths referenced by the `.scn` and `.ron` files in the assets dir exist
+//! # and fail the build if they don't. Any string without whitespace that ends in the extension of
+//! # one of the assets is treated as an asset path.
+//! validate-references = false # Default: false
+//!
+//! # Make sure that the buffers and images referenced by `.gltf` files are bundled along with them.
+//! # References to files outside of the assets dir are reported as warnings.
+//! gltf-dependencies = true # Default: true
+//!
+//! # Store the files uncompressed and compress the whole bundle with zstd, which shrinks bundles of
+//! # many similar files far more than compressing each file on its own. Solid bundles always use
+//! # the "v2" format and require the `solid` feature of `bevy_assetio_zip`.
+//! solid = false # Default: false
+//!
+//! # The size in bytes of the uncompressed data in each independently compressed frame of solid
+//! # bundles. Larger frames compress better, but a whole frame is decompressed to read any part of
+//! # it.
+//! solid-frame-size = 4194304 # Default: 4194304
+//!
+//! # The zstd compression level of solid bundles, from 1 to 22.
+//! solid-level = 19 # Default: 19
+//!
+//! # Record the modification time and content hash of each asset in the bundle, which can be read
+//! # at runtime with `ZipAssetIo::asset_metadata` to tell when data cached from an asset is out of
+//! # date, or with `ZipAssetIo::asset_fingerprints` to check that clients and servers have the same
+//! # assets.
+//! asset-metadata = false # Default: false
+//!
+//! # Record the unix permissions of each asset in the bundle, so that the executable bits of helper
+//! # scripts and tool payloads are restored when the bundle is extracted by the bundler. Executable
+//! # files are never packed. Only applies when bundling on unix.
+//! unix-permissions = false # Default: false
+//!
+//! # The modification times recorded in the bundle. "zero" records the same time for every entry
+//! # and zero for the times recorded by `asset-metadata`, so that bundling the same assets produces
+//! # the same bundle. "preserve" records the modification time of each asset file, and "build-time"
+//! # records the time that the bundle was built.
+//! timestamps = "build-time" # Default: "build-time"
+//!
+//! # The archive comment to store in the bundle, such as a build id or git commit, which can be
+//! # read at runtime with `ZipAssetIo::bundle_comment` to include in crash reports.
+//! # comment = "build 1234" # Default: none
+//!
+//! # The path, relative to the crate root, to a TOML file mapping paths in the assets dir to the
+//! # paths they are bundled at, such as `"textures/environment/outdoor" = "outdoor"`. Renaming a
+//! # directory renames everything in it. Assets are loaded by their bundled paths, so enable
+//! # `bundle-for-debug-builds` to use the same paths during development.
+//! # rename-file = "rename.toml" # Default: none
+//!
+//! # More asset dirs, relative to the `paths-relative-to` dir, whose files are bundled along with
+//! # the `asset-dir`, such as assets shared between several games. Directories found in several
+//! # asset dirs are merged.
+//! extra-asset-dirs = [] # Default: []
+//!
+//! # What to do when the asset dirs have different files at the same path, after renaming. "error"
+//! # fails the build with a report of the conflicting files, and "first-wins" and "last-wins"
+//! # bundle the file from the first or last asset dir that has one, starting with the `assets` dir.
+//! merge-conflicts = "error" # Default: "error"
+//!
+//! # The path, relative to the crate root, to the previous release of the bundle. When it exists, a
+//! # `changelog.json` entry listing the assets that were added, removed, or changed since then,
+//! # with their sizes, is written to the new bundle. It may be the same as the path of the new
+//! # bundle.
+//! # previous-bundle = "target/release/assets.zip" # Default: none
+//!
+//! # Glob patterns of the assets that must not be removed, such as assets referenced by save games.
+//! # The build fails if an asset in the `previous-bundle` that matches one of them is missing from
+//! # the new bundle. Use ["**"] to keep every asset.
+//! keep-assets = [] # Default: []
+//!
+//! # Pass the BLAKE3 hash of the bundle to the crate when the assets are bundled, so that it can be
+//! # embedded with the `bundle_hash!` macro and checked by the plugin at startup.
+//! embed-bundle-hash = false # Default: false
+//!
+//! # The path, relative to the crate root, to write statistics about the bundle to as JSON,
+//! # including the number of entries, their compressed and uncompressed sizes by extension, and the
+//! # build time.
+//! # stats-file = "target/asset_stats.json" # Default: none
+//!
+//! # The maximum size of the bundle, such as "2GiB" or "500MB", to catch assets such as
+//! # uncompressed source art that were bundled by accident. Larger bundles fail the build and list
+//! # their largest entries, or only warn about them when `max-bundle-size-action` is "warn".
+//! # max-bundle-size = "2GiB" # Default: none
+//! max-bundle-size-action = "fail" # Default: "fail"
+//!
+//! # Convert files before they are bundled. Every step whose `match` pattern matches a file is run
+//! # in order, each converting the output of the previous one. A step either runs a `command`, in
+//! # which `{input}` and `{output}` are replaced with the paths of the file and of the converted
+//! # file, or a built-in `converter`, such as "minify-json". `extension` renames the converted
+//! # file.
+//! # [[pipeline]]
+//! # match = "**/*.png"
+//! # command = "toktx --t2 {output} {input}"
+//! # extension = "ktx2"
 //! ```
 //!
+//! With `asset-paths` enabled, include the generated constants in a module so that a typo in an
+//! asset path is a compile error instead of a missing asset:
+//!
+//! ```ignore
+//! mod asset_paths {
+//!     include!(concat!(env!("OUT_DIR"), "/asset_paths.rs"));
+//! }
+//!
+//! fn setup(asset_server: Res<AssetServer>) {
+//!     let texture: Handle<Texture> = asset_server.load(asset_paths::TEXTURES_GRASS_PNG);
+//! }
+//! ```
+//!
+//! Assets can be left out of the bundle with `.assetignore` files, which use the `.gitignore`
+//! syntax and can be placed in the `assets` folder or any of its subdirectories, so that a
+//! work-in-progress folder can be excluded by an `.assetignore` file right next to it.
+//!
+//! When a bundle file has an index file next to it, the index is checked against the size and the
+//! end of the zip data of the bundle, and if it matches, entries are found using the index instead
+//! of the zip central directory. An index that doesn't match, such as one left over from an older
+//! build, is ignored with a warning. Indexes are not used in browser builds.
+//!
+//! When the `mmap` feature of this crate is enabled, uncompressed entries in plain `.zip` bundles
+//! are read directly out of a memory map of the bundle instead of through file reads.
+//!
+//! Bundles written with `solid` enabled can only be read when the `solid` feature of this crate is
+//! enabled. Only the zstd frames containing the assets that are loaded are decompressed, and the
+//! most recently decompressed frame of each archive handle is kept in memory.
+//!
+//! With the `sqlite` feature of this crate, a bundle can also be an SQLite database with an
+//! `assets (path TEXT PRIMARY KEY, blob BLOB NOT NULL, flags INTEGER NOT NULL DEFAULT 0)` table,
+//! with a row for each entry that the zip archive would have, holding its uncompressed data. Single
+//! assets can then be replaced with any SQLite client without rebuilding the bundle. SQLite bundles
+//! are detected from their first bytes, and `.sqlite` files are searched for after `.zip` files.
+//! The database is opened read-only by SQLite itself, which needs a file, so SQLite bundles can
+//! only be read from a [`FileBundleSource`]. No flags are defined yet, so `flags` must be 0.
+//!
 //! Alternatively, if you want to create your own tooling or customize the asset bundling process,
 //! you can manually bundle the assets using the [`bevy_assetio_zip_bundler::bundle_assets`]
 //! function.
@@ -88,8 +696,58 @@
 //! [`bevy_assetio_zip_bundler::bundle_assets`]:
 //! https://docs.rs/bevy_assetio_zip_bundler/latest/bevy_assetio_zip_bundler/fn.bundle_assets.html
 //!
+//! # Inspecting Bundles
+//!
+//! The `bevy_assetio_zip_bundler` crate also has a command line tool for checking and unpacking
+//! bundles, which can be installed with `cargo install bevy_assetio_zip_bundler`:
+//!
+//! ```bash
+//! # Check the CRC of every entry, and the asset hashes if the bundle has asset metadata, exiting
+//! # with an error if the bundle is damaged
+//! bevy_assetio_zip_bundler verify target/assets.bin
+//!
+//! # List the assets that were added, removed, or changed between two bundles
+//! bevy_assetio_zip_bundler diff old/assets.bin target/assets.bin
+//!
+//! # Unpack the assets of a bundle, undoing any obfuscation, for inspection
+//! bevy_assetio_zip_bundler extract target/assets.bin extracted-assets
+//!
+//! # Compress a sample of the assets with each codec and compare the sizes and speeds, to help pick
+//! # the `compression` and `solid-level` settings
+//! bevy_assetio_zip_bundler benchmark assets
+//!
+//! # Write a copy of the executable with the bundle attached to it
+//! bevy_assetio_zip_bundler append target/release/my_game target/release/assets.bin my_game
+//! ```
+//!
+//! The same tools are available as the `verify`, `diff`, `extract`, `benchmark`, and
+//! `append_to_executable` functions of the bundler crate, along with `repack`, which rewrites a
+//! bundle with different compression or obfuscation options without needing the asset dir that it
+//! was built from.
+//!
+//! Tools that read bundles themselves, such as localization extractors and QA scripts, can wrap
+//! the bundle file in `wrap_reader` to undo the obfuscation of `.bin` bundles before opening them
+//! with a zip library, and `wrap_writer` obfuscates data the same way. The `wrap_reader_with_key`
+//! and `wrap_writer_with_key` variants take a custom XOR key.
+//!
+//! # Testing
+//!
+//! The `test-util` feature adds a `test_util` module with helpers to write temporary bundles and to
+//! create a minimal app that loads assets from them, so that you can test your asset pipeline
+//! against bundles:
+//!
+//! ```toml
+//! [dev-dependencies]
+//! bevy_assetio_zip = { version = "0.1", features = ["test-util"] }
+//! ```
+//!
+//! Under `cargo test` the executable is the test binary, so a bundle left next to it by an earlier
+//! build would be picked up by the tests. Set `AssetIoZipConfig::explicit_paths_only`, or run the
+//! tests with the `ASSETIO_ZIP_EXPLICIT_PATHS_ONLY=1` environment variable, to only load bundles
+//! from the paths given in the config. The apps created by the `test_util` module already do this.
+//!
 //! # Bevy Versions
-//! 
+//!
 //! Supported bevy versions per plugin version:
 //!
 //! | Bevy Version | Plugin Version                                     |
@@ -124,115 +782,881 @@
 //!
 //! [k_license]: https://github.com/katharostech/katharos-license
 
+#[cfg(target_os = "android")]
+mod android;
+#[cfg(not(target_arch = "wasm32"))]
+mod appended;
+mod args;
+#[cfg(not(target_arch = "wasm32"))]
+mod audit;
+mod bundle;
+mod cancel;
+mod config;
+mod corrupt;
+mod entry;
+#[cfg(not(target_arch = "wasm32"))]
+mod extract;
+mod fallback;
+#[cfg(feature = "vfs")]
+mod file_system;
+mod filter;
+mod format;
+mod groups;
+mod histogram;
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+mod hot_reload;
+#[cfg(not(target_arch = "wasm32"))]
+mod index;
+#[cfg(not(target_arch = "wasm32"))]
+mod integrity;
+#[cfg(target_os = "ios")]
+mod ios;
+#[cfg(feature = "obfuscation")]
+mod keys;
+mod metadata;
+mod metadata_cache;
+#[cfg(not(target_arch = "wasm32"))]
+mod mods;
+#[cfg(not(target_arch = "wasm32"))]
+mod overlay;
+mod pack;
+mod prefetch_cache;
+mod priority;
+mod progress;
+#[cfg(not(target_arch = "wasm32"))]
+mod raw;
+mod read_ahead;
+mod retry;
+#[cfg(all(feature = "runtime-config", not(target_arch = "wasm32")))]
+mod runtime_config;
+mod server_ext;
+#[cfg(feature = "solid")]
+mod solid;
+mod source;
+mod source_log;
+#[cfg(feature = "sqlite")]
+mod sqlite;
+#[cfg(not(target_arch = "wasm32"))]
+mod streaming;
+#[cfg(feature = "test-util")]
+pub mod test_util;
+mod variants;
+#[cfg(target_arch = "wasm32")]
+mod wasm;
+mod zip_io;
+
+pub use cancel::CancellationToken;
+pub use corrupt::CorruptAssetError;
+pub use entry::EntryReader;
+pub use fallback::{MemoryAssetIo, NoopAssetIo};
+#[cfg(feature = "vfs")]
+pub use file_system::BundleFileSystem;
+pub use format::KeySlot;
+pub use groups::AssetGroups;
+pub use histogram::{ExtensionLoadTimes, LoadTimeHistogram, LOAD_TIME_BUCKETS};
+pub use metadata_cache::{CachedEntryMetadata, EntryMetadataCache};
+pub use priority::LoadPriority;
+pub use progress::AssetLoadProgress;
+#[cfg(not(target_arch = "wasm32"))]
+pub use raw::RawEntry;
+pub use retry::RetryPolicy;
+#[cfg(all(feature = "runtime-config", not(target_arch = "wasm32")))]
+pub use runtime_config::RUNTIME_CONFIG_FILE;
+pub use server_ext::BundleAssetServerExt;
+#[cfg(not(target_arch = "wasm32"))]
+pub use source::FileBundleSource;
+pub use source::{BundleSource, BundleSources, MemoryBundleSource, ReadSeekSend};
+pub use source_log::AssetSourceLog;
+#[cfg(not(target_arch = "wasm32"))]
+pub use streaming::{LevelStreamer, DEFAULT_STREAMS_IN_FLIGHT};
+pub use variants::AssetVariants;
+#[cfg(target_arch = "wasm32")]
+pub use wasm::BundleDownloadProgress;
+pub use zip_io::ZipAssetIo;
+
 use std::{
-    fs::OpenOptions,
-    io::{BufReader, Read, Seek},
+    collections::BTreeSet,
+    io::Read,
+    ops::Deref,
     path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, Instant, SystemTime},
 };
 
+#[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+use bevy::asset::FileAssetIo;
 use bevy::{
-    asset::{AssetIo, AssetIoError},
-    prelude::{AppBuilder, AssetServer, Plugin},
+    app::Events,
+    asset::{AssetIo, AssetIoError, AssetServerSettings},
+    log::{debug, error, warn},
+    prelude::{AppBuilder, AssetServer, IntoSystem, Local, Plugin, Res, ResMut},
     utils::BoxedFuture,
 };
-
-use xorio::Xor;
+use crossbeam_channel::{Receiver, Sender};
+use flate2::read::GzDecoder;
 pub use zip::CompressionMethod;
-use zip::ZipArchive;
+
+#[cfg(not(target_arch = "wasm32"))]
+use audit::AuditLog;
+#[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+use hot_reload::{reload_changed_loose_assets, LooseAssetWatcher};
 
 /// Configuration resource fro the [`AssetIoZipPlugin`]
+///
+/// The plugin logs an error for every problem found by [`validate`][Self::validate] when it is
+/// built.
 #[derive(Debug, Clone)]
 pub struct AssetIoZipConfig {
-    /// The name of the assset bundle file to load from, excluding the extension.
+    /// The names of the assset bundle files to load from, excluding the extension.
     ///
     /// The actual file read will be the filename plus either a `.zip` or a `.bin` extension,
-    /// whichever is present. If the `[file_name].zip` file is found it will load the file as a
-    /// normal zip, if the `[file_name].bin` file is found, it will attempt to load it as an
-    /// obfuscated zip by first XOR-ing the contents of the file by `0b01010101`.
-    pub file_name: String,
+    /// whichever is present. Whether the file is a normal zip or an obfuscated zip, which is read
+    /// by first XOR-ing the contents of the file by `0b01010101`, is detected from the first bytes
+    /// of the file.
+    ///
+    /// The names are tried in order and the first bundle found is used, which is useful when
+    /// migrating between naming schemes across game versions.
+    ///
+    /// `.bin` files are only searched for when the `obfuscation` feature is enabled, and `.sqlite`
+    /// files, which are searched for after the `.zip` files, when the `sqlite` feature is enabled.
+    pub file_names: Vec<String>,
+    /// The path to a specific asset bundle to load from.
+    ///
+    /// When this is set, [`file_names`][Self::file_names] is ignored and the bundle is not searched
+    /// for next to the executable. The bundle is treated as obfuscated if it has a `.bin`
+    /// extension. This is useful for installs where a launcher manages the location of the assets.
+    pub bundle_path: Option<PathBuf>,
+    /// The region of the file at [`bundle_path`][Self::bundle_path] that contains the bundle.
+    ///
+    /// This lets the bundle live appended to the game executable or inside a larger platform
+    /// container file: every read of the bundle is windowed to the region, and whether the bundle
+    /// is a v2 bundle or a plain or obfuscated zip is detected from the first bytes of the region.
+    /// It has no effect unless `bundle_path` is set, and no effect in browser builds.
+    pub bundle_region: Option<BundleRegion>,
+    /// Glob patterns of the assets of the main asset bundle to mount, such as `ui/**`.
+    ///
+    /// When this is empty every asset of the main bundle is mounted. Otherwise only the matching
+    /// assets are loaded from, listed in, and extracted from the main bundle, so that a tool or
+    /// game can use its slice of a large bundle shared with others. In patterns `*` matches within
+    /// a single directory and `**` matches across directories.
+    pub mount_patterns: Vec<String>,
+    /// The Bevy asset folder to use instead of the one in the [`AssetServerSettings`] resource.
+    ///
+    /// When this is set the plugin replaces the [`AssetServerSettings`] resource, so the bundles
+    /// are searched for in this folder and loose assets are loaded from it by the fallback asset
+    /// IO. This can be set from the command line with [`with_args`][Self::with_args].
+    pub asset_dir: Option<PathBuf>,
+    /// Also search for the asset bundle in the Bevy asset folder after searching next to the
+    /// executable.
+    ///
+    /// This allows development layouts where everything lives under `assets/` and distribution
+    /// layouts where the bundle is next to the executable to share the same config.
+    pub search_asset_dir: bool,
+    /// On Android, also search for the asset bundle in the app's APK expansion ( `.obb` ) files.
+    ///
+    /// Expansion files are searched for in the standard
+    /// `<external-storage>/Android/obb/<package-name>/` directory. The newest `main` expansion file
+    /// is used if present, otherwise the newest `patch` expansion file.
+    pub search_obb: bool,
+    /// On iOS, also search for the asset bundle in the resources of the application bundle.
+    ///
+    /// Both the root of the `.app` bundle, where Xcode copies loose resources, and its `assets`
+    /// folder, where `cargo-mobile` builds place the assets, are searched.
+    pub search_app_bundle: bool,
+    /// Look for an asset bundle appended to the executable before searching next to it.
+    ///
+    /// The `append` command of the bundler attaches a bundle to a copy of the executable, so that
+    /// the game and its assets can be distributed as a single file. The appended bundle is used
+    /// instead of the bundle found with [`file_names`][Self::file_names]. It is not looked for
+    /// when [`bundle_path`][Self::bundle_path] is set, or in browser builds.
+    pub search_exe: bool,
+    /// Only look for bundles and config files at the paths given in this config, without probing
+    /// the directory of the executable.
+    ///
+    /// Under `cargo test` the executable is the test binary, so a bundle or runtime config left
+    /// next to it by an earlier build changes which assets the tests load. With this set, the main
+    /// bundle is only searched for in the [`asset_dir`][Self::asset_dir] or at the
+    /// [`bundle_path`][Self::bundle_path], nothing is looked for in or appended to the executable,
+    /// the platform specific searches are skipped, and relative [`mod_dirs`][Self::mod_dirs] are
+    /// resolved against the current directory. It can also be switched on without changing the
+    /// config by setting the [`EXPLICIT_PATHS_ENV_VAR`] environment variable to `1`.
+    pub explicit_paths_only: bool,
+    /// Directories to search for mod bundles in, such as a Steam Workshop content directory.
+    ///
+    /// Every `.zip` and `.bin` file directly inside of each directory, or inside of one of its
+    /// immediate sub-directories, is mounted as a mod. Mods override the assets in the main asset
+    /// bundle. Relative paths are relative to the directory containing the executable.
+    pub mod_dirs: Vec<PathBuf>,
+    /// The priority order of the mods, highest priority first.
+    ///
+    /// Each entry is the name of a mod, which is the path of the mod bundle relative to its mod
+    /// directory without the extension, such as `my_mod` or `123456/my_mod`. Listing a
+    /// sub-directory name such as `123456` matches all of the bundles inside of it. Mods that are
+    /// not listed are given a lower priority than the listed mods and are ordered by name.
+    pub mod_order: Vec<String>,
+    /// Glob patterns of the asset paths that mods are allowed to provide, such as `textures/**`.
+    ///
+    /// When this is empty mods may provide any asset that is not denied by
+    /// [`mod_deny`][Self::mod_deny]. In patterns `*` matches within a single directory and `**`
+    /// matches across directories.
+    pub mod_allow: Vec<String>,
+    /// Glob patterns of the asset paths that mods are never allowed to provide, such as
+    /// `scripts/**`.
+    ///
+    /// Assets in mods that are not allowed are ignored, so they are loaded from the main asset
+    /// bundle instead. This takes precedence over [`mod_allow`][Self::mod_allow].
+    pub mod_deny: Vec<String>,
+    /// How to resolve assets that are provided by more than one mod.
+    pub mod_conflict_policy: ModConflictPolicy,
+    /// The maximum number of entries that a mod bundle may have.
+    ///
+    /// Mods with more entries are not mounted, which protects players from zip bombs delivered as
+    /// mods. When this is `None` mods may have any number of entries.
+    pub mod_max_entries: Option<usize>,
+    /// The maximum total uncompressed size in bytes of the entries of a mod bundle.
+    ///
+    /// Mods whose entries add up to more are not mounted, which protects players from zip bombs
+    /// delivered as mods. The sizes recorded in the mod bundle are checked, so nothing is
+    /// decompressed to check them. When this is `None` mods may be of any size.
+    pub mod_max_uncompressed_size: Option<u64>,
+    /// In browser builds, cache the downloaded asset bundle in IndexedDB so that returning players
+    /// don't have to download it again if it hasn't changed.
+    ///
+    /// The server must send an `ETag` header with the bundle for it to be cached.
+    pub cache_bundle: bool,
+    /// When a bundle exists but is corrupted, log an error and continue without it instead of
+    /// panicking when the plugin is built.
+    ///
+    /// Either way a detailed description of the problem including the size and the first bytes of
+    /// the bundle is reported.
+    pub corrupt_bundle_fallback: bool,
+    /// When an asset is found in a bundle but can't be read from it, such as when its data is
+    /// corrupt, log an error and load it from the next bundle or the fallback asset IO instead of
+    /// failing to load it with a [`CorruptAssetError`].
+    ///
+    /// This is off by default, because falling back to the loose assets hides bugs in the pipeline
+    /// that built the bundle.
+    pub corrupt_asset_fallback: bool,
+    /// The maximum number of opened archive handles to keep for each bundle, which is the number
+    /// of reads from a bundle that can run at once without opening it again.
+    ///
+    /// Each handle has its own reader over the bundle, such as its own file descriptor, so that
+    /// the IO task pool can decompress several assets from the same bundle in parallel. Handles are
+    /// reused by later reads, which also saves parsing the zip central directory again. When all of
+    /// the handles are in use, reads open temporary handles. Set to `Some(0)` to open the bundle
+    /// again for every read.
+    ///
+    /// Defaults to the number of threads in the IO task pool. Fewer handles can be faster on hard
+    /// drives, where parallel reads seek back and forth, and more can help to hide the latency of
+    /// network filesystems.
+    pub max_concurrent_reads: Option<usize>,
+    /// Send [`AssetLoadProgress`] events while decompressing assets that are at least this many
+    /// bytes, or `None` to never send them.
+    ///
+    /// This lets loading screens show a real progress bar while a huge asset, such as a 100 MB
+    /// level blob, is loaded. Assets read from packed blocks, from extracted files, or from the
+    /// memory map of the bundle are not reported.
+    pub progress_event_size: Option<u64>,
+    /// Decompress assets on a dedicated pool with this many threads instead of on the IO task pool.
+    ///
+    /// A small pool keeps long decompressions, such as of large bzip2 assets while streaming a
+    /// level, from occupying every core and starving the main and render threads. The threads run
+    /// at the normal priority. This has no effect in browser builds, which have a single thread.
+    pub decompression_threads: Option<usize>,
+    /// The number of bytes of the bundle to read at once when loading an entry, or `0` to read
+    /// only what the entry needs.
+    ///
+    /// The data read ahead is shared by all of the handles of the bundle, so when several queued
+    /// loads hit entries that are next to each other in the bundle, their compressed data is read
+    /// with one large sequential read and decompressed from memory instead of seeking back and
+    /// forth. This speeds up loading batches of assets from spinning disks and on some consoles. A
+    /// window is kept for each of the [`max_concurrent_reads`][Self::max_concurrent_reads].
+    pub read_ahead: u64,
+    /// The total size of the data of the prefetched assets to keep in memory until they are loaded,
+    /// or `None` for no limit.
+    ///
+    /// When prefetching an asset would go over the limit, the assets that were prefetched first are
+    /// dropped to make room for it, and they are read from the bundles again when they are loaded.
+    /// Assets that are larger than the limit aren't prefetched.
+    pub max_prefetched_bytes: Option<u64>,
+    /// What other processes may do with the main asset bundle and mod bundle files while they are
+    /// open.
+    ///
+    /// Bundles are opened allowing them to be replaced by default. Memory mapped bundles can't be
+    /// replaced on Windows regardless of this setting.
+    pub bundle_sharing: BundleFileSharing,
+    /// How to retry opening bundles and reading entries from them when they fail with transient IO
+    /// errors, such as on network drives and external disks, before the error is returned.
+    pub io_retry: RetryPolicy,
+    /// The path to a writable bundle, such as `user_assets.zip` in the user data dir, that
+    /// downloaded or player-created assets can be written into with
+    /// [`write_overlay_asset`][ZipAssetIo::write_overlay_asset].
+    ///
+    /// The overlay is mounted above all of the other bundles, including mods, and is created when
+    /// the first asset is written to it. Its name is the file name without the extension.
+    pub overlay_bundle: Option<PathBuf>,
+    /// A per-user directory of loose asset files, such as `%APPDATA%/MyGame/overrides/`, that is
+    /// consulted before any of the bundles.
+    ///
+    /// This lets players drop in custom textures and other skins without touching the install
+    /// directory. Assets are loaded from `<override_dir>/<asset path>`.
+    pub override_dir: Option<PathBuf>,
+    /// Whether or not to load assets from the [`override_dir`][Self::override_dir].
+    ///
+    /// Set this to `false` to ignore the override directory in shipping builds without removing
+    /// the rest of the configuration.
+    pub use_override_dir: bool,
+    /// Extract the main asset bundle to loose files in the [`extract_dir`][Self::extract_dir] and
+    /// load its assets from those files instead of decompressing them from the bundle.
+    ///
+    /// The bundle is only extracted the first time that a version of it is mounted, with the
+    /// assets decompressed in parallel and checked against the content hashes recorded by the
+    /// bundler. This speeds up loading on platforms where reading files through the OS file cache
+    /// is faster than decompressing them again on every run, at the cost of the disk space. Mods
+    /// and custom bundle sources are not extracted.
+    pub extract_bundle: bool,
+    /// The scratch directory that the main asset bundle is extracted to when
+    /// [`extract_bundle`][Self::extract_bundle] is enabled, such as a per-user cache directory.
+    ///
+    /// Defaults to a `bevy_assetio_zip` directory in the platform temp directory.
+    pub extract_dir: Option<PathBuf>,
+    /// The asset IO to load assets from when they are not found in any of the mounted bundles.
+    pub fallback: FallbackAssetIo,
+    /// When an asset is not found by the fallback asset IO, try loading `<path>.gz` from it and
+    /// decompress it on the fly.
+    ///
+    /// This lets large intermediate assets stay gzipped in the repository while they are still
+    /// loaded during development. Hot reloading is not supported for gzipped assets.
+    pub gzip_fallback: bool,
+    /// Asset paths to substitute with platform specific variants when they are loaded.
+    ///
+    /// The table can be replaced at runtime with [`ZipAssetIo::set_asset_variants`], and the
+    /// `variants` table of the runtime config file adds substitutions to it. See [`AssetVariants`].
+    pub asset_variants: AssetVariants,
+    /// Log the paths searched for the asset bundle, which bundle was opened, its entry count, and
+    /// whether or not it is obfuscated when the plugin is built.
+    pub verbose: bool,
+    /// Log a summary of the [`LoadTimeHistogram`] at this interval, if any assets were loaded
+    /// since the last summary.
+    pub load_time_summary_interval: Option<Duration>,
+    /// Record the source that each asset was loaded from in the [`AssetSourceLog`] resource.
+    ///
+    /// This is off by default because the log keeps an entry for every asset that was ever loaded.
+    pub record_asset_sources: bool,
+    /// Append the path, source, and checksum of every loaded asset to the file at this path.
+    ///
+    /// Some certification processes require proof that no unapproved content is loaded at
+    /// runtime, which the log provides. The file is created if it doesn't exist and added to if it
+    /// does. If the file can't be opened or a load can't be recorded, a warning is logged and the
+    /// asset is loaded anyway. Only loads through the [`AssetIoZip`] are recorded, and there is no
+    /// audit log in browser builds.
+    pub audit_log: Option<PathBuf>,
+    /// Don't mount any bundles and load every asset from the fallback asset IO.
+    ///
+    /// This makes it easy to compare loading from the bundle with loading loose files and to find
+    /// bugs that only happen with the bundle. It can also be switched on without rebuilding by
+    /// setting the [`DISABLE_ENV_VAR`] environment variable to `1`.
+    pub disable_bundles: bool,
+    /// Load assets from the fallback asset IO before looking for them in the bundles.
+    ///
+    /// This is a development mode for editing assets while a bundle is mounted: a loose asset file
+    /// shadows the asset in the bundles, and assets without a loose file are still loaded from the
+    /// bundles. With the `hot-reload` feature and [`AssetServer::watch_for_changes`], the loose
+    /// files of the loaded assets are watched, and the assets are reloaded when their files
+    /// change, even if the asset is also in a bundle. Bevy 0.4 can't reload assets through any
+    /// asset IO other than its own, so without the feature loose files are only read again when
+    /// the game restarts.
+    pub prefer_filesystem: bool,
+    /// The hex BLAKE3 hash that the main asset bundle is expected to have, which is usually
+    /// embedded in the game at build time with the [`bundle_hash!`] macro.
+    ///
+    /// When this is set the bundle is hashed when it is mounted, and a mismatch is handled as set
+    /// by [`tamper_response`][Self::tamper_response]. Mods and custom bundle sources are not
+    /// checked.
+    pub expected_bundle_hash: Option<String>,
+    /// What to do when the main asset bundle doesn't match the
+    /// [`expected_bundle_hash`][Self::expected_bundle_hash].
+    pub tamper_response: TamperResponse,
+    /// A directory, such as a per-user cache directory, to cache the hash of the main asset bundle
+    /// in across runs.
+    ///
+    /// The bundle is hashed for the [`expected_bundle_hash`][Self::expected_bundle_hash] check and
+    /// to [`extract_bundle`][Self::extract_bundle]. With a cache dir it is only hashed again when
+    /// its size or modification time changes, which makes the check cheap enough to keep enabled
+    /// in release builds. This trusts the modification time, so it only guards against accidental
+    /// corruption and casual tampering.
+    pub hash_cache_dir: Option<PathBuf>,
+    /// Supplies or overrides the salt that the keys of obfuscated entries are derived from, see
+    /// [`KeyProvider`]
+    pub key_provider: Option<Arc<dyn KeyProvider>>,
 }
 
+/// What to do when the asset bundle doesn't match the hash embedded in the game
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TamperResponse {
+    /// Log a warning and load the bundle anyway
+    Warn,
+    /// Panic with an error when the plugin is built
+    Error,
+}
+
+impl Default for TamperResponse {
+    fn default() -> Self {
+        Self::Warn
+    }
+}
+
+/// Get the hash of the asset bundle that was passed to the crate by the bundler, as an
+/// `Option<&'static str>`
+///
+/// The bundler passes the hash when `embed-bundle-hash` is enabled in the `asset_config.toml` and
+/// the assets are bundled, so this is `None` in builds that don't bundle the assets.
+///
+/// ```
+/// # use bevy_assetio_zip::{bundle_hash, AssetIoZipConfig};
+/// let config = AssetIoZipConfig {
+///     expected_bundle_hash: bundle_hash!().map(Into::into),
+///     ..Default::default()
+/// };
+/// ```
+#[macro_export]
+macro_rules! bundle_hash {
+    () => {
+        option_env!("ASSETIO_ZIP_BUNDLE_HASH")
+    };
+}
+
+/// The environment variable that sets [`AssetIoZipConfig::disable_bundles`] when it is set to
+/// anything other than an empty string, `0`, or `false`
+pub const DISABLE_ENV_VAR: &str = "ASSETIO_ZIP_DISABLE";
+
+/// The environment variable that sets [`AssetIoZipConfig::explicit_paths_only`] when it is set to
+/// anything other than an empty string, `0`, or `false`
+pub const EXPLICIT_PATHS_ENV_VAR: &str = "ASSETIO_ZIP_EXPLICIT_PATHS_ONLY";
+
 impl Default for AssetIoZipConfig {
     fn default() -> Self {
         Self {
-            file_name: "assets".into(),
+            file_names: vec!["assets".into()],
+            bundle_path: None,
+            bundle_region: None,
+            mount_patterns: Vec::new(),
+            asset_dir: None,
+            search_asset_dir: true,
+            search_obb: true,
+            search_app_bundle: true,
+            search_exe: true,
+            explicit_paths_only: false,
+            mod_dirs: Vec::new(),
+            mod_order: Vec::new(),
+            mod_allow: Vec::new(),
+            mod_deny: Vec::new(),
+            mod_conflict_policy: ModConflictPolicy::Priority,
+            mod_max_entries: None,
+            mod_max_uncompressed_size: None,
+            cache_bundle: false,
+            corrupt_bundle_fallback: true,
+            corrupt_asset_fallback: false,
+            max_concurrent_reads: None,
+            progress_event_size: None,
+            decompression_threads: None,
+            read_ahead: 0,
+            max_prefetched_bytes: None,
+            bundle_sharing: BundleFileSharing::AllowReplace,
+            io_retry: RetryPolicy::default(),
+            overlay_bundle: None,
+            override_dir: None,
+            use_override_dir: true,
+            extract_bundle: false,
+            extract_dir: None,
+            fallback: FallbackAssetIo::PlatformDefault,
+            gzip_fallback: true,
+            asset_variants: AssetVariants::default(),
+            verbose: false,
+            load_time_summary_interval: None,
+            record_asset_sources: false,
+            audit_log: None,
+            disable_bundles: false,
+            prefer_filesystem: false,
+            expected_bundle_hash: None,
+            tamper_response: TamperResponse::Warn,
+            hash_cache_dir: None,
+            key_provider: None,
         }
     }
 }
 
-trait FileReader: Read + Seek + Sync + Send {}
-impl<T: Read + Seek + Sync + Send> FileReader for T {}
+/// The asset IO used to load assets that are not in any of the mounted bundles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FallbackAssetIo {
+    /// Use the default asset IO for the platform, which loads loose files from the asset folder
+    PlatformDefault,
+    /// Don't load assets from anywhere other than the bundles, using a [`NoopAssetIo`]
+    ///
+    /// This is useful for bundle-only games because the platform default asset IO is never
+    /// created, which can fail or scan directories on some platforms.
+    Noop,
+    /// Load assets from the given [`MemoryAssetIo`], which is useful for tests and examples
+    Memory(MemoryAssetIo),
+}
+
+impl Default for FallbackAssetIo {
+    fn default() -> Self {
+        Self::PlatformDefault
+    }
+}
+
+/// How to resolve an asset that is provided by more than one mod
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ModConflictPolicy {
+    /// The asset is loaded from the mod with the highest priority in
+    /// [`AssetIoZipConfig::mod_order`]
+    Priority,
+    /// The asset is loaded from the mod that was found first, ignoring
+    /// [`AssetIoZipConfig::mod_order`]. Mods are found in the order of
+    /// [`AssetIoZipConfig::mod_dirs`] and then by name.
+    FirstWins,
+    /// Loading the asset fails with an error
+    Error,
+}
+
+impl Default for ModConflictPolicy {
+    fn default() -> Self {
+        Self::Priority
+    }
+}
+
+/// A region of a file that contains a bundle, set with [`AssetIoZipConfig::bundle_region`]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BundleRegion {
+    /// The offset of the first byte of the bundle in the file
+    pub offset: u64,
+    /// The length of the bundle in bytes
+    pub length: u64,
+}
+
+/// What other processes may do with a bundle file while it is open, set with
+/// [`AssetIoZipConfig::bundle_sharing`]
+///
+/// This only has an effect on Windows, where it sets the share mode that the bundle file is opened
+/// with. Other platforms always let files be replaced while they are open.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BundleFileSharing {
+    /// Let other processes read, write, delete, and rename the bundle file, so that an external
+    /// patcher can replace it while the game is running. Unmount the bundle and mount it again to
+    /// load assets from the new file.
+    AllowReplace,
+    /// Only let other processes read the bundle file, which guarantees that it isn't modified or
+    /// replaced while it is mounted
+    ReadOnly,
+}
+
+impl Default for BundleFileSharing {
+    fn default() -> Self {
+        Self::AllowReplace
+    }
+}
+
+/// Supplies the salt that the keys of the obfuscated entries of a bundle are derived from, set
+/// with [`AssetIoZipConfig::key_provider`]
+///
+/// This keeps key management out of the crate: the salt can be fetched from an OS keychain or a
+/// server handshake, or split across constants in the game, instead of being read from the bundle.
+/// Bundles written with the bundler's `entry_salt` option don't store their salt at all, so they
+/// can only be read with a key provider that supplies it.
+///
+/// This only has an effect when the `obfuscation` feature is enabled.
+///
+/// ```
+/// # use std::sync::Arc;
+/// # use bevy_assetio_zip::{AssetIoZipConfig, KeyProvider};
+/// struct SplitSalt;
+///
+/// impl KeyProvider for SplitSalt {
+///     fn salt(&self, bundle: &str, stored: &[u8]) -> Option<Vec<u8>> {
+///         if bundle == "assets" && stored.is_empty() {
+///             Some([&b"split"[..], &b"-salt"[..]].concat())
+///         } else {
+///             None
+///         }
+///     }
+/// }
+///
+/// let config = AssetIoZipConfig {
+///     key_provider: Some(Arc::new(SplitSalt)),
+///     ..Default::default()
+/// };
+/// ```
+pub trait KeyProvider: Send + Sync + 'static {
+    /// Get the salt to derive the entry keys of the bundle with the given name from, or `None` to
+    /// use the salt stored in the bundle
+    ///
+    /// `stored` is the salt stored in the bundle, which is empty for bundles that don't store it
+    /// and for bundles that XOR every entry with the same byte. This is called every time an
+    /// obfuscated entry of the bundle is read, so slow lookups should be cached by the provider.
+    fn salt(&self, bundle: &str, stored: &[u8]) -> Option<Vec<u8>>;
+}
+
+impl std::fmt::Debug for dyn KeyProvider {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("KeyProvider")
+    }
+}
+
+/// An asset that is provided by more than one mod
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ModConflict {
+    /// The path of the asset
+    pub path: PathBuf,
+    /// The names of the mods that provide the asset, highest priority first
+    pub providers: Vec<String>,
+    /// The name of the mod that the asset is loaded from, or `None` if the conflict is resolved
+    /// with [`ModConflictPolicy::Error`]
+    pub winner: Option<String>,
+}
+
+/// Resource listing the assets that are provided by more than one of the mounted mods
+///
+/// This is inserted by the [`AssetIoZipPlugin`] after the mods are mounted.
+#[derive(Debug, Clone, Default)]
+pub struct ModConflictReport {
+    /// The conflicting assets, sorted by path
+    pub conflicts: Vec<ModConflict>,
+}
+
+impl ModConflictReport {
+    /// Get the conflict for the given asset path, if there is one
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&ModConflict> {
+        let path = path.as_ref();
+        self.conflicts.iter().find(|x| x.path == path)
+    }
+
+    /// Whether or not there are no conflicts
+    pub fn is_empty(&self) -> bool {
+        self.conflicts.is_empty()
+    }
+}
+
+/// Where a loaded asset was read from
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AssetSource {
+    /// The asset was read from the asset bundle with the given name
+    Bundle(String),
+    /// The asset was read from the [`override_dir`][AssetIoZipConfig::override_dir]
+    Override,
+    /// The asset was not in the bundle and was read by the fallback asset IO
+    Fallback,
+}
+
+/// Event sent by the [`AssetIoZipPlugin`] for every asset that has been successfully loaded
+///
+/// These can be used to build loading profilers or to detect load time regressions as the asset
+/// bundle grows.
+#[derive(Debug, Clone)]
+pub struct AssetLoadEvent {
+    /// The path of the asset that was loaded
+    pub path: PathBuf,
+    /// Where the asset was loaded from
+    pub source: AssetSource,
+    /// The size of the loaded asset in bytes
+    pub bytes: usize,
+    /// How long it took to load the asset in microseconds
+    pub micros: u128,
+}
+
+/// The data of a loaded asset and where it was read from
+type LoadedAsset = (Vec<u8>, AssetSource);
+
+/// Information about an asset bundle mounted by the [`AssetIoZip`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MountedBundle {
+    /// The name of the bundle
+    ///
+    /// This is the file name of the bundle without the extension. For mods it is the path to the
+    /// bundle relative to its mod directory.
+    pub name: String,
+    /// The path to the bundle file, if it is on the filesystem
+    pub path: Option<PathBuf>,
+    /// Whether or not the bundle is a mod
+    pub is_mod: bool,
+}
+
+/// The version information of an asset in a mounted bundle
+///
+/// This is returned by [`ZipAssetIo::asset_metadata`] and can be used to invalidate caches of data
+/// derived from assets, such as GPU texture caches, when the bundle is updated.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetMetadata {
+    /// The name of the bundle that the asset is loaded from
+    pub bundle: String,
+    /// The modification time of the asset file when it was bundled
+    ///
+    /// This is `None` if the bundle was built without `asset-metadata`.
+    pub modified: Option<SystemTime>,
+    /// The CRC-32 checksum of the bundled asset data
+    ///
+    /// This is `None` if the bundle was built without `asset-metadata`.
+    pub hash: Option<u32>,
+}
 
 /// A custom [`AssetIo`] implementation that can load assets from an optionally obfuscated zip file
 /// and that will fall back to the default asset loader when assets are not found in the zip.
-struct AssetIoZip {
-    fallback_io: Box<dyn AssetIo>,
-    config: AssetIoZipConfig,
+///
+/// This is a [`ZipAssetIo`] composed with a fallback asset IO, and it dereferences to the
+/// [`ZipAssetIo`]. The [`AssetIoZipPlugin`] inserts a handle to the asset IO as a resource so that
+/// it can be used to [`prefetch`][ZipAssetIo::prefetch] assets and query the mounted bundles from
+/// systems.
+#[derive(Clone)]
+pub struct AssetIoZip {
+    zip_io: ZipAssetIo,
+    fallback_io: Arc<dyn AssetIo>,
+    /// Channel used to send load events from the IO tasks to the Bevy world
+    load_events: (Sender<AssetLoadEvent>, Receiver<AssetLoadEvent>),
+    /// The log that every loaded asset is recorded in, if one is configured
+    #[cfg(not(target_arch = "wasm32"))]
+    audit_log: Option<Arc<AuditLog>>,
+    /// The watcher of the loose assets, if
+    /// [`prefer_filesystem`][AssetIoZipConfig::prefer_filesystem] is set and there is an asset dir
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    loose_watcher: Option<Arc<LooseAssetWatcher>>,
 }
 
 impl AssetIoZip {
-    fn new(fallback_io: Box<dyn AssetIo>, config: AssetIoZipConfig) -> Self {
-        // let asset_reader = Self::get_asset_bundle(&config.file_name);
+    /// Create an asset IO that loads assets from the bundles of `zip_io`, and from `fallback_io`
+    /// when they are not found in any bundle
+    ///
+    /// If the [`audit_log`][AssetIoZipConfig::audit_log] of the config of `zip_io` is set but
+    /// can't be opened, a warning is logged and assets are loaded without being recorded.
+    pub fn new(zip_io: ZipAssetIo, fallback_io: Box<dyn AssetIo>) -> Self {
+        #[cfg(not(target_arch = "wasm32"))]
+        let audit_log =
+            zip_io
+                .config
+                .audit_log
+                .as_ref()
+                .and_then(|path| match AuditLog::open(path) {
+                    Ok(log) => Some(Arc::new(log)),
+                    Err(e) => {
+                        warn!("Could not open asset audit log {}: {}", path.display(), e);
+                        None
+                    }
+                });
+
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        let loose_watcher = match &zip_io.asset_dir {
+            Some(asset_dir) if zip_io.config.prefer_filesystem => {
+                Some(Arc::new(LooseAssetWatcher::new(asset_dir.clone())))
+            }
+            _ => None,
+        };
+
         Self {
-            fallback_io,
-            config,
-            // asset_reader,
+            zip_io,
+            fallback_io: fallback_io.into(),
+            load_events: crossbeam_channel::unbounded(),
+            #[cfg(not(target_arch = "wasm32"))]
+            audit_log,
+            #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+            loose_watcher,
         }
     }
 
-    fn bundle(&self) -> Option<ZipArchive<Box<dyn FileReader>>> {
-        let exe_dir = std::env::current_exe().expect("Could not obtain current exe path");
-        let exe_dir = exe_dir
-            .parent()
-            .expect("Current exe has no parent dir")
-            .to_str()
-            .expect("Exe path contains invalid unicode");
-        let file_path_bin =
-            PathBuf::from(format!("{}/{}.{}", exe_dir, self.config.file_name, "bin"));
-        let file_path_zip =
-            PathBuf::from(format!("{}/{}.{}", exe_dir, self.config.file_name, "zip"));
-
-        let (path, obfuscate) = if file_path_bin.exists() {
-            (file_path_bin, true)
-        } else if file_path_zip.exists() {
-            (file_path_zip, false)
-        } else {
-            return None;
+    async fn load_path_with_source(&self, path: &Path) -> Result<LoadedAsset, AssetIoError> {
+        if self.config.prefer_filesystem {
+            match self.load_loose(path).await {
+                Err(AssetIoError::NotFound(_)) => (),
+                result => return result,
+            }
+        }
+
+        if let Some(result) = self.zip_io.load_with_source(path).await {
+            return result;
+        }
+
+        if self.config.prefer_filesystem {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+
+        self.load_loose(path).await
+    }
+
+    /// Load an asset from the fallback asset IO
+    async fn load_loose(&self, path: &Path) -> Result<LoadedAsset, AssetIoError> {
+        let path = &self.zip_io.resolve_variant(path);
+
+        let bytes = match self.fallback_io.load_path(path).await {
+            Err(AssetIoError::NotFound(_)) if self.config.gzip_fallback => {
+                self.load_gzipped(path).await?
+            }
+            result => result?,
         };
 
-        let file = OpenOptions::new().read(true).open(path).ok()?;
-        let reader: Box<dyn FileReader> = if obfuscate {
-            Box::new(Xor::new(file))
-        } else {
-            Box::new(file)
+        Ok((bytes, AssetSource::Fallback))
+    }
+
+    /// Get the paths of the assets whose loose files changed since the last call
+    #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+    pub(crate) fn changed_loose_assets(&self) -> Vec<PathBuf> {
+        self.loose_watcher
+            .as_ref()
+            .map(|x| x.changed())
+            .unwrap_or_default()
+    }
+
+    /// Load `<path>.gz` from the fallback asset IO and decompress it
+    async fn load_gzipped(&self, path: &Path) -> Result<Vec<u8>, AssetIoError> {
+        let mut gz_path = path.as_os_str().to_owned();
+        gz_path.push(".gz");
+
+        let compressed = match self.fallback_io.load_path(Path::new(&gz_path)).await {
+            Err(AssetIoError::NotFound(_)) => return Err(AssetIoError::NotFound(path.to_owned())),
+            result => result?,
         };
 
-        Some(ZipArchive::new(Box::new(BufReader::new(reader)) as Box<dyn FileReader>).ok()?)
+        let mut bytes = Vec::new();
+        GzDecoder::new(compressed.as_slice()).read_to_end(&mut bytes)?;
+
+        Ok(bytes)
+    }
+}
+
+impl Deref for AssetIoZip {
+    type Target = ZipAssetIo;
+
+    fn deref(&self) -> &ZipAssetIo {
+        &self.zip_io
     }
 }
 
 impl AssetIo for AssetIoZip {
     fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
         Box::pin(async move {
-            if let Some(mut asset_bundle) = self.bundle() {
-                let has_file = asset_bundle
-                    .by_name(path.to_str().expect("non-unicode filename"))
-                    .ok()
-                    .is_some();
-                if has_file {
-                    let mut file = asset_bundle
-                        .by_name(path.to_str().expect("non-unicode filename"))
-                        .unwrap();
-                    let mut buf = Vec::with_capacity(file.size() as usize);
-                    file.read_to_end(&mut buf)?;
-
-                    Ok(buf)
-                } else {
-                    self.fallback_io.load_path(path).await
+            let start = Instant::now();
+            let (bytes, source) = self.load_path_with_source(path).await?;
+
+            #[cfg(not(target_arch = "wasm32"))]
+            if let Some(audit_log) = &self.audit_log {
+                if let Err(e) = audit_log.record(path, &source, &bytes) {
+                    warn!(
+                        "Could not record {} in the asset audit log: {}",
+                        path.display(),
+                        e
+                    );
                 }
-            } else {
-                self.fallback_io.load_path(path).await
             }
+
+            // The receiver is owned by this struct so sending cannot fail
+            self.load_events
+                .0
+                .send(AssetLoadEvent {
+                    path: path.to_owned(),
+                    source,
+                    bytes: bytes.len(),
+                    micros: start.elapsed().as_micros(),
+                })
+                .ok();
+
+            Ok(bytes)
         })
     }
 
@@ -240,22 +1664,65 @@ impl AssetIo for AssetIoZip {
         &self,
         path: &Path,
     ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
-        self.fallback_io.read_directory(path)
+        let mut children = self
+            .zip_io
+            .bundle_dir_children(path)
+            .into_iter()
+            .map(PathBuf::from)
+            .collect::<BTreeSet<_>>();
+        match self.fallback_io.read_directory(path) {
+            Ok(fallback_children) => children.extend(fallback_children),
+            Err(e) if children.is_empty() => return Err(e),
+            Err(_) => (),
+        }
+
+        Ok(Box::new(children.into_iter()))
     }
 
     fn is_directory(&self, path: &Path) -> bool {
-        self.fallback_io.is_directory(path)
+        // Let `BundleAssetServerExt` find this asset IO through the asset server
+        if path == Path::new(server_ext::PROBE_PATH) {
+            server_ext::answer_probe(self);
+            return false;
+        }
+
+        self.zip_io.is_directory(path) || self.fallback_io.is_directory(path)
     }
 
     fn watch_path_for_changes(&self, path: &Path) -> Result<(), AssetIoError> {
+        // Bevy 0.4 only reloads the files watched by a bare `FileAssetIo`, so the loose files are
+        // watched by our own watcher when they are preferred over the bundles
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        if let Some(watcher) = &self.loose_watcher {
+            return watcher.watch(path);
+        }
+
         // Note that we cannot watch for changes inside of the zip file, so we just defer to the
-        // default change watcher.
+        // default change watcher. Assets that only exist in the bundle have no loose file to watch.
+        if let Some(asset_dir) = &self.zip_io.asset_dir {
+            if !asset_dir.join(path).exists() {
+                return Ok(());
+            }
+        }
+
         self.fallback_io.watch_path_for_changes(path)
     }
 
     fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        if let Some(watcher) = &self.loose_watcher {
+            return watcher.start();
+        }
+
         // Note that we cannot watch for changes inside of the zip file, so we just defer to the
-        // default change watcher.
+        // default change watcher. When the game is distributed with only a bundle there is no
+        // asset folder to watch and watching is a no-op.
+        if let Some(asset_dir) = &self.zip_io.asset_dir {
+            if !asset_dir.is_dir() {
+                return Ok(());
+            }
+        }
+
         self.fallback_io.watch_for_changes()
     }
 }
@@ -269,25 +1736,153 @@ impl Plugin for AssetIoZipPlugin {
         let task_pool = app
             .resources()
             .get::<bevy::tasks::IoTaskPool>()
-            .expect("`IoTaskPool` resource not found.")
+            .expect(
+                "`IoTaskPool` resource not found. Add the `AssetIoZipPlugin` after the \
+                `CorePlugin` and before the `AssetPlugin`.",
+            )
             .0
             .clone();
 
         let asset_io = {
-            // The platform default asset io requires a reference to the app builder to find its
-            // configuration
-            let default_assetio = bevy::asset::create_platform_default_asset_io(app);
-
             let config = app
                 .resources()
                 .get::<AssetIoZipConfig>()
                 .map(|x| (*x).clone())
+                .unwrap_or_else(|| {
+                    debug!("No `AssetIoZipConfig` resource found, using the default config");
+                    AssetIoZipConfig::default()
+                });
+
+            // Let players and server admins relocate the bundles without rebuilding the game
+            #[cfg(all(feature = "runtime-config", not(target_arch = "wasm32")))]
+            let config = {
+                let mut config = config;
+                let path = ZipAssetIo::exe_dir().join(RUNTIME_CONFIG_FILE);
+                // The runtime config is next to the executable, which isn't an explicit path
+                if !config.explicit_paths_only && !zip_io::env_flag(EXPLICIT_PATHS_ENV_VAR) {
+                    match config.apply_runtime_config(&path) {
+                        Ok(true) if config.verbose => {
+                            bevy::log::info!("Applied runtime config {}", path.display())
+                        }
+                        Ok(_) => (),
+                        Err(e) => error!("{}. Ignoring it.", e),
+                    }
+                }
+                config
+            };
+
+            // Report misconfiguration up front instead of letting it look like missing assets
+            for problem in config.validate() {
+                error!("Invalid `AssetIoZipConfig`: {}", problem);
+            }
+
+            // Redirect the asset folder before anything reads the asset server settings
+            if let Some(asset_dir) = &config.asset_dir {
+                let settings = AssetServerSettings {
+                    asset_folder: asset_dir.to_string_lossy().into_owned(),
+                };
+                #[cfg(feature = "bevy-unstable")]
+                app.insert_resource(settings);
+                #[cfg(not(feature = "bevy-unstable"))]
+                app.add_resource(settings);
+            }
+
+            let fallback_io: Box<dyn AssetIo> = match &config.fallback {
+                // The platform default asset io requires a reference to the app builder to find its
+                // configuration
+                FallbackAssetIo::PlatformDefault => {
+                    bevy::asset::create_platform_default_asset_io(app)
+                }
+                FallbackAssetIo::Noop => Box::new(NoopAssetIo),
+                FallbackAssetIo::Memory(memory_io) => Box::new(memory_io.clone()),
+            };
+
+            // Find the asset folder so that we can search for bundles in it
+            #[cfg(all(not(target_arch = "wasm32"), not(target_os = "android")))]
+            let asset_dir = {
+                let asset_folder = app
+                    .resources()
+                    .get::<AssetServerSettings>()
+                    .map(|x| x.asset_folder.clone())
+                    .unwrap_or_else(|| AssetServerSettings::default().asset_folder);
+                Some(FileAssetIo::get_root_path().join(asset_folder))
+            };
+            #[cfg(target_arch = "wasm32")]
+            let asset_dir = Some(PathBuf::from(
+                app.resources()
+                    .get::<AssetServerSettings>()
+                    .map(|x| x.asset_folder.clone())
+                    .unwrap_or_else(|| AssetServerSettings::default().asset_folder),
+            ));
+            #[cfg(target_os = "android")]
+            let asset_dir = None;
+
+            let sources = app
+                .resources()
+                .get::<BundleSources>()
+                .map(|x| (*x).clone())
                 .unwrap_or_default();
 
             // Create the custom asset io instance
-            AssetIoZip::new(default_assetio, config)
+            let zip_io = ZipAssetIo::new(config, sources, asset_dir, task_pool.clone());
+            AssetIoZip::new(zip_io, fallback_io)
         };
 
+        // Report the progress of downloading the bundle in browser builds
+        #[cfg(target_arch = "wasm32")]
+        app.add_event::<BundleDownloadProgress>()
+            .add_system(send_download_progress_events.system());
+
+        // Expose the asset io as a resource so that systems can prefetch assets
+        #[cfg(feature = "bevy-unstable")]
+        app.insert_resource(asset_io.clone());
+        #[cfg(not(feature = "bevy-unstable"))]
+        app.add_resource(asset_io.clone());
+
+        // Expose the asset groups so that systems can preload levels
+        #[cfg(feature = "bevy-unstable")]
+        app.insert_resource(AssetGroups::new((*asset_io).clone()));
+        #[cfg(not(feature = "bevy-unstable"))]
+        app.add_resource(AssetGroups::new((*asset_io).clone()));
+
+        // Spread the decompression of streamed levels across frames
+        #[cfg(all(feature = "bevy-unstable", not(target_arch = "wasm32")))]
+        app.insert_resource(LevelStreamer::default());
+        #[cfg(all(not(feature = "bevy-unstable"), not(target_arch = "wasm32")))]
+        app.add_resource(LevelStreamer::default());
+        #[cfg(not(target_arch = "wasm32"))]
+        app.add_system(stream_level_assets.system());
+
+        // Reload the loose assets that shadow the bundles when they change
+        #[cfg(all(feature = "hot-reload", not(target_arch = "wasm32")))]
+        app.add_system(reload_changed_loose_assets.system());
+
+        // Expose the assets that are provided by more than one mod
+        #[cfg(feature = "bevy-unstable")]
+        app.insert_resource(asset_io.mod_conflicts());
+        #[cfg(not(feature = "bevy-unstable"))]
+        app.add_resource(asset_io.mod_conflicts());
+
+        // Keep the metadata of the mounted assets cached for systems that poll it every frame
+        #[cfg(feature = "bevy-unstable")]
+        app.insert_resource(EntryMetadataCache::default());
+        #[cfg(not(feature = "bevy-unstable"))]
+        app.add_resource(EntryMetadataCache::default());
+        app.add_system(update_entry_metadata_cache.system());
+
+        // Forward asset load events from the IO tasks to the Bevy event queue
+        #[cfg(feature = "bevy-unstable")]
+        app.insert_resource(LoadTimeHistogram::default())
+            .insert_resource(AssetSourceLog::default());
+        #[cfg(not(feature = "bevy-unstable"))]
+        app.add_resource(LoadTimeHistogram::default())
+            .add_resource(AssetSourceLog::default());
+        app.add_event::<AssetLoadEvent>()
+            .add_event::<AssetLoadProgress>()
+            .add_system(send_load_events.system())
+            .add_system(send_progress_events.system())
+            .add_system(log_load_time_summary.system());
+
         // The asset server is constructed and added the resource manager
         #[cfg(feature = "bevy-unstable")]
         app.insert_resource(AssetServer::new(asset_io, task_pool));
@@ -295,3 +1890,76 @@ impl Plugin for AssetIoZipPlugin {
         app.add_resource(AssetServer::new(asset_io, task_pool));
     }
 }
+
+/// System that sends the [`AssetLoadEvent`]s collected by the [`AssetIoZip`] and records them in
+/// the [`LoadTimeHistogram`] and, if enabled, the [`AssetSourceLog`]
+fn send_load_events(
+    asset_io: Res<AssetIoZip>,
+    mut events: ResMut<Events<AssetLoadEvent>>,
+    mut histogram: ResMut<LoadTimeHistogram>,
+    mut source_log: ResMut<AssetSourceLog>,
+) {
+    for event in asset_io.load_events.1.try_iter() {
+        histogram.record(&event);
+        if asset_io.config.record_asset_sources {
+            source_log.record(&event);
+        }
+        events.send(event);
+    }
+}
+
+/// System that rebuilds the [`EntryMetadataCache`] when the mounted bundles have changed
+fn update_entry_metadata_cache(asset_io: Res<AssetIoZip>, mut cache: ResMut<EntryMetadataCache>) {
+    if cache.is_stale(asset_io.mount_generation()) {
+        *cache = asset_io.entry_metadata_cache();
+    }
+}
+
+/// System that sends the [`AssetLoadProgress`] events collected while decompressing large assets
+fn send_progress_events(asset_io: Res<AssetIoZip>, mut events: ResMut<Events<AssetLoadProgress>>) {
+    for event in asset_io.progress_events.1.try_iter() {
+        events.send(event);
+    }
+}
+
+/// System that logs a summary of the [`LoadTimeHistogram`] every
+/// [`load_time_summary_interval`][AssetIoZipConfig::load_time_summary_interval]
+fn log_load_time_summary(
+    asset_io: Res<AssetIoZip>,
+    histogram: Res<LoadTimeHistogram>,
+    mut last_summary: Local<Option<(Instant, u64)>>,
+) {
+    let interval = match asset_io.config.load_time_summary_interval {
+        Some(interval) => interval,
+        None => return,
+    };
+    let count = histogram.extensions.values().map(|x| x.count).sum::<u64>();
+    let (time, last_count) = *last_summary.get_or_insert((Instant::now(), count));
+
+    if time.elapsed() >= interval {
+        if count != last_count {
+            bevy::log::info!("Asset load times by extension:\n{}", *histogram);
+        }
+        *last_summary = Some((Instant::now(), count));
+    }
+}
+
+/// System that collects the finished reads of the [`LevelStreamer`] and starts the reads of the
+/// assets queued in it within its per-frame budget
+#[cfg(not(target_arch = "wasm32"))]
+fn stream_level_assets(asset_io: Res<AssetIoZip>, mut streamer: ResMut<LevelStreamer>) {
+    if !streamer.is_done() {
+        streamer.stream_frame(&asset_io);
+    }
+}
+
+/// System that sends the [`BundleDownloadProgress`] events collected while downloading the bundle
+#[cfg(target_arch = "wasm32")]
+fn send_download_progress_events(
+    asset_io: Res<AssetIoZip>,
+    mut events: ResMut<Events<BundleDownloadProgress>>,
+) {
+    for event in asset_io.web_bundle.progress_events.1.try_iter() {
+        events.send(event);
+    }
+}
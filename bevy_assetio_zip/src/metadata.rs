@@ -0,0 +1,55 @@
+//! Support for reading the modification times and content hashes of assets recorded by the bundler
+
+use std::{
+    collections::HashMap,
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+/// The zip entry that contains the modification times and content hashes of the bundled assets
+pub(crate) const METADATA_ENTRY: &str = ".metadata";
+
+/// The modification time and content hash of an asset recorded by the bundler
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RecordedMetadata {
+    pub modified: SystemTime,
+    pub hash: u32,
+}
+
+/// Index mapping asset paths to their recorded metadata
+#[derive(Debug, Default)]
+pub(crate) struct MetadataIndex(HashMap<String, RecordedMetadata>);
+
+impl MetadataIndex {
+    /// Parse the metadata index written by the bundler
+    ///
+    /// Each line of the index has the form `<modified>\t<hash>\t<path>`, where `modified` is in
+    /// seconds since the Unix epoch and `hash` is the hex CRC-32 of the bundled asset. Malformed
+    /// lines are skipped.
+    pub fn parse(index: &str) -> Self {
+        Self(
+            index
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, '\t');
+                    let modified = fields.next()?.parse().ok()?;
+                    let hash = u32::from_str_radix(fields.next()?, 16).ok()?;
+                    let path = fields.next()?;
+
+                    Some((
+                        path.to_owned(),
+                        RecordedMetadata {
+                            modified: SystemTime::UNIX_EPOCH + Duration::from_secs(modified),
+                            hash,
+                        },
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the recorded metadata of the given asset
+    pub fn get(&self, path: &Path) -> Option<RecordedMetadata> {
+        self.0.get(path.to_str()?).copied()
+    }
+}
@@ -0,0 +1,68 @@
+//! A cache of the metadata of the assets in the mounted bundles
+
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+};
+
+/// The cached metadata of an asset in the mounted bundles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CachedEntryMetadata {
+    /// The name of the bundle that the asset is loaded from
+    pub bundle: String,
+    /// The uncompressed size of the asset in bytes
+    pub size: u64,
+    /// The CRC-32 checksum of the bundled asset data
+    ///
+    /// This is `None` if the bundle was built without `asset-metadata`.
+    pub hash: Option<u32>,
+}
+
+/// Resource caching the size and hash of every asset in the mounted bundles, taken from the highest
+/// priority bundle that contains each asset
+///
+/// This is inserted by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] and rebuilt only when
+/// bundles are mounted, unmounted, or written to, so that systems such as download size displays
+/// and mod listings can read it every frame without reading the bundles. Use
+/// [`ZipAssetIo::entry_metadata_cache`][crate::ZipAssetIo::entry_metadata_cache] to build one
+/// outside of a Bevy app.
+#[derive(Debug, Clone, Default)]
+pub struct EntryMetadataCache {
+    /// The metadata of each asset, sorted by path
+    pub entries: BTreeMap<PathBuf, CachedEntryMetadata>,
+    /// The mount generation of the asset IO that the cache was built for
+    pub(crate) generation: u64,
+    /// Whether or not every mounted bundle could be read when the cache was built
+    pub(crate) complete: bool,
+}
+
+impl EntryMetadataCache {
+    /// Get the cached metadata of the given asset, if it is in a mounted bundle
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&CachedEntryMetadata> {
+        self.entries.get(path.as_ref())
+    }
+
+    /// Get the total size in bytes of the assets loaded from the bundle with the given name
+    pub fn bundle_size(&self, bundle: &str) -> u64 {
+        self.entries
+            .values()
+            .filter(|x| x.bundle == bundle)
+            .map(|x| x.size)
+            .sum()
+    }
+
+    /// Get the total size in bytes of all of the cached assets
+    pub fn total_size(&self) -> u64 {
+        self.entries.values().map(|x| x.size).sum()
+    }
+
+    /// Whether or not the cache doesn't contain any assets
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Whether or not the cache is out of date for the given mount generation
+    pub(crate) fn is_stale(&self, generation: u64) -> bool {
+        self.generation != generation || !self.complete
+    }
+}
@@ -0,0 +1,152 @@
+//! Discovery of mod bundles in the configured mod directories
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use crate::{bundle::Bundle, ModConflict, ModConflictPolicy, ModConflictReport};
+
+/// A mod bundle found in one of the mod directories
+pub(crate) struct DiscoveredMod {
+    /// The path of the bundle relative to its mod dir, without the extension, using `/` as the
+    /// separator
+    pub name: String,
+    pub path: PathBuf,
+}
+
+/// Get whether the file at the given path is a bundle based on its extension
+fn is_bundle(path: &Path) -> bool {
+    match path.extension().and_then(|x| x.to_str()) {
+        Some("zip") => true,
+        #[cfg(feature = "obfuscation")]
+        Some("bin") => true,
+        #[cfg(feature = "sqlite")]
+        Some("sqlite") => true,
+        _ => false,
+    }
+}
+
+/// Find the bundles directly inside of the given dir, or inside of its immediate sub-directories,
+/// which is how Steam Workshop style content directories lay out items
+fn find_bundles(mod_dir: &Path) -> Vec<DiscoveredMod> {
+    let mut mods = Vec::new();
+
+    let mut visit = |dir: &Path, prefix: Option<&str>| -> Vec<PathBuf> {
+        let mut sub_dirs = Vec::new();
+        for entry in fs::read_dir(dir).into_iter().flatten().flatten() {
+            let path = entry.path();
+            if path.is_dir() {
+                sub_dirs.push(path);
+                continue;
+            }
+
+            if !is_bundle(&path) {
+                continue;
+            }
+            let stem = match path.file_stem().and_then(|x| x.to_str()) {
+                Some(stem) => stem,
+                None => continue,
+            };
+            let name = match prefix {
+                Some(prefix) => format!("{}/{}", prefix, stem),
+                None => stem.to_owned(),
+            };
+
+            mods.push(DiscoveredMod { name, path });
+        }
+        sub_dirs
+    };
+
+    let mut sub_dirs = visit(mod_dir, None);
+    sub_dirs.sort();
+    for sub_dir in sub_dirs {
+        if let Some(prefix) = sub_dir.file_name().and_then(|x| x.to_str()) {
+            let prefix = prefix.to_owned();
+            visit(&sub_dir, Some(&prefix));
+        }
+    }
+
+    // Sort the bundles in each directory by name so that discovery order doesn't depend on the
+    // filesystem
+    mods.sort_by(|a, b| a.name.cmp(&b.name));
+
+    mods
+}
+
+/// Get the position of the mod in the user-defined mod order, if it is listed
+fn order_position(order: &[String], name: &str) -> Option<usize> {
+    order
+        .iter()
+        .position(|x| x == name || name.starts_with(&format!("{}/", x)))
+}
+
+/// Find the mod bundles in the given mod dirs, sorted by priority, highest first
+///
+/// With the [`Priority`][ModConflictPolicy::Priority] policy, mods listed in `order` come first, in
+/// the given order, followed by the rest of the mods sorted by name. Otherwise mods are returned in
+/// the order they were found: in the order of the mod dirs and then by name.
+pub(crate) fn discover_mods(
+    mod_dirs: &[PathBuf],
+    order: &[String],
+    policy: ModConflictPolicy,
+) -> Vec<DiscoveredMod> {
+    let mut mods = mod_dirs
+        .iter()
+        .flat_map(|dir| find_bundles(dir))
+        .collect::<Vec<_>>();
+
+    if policy == ModConflictPolicy::Priority {
+        mods.sort_by(|a, b| {
+            match (
+                order_position(order, &a.name),
+                order_position(order, &b.name),
+            ) {
+                (Some(a), Some(b)) => a.cmp(&b),
+                (Some(_), None) => std::cmp::Ordering::Less,
+                (None, Some(_)) => std::cmp::Ordering::Greater,
+                (None, None) => a.name.cmp(&b.name),
+            }
+        });
+    }
+
+    mods
+}
+
+/// Find the assets that are provided by more than one of the given mounted bundles
+///
+/// The bundles must be in priority order. Bundles that are not mods are ignored.
+pub(crate) fn find_conflicts(
+    bundles: &[Arc<Bundle>],
+    policy: ModConflictPolicy,
+) -> ModConflictReport {
+    let mut providers: BTreeMap<String, Vec<String>> = BTreeMap::new();
+
+    for bundle in bundles.iter().filter(|x| x.is_mod) {
+        let mut archive = match bundle.open() {
+            Some(archive) => archive,
+            None => continue,
+        };
+
+        for path in bundle.asset_paths(&mut archive) {
+            providers.entry(path).or_default().push(bundle.name.clone());
+        }
+    }
+
+    ModConflictReport {
+        conflicts: providers
+            .into_iter()
+            .filter(|(_, providers)| providers.len() > 1)
+            .map(|(path, providers)| ModConflict {
+                path: path.into(),
+                winner: match policy {
+                    ModConflictPolicy::Error => None,
+                    _ => providers.first().cloned(),
+                },
+                providers,
+            })
+            .collect(),
+    }
+}
@@ -0,0 +1,72 @@
+//! Writing assets into the writable overlay bundle
+
+use std::{
+    fs::{File, OpenOptions},
+    io::{self, Write},
+    path::Path,
+};
+
+use zip::{result::ZipError, write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+/// Write the asset with the given name into the overlay bundle at `bundle_path`
+///
+/// The bundle is created if it doesn't exist yet. New assets are appended to the bundle, while
+/// replacing an asset rewrites the bundle so that the old data doesn't take up space forever.
+pub(crate) fn write_asset(bundle_path: &Path, name: &str, data: &[u8]) -> io::Result<()> {
+    if let Some(parent) = bundle_path.parent() {
+        std::fs::create_dir_all(parent)?;
+    }
+
+    let existing = match File::open(bundle_path) {
+        Ok(file) => Some(ZipArchive::new(file).map_err(zip_error)?),
+        Err(e) if e.kind() == io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e),
+    };
+    #[cfg(feature = "deflate")]
+    let options = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    #[cfg(not(feature = "deflate"))]
+    let options = FileOptions::default().compression_method(CompressionMethod::Stored);
+
+    match existing {
+        // Append the asset to the bundle
+        Some(archive) if archive.file_names().all(|x| x != name) => {
+            drop(archive);
+            let file = OpenOptions::new()
+                .read(true)
+                .write(true)
+                .open(bundle_path)?;
+            let mut zip = ZipWriter::new_append(file).map_err(zip_error)?;
+            zip.start_file(name, options).map_err(zip_error)?;
+            zip.write_all(data)?;
+            zip.finish().map_err(zip_error)?;
+        }
+        // Write the bundle again next to the old one, leaving out the old copy of the asset, and
+        // replace the old bundle with it
+        existing => {
+            let temp_path = bundle_path.with_extension("tmp");
+            let mut zip = ZipWriter::new(File::create(&temp_path)?);
+            if let Some(mut archive) = existing {
+                for i in 0..archive.len() {
+                    let file = archive.by_index(i).map_err(zip_error)?;
+                    if file.name() != name {
+                        zip.raw_copy_file(file).map_err(zip_error)?;
+                    }
+                }
+            }
+            zip.start_file(name, options).map_err(zip_error)?;
+            zip.write_all(data)?;
+            zip.finish().map_err(zip_error)?;
+            std::fs::rename(&temp_path, bundle_path)?;
+        }
+    }
+
+    Ok(())
+}
+
+/// Convert a zip error into an IO error
+fn zip_error(error: ZipError) -> io::Error {
+    match error {
+        ZipError::Io(e) => e,
+        e => io::Error::new(io::ErrorKind::InvalidData, e),
+    }
+}
@@ -0,0 +1,145 @@
+//! Support for reading small files that have been aggregated into packed blocks by the bundler
+
+use std::{
+    collections::{HashMap, HashSet, VecDeque},
+    path::Path,
+    sync::Arc,
+};
+
+/// The zip directory that the packed blocks and their index are stored in
+pub(crate) const PACK_DIR: &str = ".packs/";
+
+/// The zip entry that contains the index of the files in the packed blocks
+pub(crate) const PACK_INDEX_ENTRY: &str = ".packs/index";
+
+/// The number of decompressed blocks of a bundle to keep in memory while their files are read
+pub(crate) const MAX_CACHED_BLOCKS: usize = 4;
+
+/// Get the name of the zip entry containing the given packed block
+pub(crate) fn block_entry_name(block: usize) -> String {
+    format!("{}{}", PACK_DIR, block)
+}
+
+/// The location of a file inside of a packed block
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct PackedFile {
+    pub block: usize,
+    pub offset: usize,
+    pub length: usize,
+}
+
+/// Index mapping asset paths to their location in the packed blocks
+#[derive(Debug, Default)]
+pub(crate) struct PackIndex(HashMap<String, PackedFile>);
+
+impl PackIndex {
+    /// Parse the pack index written by the bundler
+    ///
+    /// Each line of the index has the form `<block>\t<offset>\t<length>\t<path>`. Malformed lines
+    /// are skipped.
+    pub fn parse(index: &str) -> Self {
+        Self(
+            index
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(4, '\t');
+                    let block = fields.next()?.parse().ok()?;
+                    let offset = fields.next()?.parse().ok()?;
+                    let length = fields.next()?.parse().ok()?;
+                    let path = fields.next()?;
+
+                    Some((
+                        path.to_owned(),
+                        PackedFile {
+                            block,
+                            offset,
+                            length,
+                        },
+                    ))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the paths of the assets in the packed blocks
+    pub fn paths(&self) -> impl Iterator<Item = &str> {
+        self.0.keys().map(String::as_str)
+    }
+
+    /// Get the location of the given asset in the packed blocks
+    pub fn get(&self, path: &Path) -> Option<PackedFile> {
+        self.0.get(path.to_str()?).copied()
+    }
+}
+
+/// A decompressed block and the files in it that haven't been read yet
+struct CachedBlock {
+    data: Arc<Vec<u8>>,
+    unread: HashSet<String>,
+}
+
+/// The packed blocks of a bundle that have been decompressed
+///
+/// A block is dropped once every file in it has been read, since the files are handed over to the
+/// asset server, and the least recently used block is dropped when more than
+/// [`MAX_CACHED_BLOCKS`] are cached, so that blocks whose files are never all loaded don't stay in
+/// memory forever.
+#[derive(Default)]
+pub(crate) struct PackBlockCache {
+    blocks: HashMap<usize, CachedBlock>,
+    /// The cached blocks, least recently used first
+    order: VecDeque<usize>,
+}
+
+impl PackBlockCache {
+    /// Get the data of the given file from its cached block, marking the file as read
+    pub fn read(&mut self, path: &str, packed: PackedFile) -> Option<Arc<Vec<u8>>> {
+        let cached = self.blocks.get_mut(&packed.block)?;
+        let data = cached.data.clone();
+        cached.unread.remove(path);
+        let read_all = cached.unread.is_empty();
+
+        self.order.retain(|&x| x != packed.block);
+        if read_all {
+            self.blocks.remove(&packed.block);
+        } else {
+            self.order.push_back(packed.block);
+        }
+
+        Some(data)
+    }
+
+    /// Cache a decompressed block and mark the given file in it as read
+    pub fn insert(
+        &mut self,
+        index: &PackIndex,
+        path: &str,
+        packed: PackedFile,
+        data: Arc<Vec<u8>>,
+    ) {
+        // Another thread decompressed the block at the same time
+        if self.read(path, packed).is_some() {
+            return;
+        }
+
+        let unread = index
+            .0
+            .iter()
+            .filter(|(other, file)| file.block == packed.block && other.as_str() != path)
+            .map(|(other, _)| other.clone())
+            .collect::<HashSet<_>>();
+        if unread.is_empty() {
+            return;
+        }
+
+        self.order.retain(|&x| x != packed.block);
+        while self.order.len() >= MAX_CACHED_BLOCKS {
+            if let Some(oldest) = self.order.pop_front() {
+                self.blocks.remove(&oldest);
+            }
+        }
+        self.order.push_back(packed.block);
+        self.blocks
+            .insert(packed.block, CachedBlock { data, unread });
+    }
+}
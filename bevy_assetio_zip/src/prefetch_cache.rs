@@ -0,0 +1,98 @@
+//! Keeping prefetched assets in memory until they are loaded
+
+use std::{
+    collections::{HashMap, VecDeque},
+    path::{Path, PathBuf},
+};
+
+use crate::{AssetSource, LoadedAsset};
+
+/// The assets that have been decompressed ahead of their loads, up to a maximum total size
+#[derive(Default)]
+pub(crate) struct PrefetchCache {
+    assets: HashMap<PathBuf, LoadedAsset>,
+    /// The cached assets, oldest first
+    order: VecDeque<PathBuf>,
+    /// The total size of the data of the cached assets
+    bytes: u64,
+    /// The total size to evict the oldest assets at, or `None` to keep every asset until it is
+    /// loaded
+    max_bytes: Option<u64>,
+}
+
+impl PrefetchCache {
+    /// Create a cache that holds up to `max_bytes` of asset data
+    pub fn new(max_bytes: Option<u64>) -> Self {
+        Self {
+            max_bytes,
+            ..Default::default()
+        }
+    }
+
+    /// Whether or not the given asset is cached
+    pub fn contains(&self, path: &Path) -> bool {
+        self.assets.contains_key(path)
+    }
+
+    /// Get the total size of the data of the cached assets
+    pub fn bytes(&self) -> u64 {
+        self.bytes
+    }
+
+    /// Cache an asset, evicting the oldest assets until the cache fits in its maximum size
+    ///
+    /// An asset that is larger than the maximum size on its own is not cached.
+    pub fn insert(&mut self, path: PathBuf, loaded: LoadedAsset) {
+        let len = loaded.0.len() as u64;
+        if matches!(self.max_bytes, Some(max) if len > max) {
+            return;
+        }
+        self.remove(&path);
+
+        while matches!(self.max_bytes, Some(max) if self.bytes + len > max) {
+            match self.order.pop_front() {
+                Some(oldest) => self.forget(&oldest),
+                None => break,
+            }
+        }
+
+        self.bytes += len;
+        self.order.push_back(path.clone());
+        self.assets.insert(path, loaded);
+    }
+
+    /// Take an asset out of the cache
+    pub fn remove(&mut self, path: &Path) -> Option<LoadedAsset> {
+        let loaded = self.assets.remove(path)?;
+        self.bytes -= loaded.0.len() as u64;
+        self.order.retain(|x| x != path);
+        Some(loaded)
+    }
+
+    /// Drop the assets read from the given source
+    pub fn remove_source(&mut self, source: &AssetSource) {
+        let paths = self
+            .assets
+            .iter()
+            .filter(|(_, (_, x))| x == source)
+            .map(|(path, _)| path.clone())
+            .collect::<Vec<_>>();
+        for path in paths {
+            self.remove(&path);
+        }
+    }
+
+    /// Drop every cached asset
+    pub fn clear(&mut self) {
+        self.assets.clear();
+        self.order.clear();
+        self.bytes = 0;
+    }
+
+    /// Drop an asset that has already been removed from the eviction order
+    fn forget(&mut self, path: &Path) {
+        if let Some((data, _)) = self.assets.remove(path) {
+            self.bytes -= data.len() as u64;
+        }
+    }
+}
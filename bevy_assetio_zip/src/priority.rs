@@ -0,0 +1,124 @@
+//! Priorities that order the reads of assets from the bundles
+
+use std::{
+    cmp::Ordering,
+    path::PathBuf,
+    sync::{Arc, Mutex},
+};
+
+use event_listener::Event;
+
+use crate::CancellationToken;
+
+/// How urgently an asset is needed, which orders the reads of assets from the bundles
+///
+/// Reads of assets wait until no reads with a higher priority are waiting or in progress, so that
+/// hero textures are decompressed before distant LODs when a level loads. Assets are loaded with
+/// the [`High`][Self::High] priority unless they are given another one with
+/// [`ZipAssetIo::set_load_priority`][crate::ZipAssetIo::set_load_priority] or
+/// [`ZipAssetIo::prefetch_with_priority`][crate::ZipAssetIo::prefetch_with_priority].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum LoadPriority {
+    /// Assets that aren't needed soon, such as distant LODs, which are only read when no assets
+    /// with a higher priority are waiting
+    Background,
+    /// Assets that are needed soon, which is the priority of assets that aren't given one
+    High,
+    /// Assets that are needed right away, such as hero textures, which are read before any others
+    Critical,
+}
+
+impl Default for LoadPriority {
+    fn default() -> Self {
+        Self::High
+    }
+}
+
+/// Orders the reads of assets, making reads wait for the reads with a higher priority
+#[derive(Default)]
+pub(crate) struct PriorityGate {
+    /// The number of reads waiting or in progress with each priority
+    pending: Mutex<[usize; 3]>,
+    /// Notified when a read finishes
+    finished: Event,
+}
+
+impl PriorityGate {
+    /// Wait until no reads with a higher priority than the given one are pending, returning a
+    /// guard that keeps the read pending until it is dropped
+    ///
+    /// The read is pending while it waits, so reads with a lower priority that start later wait for
+    /// it.
+    pub async fn enter(self: &Arc<Self>, priority: LoadPriority) -> PriorityGuard {
+        self.pending.lock().unwrap()[priority as usize] += 1;
+        let guard = PriorityGuard {
+            gate: self.clone(),
+            priority,
+        };
+
+        loop {
+            if !self.higher_pending(priority) {
+                return guard;
+            }
+
+            // Check again after listening so that we don't miss a read finishing in between
+            let listener = self.finished.listen();
+            if !self.higher_pending(priority) {
+                return guard;
+            }
+            listener.await;
+        }
+    }
+
+    /// Whether or not any reads with a higher priority than the given one are pending
+    fn higher_pending(&self, priority: LoadPriority) -> bool {
+        self.pending.lock().unwrap()[priority as usize + 1..]
+            .iter()
+            .any(|x| *x > 0)
+    }
+}
+
+/// Keeps a read pending in a [`PriorityGate`] until it is dropped
+pub(crate) struct PriorityGuard {
+    gate: Arc<PriorityGate>,
+    priority: LoadPriority,
+}
+
+impl Drop for PriorityGuard {
+    fn drop(&mut self) {
+        self.gate.pending.lock().unwrap()[self.priority as usize] -= 1;
+        self.gate.finished.notify(usize::MAX);
+    }
+}
+
+/// An asset waiting to be prefetched, ordered by priority and then by when it was queued
+pub(crate) struct QueuedPrefetch {
+    pub priority: LoadPriority,
+    /// The number of assets queued before this one, so that assets with the same priority are
+    /// prefetched in order
+    pub sequence: u64,
+    pub path: PathBuf,
+    pub token: Option<CancellationToken>,
+}
+
+impl PartialEq for QueuedPrefetch {
+    fn eq(&self, other: &Self) -> bool {
+        self.cmp(other) == Ordering::Equal
+    }
+}
+
+impl Eq for QueuedPrefetch {}
+
+impl PartialOrd for QueuedPrefetch {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for QueuedPrefetch {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.priority
+            .cmp(&other.priority)
+            .then_with(|| other.sequence.cmp(&self.sequence))
+    }
+}
@@ -0,0 +1,58 @@
+//! Reporting the progress of decompressing large assets
+
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+};
+
+/// The number of decompressed bytes between progress reports
+pub(crate) const PROGRESS_INTERVAL: u64 = 1024 * 1024;
+
+/// Event sent by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] while a large asset is
+/// decompressed from a bundle
+///
+/// These are only sent for assets of at least
+/// [`progress_event_size`][crate::AssetIoZipConfig::progress_event_size] bytes, about every
+/// megabyte, so that loading screens can show the real progress of loading huge level blobs. The
+/// last event for an asset has `bytes` equal to `total`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetLoadProgress {
+    /// The path of the asset that is being loaded
+    pub path: PathBuf,
+    /// The number of bytes of the asset that have been decompressed
+    pub bytes: u64,
+    /// The size of the asset in bytes
+    pub total: u64,
+}
+
+/// A reader that reports the number of bytes read from it every [`PROGRESS_INTERVAL`] bytes
+pub(crate) struct ProgressReader<'a, R> {
+    inner: R,
+    read: u64,
+    reported: u64,
+    report: &'a mut dyn FnMut(u64),
+}
+
+impl<'a, R> ProgressReader<'a, R> {
+    pub fn new(inner: R, report: &'a mut dyn FnMut(u64)) -> Self {
+        Self {
+            inner,
+            read: 0,
+            reported: 0,
+            report,
+        }
+    }
+}
+
+impl<R: Read> Read for ProgressReader<'_, R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.read += len as u64;
+        if self.read - self.reported >= PROGRESS_INTERVAL {
+            (self.report)(self.read);
+            self.reported = self.read;
+        }
+
+        Ok(len)
+    }
+}
@@ -0,0 +1,83 @@
+//! Passing the compressed data of bundle entries through to other machines without recompressing it
+
+use std::{
+    io::{self, Read},
+    path::PathBuf,
+};
+
+#[cfg(feature = "bzip2")]
+use bzip2_crate::read::BzDecoder;
+use flate2::read::DeflateDecoder;
+
+/// The data of an asset exactly as it is compressed in its bundle
+///
+/// This is returned by [`ZipAssetIo::raw_entry`][crate::ZipAssetIo::raw_entry] so that a game
+/// server can send assets to its clients without decompressing and recompressing them. The client
+/// gets the asset back with [`decompress`][Self::decompress], which checks it against its
+/// checksum.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RawEntry {
+    /// The path of the asset
+    pub path: PathBuf,
+    /// How the data is compressed, which is `stored`, `deflated`, or `bzip2` like in the sidecar
+    /// index of a bundle
+    pub compression: String,
+    /// The compressed data
+    pub data: Vec<u8>,
+    /// The size of the asset once it is decompressed
+    pub size: u64,
+    /// The CRC-32 checksum of the decompressed asset
+    pub crc32: u32,
+}
+
+impl RawEntry {
+    /// Create a raw entry for an asset that is stored without compression
+    pub(crate) fn stored(path: PathBuf, data: Vec<u8>) -> Self {
+        Self {
+            path,
+            compression: "stored".into(),
+            size: data.len() as u64,
+            crc32: crc32fast::hash(&data),
+            data,
+        }
+    }
+
+    /// Decompress the asset, failing if its compression method isn't supported or if it doesn't
+    /// match its checksum
+    pub fn decompress(&self) -> io::Result<Vec<u8>> {
+        decompress(&self.compression, &self.data[..], self.size, self.crc32)
+    }
+}
+
+/// Decompress the data of a zip entry with the given compression method and check it against its
+/// CRC-32 checksum
+pub(crate) fn decompress<R: Read>(
+    compression: &str,
+    data: R,
+    size: u64,
+    crc32: u32,
+) -> io::Result<Vec<u8>> {
+    let mut data = data;
+    let mut buf = Vec::with_capacity(size as usize);
+    match compression {
+        "stored" => data.read_to_end(&mut buf)?,
+        "deflated" => DeflateDecoder::new(data).read_to_end(&mut buf)?,
+        #[cfg(feature = "bzip2")]
+        "bzip2" => BzDecoder::new(data).read_to_end(&mut buf)?,
+        other => {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Unsupported compression method: {}", other),
+            ))
+        }
+    };
+
+    if crc32fast::hash(&buf) != crc32 {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidData,
+            "Asset bundle entry does not match its checksum",
+        ));
+    }
+
+    Ok(buf)
+}
@@ -0,0 +1,136 @@
+//! Reading ahead of the entries that are loaded, so that loads of the entries next to them in the
+//! bundle are served from memory
+
+use std::{
+    collections::VecDeque,
+    io::{self, Read, Seek, SeekFrom},
+    sync::{Arc, Mutex},
+};
+
+use crate::ReadSeekSend;
+
+/// A region of the bundle data that has been read ahead
+struct Window {
+    start: u64,
+    data: Vec<u8>,
+}
+
+/// The regions of a bundle that have been read ahead, shared by all of the opened archives of the
+/// bundle
+pub(crate) struct ReadAheadCache {
+    windows: Mutex<VecDeque<Window>>,
+    /// The number of bytes read ahead at once
+    window_size: u64,
+    /// The number of windows to keep, so that reads on different threads don't keep evicting each
+    /// other's windows
+    max_windows: usize,
+}
+
+impl ReadAheadCache {
+    /// Create a cache that reads `window_size` bytes at a time and keeps up to `max_windows` of
+    /// them
+    pub fn new(window_size: u64, max_windows: usize) -> Self {
+        Self {
+            windows: Default::default(),
+            window_size,
+            max_windows: max_windows.max(1),
+        }
+    }
+
+    /// Copy the cached data at `pos` into `buf`, returning `None` if the data isn't cached
+    fn read_cached(&self, pos: u64, buf: &mut [u8]) -> Option<usize> {
+        let windows = self.windows.lock().unwrap();
+        let window = windows
+            .iter()
+            .find(|x| pos >= x.start && pos < x.start + x.data.len() as u64)?;
+
+        let offset = (pos - window.start) as usize;
+        let len = buf.len().min(window.data.len() - offset);
+        buf[..len].copy_from_slice(&window.data[offset..offset + len]);
+        Some(len)
+    }
+
+    /// Add a window to the cache, evicting the oldest window if the cache is full
+    fn insert(&self, window: Window) {
+        let mut windows = self.windows.lock().unwrap();
+        if windows.len() >= self.max_windows {
+            windows.pop_front();
+        }
+        windows.push_back(window);
+    }
+}
+
+/// A reader over the bundle data that reads a whole window of the data at a time into the shared
+/// [`ReadAheadCache`]
+///
+/// Zip readers read entries in small chunks, so without this each load seeks to its own entry and
+/// reads it bit by bit. Reading a large window instead turns the loads of a batch of adjacent
+/// entries into one sequential read, which matters on spinning disks and some consoles.
+pub(crate) struct ReadAheadReader {
+    inner: Box<dyn ReadSeekSend>,
+    cache: Arc<ReadAheadCache>,
+    pos: u64,
+}
+
+impl ReadAheadReader {
+    /// Read ahead from `inner` into the given cache
+    pub fn new(inner: Box<dyn ReadSeekSend>, cache: Arc<ReadAheadCache>) -> Self {
+        Self {
+            inner,
+            cache,
+            pos: 0,
+        }
+    }
+}
+
+impl Read for ReadAheadReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if buf.is_empty() {
+            return Ok(0);
+        }
+
+        // Reads as large as a window don't benefit from the cache
+        if buf.len() as u64 >= self.cache.window_size {
+            self.inner.seek(SeekFrom::Start(self.pos))?;
+            let read = self.inner.read(buf)?;
+            self.pos += read as u64;
+            return Ok(read);
+        }
+
+        if let Some(read) = self.cache.read_cached(self.pos, buf) {
+            self.pos += read as u64;
+            return Ok(read);
+        }
+
+        self.inner.seek(SeekFrom::Start(self.pos))?;
+        let mut data = Vec::with_capacity(self.cache.window_size as usize);
+        (&mut self.inner)
+            .take(self.cache.window_size)
+            .read_to_end(&mut data)?;
+
+        let read = buf.len().min(data.len());
+        buf[..read].copy_from_slice(&data[..read]);
+        if !data.is_empty() {
+            self.cache.insert(Window {
+                start: self.pos,
+                data,
+            });
+        }
+        self.pos += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl Seek for ReadAheadReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.pos = match pos {
+            SeekFrom::Start(pos) => pos,
+            SeekFrom::Current(offset) => (self.pos as i64 + offset) as u64,
+            // The end of the data is only known to the inner reader
+            SeekFrom::End(_) => self.inner.seek(pos)?,
+        };
+
+        Ok(self.pos)
+    }
+}
@@ -0,0 +1,89 @@
+//! Retrying bundle reads that fail with transient IO errors
+
+use std::{io, time::Duration};
+
+use bevy::log::warn;
+
+/// How to retry opening bundles and reading entries when they fail with transient IO errors, set
+/// with [`AssetIoZipConfig::io_retry`][crate::AssetIoZipConfig::io_retry]
+///
+/// Reads from network drives and external disks occasionally fail for a moment. Errors that can't
+/// go away by trying again, such as missing files, denied permissions, and corrupted data, are
+/// never retried. Retries wait on the IO thread, so they are skipped in browser builds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    /// The number of times to try each operation, including the first try. `1` disables retries.
+    pub attempts: u32,
+    /// How long to wait before the first retry
+    pub backoff: Duration,
+    /// The factor that the wait is multiplied by after each retry
+    pub backoff_multiplier: u32,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            attempts: 3,
+            backoff: Duration::from_millis(20),
+            backoff_multiplier: 2,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// A policy that tries each operation only once
+    pub fn none() -> Self {
+        Self {
+            attempts: 1,
+            ..Default::default()
+        }
+    }
+
+    /// Run the operation, retrying it while it fails with a transient error and attempts remain
+    ///
+    /// `what` describes the operation for the warnings logged before each retry.
+    pub(crate) fn run<T, W, F>(&self, what: W, mut operation: F) -> io::Result<T>
+    where
+        W: Fn() -> String,
+        F: FnMut() -> io::Result<T>,
+    {
+        let mut backoff = self.backoff;
+        let mut attempt = 1;
+        loop {
+            match operation() {
+                Err(e)
+                    if cfg!(not(target_arch = "wasm32"))
+                        && attempt < self.attempts
+                        && is_transient(&e) =>
+                {
+                    warn!(
+                        "Could not {}: {}. Retrying in {:?} ({}/{}).",
+                        what(),
+                        e,
+                        backoff,
+                        attempt,
+                        self.attempts
+                    );
+                    std::thread::sleep(backoff);
+                    backoff *= self.backoff_multiplier;
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+}
+
+/// Whether or not an IO error might not happen again if the operation is retried
+fn is_transient(error: &io::Error) -> bool {
+    !matches!(
+        error.kind(),
+        io::ErrorKind::NotFound
+            | io::ErrorKind::PermissionDenied
+            | io::ErrorKind::AlreadyExists
+            | io::ErrorKind::InvalidInput
+            | io::ErrorKind::InvalidData
+            | io::ErrorKind::UnexpectedEof
+            | io::ErrorKind::Unsupported
+    )
+}
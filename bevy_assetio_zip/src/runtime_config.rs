@@ -0,0 +1,68 @@
+//! Overriding the bundle config with a file next to the executable
+
+use std::{
+    collections::HashMap,
+    path::{Path, PathBuf},
+};
+
+use serde::Deserialize;
+
+use crate::AssetIoZipConfig;
+
+/// The name of the runtime config file that is read from the directory containing the executable
+pub const RUNTIME_CONFIG_FILE: &str = "assetio.toml";
+
+/// The settings of the runtime config file, each of which overrides the matching setting of the
+/// [`AssetIoZipConfig`] when present
+#[derive(Debug, Default, Deserialize)]
+#[serde(rename_all = "kebab-case", deny_unknown_fields)]
+struct RuntimeConfig {
+    file_names: Option<Vec<String>>,
+    bundle_path: Option<PathBuf>,
+    search_asset_dir: Option<bool>,
+    mod_dirs: Option<Vec<PathBuf>>,
+    max_concurrent_reads: Option<usize>,
+    variants: Option<HashMap<String, HashMap<PathBuf, PathBuf>>>,
+}
+
+impl AssetIoZipConfig {
+    /// Override the settings of the config with the ones in the runtime config file at the given
+    /// path
+    ///
+    /// Relative paths in the file are relative to the directory containing the file. Returns
+    /// `Ok(false)` without changing the config if there is no file at the path, and an error
+    /// describing the problem if the file can't be read or parsed.
+    pub fn apply_runtime_config<P: AsRef<Path>>(&mut self, path: P) -> Result<bool, String> {
+        let path = path.as_ref();
+        let contents = match std::fs::read_to_string(path) {
+            Ok(contents) => contents,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(false),
+            Err(e) => return Err(format!("Could not read {}: {}", path.display(), e)),
+        };
+        let runtime_config: RuntimeConfig = toml::from_str(&contents)
+            .map_err(|e| format!("Could not parse {}: {}", path.display(), e))?;
+
+        let dir = path.parent().unwrap_or_else(|| Path::new(""));
+        if let Some(file_names) = runtime_config.file_names {
+            self.file_names = file_names;
+        }
+        if let Some(bundle_path) = runtime_config.bundle_path {
+            self.bundle_path = Some(dir.join(bundle_path));
+        }
+        if let Some(search_asset_dir) = runtime_config.search_asset_dir {
+            self.search_asset_dir = search_asset_dir;
+        }
+        if let Some(mod_dirs) = runtime_config.mod_dirs {
+            self.mod_dirs = mod_dirs.into_iter().map(|x| dir.join(x)).collect();
+        }
+        if let Some(max_concurrent_reads) = runtime_config.max_concurrent_reads {
+            self.max_concurrent_reads = Some(max_concurrent_reads);
+        }
+        if let Some(variants) = runtime_config.variants {
+            self.asset_variants
+                .extend_for_platform(variants, std::env::consts::OS);
+        }
+
+        Ok(true)
+    }
+}
@@ -0,0 +1,83 @@
+//! Querying the bundles through the `AssetServer`
+
+use std::{
+    cell::RefCell,
+    path::{Path, PathBuf},
+};
+
+use bevy::prelude::AssetServer;
+
+use crate::{AssetIoZip, AssetMetadata};
+
+/// The path that [`BundleAssetServerExt`] asks the asset IO of an `AssetServer` about to find the
+/// [`AssetIoZip`] behind it
+pub(crate) const PROBE_PATH: &str = ".bevy_assetio_zip-probe";
+
+thread_local! {
+    /// The asset IO that answered the last probe on this thread
+    static PROBED: RefCell<Option<AssetIoZip>> = RefCell::new(None);
+}
+
+/// Record that the given asset IO answered a probe of [`PROBE_PATH`] on this thread
+pub(crate) fn answer_probe(asset_io: &AssetIoZip) {
+    PROBED.with(|x| *x.borrow_mut() = Some(asset_io.clone()));
+}
+
+/// Find the [`AssetIoZip`] that the given asset server loads assets with, if it uses one
+fn probe(asset_server: &AssetServer) -> Option<AssetIoZip> {
+    PROBED.with(|x| x.borrow_mut().take());
+    // The asset server asks its asset IO whether the folder is a directory on this thread before
+    // anything else, which the `AssetIoZip` answers by recording itself. It is never a directory,
+    // so nothing is loaded.
+    asset_server.load_folder(PROBE_PATH).ok();
+    PROBED.with(|x| x.borrow_mut().take())
+}
+
+/// Extension trait that queries the mounted bundles through the `AssetServer`, so that game code
+/// doesn't need to pass the [`AssetIoZip`] resource around
+///
+/// The queries are routed to the [`AssetIoZip`] that the asset server was created with by the
+/// [`AssetIoZipPlugin`][crate::AssetIoZipPlugin], including when it is wrapped in another asset IO
+/// that passes [`is_directory`][bevy::asset::AssetIo::is_directory] calls through to it. Asset
+/// servers without one answer as if no bundles were mounted.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// use bevy_assetio_zip::BundleAssetServerExt;
+///
+/// fn load_optional_skin(asset_server: Res<AssetServer>) {
+///     if asset_server.bundle_contains("skins/bonus.png") {
+///         let _skin: Handle<Texture> = asset_server.load("skins/bonus.png");
+///     }
+/// }
+/// ```
+pub trait BundleAssetServerExt {
+    /// Whether or not any of the mounted bundles contains the given asset
+    fn bundle_contains<P: AsRef<Path>>(&self, path: P) -> bool;
+
+    /// Get the metadata of the given asset from the highest priority bundle that contains it, like
+    /// [`ZipAssetIo::asset_metadata`][crate::ZipAssetIo::asset_metadata]
+    fn bundle_metadata<P: AsRef<Path>>(&self, path: P) -> Option<AssetMetadata>;
+
+    /// List the paths of the assets in the mounted bundles that start with the given prefix, such
+    /// as `levels/` for every asset in the `levels` folder, in order
+    fn bundle_list(&self, prefix: &str) -> Vec<PathBuf>;
+}
+
+impl BundleAssetServerExt for AssetServer {
+    fn bundle_contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        probe(self)
+            .map(|asset_io| asset_io.contains(path))
+            .unwrap_or(false)
+    }
+
+    fn bundle_metadata<P: AsRef<Path>>(&self, path: P) -> Option<AssetMetadata> {
+        probe(self)?.asset_metadata(path)
+    }
+
+    fn bundle_list(&self, prefix: &str) -> Vec<PathBuf> {
+        probe(self)
+            .map(|asset_io| asset_io.list_assets(prefix))
+            .unwrap_or_default()
+    }
+}
@@ -0,0 +1,172 @@
+//! Reading of the zip data of bundles with solid zstd compression
+//!
+//! Solid bundles compress the whole zip data, in which the entries are stored uncompressed, as a
+//! sequence of independent zstd frames followed by a seek table in the [zstd seekable format]. The
+//! seek table lets us decompress only the frames containing the data that is read.
+//!
+//! [zstd seekable format]: https://github.com/facebook/zstd/blob/dev/contrib/seekable_format/zstd_seekable_compression_format.md
+
+use std::{
+    cmp::Ordering,
+    convert::TryInto,
+    io::{self, Read, Seek, SeekFrom},
+};
+
+use ruzstd::StreamingDecoder;
+
+use crate::entry::checked_offset;
+
+/// The magic number at the end of the seek table
+const SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+/// The length of the seek table footer
+const FOOTER_LEN: u64 = 9;
+/// The length of the skippable frame header before the seek table entries
+const SKIPPABLE_HEADER_LEN: u64 = 8;
+/// The seek table descriptor flag set when the entries have checksums
+const CHECKSUM_FLAG: u8 = 1 << 7;
+
+/// The location of a zstd frame in the compressed and decompressed data
+#[derive(Debug, Clone, Copy)]
+struct Frame {
+    compressed_start: u64,
+    compressed_size: u64,
+    decompressed_start: u64,
+}
+
+/// A reader over the decompressed data of a seekable zstd stream
+pub(crate) struct SolidReader<R> {
+    inner: R,
+    frames: Vec<Frame>,
+    /// The length of the decompressed data
+    len: u64,
+    /// The position in the decompressed data
+    pos: u64,
+    /// The index and the data of the most recently decompressed frame
+    current: Option<(usize, Vec<u8>)>,
+}
+
+impl<R: Read + Seek> SolidReader<R> {
+    pub fn new(mut inner: R) -> io::Result<Self> {
+        let mut footer = [0; FOOTER_LEN as usize];
+        let end = inner.seek(SeekFrom::End(0))?;
+        inner.seek(SeekFrom::Start(
+            end.checked_sub(FOOTER_LEN).ok_or_else(invalid)?,
+        ))?;
+        inner.read_exact(&mut footer)?;
+
+        let frame_count = u32::from_le_bytes(footer[..4].try_into().unwrap()) as u64;
+        let descriptor = footer[4];
+        if u32::from_le_bytes(footer[5..].try_into().unwrap()) != SEEKABLE_MAGIC {
+            return Err(invalid());
+        }
+
+        let entry_len = if descriptor & CHECKSUM_FLAG != 0 {
+            12
+        } else {
+            8
+        };
+        let table_len = frame_count * entry_len;
+        let table_start = end
+            .checked_sub(FOOTER_LEN + table_len)
+            .ok_or_else(invalid)?;
+        let mut table = vec![0; table_len as usize];
+        inner.seek(SeekFrom::Start(table_start))?;
+        inner.read_exact(&mut table)?;
+
+        let mut frames = Vec::with_capacity(frame_count as usize);
+        let (mut compressed_start, mut decompressed_start) = (0, 0);
+        for entry in table.chunks(entry_len as usize) {
+            let compressed_size = u32::from_le_bytes(entry[..4].try_into().unwrap()) as u64;
+            let decompressed_size = u32::from_le_bytes(entry[4..8].try_into().unwrap()) as u64;
+            frames.push(Frame {
+                compressed_start,
+                compressed_size,
+                decompressed_start,
+            });
+            compressed_start += compressed_size;
+            decompressed_start += decompressed_size;
+        }
+
+        if compressed_start + SKIPPABLE_HEADER_LEN > table_start {
+            return Err(invalid());
+        }
+
+        Ok(Self {
+            inner,
+            frames,
+            len: decompressed_start,
+            pos: 0,
+            current: None,
+        })
+    }
+
+    /// Get the decompressed data of the frame with the given index
+    fn frame_data(&mut self, index: usize) -> io::Result<&[u8]> {
+        if self.current.as_ref().map(|(i, _)| *i) != Some(index) {
+            let frame = self.frames[index];
+            self.inner.seek(SeekFrom::Start(frame.compressed_start))?;
+            let mut decoder = StreamingDecoder::new((&mut self.inner).take(frame.compressed_size))
+                .map_err(|e| io::Error::new(io::ErrorKind::InvalidData, e.to_string()))?;
+            let mut data = Vec::new();
+            decoder.read_to_end(&mut data)?;
+            self.current = Some((index, data));
+        }
+
+        Ok(&self.current.as_ref().unwrap().1)
+    }
+}
+
+impl<R: Read + Seek> Read for SolidReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.pos >= self.len || buf.is_empty() {
+            return Ok(0);
+        }
+
+        let pos = self.pos;
+        // The last frame that starts at or before the position
+        let index = self
+            .frames
+            .binary_search_by(|frame| frame.decompressed_start.cmp(&pos).then(Ordering::Less))
+            .unwrap_err()
+            - 1;
+        let offset = (pos - self.frames[index].decompressed_start) as usize;
+        let data = self.frame_data(index)?;
+        let available = data.get(offset..).ok_or_else(invalid)?;
+        if available.is_empty() {
+            return Err(invalid());
+        }
+
+        let read = available.len().min(buf.len());
+        buf[..read].copy_from_slice(&available[..read]);
+        self.pos += read as u64;
+
+        Ok(read)
+    }
+}
+
+impl<R: Seek> Seek for SolidReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let new_pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => checked_offset(self.len, offset),
+            SeekFrom::Current(offset) => checked_offset(self.pos, offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek to before the start of the bundle data",
+            )
+        })?;
+        self.pos = new_pos;
+
+        Ok(new_pos)
+    }
+}
+
+/// The error returned when the seek table doesn't match the compressed data
+fn invalid() -> io::Error {
+    io::Error::new(
+        io::ErrorKind::InvalidData,
+        "Invalid solid compression seek table in asset bundle",
+    )
+}
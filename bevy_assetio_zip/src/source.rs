@@ -0,0 +1,396 @@
+//! Sources that asset bundles can be read from
+
+#[cfg(not(target_arch = "wasm32"))]
+use std::{
+    fs::{File, OpenOptions},
+    io::{BufReader, SeekFrom},
+    path::PathBuf,
+};
+use std::{
+    io::{self, Cursor, Read, Seek},
+    path::Path,
+    sync::Arc,
+};
+
+use bevy::utils::BoxedFuture;
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::format::HEADER_LEN;
+use crate::format::{BundleLayout, KeySlot};
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{BundleFileSharing, BundleRegion};
+
+/// A readable and seekable stream that can be sent between threads
+pub trait ReadSeekSend: Read + Seek + Send {}
+impl<T: Read + Seek + Send> ReadSeekSend for T {}
+
+/// A source that an asset bundle can be read from
+///
+/// Implement this to load bundles from somewhere other than the filesystem, such as from memory,
+/// a network stream, a platform archive, or a custom DRM wrapper, and register it with the
+/// [`BundleSources`] resource.
+pub trait BundleSource: Send + Sync + 'static {
+    /// Open a new stream over the bundle zip data
+    ///
+    /// This is called every time an asset is read from the bundle, so it should be cheap.
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>>;
+
+    /// Wait for the bundle to become available, such as while it is being downloaded
+    ///
+    /// Assets are not read from the bundle until this has completed. By default the bundle is
+    /// available immediately.
+    fn ready(&self) -> BoxedFuture<'_, ()> {
+        Box::pin(async {})
+    }
+
+    /// The path of the bundle file, if it is on the filesystem
+    ///
+    /// This is used to report where the bundle was mounted from, and to memory map the bundle when
+    /// the `mmap` feature is enabled and the bundle is not [obfuscated][Self::obfuscated].
+    fn path(&self) -> Option<&Path> {
+        None
+    }
+
+    /// Whether or not the whole bundle is obfuscated, which the stream returned by
+    /// [`open`][Self::open] takes care of de-obfuscating
+    fn obfuscated(&self) -> bool {
+        false
+    }
+
+    /// Whether or not the zip data of the bundle is compressed as a whole with solid compression,
+    /// which the stream returned by [`open`][Self::open] takes care of decompressing
+    ///
+    /// Solid bundles can't be memory mapped.
+    fn solid(&self) -> bool {
+        false
+    }
+
+    /// The offset of the zip data in the file at [`path`][Self::path], such as when the bundle
+    /// starts with a v2 header
+    ///
+    /// This is used to find entries in the memory mapped bundle.
+    fn data_offset(&self) -> u64 {
+        0
+    }
+
+    /// The key slot of the v2 header of the bundle, which describes how the data of its entries is
+    /// obfuscated
+    ///
+    /// Bundles without a key slot fall back to the obfuscated entries marker in the zip data.
+    fn key_slot(&self) -> Option<KeySlot> {
+        None
+    }
+}
+
+/// A [`BundleSource`] that reads a bundle file from the filesystem
+#[derive(Debug, Clone)]
+#[cfg(not(target_arch = "wasm32"))]
+pub struct FileBundleSource {
+    path: PathBuf,
+    region: Option<BundleRegion>,
+    layout: BundleLayout,
+    key_slot: Option<KeySlot>,
+    sharing: BundleFileSharing,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl FileBundleSource {
+    /// Create a source that reads the bundle file at the given path
+    ///
+    /// If `obfuscated` is true the bundle file is de-obfuscated as it is read. This requires the
+    /// `obfuscation` feature.
+    pub fn new<P: Into<PathBuf>>(path: P, obfuscated: bool) -> Self {
+        Self {
+            path: path.into(),
+            region: None,
+            layout: BundleLayout {
+                obfuscated,
+                ..Default::default()
+            },
+            key_slot: None,
+            sharing: BundleFileSharing::default(),
+        }
+    }
+
+    /// Set what other processes may do with the bundle file while it is open
+    pub fn with_sharing(mut self, sharing: BundleFileSharing) -> Self {
+        self.sharing = sharing;
+        self
+    }
+
+    /// Create a source that reads the bundle file at the given path, detecting whether it is a v2
+    /// bundle or a plain or obfuscated zip archive from the first bytes of the file
+    ///
+    /// This makes bundles that have been renamed or mislabeled just work. If the first bytes can't
+    /// be read or don't look like a bundle, the bundle is treated as obfuscated if it has a `.bin`
+    /// extension.
+    pub fn detect<P: Into<PathBuf>>(path: P) -> Self {
+        Self::detect_at(path.into(), None)
+    }
+
+    /// Create a source that reads the bundle in the given region of the file at the given path,
+    /// such as a bundle appended to the game executable, detecting whether it is a v2 bundle or a
+    /// plain or obfuscated zip archive from the first bytes of the region
+    ///
+    /// Every read of the bundle is windowed to the region, so the bundle is read as if the region
+    /// was the whole file.
+    pub fn detect_region<P: Into<PathBuf>>(path: P, region: BundleRegion) -> Self {
+        Self::detect_at(path.into(), Some(region))
+    }
+
+    /// Create a source that reads the bundle appended to the file at the given path by the
+    /// bundler's `append` command, returning `None` if the file doesn't have an appended bundle
+    pub fn detect_appended<P: Into<PathBuf>>(path: P) -> Option<Self> {
+        let path = path.into();
+        let region = crate::appended::appended_region(&path)?;
+        Some(Self::detect_region(path, region))
+    }
+
+    /// Create a source that reads the bundle in the given region of the file at the given path, or
+    /// in the whole file if there is no region
+    fn detect_at(path: PathBuf, region: Option<BundleRegion>) -> Self {
+        let mut header = Vec::with_capacity(HEADER_LEN);
+        let mut layout = None;
+        let file = File::open(&path).and_then(|file| match region {
+            Some(region) => WindowReader::new(file, region),
+            None => Ok(WindowReader::whole(file)),
+        });
+        if let Ok(mut file) = file {
+            if (&mut file)
+                .take(HEADER_LEN as u64)
+                .read_to_end(&mut header)
+                .is_ok()
+            {
+                layout = BundleLayout::detect(&header);
+            }
+
+            // Read the rest of the v2 header, which may contain a key slot
+            if let Some(layout) = layout {
+                let rest = layout.offset.saturating_sub(header.len() as u64);
+                file.take(rest).read_to_end(&mut header).ok();
+            }
+        }
+
+        match layout {
+            Some(layout) => Self {
+                path,
+                region,
+                layout,
+                key_slot: KeySlot::parse(&header),
+                sharing: BundleFileSharing::default(),
+            },
+            None => {
+                let obfuscated = path.extension().map(|x| x == "bin").unwrap_or(false);
+                Self {
+                    region,
+                    ..Self::new(path, obfuscated)
+                }
+            }
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl BundleSource for FileBundleSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        let file = BufReader::new(open_options(self.sharing).open(&self.path)?);
+        match self.region {
+            Some(region) => self.layout.open(WindowReader::new(file, region)?),
+            None => self.layout.open(file),
+        }
+    }
+
+    fn path(&self) -> Option<&Path> {
+        Some(&self.path)
+    }
+
+    fn obfuscated(&self) -> bool {
+        self.layout.obfuscated
+    }
+
+    fn solid(&self) -> bool {
+        self.layout.solid
+    }
+
+    fn data_offset(&self) -> u64 {
+        self.region.map(|x| x.offset).unwrap_or(0) + self.layout.offset
+    }
+
+    fn key_slot(&self) -> Option<KeySlot> {
+        self.key_slot.clone()
+    }
+}
+
+/// A reader over a region of a file, which reads and seeks as if the region was the whole file
+#[cfg(not(target_arch = "wasm32"))]
+struct WindowReader<R> {
+    inner: R,
+    start: u64,
+    length: u64,
+    /// The position in the region
+    pos: u64,
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<R: Seek> WindowReader<R> {
+    fn new(mut inner: R, region: BundleRegion) -> io::Result<Self> {
+        inner.seek(SeekFrom::Start(region.offset))?;
+        Ok(Self {
+            inner,
+            start: region.offset,
+            length: region.length,
+            pos: 0,
+        })
+    }
+
+    /// Read the whole of a reader that hasn't been read from yet, which can't be seeked from the
+    /// end
+    fn whole(inner: R) -> Self {
+        Self {
+            inner,
+            start: 0,
+            length: u64::MAX,
+            pos: 0,
+        }
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<R: Read> Read for WindowReader<R> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let remaining = self.length.saturating_sub(self.pos);
+        let len = (buf.len() as u64).min(remaining) as usize;
+        let read = self.inner.read(&mut buf[..len])?;
+        self.pos += read as u64;
+        Ok(read)
+    }
+}
+
+#[cfg(not(target_arch = "wasm32"))]
+impl<R: Seek> Seek for WindowReader<R> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => Some(pos),
+            SeekFrom::End(offset) => checked_add_signed(self.length, offset),
+            SeekFrom::Current(offset) => checked_add_signed(self.pos, offset),
+        }
+        .ok_or_else(|| {
+            io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "Seek to before the start of the bundle region",
+            )
+        })?;
+
+        self.inner.seek(SeekFrom::Start(self.start + pos))?;
+        self.pos = pos;
+        Ok(pos)
+    }
+}
+
+/// Add a signed offset to a position, returning `None` if the result is negative
+#[cfg(not(target_arch = "wasm32"))]
+fn checked_add_signed(pos: u64, offset: i64) -> Option<u64> {
+    if offset < 0 {
+        pos.checked_sub(offset.wrapping_neg() as u64)
+    } else {
+        pos.checked_add(offset as u64)
+    }
+}
+
+/// Get the options to open a bundle file for reading with the given sharing
+#[cfg(not(target_arch = "wasm32"))]
+fn open_options(sharing: BundleFileSharing) -> OpenOptions {
+    let mut options = OpenOptions::new();
+    options.read(true);
+
+    #[cfg(windows)]
+    {
+        use std::os::windows::fs::OpenOptionsExt;
+
+        const FILE_SHARE_READ: u32 = 0x1;
+        const FILE_SHARE_WRITE: u32 = 0x2;
+        const FILE_SHARE_DELETE: u32 = 0x4;
+        options.share_mode(match sharing {
+            BundleFileSharing::AllowReplace => {
+                FILE_SHARE_READ | FILE_SHARE_WRITE | FILE_SHARE_DELETE
+            }
+            BundleFileSharing::ReadOnly => FILE_SHARE_READ,
+        });
+    }
+    #[cfg(not(windows))]
+    let _ = sharing;
+
+    options
+}
+
+/// A [`BundleSource`] that reads a bundle that is already in memory, such as one embedded in the
+/// executable with [`include_bytes!`]
+///
+/// The bytes may be a v2 bundle or a plain or obfuscated zip archive, which is detected from the
+/// first bytes.
+#[derive(Clone)]
+pub struct MemoryBundleSource {
+    bytes: Arc<dyn AsRef<[u8]> + Send + Sync>,
+    layout: BundleLayout,
+    key_slot: Option<KeySlot>,
+}
+
+impl MemoryBundleSource {
+    /// Create a source that reads the bundle from the given bytes
+    pub fn new<B: AsRef<[u8]> + Send + Sync + 'static>(bytes: B) -> Self {
+        let layout = BundleLayout::detect(bytes.as_ref()).unwrap_or_default();
+        let key_slot = KeySlot::parse(bytes.as_ref());
+        Self {
+            bytes: Arc::new(bytes),
+            layout,
+            key_slot,
+        }
+    }
+}
+
+impl BundleSource for MemoryBundleSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        self.layout
+            .open(Cursor::new(SharedBytes(self.bytes.clone())))
+    }
+
+    fn obfuscated(&self) -> bool {
+        self.layout.obfuscated
+    }
+
+    fn solid(&self) -> bool {
+        self.layout.solid
+    }
+
+    fn key_slot(&self) -> Option<KeySlot> {
+        self.key_slot.clone()
+    }
+}
+
+/// Bytes shared between the streams opened from a [`MemoryBundleSource`]
+struct SharedBytes(Arc<dyn AsRef<[u8]> + Send + Sync>);
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        (*self.0).as_ref()
+    }
+}
+
+/// Resource containing the custom [`BundleSource`]s to mount, which must be inserted before the
+/// [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] is built
+///
+/// The sources are mounted in the order they are added, after any mods and before the asset bundle
+/// found using [`AssetIoZipConfig::file_names`][crate::AssetIoZipConfig::file_names], so assets in
+/// the first source take priority.
+#[derive(Clone, Default)]
+pub struct BundleSources(pub(crate) Vec<(String, Arc<dyn BundleSource>)>);
+
+impl BundleSources {
+    /// Add a source with the given name, which is used to identify it in
+    /// [`AssetSource::Bundle`][crate::AssetSource::Bundle] and
+    /// [`MountedBundle`][crate::MountedBundle]
+    pub fn add<N: Into<String>, S: BundleSource>(&mut self, name: N, source: S) -> &mut Self {
+        self.0.push((name.into(), Arc::new(source)));
+        self
+    }
+}
@@ -0,0 +1,60 @@
+//! A record of where every loaded asset came from
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    path::{Path, PathBuf},
+};
+
+use crate::{AssetLoadEvent, AssetSource};
+
+/// Resource recording the source that each asset was last loaded from
+///
+/// This is inserted by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] and only filled in when
+/// [`record_asset_sources`][crate::AssetIoZipConfig::record_asset_sources] is enabled, so that QA
+/// builds can dump exactly where every loaded asset came from when investigating assets that are
+/// out of date. The [`Display`][fmt::Display] implementation renders one line per asset, sorted by
+/// path.
+#[derive(Debug, Clone, Default)]
+pub struct AssetSourceLog {
+    /// The source that each asset was last loaded from
+    pub sources: BTreeMap<PathBuf, AssetSource>,
+}
+
+impl AssetSourceLog {
+    /// Record the source of a loaded asset, replacing the source of any earlier load of it
+    pub fn record(&mut self, event: &AssetLoadEvent) {
+        self.sources
+            .insert(event.path.clone(), event.source.clone());
+    }
+
+    /// Get the source that the given asset was last loaded from, if it has been loaded
+    pub fn get<P: AsRef<Path>>(&self, path: P) -> Option<&AssetSource> {
+        self.sources.get(path.as_ref())
+    }
+
+    /// Forget all of the recorded sources
+    pub fn clear(&mut self) {
+        self.sources.clear();
+    }
+}
+
+impl fmt::Display for AssetSourceLog {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (path, source) in &self.sources {
+            writeln!(f, "{}: {}", path.display(), source)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl fmt::Display for AssetSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            AssetSource::Bundle(name) => write!(f, "bundle {}", name),
+            AssetSource::Override => write!(f, "override dir"),
+            AssetSource::Fallback => write!(f, "fallback"),
+        }
+    }
+}
@@ -0,0 +1,161 @@
+//! Reading of bundles stored in SQLite databases
+//!
+//! An SQLite bundle has an `assets` table with a row for each entry that a zip bundle would have,
+//! holding the path of the entry, its uncompressed data in the `blob` column and its `flags`. The
+//! database is opened read-only by SQLite, so single assets can be replaced by any SQLite client
+//! without rebuilding the bundle.
+
+use std::{
+    collections::HashMap,
+    io::{self, SeekFrom},
+};
+
+use rusqlite::{params, Connection, OpenFlags, NO_PARAMS};
+
+#[cfg(not(target_arch = "wasm32"))]
+use crate::index::IndexEntry;
+use crate::{bundle::ArchiveBackend, BundleSource, ReadSeekSend};
+
+/// The header at the start of every SQLite database file
+const SQLITE_MAGIC: &[u8; 16] = b"SQLite format 3\0";
+
+/// Lists the entries of the bundle with their uncompressed sizes
+const LIST_ENTRIES: &str = "SELECT path, length(blob) AS size FROM assets";
+
+/// Reads the data and the flags of a single entry of the bundle
+const READ_ENTRY: &str = "SELECT blob, flags FROM assets WHERE path = ?";
+
+/// Whether or not the bundle data read by the given reader is an SQLite database
+///
+/// The reader is left at the start of the bundle data.
+pub(crate) fn is_sqlite(reader: &mut dyn ReadSeekSend) -> io::Result<bool> {
+    let mut magic = [0; SQLITE_MAGIC.len()];
+    reader.seek(SeekFrom::Start(0))?;
+    let result = match reader.read_exact(&mut magic) {
+        Ok(()) => Ok(&magic == SQLITE_MAGIC),
+        Err(e) if e.kind() == io::ErrorKind::UnexpectedEof => Ok(false),
+        Err(e) => Err(e),
+    };
+    reader.seek(SeekFrom::Start(0))?;
+    result
+}
+
+/// An archive whose entries are the rows of the `assets` table of an SQLite database
+pub(crate) struct SqliteArchive {
+    connection: Connection,
+    /// The uncompressed size of each entry by its name
+    sizes: HashMap<String, u64>,
+}
+
+impl SqliteArchive {
+    /// Open the SQLite database of the given bundle source and list its entries
+    ///
+    /// SQLite reads the database itself, so only plain files on the filesystem can be opened.
+    pub fn open(source: &dyn BundleSource) -> io::Result<Self> {
+        let path = match source.path() {
+            Some(path) if !source.obfuscated() && !source.solid() && source.data_offset() == 0 => {
+                path
+            }
+            _ => {
+                return Err(io::Error::new(
+                    io::ErrorKind::InvalidInput,
+                    "SQLite bundles can only be read from plain files on the filesystem",
+                ))
+            }
+        };
+
+        let connection = Connection::open_with_flags(path, OpenFlags::SQLITE_OPEN_READ_ONLY)
+            .map_err(sqlite_error)?;
+        let sizes = connection
+            .prepare(LIST_ENTRIES)
+            .and_then(|mut statement| {
+                statement
+                    .query_map(NO_PARAMS, |row| {
+                        Ok((row.get("path")?, row.get::<_, i64>("size")? as u64))
+                    })?
+                    .collect()
+            })
+            .map_err(sqlite_error)?;
+        // Make sure that the entries can be read before the bundle is mounted
+        connection
+            .prepare_cached(READ_ENTRY)
+            .map_err(sqlite_error)?;
+
+        Ok(Self { connection, sizes })
+    }
+}
+
+impl ArchiveBackend for SqliteArchive {
+    fn len(&self) -> usize {
+        self.sizes.len()
+    }
+
+    fn total_size(&mut self) -> u64 {
+        self.sizes.values().sum()
+    }
+
+    fn entry_names(&self) -> Vec<&str> {
+        self.sizes.keys().map(String::as_str).collect()
+    }
+
+    fn has_entry(&mut self, name: &str) -> bool {
+        self.sizes.contains_key(name)
+    }
+
+    fn entry_size(&mut self, name: &str) -> Option<u64> {
+        self.sizes.get(name).copied()
+    }
+
+    fn stored_entry(&mut self, _name: &str) -> Option<(u64, u64)> {
+        // SQLite splits large rows across pages, so the data is never read from the bundle data
+        None
+    }
+
+    #[cfg(not(target_arch = "wasm32"))]
+    fn entry_location(&mut self, _name: &str) -> Option<IndexEntry> {
+        None
+    }
+
+    fn read_entry_data(
+        &mut self,
+        name: &str,
+        progress: &mut dyn FnMut(u64, u64),
+    ) -> Option<io::Result<Vec<u8>>> {
+        if !self.sizes.contains_key(name) {
+            return None;
+        }
+
+        let row = self
+            .connection
+            .prepare_cached(READ_ENTRY)
+            .and_then(|mut statement| {
+                statement.query_row(params![name], |row| {
+                    Ok((row.get::<_, Vec<u8>>("blob")?, row.get::<_, i64>("flags")?))
+                })
+            });
+        let (data, flags) = match row {
+            Ok(row) => row,
+            // The row has been deleted since the entries were listed
+            Err(rusqlite::Error::QueryReturnedNoRows) => return None,
+            Err(e) => return Some(Err(sqlite_error(e))),
+        };
+
+        // No flags are defined yet, so we can't know how to read the data of flagged entries
+        if flags != 0 {
+            return Some(Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Entry {} has unsupported flags {:#x}", name, flags),
+            )));
+        }
+
+        // SQLite reads the whole blob at once
+        let size = data.len() as u64;
+        progress(size, size);
+        Some(Ok(data))
+    }
+}
+
+/// Convert an error from SQLite into an IO error
+fn sqlite_error(e: rusqlite::Error) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
@@ -0,0 +1,164 @@
+//! Spreading the decompression of asset groups across frames
+
+use std::{
+    collections::{HashSet, VecDeque},
+    path::PathBuf,
+};
+
+use crossbeam_channel::{Receiver, Sender};
+
+use crate::{AssetGroups, LoadPriority, ZipAssetIo};
+
+/// The default number of assets that a [`LevelStreamer`] decompresses at once
+pub const DEFAULT_STREAMS_IN_FLIGHT: usize = 2;
+
+/// Resource that decompresses the assets of [asset groups][AssetGroups] a few at a time in the
+/// background, so that loading a level during gameplay doesn't cause hitches
+///
+/// Queue groups with [`stream_group`][Self::stream_group] or single assets with
+/// [`stream`][Self::stream]. A system added by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin]
+/// starts the reads of queued assets on the IO task pool every frame, with no more than
+/// [`max_in_flight`][Self::max_in_flight] of them running at once, and collects the finished reads
+/// on later frames. The system never waits for a read, and the reads wait for the loads with a
+/// higher [`priority`][Self::priority] like any other read of the bundles. Load the assets through
+/// the `AssetServer` once [`is_done`][Self::is_done] returns `true` to get them without
+/// decompressing them again.
+///
+/// Unlike [`AssetGroups::load`], which decompresses every asset on the IO threads at once, this
+/// keeps the number of assets being decompressed small, so that the IO threads stay free for the
+/// assets the game loads in the meantime. It is not available in browser builds.
+///
+/// ```no_run
+/// # use bevy::prelude::*;
+/// use bevy_assetio_zip::LevelStreamer;
+///
+/// fn approach_level_2(mut streamer: ResMut<LevelStreamer>) {
+///     streamer.bytes_per_frame = Some(4 * 1024 * 1024);
+///     streamer.stream_group("level_2");
+/// }
+/// ```
+#[derive(Debug)]
+pub struct LevelStreamer {
+    /// The number of assets to decompress at once, at least one
+    ///
+    /// This is [`DEFAULT_STREAMS_IN_FLIGHT`] by default.
+    pub max_in_flight: usize,
+    /// The number of bytes of decompressed asset data to stream per frame
+    ///
+    /// No more reads are started on a frame once the reads that finished since the last frame
+    /// have produced this many bytes, so streaming large assets slows down to this rate.
+    pub bytes_per_frame: Option<u64>,
+    /// The priority that the streamed assets are read with
+    ///
+    /// This is [`Background`][LoadPriority::Background] by default, so that the assets that the
+    /// game loads in the meantime are read first.
+    pub priority: LoadPriority,
+    /// The groups to queue the assets of on the next frame
+    groups: Vec<String>,
+    /// The assets waiting to be decompressed, in order
+    queue: VecDeque<PathBuf>,
+    /// The assets that have been queued, so that each asset is queued once
+    queued: HashSet<PathBuf>,
+    /// The number of reads that have been started but not collected yet
+    in_flight: usize,
+    /// Channel that the reads send the size of their data on when they finish
+    finished: (Sender<usize>, Receiver<usize>),
+}
+
+impl Default for LevelStreamer {
+    fn default() -> Self {
+        Self {
+            max_in_flight: DEFAULT_STREAMS_IN_FLIGHT,
+            bytes_per_frame: None,
+            priority: LoadPriority::Background,
+            groups: Default::default(),
+            queue: Default::default(),
+            queued: Default::default(),
+            in_flight: 0,
+            finished: crossbeam_channel::unbounded(),
+        }
+    }
+}
+
+impl LevelStreamer {
+    /// Queue every asset in the given group to be decompressed
+    ///
+    /// The assets of the group are looked up on the next frame, after any groups that were queued
+    /// before it.
+    pub fn stream_group(&mut self, group: &str) -> &mut Self {
+        self.groups.push(group.to_owned());
+        self
+    }
+
+    /// Queue the given assets to be decompressed, after the ones that are already queued
+    ///
+    /// Assets that are already queued are skipped.
+    pub fn stream<I, P>(&mut self, paths: I) -> &mut Self
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        for path in paths {
+            let path = path.into();
+            if self.queued.insert(path.clone()) {
+                self.queue.push_back(path);
+            }
+        }
+        self
+    }
+
+    /// Get the number of assets waiting for their read to start, not counting the assets of groups
+    /// that haven't been looked up yet
+    pub fn remaining(&self) -> usize {
+        self.queue.len()
+    }
+
+    /// Get the number of assets that are being decompressed
+    pub fn in_flight(&self) -> usize {
+        self.in_flight
+    }
+
+    /// Whether or not every queued asset and group has been decompressed
+    pub fn is_done(&self) -> bool {
+        self.queue.is_empty() && self.groups.is_empty() && self.in_flight == 0
+    }
+
+    /// Forget all of the queued assets and groups
+    ///
+    /// The reads that have already started are finished, and assets that have already been
+    /// decompressed stay in the prefetched assets until they are loaded.
+    pub fn clear(&mut self) {
+        self.groups.clear();
+        self.queue.clear();
+        self.queued.clear();
+    }
+
+    /// Collect the reads that finished since the last frame and start the reads of queued assets
+    /// from the bundles of the given asset IO, within the budget of this frame
+    pub(crate) fn stream_frame(&mut self, zip_io: &ZipAssetIo) {
+        let mut bytes = 0;
+        for len in self.finished.1.try_iter() {
+            self.in_flight -= 1;
+            bytes += len as u64;
+        }
+
+        if !self.groups.is_empty() {
+            let asset_groups = AssetGroups::new(zip_io.clone());
+            for group in std::mem::take(&mut self.groups) {
+                self.stream(asset_groups.paths(&group));
+            }
+        }
+
+        while self.in_flight < self.max_in_flight.max(1)
+            && !matches!(self.bytes_per_frame, Some(budget) if bytes >= budget)
+        {
+            let path = match self.queue.pop_front() {
+                Some(path) => path,
+                None => break,
+            };
+            self.queued.remove(&path);
+            zip_io.spawn_stream(path, self.priority, self.finished.0.clone());
+            self.in_flight += 1;
+        }
+    }
+}
@@ -0,0 +1,177 @@
+//! Helpers for testing asset pipelines against asset bundles
+//!
+//! This module is only available when the `test-util` feature is enabled.
+//!
+//! ```no_run
+//! # use std::collections::HashMap;
+//! # use bevy::prelude::*;
+//! use bevy_assetio_zip::test_util::{app_with_bundle, TempBundle};
+//!
+//! let mut files = HashMap::new();
+//! files.insert("levels/1.ron", &b"()"[..]);
+//!
+//! let bundle = TempBundle::new(&files, false);
+//! let app = app_with_bundle(&bundle);
+//! let asset_server = app.resources().get::<AssetServer>().unwrap();
+//! ```
+
+use std::{
+    collections::HashMap,
+    fs,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+    sync::atomic::{AtomicUsize, Ordering},
+};
+
+use bevy::{asset::AssetPlugin, core::CorePlugin, prelude::*, reflect::ReflectPlugin};
+use zip::{write::FileOptions, ZipWriter};
+
+#[cfg(feature = "obfuscation")]
+use crate::{
+    bundle::OBFUSCATED_ENTRIES_MARKER,
+    format::{FLAG_KEY_SLOT, FORMAT_VERSION, HEADER_LEN, HEADER_MAGIC},
+    keys::{EntryObfuscation, KDF_FNV1A_SPLITMIX, KEY_ALGORITHM_KEYSTREAM},
+    MemoryBundleSource,
+};
+use crate::{format::OBFUSCATION_KEY, AssetIoZipConfig, AssetIoZipPlugin, FallbackAssetIo};
+
+/// Counter used to give each temporary bundle its own directory
+static NEXT_BUNDLE_ID: AtomicUsize = AtomicUsize::new(0);
+
+/// An asset bundle written to a temporary directory, which is deleted when this is dropped
+#[derive(Debug)]
+pub struct TempBundle {
+    dir: PathBuf,
+    path: PathBuf,
+}
+
+impl TempBundle {
+    /// Write a bundle containing the given files to a temporary directory
+    ///
+    /// If `obfuscated` is true an obfuscated `assets.bin` bundle is written, which requires the
+    /// `obfuscation` feature to load, otherwise a plain `assets.zip` bundle is written.
+    pub fn new(files: &HashMap<&str, &[u8]>, obfuscated: bool) -> Self {
+        let dir = std::env::temp_dir().join(format!(
+            "bevy_assetio_zip_test_{}_{}",
+            std::process::id(),
+            NEXT_BUNDLE_ID.fetch_add(1, Ordering::Relaxed)
+        ));
+        fs::create_dir_all(&dir).expect("Could not create temporary bundle dir");
+
+        let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+        let mut paths = files.keys().collect::<Vec<_>>();
+        paths.sort();
+        for path in paths {
+            zip.start_file(*path, FileOptions::default()).unwrap();
+            zip.write_all(files[path]).unwrap();
+        }
+        let mut bytes = zip.finish().unwrap().into_inner();
+
+        let path = if obfuscated {
+            bytes.iter_mut().for_each(|byte| *byte ^= OBFUSCATION_KEY);
+            dir.join("assets.bin")
+        } else {
+            dir.join("assets.zip")
+        };
+        fs::write(&path, bytes).expect("Could not write temporary bundle");
+
+        Self { dir, path }
+    }
+
+    /// The path of the bundle file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+}
+
+impl Drop for TempBundle {
+    fn drop(&mut self) {
+        fs::remove_dir_all(&self.dir).ok();
+    }
+}
+
+/// Create a minimal app with the [`AssetIoZipPlugin`] and the asset server, loading assets only
+/// from the given bundle
+///
+/// Nothing next to the test binary is loaded, because the app is configured with
+/// [`explicit_paths_only`][AssetIoZipConfig::explicit_paths_only].
+///
+/// Asset types and loaders can be added to the returned app builder before using it.
+pub fn app_with_bundle(bundle: &TempBundle) -> AppBuilder {
+    let config = AssetIoZipConfig {
+        bundle_path: Some(bundle.path().to_owned()),
+        fallback: FallbackAssetIo::Noop,
+        explicit_paths_only: true,
+        ..Default::default()
+    };
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder.insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder.add_resource(config);
+    builder
+        .add_plugin(ReflectPlugin)
+        .add_plugin(CorePlugin)
+        .add_plugin(AssetIoZipPlugin)
+        .add_plugin(AssetPlugin);
+
+    builder
+}
+
+/// Create an in-memory bundle containing the given files with the data of each file obfuscated
+/// with its own key derived from the given salt, like the bundler's `obfuscate_entries` option
+///
+/// When `store_salt` is false the salt is left out of the bundle, like the bundler's `entry_salt`
+/// option, so the bundle can only be read with a [`KeyProvider`][crate::KeyProvider] that supplies
+/// it.
+#[cfg(feature = "obfuscation")]
+pub fn entry_obfuscated_bundle(
+    files: &[(&str, &str)],
+    salt: &[u8],
+    store_salt: bool,
+) -> MemoryBundleSource {
+    let obfuscation = EntryObfuscation::Keyed(salt.to_vec());
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, contents) in files {
+        let mut data = contents.as_bytes().to_vec();
+        obfuscation.keystream(path).apply(0, &mut data);
+        zip.start_file(*path, FileOptions::default()).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    zip.start_file(OBFUSCATED_ENTRIES_MARKER, FileOptions::default())
+        .unwrap();
+    if store_salt {
+        zip.write_all(salt).unwrap();
+    }
+    MemoryBundleSource::new(zip.finish().unwrap().into_inner())
+}
+
+/// Create an in-memory v2 bundle containing the given files with the data of each file obfuscated
+/// with its own key derived from the given salt, which is stored only in the key slot of the bundle
+/// header rather than in an obfuscated entries marker
+#[cfg(feature = "obfuscation")]
+pub fn key_slot_bundle(files: &[(&str, &str)], salt: &[u8]) -> MemoryBundleSource {
+    let obfuscation = EntryObfuscation::Keyed(salt.to_vec());
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, contents) in files {
+        let mut data = contents.as_bytes().to_vec();
+        obfuscation.keystream(path).apply(0, &mut data);
+        zip.start_file(*path, FileOptions::default()).unwrap();
+        zip.write_all(&data).unwrap();
+    }
+    let data = zip.finish().unwrap().into_inner();
+
+    let header_len = HEADER_LEN + 4 + salt.len();
+    let mut bytes = Vec::with_capacity(header_len + data.len());
+    bytes.extend_from_slice(HEADER_MAGIC);
+    bytes.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    bytes.extend_from_slice(&FLAG_KEY_SLOT.to_le_bytes());
+    bytes.extend_from_slice(&(header_len as u32).to_le_bytes());
+    bytes.extend_from_slice(&0u64.to_le_bytes());
+    bytes.extend_from_slice(&[KEY_ALGORITHM_KEYSTREAM, KDF_FNV1A_SPLITMIX]);
+    bytes.extend_from_slice(&(salt.len() as u16).to_le_bytes());
+    bytes.extend_from_slice(salt);
+    bytes.extend_from_slice(&data);
+    MemoryBundleSource::new(bytes)
+}
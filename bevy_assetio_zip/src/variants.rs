@@ -0,0 +1,100 @@
+//! Substituting platform specific variants of assets
+
+use std::{
+    collections::HashMap,
+    iter::FromIterator,
+    path::{Path, PathBuf},
+};
+
+/// A table of asset paths to substitute with other asset paths when they are loaded, such as
+/// `shaders/foo.metal.wgsl` for `shaders/foo.wgsl` on macOS
+///
+/// This lets one bundle carry the variants of an asset for every platform, with the right one
+/// resolved transparently when the game loads the asset by its common path. Substitutions are not
+/// chained, so the variant itself is loaded as is. The variant is loaded from the user override
+/// dir, the bundles, or the fallback asset IO like any other asset, and the load fails if it
+/// doesn't exist instead of loading the substituted asset.
+///
+/// The table is set with [`asset_variants`][crate::AssetIoZipConfig::asset_variants] in the
+/// config and can be replaced at runtime with
+/// [`set_asset_variants`][crate::ZipAssetIo::set_asset_variants], such as after reading it from a
+/// settings file. Assets that were already loaded keep the data of the variant they were loaded
+/// with.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AssetVariants {
+    substitutions: HashMap<PathBuf, PathBuf>,
+}
+
+impl AssetVariants {
+    /// Create an empty table that doesn't substitute any assets
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Load `variant` whenever `path` is loaded
+    pub fn insert<P: Into<PathBuf>, Q: Into<PathBuf>>(&mut self, path: P, variant: Q) -> &mut Self {
+        self.substitutions.insert(path.into(), variant.into());
+        self
+    }
+
+    /// Get the path to load when the given asset is loaded, which is the asset itself if it isn't
+    /// substituted
+    pub fn resolve<'a>(&'a self, path: &'a Path) -> &'a Path {
+        self.substitutions.get(path).map_or(path, |x| x.as_path())
+    }
+
+    /// Whether or not the table substitutes any assets
+    pub fn is_empty(&self) -> bool {
+        self.substitutions.is_empty()
+    }
+
+    /// Parse the substitutions for the given platform out of a TOML table
+    ///
+    /// Each platform has its own table of asset paths to variant paths, named after the platform
+    /// as reported by [`std::env::consts::OS`], and substitutions in the `all` table apply to
+    /// every platform unless the platform's own table overrides them:
+    ///
+    /// ```toml
+    /// [macos]
+    /// "shaders/foo.wgsl" = "shaders/foo.metal.wgsl"
+    ///
+    /// [all]
+    /// "config/defaults.ron" = "config/defaults.release.ron"
+    /// ```
+    ///
+    /// Returns an error describing the problem if the TOML is invalid.
+    #[cfg(all(feature = "runtime-config", not(target_arch = "wasm32")))]
+    pub fn from_toml(contents: &str, platform: &str) -> Result<Self, String> {
+        let tables =
+            toml::from_str(contents).map_err(|e| format!("Invalid asset variants: {}", e))?;
+
+        let mut variants = Self::new();
+        variants.extend_for_platform(tables, platform);
+        Ok(variants)
+    }
+
+    /// Add the substitutions of the `all` table and then of the given platform's table from the
+    /// given tables of substitutions by platform
+    #[cfg(all(feature = "runtime-config", not(target_arch = "wasm32")))]
+    pub(crate) fn extend_for_platform(
+        &mut self,
+        mut tables: HashMap<String, HashMap<PathBuf, PathBuf>>,
+        platform: &str,
+    ) {
+        for table in &["all", platform] {
+            self.substitutions
+                .extend(tables.remove(*table).unwrap_or_default());
+        }
+    }
+}
+
+impl<P: Into<PathBuf>, Q: Into<PathBuf>> FromIterator<(P, Q)> for AssetVariants {
+    fn from_iter<I: IntoIterator<Item = (P, Q)>>(iter: I) -> Self {
+        Self {
+            substitutions: iter
+                .into_iter()
+                .map(|(path, variant)| (path.into(), variant.into()))
+                .collect(),
+        }
+    }
+}
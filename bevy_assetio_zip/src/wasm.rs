@@ -0,0 +1,318 @@
+//! Support for downloading the asset bundle in browser builds
+
+use std::{
+    io::{self, Cursor},
+    path::PathBuf,
+    sync::{Arc, RwLock},
+};
+
+use bevy::{log::warn, utils::BoxedFuture};
+use crossbeam_channel::{Receiver, Sender};
+use event_listener::Event;
+use js_sys::{Object, Reflect, Uint8Array};
+use wasm_bindgen::{closure::Closure, JsCast, JsValue};
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{IdbDatabase, IdbRequest, IdbTransactionMode, RequestInit, Response};
+
+use crate::{
+    format::{BundleLayout, KeySlot},
+    BundleSource, ReadSeekSend,
+};
+
+/// The name of the IndexedDB database used to cache downloaded bundles
+const CACHE_DB_NAME: &str = "bevy_assetio_zip";
+/// The name of the object store that cached bundles are stored in
+const CACHE_STORE_NAME: &str = "bundles";
+
+/// Event sent by the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] as the asset bundle is
+/// downloaded in browser builds
+#[derive(Debug, Clone)]
+pub struct BundleDownloadProgress {
+    /// The URL of the bundle being downloaded
+    pub url: String,
+    /// The number of bytes that have been downloaded so far
+    pub bytes: u64,
+    /// The total size of the bundle in bytes, if the server reported it
+    pub total: Option<u64>,
+    /// Whether or not the download has finished. This is also set when the bundle was loaded from
+    /// the browser cache without being downloaded.
+    pub finished: bool,
+}
+
+/// The downloaded bundle data, shared so that archives can be opened without copying it
+#[derive(Clone)]
+struct SharedBytes(Arc<Vec<u8>>);
+
+impl AsRef<[u8]> for SharedBytes {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_slice()
+    }
+}
+
+enum WebBundleState {
+    Downloading,
+    Ready {
+        bytes: SharedBytes,
+        layout: BundleLayout,
+    },
+    Unavailable,
+}
+
+/// The asset bundle downloaded over HTTP in browser builds
+#[derive(Clone)]
+pub(crate) struct WebBundle {
+    state: Arc<RwLock<WebBundleState>>,
+    /// Notified when the download has finished or failed
+    done: Arc<Event>,
+    pub(crate) progress_events: (
+        Sender<BundleDownloadProgress>,
+        Receiver<BundleDownloadProgress>,
+    ),
+}
+
+impl Default for WebBundle {
+    fn default() -> Self {
+        Self {
+            state: Arc::new(RwLock::new(WebBundleState::Downloading)),
+            done: Default::default(),
+            progress_events: crossbeam_channel::unbounded(),
+        }
+    }
+}
+
+impl WebBundle {
+    /// Start downloading the first of the given candidate bundle URLs that exists, optionally
+    /// caching it in IndexedDB so that unchanged bundles are not re-downloaded
+    pub fn start_download(&self, candidates: Vec<(PathBuf, bool)>, use_cache: bool) {
+        let bundle = self.clone();
+        wasm_bindgen_futures::spawn_local(async move {
+            let mut new_state = WebBundleState::Unavailable;
+
+            for (path, obfuscated) in candidates {
+                let url = path.to_string_lossy().replace('\\', "/");
+                match bundle.download(&url, use_cache).await {
+                    Ok(Some(bytes)) => {
+                        // Trust the first bytes of the bundle over its extension
+                        let layout = BundleLayout::detect(&bytes).unwrap_or(BundleLayout {
+                            obfuscated,
+                            ..Default::default()
+                        });
+                        new_state = WebBundleState::Ready {
+                            bytes: SharedBytes(Arc::new(bytes)),
+                            layout,
+                        };
+                        break;
+                    }
+                    Ok(None) => (),
+                    Err(e) => warn!("Could not download asset bundle {}: {:?}", url, e),
+                }
+            }
+
+            *bundle.state.write().unwrap() = new_state;
+            bundle.done.notify(usize::MAX);
+        });
+    }
+
+    /// Wait for the bundle download to finish or fail
+    pub async fn wait_ready(&self) {
+        loop {
+            if !matches!(*self.state.read().unwrap(), WebBundleState::Downloading) {
+                return;
+            }
+
+            let listener = self.done.listen();
+
+            if !matches!(*self.state.read().unwrap(), WebBundleState::Downloading) {
+                return;
+            }
+
+            listener.await;
+        }
+    }
+
+    /// Download the bundle at the given URL, returning `None` if it doesn't exist
+    async fn download(&self, url: &str, use_cache: bool) -> Result<Option<Vec<u8>>, JsValue> {
+        let window = web_sys::window().ok_or("No window")?;
+
+        // Look for a cached copy of the bundle
+        let cache = if use_cache {
+            match open_cache().await {
+                Ok(db) => Some(db),
+                Err(e) => {
+                    warn!("Could not open asset bundle cache: {:?}", e);
+                    None
+                }
+            }
+        } else {
+            None
+        };
+        let cached = match &cache {
+            Some(db) => get_cached(db, url).await.unwrap_or(None),
+            None => None,
+        };
+
+        // Ask the server to only send the bundle if it has changed
+        let mut init = RequestInit::new();
+        if let Some((etag, _)) = &cached {
+            let headers = web_sys::Headers::new()?;
+            headers.set("If-None-Match", etag)?;
+            init.headers(&headers);
+        }
+        let response: Response = JsFuture::from(window.fetch_with_str_and_init(url, &init))
+            .await?
+            .dyn_into()?;
+
+        if response.status() == 304 {
+            if let Some((_, bytes)) = cached {
+                self.send_progress(url, bytes.len() as u64, Some(bytes.len() as u64), true);
+                return Ok(Some(bytes));
+            }
+        }
+        if !response.ok() {
+            return Ok(None);
+        }
+
+        let total = response
+            .headers()
+            .get("Content-Length")?
+            .and_then(|x| x.parse::<u64>().ok());
+        let etag = response.headers().get("ETag")?;
+
+        // Read the body in chunks so that we can report progress
+        let mut bytes = Vec::with_capacity(total.unwrap_or(0) as usize);
+        let body = response.body().ok_or("Response has no body")?;
+        let get_reader: js_sys::Function = Reflect::get(&body, &"getReader".into())?.dyn_into()?;
+        let reader = get_reader.call0(&body)?;
+        let read: js_sys::Function = Reflect::get(&reader, &"read".into())?.dyn_into()?;
+        loop {
+            let chunk = JsFuture::from(js_sys::Promise::from(read.call0(&reader)?)).await?;
+            if Reflect::get(&chunk, &"done".into())?.is_truthy() {
+                break;
+            }
+
+            let value: Uint8Array = Reflect::get(&chunk, &"value".into())?.dyn_into()?;
+            let offset = bytes.len();
+            bytes.resize(offset + value.length() as usize, 0);
+            value.copy_to(&mut bytes[offset..]);
+
+            self.send_progress(url, bytes.len() as u64, total, false);
+        }
+        self.send_progress(url, bytes.len() as u64, total, true);
+
+        if let (Some(db), Some(etag)) = (&cache, etag) {
+            if let Err(e) = put_cached(db, url, &etag, &bytes).await {
+                warn!("Could not cache asset bundle: {:?}", e);
+            }
+        }
+
+        Ok(Some(bytes))
+    }
+
+    fn send_progress(&self, url: &str, bytes: u64, total: Option<u64>, finished: bool) {
+        self.progress_events
+            .0
+            .send(BundleDownloadProgress {
+                url: url.into(),
+                bytes,
+                total,
+                finished,
+            })
+            .ok();
+    }
+}
+
+impl BundleSource for WebBundle {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        match &*self.state.read().unwrap() {
+            WebBundleState::Ready { bytes, layout } => layout.open(Cursor::new(bytes.clone())),
+            _ => Err(io::Error::new(
+                io::ErrorKind::NotFound,
+                "Asset bundle has not been downloaded",
+            )),
+        }
+    }
+
+    fn ready(&self) -> BoxedFuture<'_, ()> {
+        Box::pin(self.wait_ready())
+    }
+
+    fn obfuscated(&self) -> bool {
+        match &*self.state.read().unwrap() {
+            WebBundleState::Ready { layout, .. } => layout.obfuscated,
+            _ => false,
+        }
+    }
+
+    fn key_slot(&self) -> Option<KeySlot> {
+        match &*self.state.read().unwrap() {
+            WebBundleState::Ready { bytes, .. } => KeySlot::parse(bytes.as_ref()),
+            _ => None,
+        }
+    }
+}
+
+/// Wait for an IndexedDB request to complete and return its result
+async fn request_result(request: &IdbRequest) -> Result<JsValue, JsValue> {
+    let promise = js_sys::Promise::new(&mut |resolve, reject| {
+        request.set_onsuccess(Some(&resolve));
+        request.set_onerror(Some(&reject));
+    });
+    JsFuture::from(promise).await?;
+    request.result()
+}
+
+/// Open the IndexedDB database that bundles are cached in
+async fn open_cache() -> Result<IdbDatabase, JsValue> {
+    let window = web_sys::window().ok_or("No window")?;
+    let factory = window.indexed_db()?.ok_or("IndexedDB not supported")?;
+    let request = factory.open_with_u32(CACHE_DB_NAME, 1)?;
+
+    // Create the object store the first time the database is opened
+    let upgrade_request = request.clone();
+    let on_upgrade = Closure::wrap(Box::new(move |_: web_sys::Event| {
+        if let Ok(db) = upgrade_request
+            .result()
+            .and_then(|x| x.dyn_into::<IdbDatabase>())
+        {
+            db.create_object_store(CACHE_STORE_NAME).ok();
+        }
+    }) as Box<dyn FnMut(web_sys::Event)>);
+    request.set_onupgradeneeded(Some(on_upgrade.as_ref().unchecked_ref()));
+
+    let db = request_result(&request).await?.dyn_into()?;
+    request.set_onupgradeneeded(None);
+
+    Ok(db)
+}
+
+/// Get the ETag and data of the cached bundle downloaded from the given URL
+async fn get_cached(db: &IdbDatabase, url: &str) -> Result<Option<(String, Vec<u8>)>, JsValue> {
+    let store = db
+        .transaction_with_str(CACHE_STORE_NAME)?
+        .object_store(CACHE_STORE_NAME)?;
+    let value = request_result(&store.get(&url.into())?).await?;
+    if value.is_undefined() {
+        return Ok(None);
+    }
+
+    let etag = Reflect::get(&value, &"etag".into())?
+        .as_string()
+        .ok_or("Invalid cache entry")?;
+    let data: Uint8Array = Reflect::get(&value, &"data".into())?.dyn_into()?;
+
+    Ok(Some((etag, data.to_vec())))
+}
+
+/// Store the bundle downloaded from the given URL in the cache
+async fn put_cached(db: &IdbDatabase, url: &str, etag: &str, bytes: &[u8]) -> Result<(), JsValue> {
+    let store = db
+        .transaction_with_str_and_mode(CACHE_STORE_NAME, IdbTransactionMode::Readwrite)?
+        .object_store(CACHE_STORE_NAME)?;
+
+    let value = Object::new();
+    Reflect::set(&value, &"etag".into(), &etag.into())?;
+    Reflect::set(&value, &"data".into(), &Uint8Array::from(bytes))?;
+    request_result(&store.put_with_key(&value, &url.into())?).await?;
+
+    Ok(())
+}
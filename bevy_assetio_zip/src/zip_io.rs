@@ -0,0 +1,1390 @@
+//! The [`ZipAssetIo`], which loads assets from the mounted asset bundles without a fallback
+
+use std::{
+    collections::{BTreeMap, BTreeSet, BinaryHeap, HashMap, HashSet},
+    io,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex, RwLock,
+    },
+};
+
+#[cfg(not(target_arch = "wasm32"))]
+use bevy::tasks::TaskPoolBuilder;
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    log::{error, info, warn},
+    tasks::TaskPool,
+    utils::BoxedFuture,
+};
+use crossbeam_channel::{Receiver, Sender};
+
+#[cfg(target_arch = "wasm32")]
+use crate::wasm;
+#[cfg(not(target_arch = "wasm32"))]
+use crate::{
+    appended, extract, integrity, mods, overlay, BundleRegion, FileBundleSource, ModConflictPolicy,
+    RawEntry, TamperResponse,
+};
+use crate::{
+    bundle::Bundle,
+    filter::PathFilter,
+    prefetch_cache::PrefetchCache,
+    priority::{PriorityGate, QueuedPrefetch},
+    AssetIoZipConfig, AssetLoadProgress, AssetMetadata, AssetSource, AssetVariants, BundleSources,
+    CachedEntryMetadata, CancellationToken, CorruptAssetError, EntryMetadataCache, EntryReader,
+    LoadPriority, LoadedAsset, ModConflictReport, MountedBundle, DISABLE_ENV_VAR,
+    EXPLICIT_PATHS_ENV_VAR,
+};
+
+/// The entry that the bundler collects the license and credits files into
+const CREDITS_ENTRY: &str = "credits.txt";
+
+/// An [`AssetIo`] implementation that loads assets from the mounted asset bundles only
+///
+/// Unlike the [`AssetIoZip`][crate::AssetIoZip], this doesn't fall back to any other asset IO when
+/// an asset is not in a bundle, so it can be layered with other asset IO implementations in any
+/// order, such as trying a network asset IO, then the bundles, then the filesystem. The
+/// [`AssetIoZip`][crate::AssetIoZip] is the composition of a `ZipAssetIo` with a fallback and
+/// dereferences to it, so all of the methods here can be called on the resource inserted by the
+/// [`AssetIoZipPlugin`][crate::AssetIoZipPlugin].
+///
+/// The archives of each bundle are opened once and kept in a pool of handles that is shared by all
+/// of the clones of the asset IO, which are `Send + Sync`, so it can also be used to read assets
+/// outside of the asset server from any thread.
+///
+/// ```no_run
+/// # use bevy::tasks::TaskPool;
+/// # use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+/// let zip_io = ZipAssetIo::new(
+///     AssetIoZipConfig::default(),
+///     BundleSources::default(),
+///     Some("assets".into()),
+///     TaskPool::new(),
+/// );
+/// // Wrap `zip_io` in your own asset IO and create the `AssetServer` with it
+/// ```
+#[derive(Clone)]
+pub struct ZipAssetIo {
+    pub(crate) config: AssetIoZipConfig,
+    /// The Bevy asset folder, if the platform has one on the filesystem
+    pub(crate) asset_dir: Option<PathBuf>,
+    pub(crate) task_pool: TaskPool,
+    /// The dedicated pool that assets are decompressed on, if one is configured
+    #[cfg(not(target_arch = "wasm32"))]
+    decompression_pool: Option<TaskPool>,
+    /// The mounted bundles in priority order, highest priority first
+    bundles: Arc<RwLock<Vec<Arc<Bundle>>>>,
+    /// Incremented every time that the mounted bundles change
+    mount_generation: Arc<AtomicU64>,
+    /// The assets provided by more than one of the mounted mods
+    mod_conflicts: Arc<RwLock<ModConflictReport>>,
+    /// Decompressed entries that have been prefetched but not yet loaded
+    cache: Arc<RwLock<PrefetchCache>>,
+    /// The tokens that cancel the pending loads of assets
+    cancellations: Arc<RwLock<HashMap<PathBuf, CancellationToken>>>,
+    /// The priorities of the pending loads of assets that don't have the default priority
+    priorities: Arc<RwLock<HashMap<PathBuf, LoadPriority>>>,
+    /// Orders the reads of assets from the bundles by priority
+    priority_gate: Arc<PriorityGate>,
+    /// The assets waiting to be prefetched, highest priority first
+    prefetch_queue: Arc<Mutex<BinaryHeap<QueuedPrefetch>>>,
+    /// The number of assets that have been queued to be prefetched
+    prefetch_sequence: Arc<AtomicU64>,
+    /// The asset paths that are substituted with variants when they are loaded
+    asset_variants: Arc<RwLock<AssetVariants>>,
+    /// Channel used to send the progress of decompressing large assets to the Bevy world
+    pub(crate) progress_events: (Sender<AssetLoadProgress>, Receiver<AssetLoadProgress>),
+    /// The bundle downloaded in browser builds
+    #[cfg(target_arch = "wasm32")]
+    pub(crate) web_bundle: wasm::WebBundle,
+}
+
+/// Whether or not the given environment variable is set to anything other than an empty string,
+/// `0`, or `false`
+pub(crate) fn env_flag(name: &str) -> bool {
+    std::env::var(name)
+        .map(|x| !matches!(x.as_str(), "" | "0" | "false"))
+        .unwrap_or(false)
+}
+
+impl ZipAssetIo {
+    /// Mount the bundles found with the given config and the given custom bundle sources
+    ///
+    /// Bundles are searched for next to the executable and, if it is given, in the Bevy asset
+    /// folder `asset_dir`, exactly like the [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] does.
+    /// The [`fallback`][AssetIoZipConfig::fallback] setting of the config is ignored. In browser
+    /// builds this starts downloading the bundle from the asset folder. Nothing is mounted when
+    /// [`disable_bundles`][AssetIoZipConfig::disable_bundles] is set or when the
+    /// [`DISABLE_ENV_VAR`] environment variable is set. Only the paths given in the config are
+    /// searched when [`explicit_paths_only`][AssetIoZipConfig::explicit_paths_only] is set or
+    /// when the [`EXPLICIT_PATHS_ENV_VAR`] environment variable is set.
+    pub fn new(
+        config: AssetIoZipConfig,
+        sources: BundleSources,
+        asset_dir: Option<PathBuf>,
+        task_pool: TaskPool,
+    ) -> Self {
+        let mut config = config;
+        config.disable_bundles |= env_flag(DISABLE_ENV_VAR);
+        config.explicit_paths_only |= env_flag(EXPLICIT_PATHS_ENV_VAR);
+        config
+            .max_concurrent_reads
+            .get_or_insert(task_pool.thread_num());
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let decompression_pool = config.decompression_threads.map(|threads| {
+            TaskPoolBuilder::new()
+                .num_threads(threads)
+                .thread_name("Asset Decompression".into())
+                .build()
+        });
+
+        let asset_variants = Arc::new(RwLock::new(config.asset_variants.clone()));
+        let cache = Arc::new(RwLock::new(PrefetchCache::new(config.max_prefetched_bytes)));
+        let zip_io = Self {
+            config,
+            asset_dir,
+            task_pool,
+            #[cfg(not(target_arch = "wasm32"))]
+            decompression_pool,
+            bundles: Default::default(),
+            mount_generation: Default::default(),
+            mod_conflicts: Default::default(),
+            cache,
+            cancellations: Default::default(),
+            priorities: Default::default(),
+            priority_gate: Default::default(),
+            prefetch_queue: Default::default(),
+            prefetch_sequence: Default::default(),
+            asset_variants,
+            progress_events: crossbeam_channel::unbounded(),
+            #[cfg(target_arch = "wasm32")]
+            web_bundle: Default::default(),
+        };
+
+        if zip_io.config.disable_bundles {
+            info!("Asset bundles are disabled, loading assets from the fallback asset IO only");
+            return zip_io;
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        zip_io
+            .web_bundle
+            .start_download(zip_io.bundle_candidates(), zip_io.config.cache_bundle);
+
+        zip_io.mount_bundles(sources);
+
+        if zip_io.config.verbose {
+            zip_io.log_bundle_info();
+        }
+
+        zip_io
+    }
+
+    /// Get the directory that the executable is in
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn exe_dir() -> PathBuf {
+        let exe_path = std::env::current_exe().expect("Could not obtain current exe path");
+        // The iOS sandbox reports the app container under `/var`, which is a symlink to
+        // `/private/var`, so resolve it to match the paths returned by the file APIs
+        #[cfg(target_os = "ios")]
+        let exe_path = exe_path.canonicalize().unwrap_or(exe_path);
+        exe_path
+            .parent()
+            .expect("Current exe has no parent dir")
+            .to_owned()
+    }
+
+    /// Get the paths that will be searched for the asset bundle in order, along with whether or
+    /// not the bundle at each path is obfuscated
+    fn bundle_candidates(&self) -> Vec<(PathBuf, bool)> {
+        if let Some(path) = &self.config.bundle_path {
+            let obfuscated = cfg!(feature = "obfuscation")
+                && path.extension().map(|x| x == "bin").unwrap_or(false);
+            return vec![(path.clone(), obfuscated)];
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        let search_dirs = {
+            let mut search_dirs = Vec::new();
+            if !self.config.explicit_paths_only {
+                search_dirs.push(Self::exe_dir());
+            }
+            if self.config.search_asset_dir {
+                search_dirs.extend(self.asset_dir.clone());
+            }
+            #[cfg(target_os = "ios")]
+            if self.config.search_app_bundle && !self.config.explicit_paths_only {
+                for dir in crate::ios::resource_dirs() {
+                    if !search_dirs.contains(&dir) {
+                        search_dirs.push(dir);
+                    }
+                }
+            }
+            search_dirs
+        };
+        // There is no executable dir in the browser so bundles are downloaded from the asset folder
+        #[cfg(target_arch = "wasm32")]
+        let search_dirs: Vec<PathBuf> = self.asset_dir.iter().cloned().collect();
+
+        let mut candidates = Vec::new();
+        for dir in search_dirs {
+            for file_name in &self.config.file_names {
+                #[cfg(feature = "obfuscation")]
+                candidates.push((dir.join(format!("{}.{}", file_name, "bin")), true));
+                candidates.push((dir.join(format!("{}.{}", file_name, "zip")), false));
+                #[cfg(feature = "sqlite")]
+                candidates.push((dir.join(format!("{}.{}", file_name, "sqlite")), false));
+            }
+        }
+
+        #[cfg(target_os = "android")]
+        if self.config.search_obb && !self.config.explicit_paths_only {
+            candidates.extend(
+                crate::android::obb_candidates()
+                    .into_iter()
+                    .map(|x| (x, false)),
+            );
+        }
+
+        candidates
+    }
+
+    /// Whether or not to look for a bundle appended to the executable
+    #[cfg(not(target_arch = "wasm32"))]
+    fn searches_exe(&self) -> bool {
+        self.config.search_exe && !self.config.explicit_paths_only
+    }
+
+    /// Find the asset bundle file, along with the region of the file that contains the bundle if
+    /// it doesn't take up the whole file
+    ///
+    /// A bundle appended to the executable takes precedence over the bundles next to it.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn find_bundle(&self) -> Option<(PathBuf, Option<BundleRegion>)> {
+        if let Some(path) = &self.config.bundle_path {
+            return Some((path.clone(), self.config.bundle_region)).filter(|x| x.0.exists());
+        }
+
+        if self.searches_exe() {
+            let exe_path = std::env::current_exe().expect("Could not obtain current exe path");
+            if let Some(region) = appended::appended_region(&exe_path) {
+                return Some((exe_path, Some(region)));
+            }
+        }
+
+        self.bundle_candidates()
+            .into_iter()
+            .map(|(path, _)| path)
+            .find(|path| path.exists())
+            .map(|path| (path, None))
+    }
+
+    /// Get the hash of the bundle in the given region of the file at the given path, which is
+    /// cached across runs in the [`hash_cache_dir`][AssetIoZipConfig::hash_cache_dir] if one is
+    /// set
+    #[cfg(not(target_arch = "wasm32"))]
+    fn bundle_hash(&self, path: &Path, region: Option<BundleRegion>) -> io::Result<String> {
+        integrity::cached_bundle_hash(path, region, self.config.hash_cache_dir.as_deref())
+    }
+
+    /// Check the hash of the main asset bundle against the hash embedded in the game, responding to
+    /// a mismatch as set by the [`tamper_response`][AssetIoZipConfig::tamper_response]
+    #[cfg(not(target_arch = "wasm32"))]
+    fn check_bundle_hash(&self, path: &Path, region: Option<BundleRegion>, expected: &str) {
+        let message = match self.bundle_hash(path, region) {
+            Ok(hash) if hash.eq_ignore_ascii_case(expected) => return,
+            Ok(hash) => format!(
+                "Asset bundle {} has been modified: expected hash {} but found {}",
+                path.display(),
+                expected,
+                hash
+            ),
+            Err(e) => format!(
+                "Could not hash asset bundle {} to check it: {}",
+                path.display(),
+                e
+            ),
+        };
+
+        match self.config.tamper_response {
+            TamperResponse::Warn => warn!("{}", message),
+            TamperResponse::Error => panic!("{}", message),
+        }
+    }
+
+    /// Find and mount the mod bundles, the given custom bundle sources, and the main asset bundle
+    fn mount_bundles(&self, sources: BundleSources) {
+        let mut bundles = Vec::new();
+
+        #[cfg(not(target_arch = "wasm32"))]
+        {
+            // Relative paths are joined onto the current directory by the file system
+            let base_dir = if self.config.explicit_paths_only {
+                PathBuf::new()
+            } else {
+                Self::exe_dir()
+            };
+            let mod_dirs = self
+                .config
+                .mod_dirs
+                .iter()
+                .map(|dir| base_dir.join(dir))
+                .collect::<Vec<_>>();
+            let mod_filter = PathFilter::new(&self.config.mod_allow, &self.config.mod_deny);
+            for discovered in mods::discover_mods(
+                &mod_dirs,
+                &self.config.mod_order,
+                self.config.mod_conflict_policy,
+            ) {
+                let source = FileBundleSource::detect(discovered.path)
+                    .with_sharing(self.config.bundle_sharing);
+                let bundle = Bundle::new(discovered.name, true, Arc::new(source), &self.config)
+                    .with_filter(mod_filter.clone());
+
+                // Refuse mods that could be zip bombs before anything is read from them
+                let limits = bundle.check_limits(
+                    self.config.mod_max_entries,
+                    self.config.mod_max_uncompressed_size,
+                );
+                match limits {
+                    Ok(()) => bundles.push(Arc::new(bundle)),
+                    Err(e) => error!("{}. Refusing to mount mod {}.", e, bundle.name),
+                }
+            }
+
+            let conflicts = mods::find_conflicts(&bundles, self.config.mod_conflict_policy);
+            if self.config.mod_conflict_policy == ModConflictPolicy::Error {
+                for conflict in &conflicts.conflicts {
+                    warn!(
+                        "Asset {} is provided by multiple mods and will fail to load: {}",
+                        conflict.path.display(),
+                        conflict.providers.join(", ")
+                    );
+                }
+            }
+            *self.mod_conflicts.write().unwrap() = conflicts;
+        }
+
+        for (name, source) in sources.0 {
+            bundles.push(Arc::new(Bundle::new(name, false, source, &self.config)));
+        }
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((path, region)) = self.find_bundle() {
+            if let Some(expected) = &self.config.expected_bundle_hash {
+                self.check_bundle_hash(&path, region, expected);
+            }
+            let name = path
+                .file_stem()
+                .map(|x| x.to_string_lossy().into_owned())
+                .unwrap_or_default();
+            let source = match region {
+                Some(region) => FileBundleSource::detect_region(&path, region),
+                None => FileBundleSource::detect(&path),
+            }
+            .with_sharing(self.config.bundle_sharing);
+            let bundle =
+                self.mount_filter(Bundle::new(name, false, Arc::new(source), &self.config));
+            let bundle = match self.extract_dir() {
+                Some(dir) => {
+                    let extracted = self
+                        .bundle_hash(&path, region)
+                        .map_err(|e| {
+                            format!("Could not hash asset bundle {}: {}", path.display(), e)
+                        })
+                        .and_then(|x| extract::extract_bundle(&bundle, &x, &dir, &self.task_pool));
+                    match extracted {
+                        Ok(dir) => bundle.with_extracted_dir(dir),
+                        Err(e) => {
+                            error!("{}. Loading assets from the bundle instead.", e);
+                            bundle
+                        }
+                    }
+                }
+                None => bundle,
+            };
+            bundles.push(Arc::new(bundle));
+        }
+
+        #[cfg(target_arch = "wasm32")]
+        {
+            let name = self.config.file_names.first().cloned().unwrap_or_default();
+            let source = Arc::new(self.web_bundle.clone());
+            bundles.push(Arc::new(self.mount_filter(Bundle::new(
+                name,
+                false,
+                source,
+                &self.config,
+            ))));
+        }
+
+        // The overlay is mounted even if it doesn't exist yet, so that assets written to it later
+        // are found
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some((name, path)) = self.overlay() {
+            let source = FileBundleSource::new(path, false);
+            bundles.insert(
+                0,
+                Arc::new(Bundle::new(name, false, Arc::new(source), &self.config)),
+            );
+        }
+
+        // Report bundles that exist but are corrupted instead of silently ignoring them
+        bundles.retain(|bundle| match bundle.check() {
+            Ok(()) => true,
+            Err(e) if self.config.corrupt_bundle_fallback => {
+                error!("{}. Continuing without it.", e);
+                false
+            }
+            Err(e) => panic!("{}", e),
+        });
+
+        *self.bundles.write().unwrap() = bundles;
+        self.mount_generation.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Restrict the main asset bundle to the assets matching the
+    /// [`mount_patterns`][AssetIoZipConfig::mount_patterns], if there are any
+    fn mount_filter(&self, bundle: Bundle) -> Bundle {
+        if self.config.mount_patterns.is_empty() {
+            bundle
+        } else {
+            bundle.with_filter(PathFilter::new(&self.config.mount_patterns, &[]))
+        }
+    }
+
+    /// Get the bundles currently mounted, in priority order, highest priority first
+    pub(crate) fn bundles(&self) -> Vec<Arc<Bundle>> {
+        self.bundles.read().unwrap().clone()
+    }
+
+    /// Get information about the mounted asset bundles, in priority order, highest priority first
+    pub fn mounted_bundles(&self) -> Vec<MountedBundle> {
+        self.bundles()
+            .iter()
+            .map(|bundle| MountedBundle {
+                name: bundle.name.clone(),
+                path: bundle.path().map(ToOwned::to_owned),
+                is_mod: bundle.is_mod,
+            })
+            .collect()
+    }
+
+    /// Unmount the bundle with the given name, returning whether or not a bundle was unmounted
+    ///
+    /// This drops the assets prefetched from the bundle and the data it has decompressed or
+    /// memory mapped, and the bundle file is closed as soon as any reads from it that are in
+    /// progress have finished. This allows the bundle file to be replaced, such as by an updater,
+    /// while the game is running. Assets are loaded from the remaining bundles afterwards.
+    ///
+    /// If the bundle is a mod, the report returned by [`mod_conflicts`][Self::mod_conflicts] is
+    /// updated, but the [`ModConflictReport`] resource is not.
+    pub fn unmount(&self, name: &str) -> bool {
+        let unmounted = {
+            let mut bundles = self.bundles.write().unwrap();
+            let index = match bundles.iter().position(|bundle| bundle.name == name) {
+                Some(index) => index,
+                None => return false,
+            };
+            bundles.remove(index)
+        };
+        self.mount_generation.fetch_add(1, Ordering::Relaxed);
+
+        self.cache
+            .write()
+            .unwrap()
+            .remove_source(&AssetSource::Bundle(name.to_owned()));
+
+        #[cfg(not(target_arch = "wasm32"))]
+        if unmounted.is_mod {
+            *self.mod_conflicts.write().unwrap() =
+                mods::find_conflicts(&self.bundles(), self.config.mod_conflict_policy);
+        }
+
+        info!("Unmounted asset bundle {}", unmounted.location());
+
+        true
+    }
+
+    /// Get the user override directory, if one is configured and enabled
+    ///
+    /// The override dir is skipped along with the bundles when they are disabled, so that every
+    /// asset comes from the fallback asset IO.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn override_dir(&self) -> Option<&Path> {
+        if self.config.use_override_dir && !self.config.disable_bundles {
+            self.config.override_dir.as_deref()
+        } else {
+            None
+        }
+    }
+
+    /// Get the directory to extract the main asset bundle to, if extraction is enabled
+    ///
+    /// This defaults to a `bevy_assetio_zip` directory in the temp dir of the platform.
+    #[cfg(not(target_arch = "wasm32"))]
+    fn extract_dir(&self) -> Option<PathBuf> {
+        if !self.config.extract_bundle {
+            return None;
+        }
+
+        Some(
+            self.config
+                .extract_dir
+                .clone()
+                .unwrap_or_else(|| std::env::temp_dir().join("bevy_assetio_zip")),
+        )
+    }
+
+    /// Get the name and path of the writable overlay bundle, if one is configured
+    #[cfg(not(target_arch = "wasm32"))]
+    fn overlay(&self) -> Option<(String, PathBuf)> {
+        let path = self.config.overlay_bundle.clone()?;
+        let name = path
+            .file_stem()
+            .map(|x| x.to_string_lossy().into_owned())
+            .unwrap_or_default();
+        Some((name, path))
+    }
+
+    /// Write an asset into the writable [`overlay_bundle`][AssetIoZipConfig::overlay_bundle], such
+    /// as an asset downloaded from a server or created by the player
+    ///
+    /// The overlay bundle is created if it doesn't exist yet, and an asset that is already in it is
+    /// replaced. The asset is loaded from the overlay from then on, since it has a higher priority
+    /// than any other bundle. A [`NotFound`][io::ErrorKind::NotFound] error is returned if no
+    /// overlay bundle is configured.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn write_overlay_asset<P: AsRef<Path>>(&self, path: P, data: &[u8]) -> io::Result<()> {
+        let path = path.as_ref();
+        let (name, bundle_path) = self.overlay().ok_or_else(|| {
+            io::Error::new(io::ErrorKind::NotFound, "No overlay bundle is configured")
+        })?;
+        let entry_name = path
+            .to_str()
+            .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Non-unicode asset path"))?
+            .replace('\\', "/");
+
+        // Reads are blocked while the bundle is written so that none of them see it half written
+        let mut bundles = self.bundles.write().unwrap();
+        overlay::write_asset(&bundle_path, &entry_name, data)?;
+
+        // Mount the overlay again, since the opened archives still list the old entries
+        if let Some(bundle) = bundles.iter_mut().find(|bundle| bundle.name == name) {
+            *bundle = Arc::new(Bundle::new(
+                name,
+                false,
+                Arc::new(FileBundleSource::new(bundle_path, false)),
+                &self.config,
+            ));
+        }
+        self.mount_generation.fetch_add(1, Ordering::Relaxed);
+        self.cache.write().unwrap().remove(path);
+
+        Ok(())
+    }
+
+    /// Get the report of the assets that are provided by more than one of the mounted mods
+    pub fn mod_conflicts(&self) -> ModConflictReport {
+        self.mod_conflicts.read().unwrap().clone()
+    }
+
+    /// Get the mounted bundles that contain the given asset, in priority order
+    ///
+    /// The first bundle in the list is the one that the asset will be loaded from, and the
+    /// following bundles are the ones that it overrides. If no bundle contains the asset, it will
+    /// be loaded from the fallback asset IO, if there is one.
+    pub fn override_chain<P: AsRef<Path>>(&self, path: P) -> Vec<MountedBundle> {
+        let path = path.as_ref();
+        self.bundles()
+            .iter()
+            .filter(|bundle| {
+                bundle
+                    .open()
+                    .map(|mut archive| bundle.contains(&mut archive, path))
+                    .unwrap_or(false)
+            })
+            .map(|bundle| MountedBundle {
+                name: bundle.name.clone(),
+                path: bundle.path().map(ToOwned::to_owned),
+                is_mod: bundle.is_mod,
+            })
+            .collect()
+    }
+
+    /// Read an asset from the highest priority bundle that contains it, returning `None` if no
+    /// bundle contains the asset
+    pub(crate) async fn read_from_bundles(
+        &self,
+        path: &Path,
+    ) -> Option<Result<LoadedAsset, AssetIoError>> {
+        if let Some(e) = self.conflict_error(path) {
+            return Some(Err(e.into()));
+        }
+
+        for bundle in self.bundles() {
+            // Make sure the bundle is available before we try to load from it
+            bundle.wait_ready().await;
+
+            if let Some(e) = self.cancelled_error(path) {
+                return Some(Err(e.into()));
+            }
+
+            let progress = self
+                .config
+                .progress_event_size
+                .map(|size| (size, self.progress_events.0.clone()));
+            #[cfg(not(target_arch = "wasm32"))]
+            let result = match &self.decompression_pool {
+                Some(pool) => {
+                    let (bundle, path) = (bundle.clone(), path.to_owned());
+                    pool.spawn(async move { Self::read_from_bundle(&bundle, &path, progress) })
+                        .await
+                }
+                None => Self::read_from_bundle(&bundle, path, progress),
+            };
+            #[cfg(target_arch = "wasm32")]
+            let result = Self::read_from_bundle(&bundle, path, progress);
+
+            match result {
+                Some(Ok(bytes)) => {
+                    return Some(Ok((bytes, AssetSource::Bundle(bundle.name.clone()))))
+                }
+                Some(Err(e)) => {
+                    let error = CorruptAssetError {
+                        path: path.to_owned(),
+                        bundle: bundle.name.clone(),
+                        source: match e {
+                            AssetIoError::Io(e) => e,
+                            e => io::Error::new(io::ErrorKind::Other, e.to_string()),
+                        },
+                    };
+                    if !self.config.corrupt_asset_fallback {
+                        return Some(Err(error.into_asset_io_error()));
+                    }
+                    error!(
+                        "{}. Loading it from the next bundle or the fallback asset IO instead.",
+                        error
+                    );
+                }
+                None => (),
+            }
+        }
+
+        None
+    }
+
+    /// Read and decompress an asset from the given bundle, returning `None` if the bundle can't be
+    /// opened or doesn't contain the asset
+    ///
+    /// If `progress` is given, the progress of decompressing the asset is sent to its channel when
+    /// the asset is at least as big as its minimum size.
+    fn read_from_bundle(
+        bundle: &Bundle,
+        path: &Path,
+        progress: Option<(u64, Sender<AssetLoadProgress>)>,
+    ) -> Option<Result<Vec<u8>, AssetIoError>> {
+        let mut archive = bundle.open()?;
+        let mut report = |bytes: u64, total: u64| match &progress {
+            Some((min_size, events)) if total >= *min_size => {
+                // The receiver is owned by the asset IO, which outlives the read
+                events
+                    .send(AssetLoadProgress {
+                        path: path.to_owned(),
+                        bytes,
+                        total,
+                    })
+                    .ok();
+            }
+            _ => (),
+        };
+
+        bundle.read_asset_with_progress(&mut archive, path, &mut report)
+    }
+
+    /// Get the error for an asset whose load has been cancelled with a [`CancellationToken`]
+    fn cancelled_error(&self, path: &Path) -> Option<io::Error> {
+        let cancellations = self.cancellations.read().unwrap();
+        cancellations.get(path).filter(|x| x.is_cancelled())?;
+
+        Some(io::Error::new(
+            io::ErrorKind::Interrupted,
+            format!("Loading asset {} was cancelled", path.display()),
+        ))
+    }
+
+    /// Get the error for an asset that is provided by more than one mod, if conflicts are resolved
+    /// with [`ModConflictPolicy::Error`]
+    fn conflict_error(&self, path: &Path) -> Option<io::Error> {
+        let conflicts = self.mod_conflicts.read().unwrap();
+        let conflict = conflicts.get(path).filter(|x| x.winner.is_none())?;
+
+        Some(io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!(
+                "Asset {} is provided by multiple mods: {}",
+                path.display(),
+                conflict.providers.join(", ")
+            ),
+        ))
+    }
+
+    /// Open a streaming reader over an asset in the highest priority bundle that contains it
+    ///
+    /// This lets streaming audio and video decoders read assets directly from the bundle without
+    /// loading them into memory first. Only assets that are stored uncompressed in the bundle can
+    /// be streamed, such as by bundling with the `none` compression, so assets in SQLite bundles
+    /// can't be streamed. Assets that are not in any bundle are not read from the fallback asset IO
+    /// and return a [`NotFound`][io::ErrorKind::NotFound] error.
+    ///
+    /// In browser builds this fails until the bundle has been downloaded.
+    pub fn open_entry<P: AsRef<Path>>(&self, path: P) -> io::Result<EntryReader> {
+        let path = path.as_ref();
+        if let Some(e) = self.conflict_error(path) {
+            return Err(e);
+        }
+
+        for bundle in self.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => continue,
+            };
+
+            if let Some(result) = bundle.open_entry(&mut archive, path) {
+                return result;
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Asset {} is not in any bundle", path.display()),
+        ))
+    }
+
+    /// Get the compressed data of an asset in the highest priority bundle that contains it, without
+    /// decompressing it
+    ///
+    /// This lets a game server send assets to its clients over the network without recompressing
+    /// them, with the client getting the asset back with [`RawEntry::decompress`]. Assets in packed
+    /// blocks, extracted bundles, or SQLite bundles are returned uncompressed. Assets in bundles
+    /// with obfuscated entries can't be passed through and return an
+    /// [`InvalidInput`][io::ErrorKind::InvalidInput] error. Assets that are not in any bundle
+    /// return a [`NotFound`][io::ErrorKind::NotFound] error.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub fn raw_entry<P: AsRef<Path>>(&self, path: P) -> io::Result<RawEntry> {
+        let path = path.as_ref();
+        if let Some(e) = self.conflict_error(path) {
+            return Err(e);
+        }
+
+        for bundle in self.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => continue,
+            };
+
+            if let Some(result) = bundle.raw_entry(&mut archive, path) {
+                return result;
+            }
+        }
+
+        Err(io::Error::new(
+            io::ErrorKind::NotFound,
+            format!("Asset {} is not in any bundle", path.display()),
+        ))
+    }
+
+    /// Get the version information of an asset in the highest priority bundle that contains it,
+    /// returning `None` if no bundle contains the asset
+    ///
+    /// The modification time and content hash are only recorded by the bundler when
+    /// `asset-metadata` is enabled in the `asset_config.toml`. In browser builds this returns
+    /// `None` until the bundle has been downloaded.
+    pub fn asset_metadata<P: AsRef<Path>>(&self, path: P) -> Option<AssetMetadata> {
+        let path = path.as_ref();
+        self.bundles().iter().find_map(|bundle| {
+            let mut archive = bundle.open()?;
+            bundle.asset_metadata(&mut archive, path)
+        })
+    }
+
+    /// Whether or not any of the mounted bundles contains the given asset
+    ///
+    /// Assets that are only available from the user override dir or the fallback asset IO are not
+    /// counted. In browser builds this returns `false` until the bundle has been downloaded.
+    pub fn contains<P: AsRef<Path>>(&self, path: P) -> bool {
+        let path = path.as_ref();
+        self.bundles().iter().any(|bundle| {
+            bundle
+                .open()
+                .map(|mut archive| bundle.contains(&mut archive, path))
+                .unwrap_or(false)
+        })
+    }
+
+    /// Get the paths of the assets in the mounted bundles that start with the given prefix, in
+    /// order and without duplicates
+    ///
+    /// The prefix is matched against the whole path, so `levels/` lists every asset in the
+    /// `levels` folder and its sub-folders, and an empty prefix lists every asset.
+    pub fn list_assets(&self, prefix: &str) -> Vec<PathBuf> {
+        self.bundle_asset_paths()
+            .into_iter()
+            .filter(|path| path.starts_with(prefix))
+            .map(PathBuf::from)
+            .collect()
+    }
+
+    /// Get the content hash of every asset in the mounted bundles, taken from the highest priority
+    /// bundle that contains each asset
+    ///
+    /// Multiplayer games can compare the fingerprints of the client and the server to make sure
+    /// that they are running identical asset sets before allowing a match. The hashes are the
+    /// CRC-32 checksums recorded by the bundler when `asset-metadata` is enabled in the
+    /// `asset_config.toml`, so assets from bundles built without it are left out. Assets that are
+    /// only available from the fallback asset IO are not included. In browser builds, bundles that
+    /// are still downloading are skipped.
+    pub fn asset_fingerprints(&self) -> HashMap<PathBuf, u32> {
+        let mut seen = HashSet::new();
+        let mut fingerprints = HashMap::new();
+        for bundle in self.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => continue,
+            };
+
+            for (path, hash) in bundle.asset_hashes(&mut archive) {
+                let path = PathBuf::from(path);
+                if !seen.insert(path.clone()) {
+                    continue;
+                }
+                if let Some(hash) = hash {
+                    fingerprints.insert(path, hash);
+                }
+            }
+        }
+
+        fingerprints
+    }
+
+    /// Get the number of times that the mounted bundles have changed, which is incremented when
+    /// bundles are mounted or unmounted and when an asset is written to the overlay bundle
+    pub fn mount_generation(&self) -> u64 {
+        self.mount_generation.load(Ordering::Relaxed)
+    }
+
+    /// Build a cache of the size and hash of every asset in the mounted bundles, taken from the
+    /// highest priority bundle that contains each asset
+    ///
+    /// The [`AssetIoZipPlugin`][crate::AssetIoZipPlugin] keeps an [`EntryMetadataCache`] resource
+    /// up to date with this. In browser builds, bundles that are still downloading are skipped.
+    pub fn entry_metadata_cache(&self) -> EntryMetadataCache {
+        // Read the generation first so that a change while the cache is built makes it stale
+        let generation = self.mount_generation();
+        let mut complete = true;
+        let mut entries = BTreeMap::new();
+        for bundle in self.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => {
+                    complete = false;
+                    continue;
+                }
+            };
+
+            for path in bundle.asset_paths(&mut archive) {
+                let path = PathBuf::from(path);
+                if entries.contains_key(&path) {
+                    continue;
+                }
+                let size = match bundle.asset_size(&mut archive, &path) {
+                    Some(size) => size,
+                    None => continue,
+                };
+                let hash = bundle
+                    .asset_metadata(&mut archive, &path)
+                    .and_then(|x| x.hash);
+                entries.insert(
+                    path,
+                    CachedEntryMetadata {
+                        bundle: bundle.name.clone(),
+                        size,
+                        hash,
+                    },
+                );
+            }
+        }
+
+        EntryMetadataCache {
+            entries,
+            generation,
+            complete,
+        }
+    }
+
+    /// Get the license and credits text collected into each of the mounted bundles by the bundler,
+    /// joined in priority order, or `None` if no bundle contains credits
+    ///
+    /// The bundler only collects credits when `collect-credits` is enabled in the
+    /// `asset_config.toml`. In browser builds this returns `None` until the bundle has been
+    /// downloaded.
+    pub fn credits(&self) -> Option<String> {
+        let credits = self
+            .bundles()
+            .iter()
+            .filter_map(|bundle| {
+                let mut archive = bundle.open()?;
+                let bytes = bundle
+                    .read_asset(&mut archive, Path::new(CREDITS_ENTRY))?
+                    .ok()?;
+                Some(String::from_utf8_lossy(&bytes).into_owned())
+            })
+            .collect::<Vec<_>>();
+
+        if credits.is_empty() {
+            None
+        } else {
+            Some(credits.join("\n"))
+        }
+    }
+
+    /// Get the archive comment stored in the mounted bundle with the given name, such as a build id
+    /// or git commit to include in crash reports
+    ///
+    /// This returns `None` if there is no such bundle or if the bundle was built without a
+    /// `comment` in the `asset_config.toml`. In browser builds this returns `None` until the bundle
+    /// has been downloaded.
+    pub fn bundle_comment(&self, name: &str) -> Option<String> {
+        self.bundles()
+            .iter()
+            .find(|bundle| bundle.name == name)?
+            .comment()
+    }
+
+    /// Decompress the given assets from the bundle in the background so that they are ready by the
+    /// time they are loaded
+    ///
+    /// This is useful for warming up the assets needed by the next level while the player is still
+    /// in a menu or loading screen. Each prefetched asset is kept in memory until it is loaded
+    /// through the asset server, at which point it is handed over and removed from the cache, or
+    /// until it is dropped with [`evict_prefetched`][Self::evict_prefetched] or
+    /// [`clear_prefetched`][Self::clear_prefetched]. Set
+    /// [`max_prefetched_bytes`][AssetIoZipConfig::max_prefetched_bytes] to bound the memory that
+    /// prefetched assets that are never loaded can take up. Assets that are not in the bundle or
+    /// that have already been prefetched are skipped.
+    ///
+    /// The assets are prefetched with the [`High`][LoadPriority::High] priority.
+    pub fn prefetch<I, P>(&self, paths: I)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        self.spawn_prefetch(
+            paths.into_iter().map(Into::into).collect(),
+            None,
+            LoadPriority::default(),
+        );
+    }
+
+    /// [`Prefetch`][Self::prefetch] the given assets with the given priority
+    ///
+    /// Queued assets are prefetched highest priority first, and in the order they were queued
+    /// within each priority, so hero textures can be decompressed before distant LODs when a level
+    /// loads. The loads of the assets are also given the priority with
+    /// [`set_load_priority`][Self::set_load_priority], in case they are loaded before they have
+    /// been prefetched.
+    pub fn prefetch_with_priority<I, P>(&self, paths: I, priority: LoadPriority)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        self.set_load_priority(paths.clone(), priority);
+        self.spawn_prefetch(paths, None, priority);
+    }
+
+    /// [`Prefetch`][Self::prefetch] the given assets until the given token is cancelled
+    ///
+    /// The loads of the assets are also bound to the token with
+    /// [`bind_cancellation`][Self::bind_cancellation], so cancelling it drops the prefetched data
+    /// and makes the loads of the assets that haven't been read yet fail.
+    pub fn prefetch_cancellable<I, P>(&self, paths: I, token: &CancellationToken)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let paths: Vec<PathBuf> = paths.into_iter().map(Into::into).collect();
+        self.bind_cancellation(paths.clone(), token);
+        self.spawn_prefetch(paths, Some(token.clone()), LoadPriority::default());
+    }
+
+    /// Set the [`LoadPriority`] of the loads of the given assets
+    ///
+    /// Reading an asset from the bundles waits until no reads with a higher priority are waiting
+    /// or in progress, so setting the priorities before loading a level makes the IO threads
+    /// decompress the assets needed first before the rest. Each asset is given the default
+    /// [`High`][LoadPriority::High] priority again when it is loaded.
+    pub fn set_load_priority<I, P>(&self, paths: I, priority: LoadPriority)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let mut priorities = self.priorities.write().unwrap();
+        for path in paths {
+            priorities.insert(path.into(), priority);
+        }
+    }
+
+    /// Replace the table of asset paths that are substituted with variants when they are loaded
+    ///
+    /// This takes effect for the loads that start after it is called. See [`AssetVariants`].
+    pub fn set_asset_variants(&self, variants: AssetVariants) {
+        *self.asset_variants.write().unwrap() = variants;
+    }
+
+    /// Get the table of asset paths that are substituted with variants when they are loaded
+    pub fn asset_variants(&self) -> AssetVariants {
+        self.asset_variants.read().unwrap().clone()
+    }
+
+    /// Get the path to load when the given asset is loaded, which is its variant if it has one
+    pub(crate) fn resolve_variant(&self, path: &Path) -> PathBuf {
+        self.asset_variants.read().unwrap().resolve(path).to_owned()
+    }
+
+    /// Drop the prefetched data of the given assets, such as when the level they were prefetched
+    /// for isn't going to be loaded after all
+    ///
+    /// Loading one of the assets afterwards reads it from the bundles again.
+    pub fn evict_prefetched<I, P>(&self, paths: I)
+    where
+        I: IntoIterator<Item = P>,
+        P: AsRef<Path>,
+    {
+        let mut cache = self.cache.write().unwrap();
+        for path in paths {
+            cache.remove(path.as_ref());
+        }
+    }
+
+    /// Drop the prefetched data of every asset that hasn't been loaded yet
+    pub fn clear_prefetched(&self) {
+        self.cache.write().unwrap().clear();
+    }
+
+    /// Get the total size of the data of the prefetched assets that haven't been loaded yet
+    ///
+    /// This is at most [`max_prefetched_bytes`][AssetIoZipConfig::max_prefetched_bytes] when it is
+    /// set.
+    pub fn prefetched_bytes(&self) -> u64 {
+        self.cache.read().unwrap().bytes()
+    }
+
+    /// Bind the loads of the given assets to a [`CancellationToken`]
+    ///
+    /// Once the token is cancelled, loading any of the assets through the asset server fails with
+    /// an [`Interrupted`][io::ErrorKind::Interrupted] error before it is read from a bundle, so an
+    /// aborted level load doesn't keep the IO threads busy. Each asset is unbound when it is
+    /// loaded, and binding an asset again replaces its token.
+    pub fn bind_cancellation<I, P>(&self, paths: I, token: &CancellationToken)
+    where
+        I: IntoIterator<Item = P>,
+        P: Into<PathBuf>,
+    {
+        let mut cancellations = self.cancellations.write().unwrap();
+        for path in paths {
+            cancellations.insert(path.into(), token.clone());
+        }
+    }
+
+    /// Decompress the given asset from the bundles into the prefetched assets on the IO task pool
+    /// with the given priority, and send the size of its data on `finished` once it is done
+    ///
+    /// A size of zero is sent if the asset was already prefetched or couldn't be read, so that
+    /// every stream is finished exactly once.
+    #[cfg(not(target_arch = "wasm32"))]
+    pub(crate) fn spawn_stream(
+        &self,
+        path: PathBuf,
+        priority: LoadPriority,
+        finished: Sender<usize>,
+    ) {
+        let asset_io = self.clone();
+        self.task_pool
+            .spawn(async move {
+                let mut len = 0;
+                if !asset_io.cache.read().unwrap().contains(&path) {
+                    let _guard = asset_io.priority_gate.enter(priority).await;
+                    if let Some(Ok(loaded)) = asset_io
+                        .read_from_bundles(&asset_io.resolve_variant(&path))
+                        .await
+                    {
+                        len = loaded.0.len();
+                        asset_io.cache.write().unwrap().insert(path, loaded);
+                    }
+                }
+                // The streamer may have been dropped, in which case nobody is waiting for this
+                finished.send(len).ok();
+            })
+            .detach();
+    }
+
+    /// Queue the given assets to be prefetched with the given priority, and prefetch the queued
+    /// assets on the IO task pool, skipping the ones whose token is cancelled
+    fn spawn_prefetch(
+        &self,
+        paths: Vec<PathBuf>,
+        token: Option<CancellationToken>,
+        priority: LoadPriority,
+    ) {
+        {
+            let mut queue = self.prefetch_queue.lock().unwrap();
+            for path in paths {
+                queue.push(QueuedPrefetch {
+                    priority,
+                    sequence: self.prefetch_sequence.fetch_add(1, Ordering::Relaxed),
+                    path,
+                    token: token.clone(),
+                });
+            }
+        }
+
+        let asset_io = self.clone();
+        self.task_pool
+            .spawn(async move {
+                loop {
+                    let queued = match asset_io.prefetch_queue.lock().unwrap().pop() {
+                        Some(queued) => queued,
+                        None => return,
+                    };
+                    let cancelled = || {
+                        queued
+                            .token
+                            .as_ref()
+                            .map(|x| x.is_cancelled())
+                            .unwrap_or(false)
+                    };
+                    if cancelled() || asset_io.cache.read().unwrap().contains(&queued.path) {
+                        continue;
+                    }
+
+                    let _guard = asset_io.priority_gate.enter(queued.priority).await;
+                    if let Some(Ok(loaded)) = asset_io
+                        .read_from_bundles(&asset_io.resolve_variant(&queued.path))
+                        .await
+                    {
+                        // Drop the data if the prefetch was cancelled while it was decompressed
+                        if !cancelled() {
+                            asset_io.cache.write().unwrap().insert(queued.path, loaded);
+                        }
+                    }
+                }
+            })
+            .detach();
+    }
+}
+
+impl ZipAssetIo {
+    /// Log the paths probed for the asset bundle and the result of opening the mounted bundles
+    fn log_bundle_info(&self) {
+        #[cfg(not(target_arch = "wasm32"))]
+        if self.searches_exe() && self.config.bundle_path.is_none() {
+            let exe_path = std::env::current_exe().expect("Could not obtain current exe path");
+            let status = match appended::appended_region(&exe_path) {
+                Some(_) => "found",
+                None => "not found",
+            };
+            info!(
+                "Probed for asset bundle appended to {}: {}",
+                exe_path.display(),
+                status
+            );
+        }
+        #[cfg(not(target_arch = "wasm32"))]
+        for (path, _) in self.bundle_candidates() {
+            let status = if path.exists() { "found" } else { "not found" };
+            info!("Probed for asset bundle at {}: {}", path.display(), status);
+        }
+
+        let bundles = self.bundles();
+        if bundles.is_empty() {
+            info!("No asset bundle found, all assets will be loaded by the fallback asset IO");
+            return;
+        }
+
+        for bundle in bundles {
+            let location = bundle.location();
+            let kind = if bundle.is_mod {
+                "mod bundle"
+            } else {
+                "asset bundle"
+            };
+
+            match bundle.open() {
+                #[cfg_attr(not(feature = "obfuscation"), allow(unused_mut))]
+                Some(mut archive) => {
+                    #[cfg(feature = "obfuscation")]
+                    let entries_obfuscated = crate::bundle::entries_obfuscated(&mut archive);
+                    #[cfg(not(feature = "obfuscation"))]
+                    let entries_obfuscated = false;
+
+                    info!(
+                        "Opened {} {} with {} entries ( obfuscated: {}, entries obfuscated: {} )",
+                        kind,
+                        location,
+                        archive.len(),
+                        bundle.obfuscated(),
+                        entries_obfuscated
+                    );
+                }
+                None => warn!(
+                    "Found {} {} but it could not be opened as a zip archive",
+                    kind, location
+                ),
+            }
+        }
+    }
+}
+
+impl ZipAssetIo {
+    /// Get the paths of the files and directories directly inside of the given directory across all
+    /// of the mounted bundles
+    ///
+    /// Zip archives don't necessarily contain entries for directories, so directories are inferred
+    /// from the paths of the assets inside of them. This means that empty directories are not
+    /// found.
+    pub(crate) fn bundle_dir_children(&self, dir: &Path) -> BTreeSet<String> {
+        let dir = dir.to_string_lossy().replace('\\', "/");
+        let dir = dir.trim_matches('/');
+        let prefix = if dir.is_empty() {
+            String::new()
+        } else {
+            format!("{}/", dir)
+        };
+
+        let mut children = BTreeSet::new();
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(entries) = self
+            .override_dir()
+            .and_then(|x| std::fs::read_dir(x.join(dir)).ok())
+        {
+            children.extend(
+                entries
+                    .filter_map(Result::ok)
+                    .map(|x| format!("{}{}", prefix, x.file_name().to_string_lossy())),
+            );
+        }
+        for bundle in self.bundles() {
+            let mut archive = match bundle.open() {
+                Some(archive) => archive,
+                None => continue,
+            };
+
+            for path in bundle.asset_paths(&mut archive) {
+                if let Some(rest) = path.strip_prefix(&prefix) {
+                    let child = rest.split('/').next().unwrap_or(rest);
+                    children.insert(format!("{}{}", prefix, child));
+                }
+            }
+        }
+
+        children
+    }
+
+    /// Get the paths of all of the assets in the mounted bundles
+    pub(crate) fn bundle_asset_paths(&self) -> BTreeSet<String> {
+        let mut paths = BTreeSet::new();
+        for bundle in self.bundles() {
+            if let Some(mut archive) = bundle.open() {
+                paths.extend(bundle.asset_paths(&mut archive));
+            }
+        }
+
+        paths
+    }
+
+    /// Load an asset from the user override dir, the prefetched assets, or the mounted bundles,
+    /// returning `None` if none of them contain the asset
+    pub(crate) async fn load_with_source(
+        &self,
+        path: &Path,
+    ) -> Option<Result<LoadedAsset, AssetIoError>> {
+        // The cancellations, priorities, and prefetched assets are keyed by the requested path, but
+        // the data is read from its variant
+        let variant = self.resolve_variant(path);
+
+        // Assets in the override dir take precedence over everything else
+        #[cfg(not(target_arch = "wasm32"))]
+        if let Some(dir) = self.override_dir() {
+            let override_path = dir.join(&variant);
+            if override_path.is_file() {
+                return Some(
+                    std::fs::read(override_path)
+                        .map(|bytes| (bytes, AssetSource::Override))
+                        .map_err(AssetIoError::Io),
+                );
+            }
+        }
+
+        // Drop the prefetched asset if its load has been cancelled
+        if let Some(e) = self.cancelled_error(path) {
+            self.cache.write().unwrap().remove(path);
+            self.cancellations.write().unwrap().remove(path);
+            return Some(Err(e.into()));
+        }
+        self.cancellations.write().unwrap().remove(path);
+        let priority = self
+            .priorities
+            .write()
+            .unwrap()
+            .remove(path)
+            .unwrap_or_default();
+
+        // Use the prefetched asset if we have one
+        if let Some(loaded) = self.cache.write().unwrap().remove(path) {
+            return Some(Ok(loaded));
+        }
+
+        let _guard = self.priority_gate.enter(priority).await;
+        self.read_from_bundles(&variant).await
+    }
+}
+
+impl AssetIo for ZipAssetIo {
+    fn load_path<'a>(&'a self, path: &'a Path) -> BoxedFuture<'a, Result<Vec<u8>, AssetIoError>> {
+        Box::pin(async move {
+            match self.load_with_source(path).await {
+                Some(result) => result.map(|(bytes, _)| bytes),
+                None => Err(AssetIoError::NotFound(path.to_owned())),
+            }
+        })
+    }
+
+    fn read_directory(
+        &self,
+        path: &Path,
+    ) -> Result<Box<dyn Iterator<Item = PathBuf>>, AssetIoError> {
+        let children = self.bundle_dir_children(path);
+        if children.is_empty() {
+            return Err(AssetIoError::NotFound(path.to_owned()));
+        }
+
+        Ok(Box::new(children.into_iter().map(PathBuf::from)))
+    }
+
+    fn is_directory(&self, path: &Path) -> bool {
+        !self.bundle_dir_children(path).is_empty()
+    }
+
+    fn watch_path_for_changes(&self, _path: &Path) -> Result<(), AssetIoError> {
+        // We cannot watch for changes inside of the zip file
+        Ok(())
+    }
+
+    fn watch_for_changes(&self) -> Result<(), AssetIoError> {
+        Ok(())
+    }
+}
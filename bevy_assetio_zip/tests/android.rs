@@ -0,0 +1,56 @@
+//! Android tests, run on a device or emulator with e.g. `cross test --target aarch64-linux-android`
+#![cfg(target_os = "android")]
+
+mod common;
+
+use std::{fs, io::Write};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::AssetIoZipConfig;
+use zip::{write::FileOptions, ZipWriter};
+
+use common::{asset_io, build_app, load};
+
+#[test]
+fn newest_expansion_files_are_searched() {
+    let storage = std::env::temp_dir().join(format!("bevy_assetio_zip_obb-{}", std::process::id()));
+    // The package name is the name of the process, which is the test executable here
+    let package = std::env::current_exe()
+        .unwrap()
+        .file_name()
+        .unwrap()
+        .to_str()
+        .unwrap()
+        .to_owned();
+    let obb_dir = storage.join("Android/obb").join(&package);
+    fs::create_dir_all(&obb_dir).unwrap();
+    let write_obb = |name: &str, contents: &str| {
+        let file = fs::File::create(obb_dir.join(format!("{}.{}.obb", name, package))).unwrap();
+        let mut zip = ZipWriter::new(file);
+        zip.start_file("a.txt", FileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+        zip.finish().unwrap();
+    };
+    write_obb("patch.3", "patch");
+    std::env::set_var("EXTERNAL_STORAGE", &storage);
+
+    let build = |search_obb| {
+        let config = AssetIoZipConfig {
+            file_names: vec!["missing".into()],
+            search_obb,
+            ..Default::default()
+        };
+        asset_io(&build_app(config, TaskPool::new()))
+    };
+
+    // Patch expansion files are used when there is no main expansion file
+    assert_eq!(load(&build(true), "a.txt").as_deref(), Some("patch"));
+
+    // The newest main expansion file comes first
+    write_obb("main.1", "main 1");
+    write_obb("main.2", "main 2");
+    assert_eq!(load(&build(true), "a.txt").as_deref(), Some("main 2"));
+    assert_eq!(load(&build(false), "a.txt"), None);
+
+    fs::remove_dir_all(&storage).unwrap();
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::{
+    fs,
+    io::{Cursor, Write},
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, FileBundleSource, ZipAssetIo};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::load;
+
+#[test]
+fn bundles_appended_to_executables_are_found_from_the_footer() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_append-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("levels/1.txt", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"single file").unwrap();
+    let bundle = zip.finish().unwrap().into_inner();
+
+    // The footer written by the bundler's `append` command
+    let exe = b"executable code".to_vec();
+    let mut file = exe.clone();
+    file.extend(&bundle);
+    file.extend(&(exe.len() as u64).to_le_bytes());
+    file.extend(&(bundle.len() as u64).to_le_bytes());
+    file.extend(b"BAZBAPND");
+    fs::write(dir.join("game"), &file).unwrap();
+    fs::write(dir.join("plain"), &exe).unwrap();
+
+    assert!(FileBundleSource::detect_appended(dir.join("plain")).is_none());
+    let source = FileBundleSource::detect_appended(dir.join("game")).unwrap();
+    let mut sources = BundleSources::default();
+    sources.add("game", source);
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+    assert_eq!(
+        load(&zip_io, "levels/1.txt").as_deref(),
+        Some("single file")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,214 @@
+mod common;
+
+use std::{
+    io::{self, Cursor},
+    sync::{
+        atomic::{AtomicUsize, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Barrier, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::tasks::{TaskPool, TaskPoolBuilder};
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipConfig, BundleSource, BundleSources, ReadSeekSend};
+
+use common::{asset_io, build_app_with_sources, load, zip_bytes};
+
+/// A bundle source that counts how many times it has been opened
+struct CountingSource {
+    bytes: Vec<u8>,
+    opens: Arc<AtomicUsize>,
+}
+
+impl BundleSource for CountingSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        self.opens.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(Cursor::new(self.bytes.clone())))
+    }
+}
+
+fn build(
+    max_concurrent_reads: Option<usize>,
+    task_pool: TaskPool,
+    source: impl BundleSource,
+) -> AssetIoZip {
+    let mut sources = BundleSources::default();
+    sources.add("bundle", source);
+    let config = AssetIoZipConfig {
+        file_names: vec![format!("archive-pool-missing-{}", std::process::id())],
+        max_concurrent_reads,
+        ..Default::default()
+    };
+    asset_io(&build_app_with_sources(config, task_pool, sources))
+}
+
+#[test]
+fn archives_are_reused_by_later_reads() {
+    let opens = Arc::new(AtomicUsize::new(0));
+    let asset_io = build(
+        Some(4),
+        TaskPool::new(),
+        CountingSource {
+            bytes: zip_bytes(&[("a.txt", "a"), ("b.txt", "b")]),
+            opens: opens.clone(),
+        },
+    );
+
+    // The archive opened to check the bundle when it was mounted is the one that is reused
+    let opens_after_mount = opens.load(Ordering::SeqCst);
+    for _ in 0..3 {
+        assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("a"));
+        assert_eq!(load(&asset_io, "b.txt").as_deref(), Some("b"));
+    }
+    assert_eq!(opens.load(Ordering::SeqCst), opens_after_mount);
+}
+
+#[test]
+fn archives_are_opened_for_every_read_without_handles() {
+    let opens = Arc::new(AtomicUsize::new(0));
+    let asset_io = build(
+        Some(0),
+        TaskPool::new(),
+        CountingSource {
+            bytes: zip_bytes(&[("a.txt", "a")]),
+            opens: opens.clone(),
+        },
+    );
+
+    let opens_after_mount = opens.load(Ordering::SeqCst);
+    for _ in 0..3 {
+        assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("a"));
+    }
+    assert_eq!(opens.load(Ordering::SeqCst), opens_after_mount + 3);
+}
+
+/// A bundle source whose readers block on their first read while it is armed, until every reader
+/// has reached the barrier, including the readers that were opened before it was armed
+struct BarrierSource {
+    bytes: Vec<u8>,
+    barrier: Arc<Barrier>,
+    armed: Arc<Mutex<bool>>,
+    started: Mutex<Sender<()>>,
+    open_readers: Arc<AtomicUsize>,
+}
+
+struct BarrierReader {
+    inner: Cursor<Vec<u8>>,
+    barrier: Arc<Barrier>,
+    armed: Arc<Mutex<bool>>,
+    waited: bool,
+    started: Sender<()>,
+    open_readers: Arc<AtomicUsize>,
+}
+
+impl Drop for BarrierReader {
+    fn drop(&mut self) {
+        self.open_readers.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+impl io::Read for BarrierReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if !self.waited && *self.armed.lock().unwrap() {
+            self.waited = true;
+            self.started.send(()).unwrap();
+            self.barrier.wait();
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl io::Seek for BarrierReader {
+    fn seek(&mut self, pos: io::SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl BundleSource for BarrierSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        self.open_readers.fetch_add(1, Ordering::SeqCst);
+        Ok(Box::new(BarrierReader {
+            inner: Cursor::new(self.bytes.clone()),
+            barrier: self.barrier.clone(),
+            armed: self.armed.clone(),
+            waited: false,
+            started: self.started.lock().unwrap().clone(),
+            open_readers: self.open_readers.clone(),
+        }))
+    }
+}
+
+/// Start a read of each path and wait for all of them, which only finishes if they are reading
+/// from the bundle at the same time
+fn read_at_once(
+    asset_io: &AssetIoZip,
+    armed: &Mutex<bool>,
+    started: &Receiver<()>,
+    paths: &[&'static str],
+) -> Vec<Option<String>> {
+    *armed.lock().unwrap() = true;
+    let reads = paths
+        .iter()
+        .map(|&path| {
+            let asset_io = asset_io.clone();
+            thread::spawn(move || load(&asset_io, path))
+        })
+        .collect::<Vec<_>>();
+    for _ in paths {
+        started.recv_timeout(Duration::from_secs(5)).unwrap();
+    }
+
+    reads.into_iter().map(|read| read.join().unwrap()).collect()
+}
+
+#[test]
+fn bundles_are_read_in_parallel() {
+    let armed = Arc::new(Mutex::new(false));
+    let (started, started_receiver) = mpsc::channel();
+    let asset_io = build(
+        Some(2),
+        TaskPool::new(),
+        BarrierSource {
+            bytes: zip_bytes(&[("a.txt", "a"), ("b.txt", "b")]),
+            barrier: Arc::new(Barrier::new(2)),
+            armed: armed.clone(),
+            started: Mutex::new(started),
+            open_readers: Default::default(),
+        },
+    );
+
+    assert_eq!(
+        read_at_once(&asset_io, &armed, &started_receiver, &["a.txt", "b.txt"]),
+        vec![Some("a".to_owned()), Some("b".to_owned())]
+    );
+}
+
+#[test]
+fn reads_default_to_the_size_of_the_io_task_pool() {
+    let armed = Arc::new(Mutex::new(false));
+    let (started, started_receiver) = mpsc::channel();
+    let open_readers = Arc::new(AtomicUsize::new(0));
+    let asset_io = build(
+        None,
+        TaskPoolBuilder::new().num_threads(3).build(),
+        BarrierSource {
+            bytes: zip_bytes(&[("a.txt", "a")]),
+            barrier: Arc::new(Barrier::new(4)),
+            armed: armed.clone(),
+            started: Mutex::new(started),
+            open_readers: open_readers.clone(),
+        },
+    );
+
+    // One more read than there are threads, so that one of the archives has to be closed again
+    let results = read_at_once(
+        &asset_io,
+        &armed,
+        &started_receiver,
+        &["a.txt", "a.txt", "a.txt", "a.txt"],
+    );
+    assert!(results.iter().all(|x| x.as_deref() == Some("a")));
+    assert_eq!(open_readers.load(Ordering::SeqCst), 3);
+}
@@ -0,0 +1,21 @@
+mod common;
+
+use bevy_assetio_zip::AssetIoZipConfig;
+
+#[test]
+fn command_line_args_override_the_config() {
+    let dir = std::env::current_dir().unwrap();
+    let mut config = AssetIoZipConfig::default();
+    config.apply_args(vec![
+        "--fullscreen",
+        "--asset-bundle",
+        "dlc.zip",
+        "--asset-dir=/srv/assets",
+    ]);
+
+    assert_eq!(config.bundle_path, Some(dir.join("dlc.zip")));
+    assert_eq!(config.asset_dir, Some("/srv/assets".into()));
+
+    config.apply_args(vec!["--asset-bundle"]);
+    assert_eq!(config.bundle_path, Some(dir.join("dlc.zip")));
+}
@@ -0,0 +1,84 @@
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipConfig, BundleSources, MemoryAssetIo, ZipAssetIo};
+
+use common::{bundle, load};
+
+#[test]
+fn loaded_assets_are_appended_to_the_audit_log() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_audit-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let log_path = dir.join("audit.log");
+    fs::write(&log_path, "earlier run\n").unwrap();
+
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+    let config = AssetIoZipConfig {
+        audit_log: Some(log_path.clone()),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    let fallback = vec![("levels/2.txt", "loose")]
+        .into_iter()
+        .collect::<MemoryAssetIo>();
+    let asset_io = AssetIoZip::new(zip_io, Box::new(fallback));
+
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("base"));
+    assert_eq!(load(&asset_io, "levels/2.txt").as_deref(), Some("loose"));
+    assert_eq!(load(&asset_io, "levels/3.txt"), None);
+
+    let log = fs::read_to_string(&log_path).unwrap();
+    let lines = log.lines().collect::<Vec<_>>();
+    assert_eq!(lines.len(), 3, "{}", log);
+    assert_eq!(lines[0], "earlier run");
+    let fields = |line: &str| {
+        line.split('\t')
+            .skip(1)
+            .map(str::to_owned)
+            .collect::<Vec<_>>()
+    };
+    assert_eq!(
+        fields(lines[1]),
+        vec![
+            "bundle base".to_owned(),
+            format!("{:08x}", crc32fast::hash(b"base")),
+            "levels/1.txt".to_owned(),
+        ]
+    );
+    assert_eq!(
+        fields(lines[2]),
+        vec![
+            "fallback".to_owned(),
+            format!("{:08x}", crc32fast::hash(b"loose")),
+            "levels/2.txt".to_owned(),
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn assets_load_when_the_audit_log_cant_be_opened() {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_audit_unopenable-{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+    // A directory can't be opened as the log file
+    let config = AssetIoZipConfig {
+        audit_log: Some(dir.clone()),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    let asset_io = AssetIoZip::new(zip_io, Box::new(MemoryAssetIo::new()));
+
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("base"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
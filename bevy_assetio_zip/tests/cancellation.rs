@@ -0,0 +1,39 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, CancellationToken, ZipAssetIo};
+
+use common::{bundle, load};
+
+#[test]
+fn cancelled_loads_fail_once() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[("levels/1.txt", "base"), ("levels/2.txt", "base")]),
+    );
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    let token = CancellationToken::new();
+    zip_io.bind_cancellation(vec!["levels/1.txt", "levels/2.txt"], &token);
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+
+    token.cancel();
+    assert_eq!(load(&zip_io, "levels/2.txt"), None);
+    // The asset is unbound from the token once its load has been attempted
+    assert_eq!(load(&zip_io, "levels/2.txt").as_deref(), Some("base"));
+}
+
+#[test]
+fn cancelled_prefetches_are_dropped() {
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    let token = CancellationToken::new();
+    zip_io.prefetch_cancellable(vec!["levels/1.txt"], &token);
+    token.cancel();
+    // Whether or not the prefetch finished first, its data is not handed over
+    assert_eq!(load(&zip_io, "levels/1.txt"), None);
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+}
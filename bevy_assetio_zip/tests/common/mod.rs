@@ -0,0 +1,94 @@
+//! Helpers shared by the integration tests
+//!
+//! Every test file compiles its own copy of this module, and not every file uses every helper.
+#![allow(dead_code)]
+
+use std::{
+    fs::File,
+    io::{Cursor, Write},
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::AssetIo,
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin, BundleSources, MemoryBundleSource,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+/// Write a zip containing the given files to the given path, creating its parent dirs
+pub fn write_zip(path: &Path, files: &[(&str, &str)]) {
+    std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for (name, contents) in files {
+        zip.start_file(*name, FileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+/// Get the bytes of a zip containing the given files
+pub fn zip_bytes(files: &[(&str, &str)]) -> Vec<u8> {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, contents) in files {
+        zip.start_file(*path, FileOptions::default()).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    zip.finish().unwrap().into_inner()
+}
+
+/// Build an in-memory bundle containing the given files
+pub fn bundle(files: &[(&str, &str)]) -> MemoryBundleSource {
+    MemoryBundleSource::new(zip_bytes(files))
+}
+
+/// Write a bundle next to the test executable, where the plugin looks for it
+pub fn write_bundle(file_name: &str, files: &[(&str, &str)]) -> PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+    write_zip(&path, files);
+    path
+}
+
+pub fn load(asset_io: &dyn AssetIo, path: &str) -> Option<String> {
+    TaskPool::new()
+        .scope(|scope| scope.spawn(asset_io.load_path(Path::new(path))))
+        .pop()
+        .unwrap()
+        .ok()
+        .map(|bytes| String::from_utf8(bytes).unwrap())
+}
+
+/// Build an app with the plugin, which reads assets with the given config on the given task pool
+pub fn build_app(config: AssetIoZipConfig, task_pool: TaskPool) -> AppBuilder {
+    build_app_with_sources(config, task_pool, BundleSources::default())
+}
+
+/// Build an app with the plugin, which also mounts the given custom bundle sources
+pub fn build_app_with_sources(
+    config: AssetIoZipConfig,
+    task_pool: TaskPool,
+    sources: BundleSources,
+) -> AppBuilder {
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(task_pool))
+        .insert_resource(config)
+        .insert_resource(sources);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(task_pool))
+        .add_resource(config)
+        .add_resource(sources);
+    builder.add_plugin(AssetIoZipPlugin);
+    builder
+}
+
+/// Get the asset IO that the plugin added to the app
+pub fn asset_io(builder: &AppBuilder) -> AssetIoZip {
+    (*builder.resources().get::<AssetIoZip>().unwrap()).clone()
+}
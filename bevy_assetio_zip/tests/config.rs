@@ -0,0 +1,109 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy::{asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipConfig, BundleSources, MemoryAssetIo, ZipAssetIo};
+
+use common::{bundle, load, write_zip};
+
+#[test]
+fn config_problems_are_reported() {
+    assert!(AssetIoZipConfig::default().validate().is_empty());
+
+    let config = AssetIoZipConfig {
+        file_names: vec!["".into(), "assets.zip".into(), "data/assets".into()],
+        bundle_path: Some("missing/assets.zip".into()),
+        mod_deny: vec!["scripts/[".into()],
+        ..Default::default()
+    };
+    let problems = config.validate();
+
+    assert_eq!(problems.len(), 5, "{:#?}", problems);
+    assert!(problems[0].contains("missing/assets.zip does not exist"));
+    assert!(problems[2].contains("Use \"assets\" instead"));
+    assert!(problems[4].contains("`mod_deny` pattern `scripts/[`"));
+}
+
+#[test]
+fn disabled_bundles_are_not_mounted() {
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+    let mut memory_io = MemoryAssetIo::new();
+    memory_io.insert("levels/1.txt", "loose");
+    let config = AssetIoZipConfig {
+        disable_bundles: true,
+        ..Default::default()
+    };
+
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    assert!(zip_io.mounted_bundles().is_empty());
+
+    let asset_io = AssetIoZip::new(zip_io, Box::new(memory_io));
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("loose"));
+}
+
+#[test]
+fn only_matching_assets_of_the_main_bundle_are_mounted() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_mount-{}", std::process::id()));
+    write_zip(
+        &dir.join("assets.zip"),
+        &[("ui/button.txt", "shared"), ("levels/1.txt", "shared")],
+    );
+    let config = AssetIoZipConfig {
+        bundle_path: Some(dir.join("assets.zip")),
+        mount_patterns: vec!["ui/**".into()],
+        ..Default::default()
+    };
+    assert!(config.validate().is_empty());
+
+    let zip_io = ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+
+    assert_eq!(load(&zip_io, "ui/button.txt").as_deref(), Some("shared"));
+    assert_eq!(load(&zip_io, "levels/1.txt"), None);
+    assert!(!zip_io.is_directory(Path::new("levels")));
+
+    let config = AssetIoZipConfig {
+        mount_patterns: vec!["ui/[".into()],
+        ..Default::default()
+    };
+    assert!(config.validate()[0].contains("`mount_patterns` pattern `ui/[`"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn explicit_paths_only_ignores_bundles_next_to_the_executable() {
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_owned();
+    let file_name = format!("bevy_assetio_zip_explicit-{}", std::process::id());
+    let bundle_path = exe_dir.join(format!("{}.zip", file_name));
+    write_zip(&bundle_path, &[("levels/1.txt", "next to the exe")]);
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(
+        config.clone(),
+        BundleSources::default(),
+        None,
+        TaskPool::new(),
+    );
+    assert_eq!(
+        load(&zip_io, "levels/1.txt").as_deref(),
+        Some("next to the exe")
+    );
+
+    let config = AssetIoZipConfig {
+        explicit_paths_only: true,
+        ..config
+    };
+    let zip_io = ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+    assert_eq!(load(&zip_io, "levels/1.txt"), None);
+
+    fs::remove_file(&bundle_path).unwrap();
+}
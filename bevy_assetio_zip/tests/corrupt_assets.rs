@@ -0,0 +1,62 @@
+mod common;
+
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+};
+
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    tasks::TaskPool,
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, BundleSources, CorruptAssetError, MemoryAssetIo,
+    MemoryBundleSource, ZipAssetIo,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::load;
+
+#[test]
+fn corrupt_assets_fail_instead_of_falling_back() {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(zip::CompressionMethod::Stored);
+    zip.start_file("levels/1.txt", options).unwrap();
+    zip.write_all(b"pristine").unwrap();
+    let mut bytes = zip.finish().unwrap().into_inner();
+    let data = bytes.windows(8).position(|x| x == b"pristine").unwrap();
+    bytes[data] = b'P';
+
+    let mount = |corrupt_asset_fallback| {
+        let mut sources = BundleSources::default();
+        sources.add("base", MemoryBundleSource::new(bytes.clone()));
+        let config = AssetIoZipConfig {
+            corrupt_asset_fallback,
+            ..Default::default()
+        };
+        let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+        let mut fallback = MemoryAssetIo::new();
+        fallback.insert("levels/1.txt", "loose");
+        AssetIoZip::new(zip_io, Box::new(fallback))
+    };
+
+    let asset_io = mount(false);
+    let load_error = |path: &str| {
+        TaskPool::new()
+            .scope(|scope| scope.spawn(asset_io.load_path(Path::new(path))))
+            .pop()
+            .unwrap()
+            .unwrap_err()
+    };
+    let error = load_error("levels/1.txt");
+    let corrupt = CorruptAssetError::from_asset_io_error(&error).unwrap();
+    assert_eq!(corrupt.path, Path::new("levels/1.txt"));
+    assert_eq!(corrupt.bundle, "base");
+    assert!(matches!(
+        load_error("levels/2.txt"),
+        AssetIoError::NotFound(_)
+    ));
+
+    let asset_io = mount(true);
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("loose"));
+}
@@ -0,0 +1,64 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, MemoryBundleSource};
+
+use common::{asset_io, build_app_with_sources, bundle, load};
+
+fn sources() -> BundleSources {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "corrupt",
+            MemoryBundleSource::new(&b"PK\x03\x04 truncated"[..]),
+        )
+        .add("good", bundle(&[("a.txt", "good a")]));
+    sources
+}
+
+fn config(corrupt_bundle_fallback: bool) -> AssetIoZipConfig {
+    AssetIoZipConfig {
+        file_names: vec![format!("corrupt-missing-{}", std::process::id())],
+        corrupt_bundle_fallback,
+        ..Default::default()
+    }
+}
+
+#[test]
+fn corrupt_bundles_are_skipped() {
+    let asset_io = asset_io(&build_app_with_sources(
+        config(true),
+        TaskPool::new(),
+        sources(),
+    ));
+
+    let mounted = asset_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(mounted, vec!["good"]);
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("good a"));
+}
+
+#[test]
+fn corrupt_bundles_panic_with_a_description_without_the_fallback() {
+    let error = std::panic::catch_unwind(|| {
+        build_app_with_sources(config(false), TaskPool::new(), sources());
+    })
+    .unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+
+    assert!(
+        message.starts_with("Asset bundle corrupt is corrupted"),
+        "{}",
+        message
+    );
+    assert!(message.contains("size: 14 bytes"), "{}", message);
+    assert!(
+        message.contains("magic bytes: [50, 4b, 03, 04]"),
+        "{}",
+        message
+    );
+    assert!(message.contains("obfuscated: false"), "{}", message);
+}
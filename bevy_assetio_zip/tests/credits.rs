@@ -0,0 +1,22 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::bundle;
+
+#[test]
+fn credits_are_joined_in_priority_order() {
+    let mut sources = BundleSources::default();
+    sources
+        .add("dlc", bundle(&[("credits.txt", "dlc credits")]))
+        .add("base", bundle(&[("credits.txt", "base credits")]))
+        .add("extra", bundle(&[("levels/1.txt", "extra")]));
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    assert_eq!(
+        zip_io.credits().as_deref(),
+        Some("dlc credits\nbase credits")
+    );
+}
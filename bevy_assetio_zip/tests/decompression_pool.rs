@@ -0,0 +1,39 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::{bundle, load};
+
+#[test]
+fn assets_load_on_a_dedicated_decompression_pool() {
+    let mut sources = BundleSources::default();
+    sources
+        .add("dlc", bundle(&[("levels/1.txt", "dlc")]))
+        .add("base", bundle(&[("levels/2.txt", "base")]));
+    let config = AssetIoZipConfig {
+        decompression_threads: Some(1),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("dlc"));
+    assert_eq!(load(&zip_io, "levels/2.txt").as_deref(), Some("base"));
+    assert_eq!(load(&zip_io, "levels/3.txt"), None);
+}
+
+#[test]
+fn decompression_pools_without_threads_are_reported() {
+    let config = AssetIoZipConfig {
+        decompression_threads: Some(0),
+        ..Default::default()
+    };
+    let problems = config.validate();
+    assert!(
+        problems
+            .iter()
+            .any(|x| x.contains("`decompression_threads` is 0")),
+        "{:?}",
+        problems
+    );
+}
@@ -0,0 +1,45 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::{asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipConfig, BundleSources, MemoryAssetIo, ZipAssetIo};
+
+use common::bundle;
+
+#[test]
+fn directories_list_the_assets_of_every_bundle_and_the_fallback() {
+    let mut sources = BundleSources::default();
+    sources.add("dlc", bundle(&[("levels/2.txt", "dlc")])).add(
+        "base",
+        bundle(&[("levels/1.txt", "base"), ("levels/2.txt", "base")]),
+    );
+    let mut fallback = MemoryAssetIo::new();
+    fallback
+        .insert("levels/1.txt", "loose")
+        .insert("levels/3.txt", "loose")
+        .insert("music/theme.ogg", "loose");
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+    let asset_io = AssetIoZip::new(zip_io, Box::new(fallback));
+
+    assert_eq!(
+        asset_io
+            .read_directory(Path::new("levels"))
+            .unwrap()
+            .collect::<Vec<_>>(),
+        [
+            Path::new("levels/1.txt"),
+            Path::new("levels/2.txt"),
+            Path::new("levels/3.txt")
+        ]
+    );
+    assert_eq!(
+        asset_io
+            .read_directory(Path::new("music"))
+            .unwrap()
+            .collect::<Vec<_>>(),
+        [Path::new("music/theme.ogg")]
+    );
+    assert!(asset_io.read_directory(Path::new("missing")).is_err());
+}
@@ -0,0 +1,25 @@
+//! The environment variable is set for the whole process, so this is the only test in its binary
+
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo, DISABLE_ENV_VAR};
+
+use common::bundle;
+
+#[test]
+fn bundles_are_disabled_by_the_environment_variable() {
+    let mount = || {
+        let mut sources = BundleSources::default();
+        sources.add("base", bundle(&[("levels/1.txt", "base")]));
+        ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new())
+            .mounted_bundles()
+            .len()
+    };
+
+    std::env::set_var(DISABLE_ENV_VAR, "0");
+    assert_eq!(mount(), 1);
+    std::env::set_var(DISABLE_ENV_VAR, "1");
+    assert_eq!(mount(), 0);
+    std::env::remove_var(DISABLE_ENV_VAR);
+}
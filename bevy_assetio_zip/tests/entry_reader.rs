@@ -0,0 +1,66 @@
+mod common;
+
+use std::io::{Cursor, ErrorKind, Read, Seek, SeekFrom, Write};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, MemoryBundleSource};
+use zip::{write::FileOptions, CompressionMethod, ZipWriter};
+
+use common::{asset_io, build_app_with_sources};
+
+/// Build an in-memory bundle with the given files stored with the given compression methods
+fn bundle(files: &[(&str, &str, CompressionMethod)]) -> MemoryBundleSource {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    for (path, contents, method) in files {
+        let options = FileOptions::default().compression_method(*method);
+        zip.start_file(*path, options).unwrap();
+        zip.write_all(contents.as_bytes()).unwrap();
+    }
+    MemoryBundleSource::new(zip.finish().unwrap().into_inner())
+}
+
+#[test]
+fn stored_entries_are_streamed_out_of_the_bundle() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("first.txt", "not this one", CompressionMethod::Stored),
+            ("music.ogg", "0123456789", CompressionMethod::Stored),
+            ("level.txt", "compressed", CompressionMethod::Deflated),
+        ]),
+    );
+    let asset_io = asset_io(&build_app_with_sources(
+        AssetIoZipConfig::default(),
+        TaskPool::new(),
+        sources,
+    ));
+
+    let mut reader = asset_io.open_entry("music.ogg").unwrap();
+    assert_eq!(reader.len(), 10);
+    assert!(!reader.is_empty());
+    let mut contents = String::new();
+    reader.read_to_string(&mut contents).unwrap();
+    assert_eq!(contents, "0123456789");
+
+    // Seeks are relative to the entry, not the bundle
+    let mut bytes = [0; 3];
+    assert_eq!(reader.seek(SeekFrom::Start(2)).unwrap(), 2);
+    reader.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"234");
+    assert_eq!(reader.seek(SeekFrom::Current(1)).unwrap(), 6);
+    reader.read_exact(&mut bytes).unwrap();
+    assert_eq!(&bytes, b"678");
+    assert_eq!(reader.seek(SeekFrom::End(-2)).unwrap(), 8);
+    let mut rest = Vec::new();
+    reader.read_to_end(&mut rest).unwrap();
+    assert_eq!(rest, b"89");
+    let error = reader.seek(SeekFrom::Current(-11)).unwrap_err();
+    assert_eq!(error.kind(), ErrorKind::InvalidInput);
+
+    // Compressed entries can't be streamed and missing ones aren't found
+    let compressed = asset_io.open_entry("level.txt").err().unwrap();
+    assert_eq!(compressed.kind(), ErrorKind::InvalidInput);
+    let missing = asset_io.open_entry("missing.ogg").err().unwrap();
+    assert_eq!(missing.kind(), ErrorKind::NotFound);
+}
@@ -0,0 +1,38 @@
+//! The environment variable is set for the whole process, so this is the only test in its binary
+
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo, EXPLICIT_PATHS_ENV_VAR};
+
+use common::{load, write_zip};
+
+#[test]
+fn only_explicit_paths_are_searched_with_the_environment_variable() {
+    let exe_dir = std::env::current_exe()
+        .unwrap()
+        .parent()
+        .unwrap()
+        .to_owned();
+    let file_name = format!("bevy_assetio_zip_explicit_env-{}", std::process::id());
+    let bundle_path = exe_dir.join(format!("{}.zip", file_name));
+    write_zip(&bundle_path, &[("levels/1.txt", "next to the exe")]);
+    let load_level = || {
+        let config = AssetIoZipConfig {
+            file_names: vec![file_name.clone()],
+            ..Default::default()
+        };
+        let zip_io = ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+        load(&zip_io, "levels/1.txt")
+    };
+
+    std::env::set_var(EXPLICIT_PATHS_ENV_VAR, "0");
+    assert_eq!(load_level().as_deref(), Some("next to the exe"));
+    std::env::set_var(EXPLICIT_PATHS_ENV_VAR, "1");
+    assert_eq!(load_level(), None);
+    std::env::remove_var(EXPLICIT_PATHS_ENV_VAR);
+
+    fs::remove_file(&bundle_path).unwrap();
+}
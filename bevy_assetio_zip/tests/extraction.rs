@@ -0,0 +1,48 @@
+mod common;
+
+use std::{fs, io::Write};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::load;
+
+#[test]
+fn extracted_bundles_are_served_from_loose_files() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_extract-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mut zip = ZipWriter::new(fs::File::create(dir.join("assets.zip")).unwrap());
+    zip.start_file("levels/1.txt", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"base").unwrap();
+    zip.finish().unwrap();
+    let zip_io = || {
+        let config = AssetIoZipConfig {
+            bundle_path: Some(dir.join("assets.zip")),
+            extract_bundle: true,
+            extract_dir: Some(dir.join("extracted")),
+            ..Default::default()
+        };
+        ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new())
+    };
+
+    assert_eq!(load(&zip_io(), "levels/1.txt").as_deref(), Some("base"));
+    let extracted = fs::read_dir(dir.join("extracted"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path()
+        .join("levels/1.txt");
+    assert_eq!(fs::read_to_string(&extracted).unwrap(), "base");
+
+    // The bundle is not extracted again, so the assets are read from the extracted files
+    fs::write(&extracted, "extracted").unwrap();
+    assert_eq!(
+        load(&zip_io(), "levels/1.txt").as_deref(),
+        Some("extracted")
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
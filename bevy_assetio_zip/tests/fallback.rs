@@ -0,0 +1,116 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{AssetIo, AssetServerSettings},
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin, FallbackAssetIo, MemoryAssetIo,
+};
+
+use common::{asset_io, load, write_zip};
+
+/// Build the plugin's asset IO with the given asset folder and fallback
+fn build_asset_io(asset_folder: &Path, fallback: FallbackAssetIo) -> AssetIoZip {
+    let settings = AssetServerSettings {
+        asset_folder: asset_folder.to_string_lossy().into_owned(),
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(asset_folder.join("assets.zip")),
+        fallback,
+        ..Default::default()
+    };
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(settings)
+        .insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(TaskPool::new()))
+        .add_resource(settings)
+        .add_resource(config);
+    builder.add_plugin(AssetIoZipPlugin);
+    asset_io(&builder)
+}
+
+fn asset_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_fallback_{}_{}",
+        name,
+        std::process::id()
+    ));
+    fs::create_dir_all(dir.join("loose")).unwrap();
+    fs::write(dir.join("loose/a.txt"), "loose a").unwrap();
+    write_zip(&dir.join("assets.zip"), &[("bundled.txt", "bundled")]);
+    dir
+}
+
+#[test]
+fn platform_default_fallback_loads_loose_assets() {
+    let dir = asset_dir("default");
+    let asset_io = build_asset_io(&dir, FallbackAssetIo::PlatformDefault);
+
+    assert_eq!(load(&asset_io, "bundled.txt").as_deref(), Some("bundled"));
+    assert_eq!(load(&asset_io, "loose/a.txt").as_deref(), Some("loose a"));
+    assert!(asset_io.is_directory(Path::new("loose")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn noop_fallback_only_loads_bundled_assets() {
+    let dir = asset_dir("noop");
+    let asset_io = build_asset_io(&dir, FallbackAssetIo::Noop);
+
+    assert_eq!(load(&asset_io, "bundled.txt").as_deref(), Some("bundled"));
+    assert_eq!(load(&asset_io, "loose/a.txt"), None);
+    assert!(!asset_io.is_directory(Path::new("loose")));
+    asset_io.watch_for_changes().unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn memory_fallback_loads_and_lists_its_assets() {
+    let dir = asset_dir("memory");
+    let mut memory_io = MemoryAssetIo::new();
+    memory_io
+        .insert("levels/1.txt", "one")
+        .insert("levels/2.txt", "two")
+        .insert("levels/extra/3.txt", "three")
+        .insert("removed.txt", "removed");
+    assert_eq!(memory_io.remove("removed.txt"), Some(b"removed".to_vec()));
+    let asset_io = build_asset_io(&dir, FallbackAssetIo::Memory(memory_io));
+
+    assert_eq!(load(&asset_io, "bundled.txt").as_deref(), Some("bundled"));
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("one"));
+    assert_eq!(load(&asset_io, "removed.txt"), None);
+    // Loose files aren't used when the fallback is in memory
+    assert_eq!(load(&asset_io, "loose/a.txt"), None);
+
+    assert!(asset_io.is_directory(Path::new("levels")));
+    assert!(!asset_io.is_directory(Path::new("levels/1.txt")));
+    let mut children = asset_io
+        .read_directory(Path::new("levels"))
+        .unwrap()
+        .collect::<Vec<_>>();
+    children.sort();
+    assert_eq!(
+        children,
+        vec![
+            Path::new("levels/1.txt"),
+            Path::new("levels/2.txt"),
+            Path::new("levels/extra")
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,129 @@
+mod common;
+
+use std::panic::AssertUnwindSafe;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, MemoryBundleSource};
+
+use common::{asset_io, build_app_with_sources, load, zip_bytes};
+
+/// Build a v2 bundle with the given header fields around a zip of the given files
+fn v2_bundle(version: u16, flags: u16, files: &[(&str, &str)]) -> Vec<u8> {
+    let mut zip = zip_bytes(files);
+    if flags & 1 != 0 {
+        zip.iter_mut().for_each(|x| *x ^= 0b0101_0101);
+    }
+
+    // Pad the header past the fields we know about, like future versions may
+    let header_len = 32u32;
+    let mut bundle = b"BAZB".to_vec();
+    bundle.extend_from_slice(&version.to_le_bytes());
+    bundle.extend_from_slice(&flags.to_le_bytes());
+    bundle.extend_from_slice(&header_len.to_le_bytes());
+    bundle.extend_from_slice(&0u64.to_le_bytes());
+    bundle.resize(header_len as usize, 0);
+    bundle.extend(zip);
+    bundle
+}
+
+/// Mount the given bundle data as the only bundle, returning the names of the mounted bundles and
+/// the contents of `a.txt`
+fn mount(bundle: Vec<u8>) -> (Vec<String>, Option<String>) {
+    let mut sources = BundleSources::default();
+    sources.add("bundle", MemoryBundleSource::new(bundle));
+    let config = AssetIoZipConfig {
+        file_names: vec![format!("format-missing-{}", std::process::id())],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app_with_sources(config, TaskPool::new(), sources));
+
+    let mounted = asset_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| x.name)
+        .collect();
+    (mounted, load(&asset_io, "a.txt"))
+}
+
+/// Get the message that mounting the given bundle data panics with when corrupt bundles are not
+/// skipped
+fn mount_error(bundle: Vec<u8>) -> String {
+    let config = AssetIoZipConfig {
+        file_names: vec![format!("format-missing-{}", std::process::id())],
+        corrupt_bundle_fallback: false,
+        ..Default::default()
+    };
+    let error = std::panic::catch_unwind(AssertUnwindSafe(move || {
+        let mut sources = BundleSources::default();
+        sources.add("bundle", MemoryBundleSource::new(bundle));
+        build_app_with_sources(config, TaskPool::new(), sources);
+    }))
+    .unwrap_err();
+    error.downcast_ref::<String>().unwrap().clone()
+}
+
+#[test]
+fn v2_bundles_are_read_after_their_header() {
+    let (mounted, a) = mount(v2_bundle(2, 0, &[("a.txt", "v2")]));
+    assert_eq!(mounted, vec!["bundle"]);
+    assert_eq!(a.as_deref(), Some("v2"));
+}
+
+#[test]
+fn newer_format_versions_fail_with_a_clear_error() {
+    let error = mount_error(v2_bundle(3, 0, &[("a.txt", "v3")]));
+    assert!(
+        error.contains("Asset bundle format version 3 is newer than the supported version 2"),
+        "{}",
+        error
+    );
+
+    let (mounted, a) = mount(v2_bundle(3, 0, &[("a.txt", "v3")]));
+    assert!(mounted.is_empty());
+    assert_eq!(a, None);
+}
+
+#[test]
+fn encrypted_bundles_are_not_supported() {
+    let error = mount_error(v2_bundle(2, 2, &[("a.txt", "encrypted")]));
+    assert!(
+        error.contains("Encrypted asset bundles are not supported"),
+        "{}",
+        error
+    );
+}
+
+#[test]
+fn truncated_headers_are_corrupt() {
+    let bundle = v2_bundle(2, 0, &[("a.txt", "truncated")])[..12].to_vec();
+    let error = mount_error(bundle.clone());
+    assert!(error.contains("is corrupted"), "{}", error);
+    assert!(error.contains("size: 12 bytes"), "{}", error);
+
+    let (mounted, _) = mount(bundle);
+    assert!(mounted.is_empty());
+}
+
+#[test]
+fn bad_magic_bytes_are_corrupt() {
+    // The obfuscation flag is not trusted without the magic bytes
+    let mut bundle = v2_bundle(2, 1, &[("a.txt", "bad magic")]);
+    bundle[..4].copy_from_slice(b"BAZX");
+    let error = mount_error(bundle.clone());
+    assert!(error.contains("is corrupted"), "{}", error);
+    assert!(error.contains("magic bytes: [42, 41, 5a, 58]"), "{}", error);
+
+    let (mounted, _) = mount(bundle);
+    assert!(mounted.is_empty());
+}
+
+#[test]
+#[cfg(not(feature = "solid"))]
+fn solid_bundles_need_the_solid_feature() {
+    let error = mount_error(v2_bundle(2, 8, &[("a.txt", "solid")]));
+    assert!(
+        error.contains("Asset bundles with solid compression require the `solid` feature"),
+        "{}",
+        error
+    );
+}
@@ -0,0 +1,48 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetGroups, AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::bundle;
+
+#[test]
+fn asset_groups_are_merged_across_bundles() {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "dlc",
+            bundle(&[
+                ("levels/2/boss.txt", "dlc"),
+                (".groups", "level_2\tlevels/2/**\n"),
+            ]),
+        )
+        .add(
+            "base",
+            bundle(&[
+                ("levels/1/map.txt", "base"),
+                ("levels/2/map.txt", "base"),
+                ("music/boss.ogg", "base"),
+                (".groups", "level_1\tlevels/1/*\nlevel_2\tmusic/boss.ogg\n"),
+            ]),
+        );
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+    let groups = AssetGroups::new(zip_io);
+
+    assert_eq!(
+        groups.names().into_iter().collect::<Vec<_>>(),
+        ["level_1", "level_2"]
+    );
+    assert_eq!(
+        groups.paths("level_2"),
+        [
+            Path::new("levels/2/boss.txt"),
+            Path::new("levels/2/map.txt"),
+            Path::new("music/boss.ogg")
+        ]
+    );
+    assert_eq!(groups.paths("level_1"), [Path::new("levels/1/map.txt")]);
+    assert!(groups.paths("level_3").is_empty());
+}
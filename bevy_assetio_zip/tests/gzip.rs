@@ -0,0 +1,75 @@
+mod common;
+
+use std::io::Write;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, BundleSources, MemoryAssetIo, RawEntry, ZipAssetIo,
+};
+use flate2::{write::GzEncoder, Compression};
+
+use common::{bundle, load};
+
+#[test]
+fn gzipped_loose_assets_are_decompressed() {
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    gz.write_all(b"gzipped").unwrap();
+
+    let mut fallback = MemoryAssetIo::new();
+    fallback
+        .insert("meshes/big.obj.gz", gz.finish().unwrap())
+        .insert("meshes/small.obj", "loose");
+
+    let zip_io = ZipAssetIo::new(
+        AssetIoZipConfig::default(),
+        BundleSources::default(),
+        None,
+        TaskPool::new(),
+    );
+    let asset_io = AssetIoZip::new(zip_io, Box::new(fallback));
+
+    assert_eq!(
+        load(&asset_io, "meshes/big.obj").as_deref(),
+        Some("gzipped")
+    );
+    assert_eq!(
+        load(&asset_io, "meshes/small.obj").as_deref(),
+        Some("loose")
+    );
+    assert_eq!(load(&asset_io, "meshes/missing.obj"), None);
+}
+
+#[test]
+fn raw_entries_are_decompressed_through_the_same_codecs() {
+    let level = "wall ".repeat(100);
+    let mut sources = BundleSources::default();
+    sources
+        .add("dlc", bundle(&[("levels/1.txt", &level)]))
+        .add("base", bundle(&[("levels/1.txt", "base")]));
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    let entry = zip_io.raw_entry("levels/1.txt").unwrap();
+    assert_eq!(entry.compression, "deflated");
+    assert!(entry.data.len() < level.len());
+    assert_eq!(entry.size, level.len() as u64);
+
+    // Send the entry over the "network" and decompress it on the client
+    let received = RawEntry {
+        path: entry.path.clone(),
+        compression: entry.compression.clone(),
+        data: entry.data.clone(),
+        size: entry.size,
+        crc32: entry.crc32,
+    };
+    assert_eq!(received.decompress().unwrap(), level.as_bytes());
+
+    let corrupted = RawEntry {
+        crc32: entry.crc32 ^ 1,
+        ..received
+    };
+    assert!(corrupted.decompress().is_err());
+    assert_eq!(
+        zip_io.raw_entry("missing.txt").unwrap_err().kind(),
+        std::io::ErrorKind::NotFound
+    );
+}
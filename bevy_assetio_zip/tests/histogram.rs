@@ -0,0 +1,32 @@
+mod common;
+
+use bevy_assetio_zip::{AssetLoadEvent, AssetSource, LoadTimeHistogram, LOAD_TIME_BUCKETS};
+
+#[test]
+fn load_times_are_bucketed_by_extension() {
+    let mut histogram = LoadTimeHistogram::default();
+    for (path, micros) in &[
+        ("a.PNG", 50),
+        ("b.png", 2_000),
+        ("c.ogg", 900_000),
+        ("README", 10),
+    ] {
+        histogram.record(&AssetLoadEvent {
+            path: (*path).into(),
+            source: AssetSource::Bundle("base".into()),
+            bytes: 100,
+            micros: *micros,
+        });
+    }
+
+    let png = &histogram.extensions["png"];
+    assert_eq!((png.count, png.bytes, png.max_micros), (2, 200, 2_000));
+    assert_eq!(png.mean_micros(), 1_025);
+    assert_eq!(png.buckets[0], 1);
+    assert_eq!(png.buckets[3], 1);
+    assert_eq!(
+        histogram.extensions["ogg"].buckets[LOAD_TIME_BUCKETS.len()],
+        1
+    );
+    assert_eq!(histogram.extensions[""].count, 1);
+}
@@ -0,0 +1,115 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    asset::{AssetPlugin, AssetServerSettings},
+    core::CorePlugin,
+    prelude::*,
+    reflect::ReflectPlugin,
+    render::texture::ImageTextureLoader,
+};
+use bevy_assetio_zip::{AssetIoZipConfig, AssetIoZipPlugin};
+use zip::{write::FileOptions, ZipWriter};
+
+/// A 1x1 white PNG image
+const SQUARE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0xf0,
+    0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99, 0x3d, 0x1d, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+/// A 2x1 white PNG image
+const WIDE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x02, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0xf4, 0x22, 0x7f,
+    0x8a, 0x00, 0x00, 0x00, 0x0b, 0x49, 0x44, 0x41, 0x54, 0x78, 0xda, 0x63, 0xf8, 0x0f, 0x05, 0x00,
+    0x23, 0xe5, 0x07, 0xf9, 0x19, 0x90, 0x0f, 0x54, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45, 0x4e, 0x44,
+    0xae, 0x42, 0x60, 0x82,
+];
+
+/// Update the app until the texture has the given width
+fn wait_for_width(builder: &mut AppBuilder, handle: &Handle<Texture>, width: u32) {
+    let start = Instant::now();
+    loop {
+        let current = builder
+            .resources()
+            .get::<Assets<Texture>>()
+            .unwrap()
+            .get(handle)
+            .map(|x| x.size.width);
+        if current == Some(width) {
+            return;
+        }
+        assert!(
+            start.elapsed() < Duration::from_secs(10),
+            "Timed out waiting for a width of {}, the width is {:?}",
+            width,
+            current
+        );
+
+        // Loaded assets are only committed when the app updates
+        builder.app.update();
+        std::thread::sleep(Duration::from_millis(10));
+    }
+}
+
+#[test]
+fn changed_loose_assets_are_reloaded_over_the_bundle() {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_hot_reload_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(dir.join("assets")).unwrap();
+    let mut zip = ZipWriter::new(File::create(dir.join("assets.zip")).unwrap());
+    zip.start_file("sprite.png", FileOptions::default())
+        .unwrap();
+    zip.write_all(WIDE).unwrap();
+    zip.finish().unwrap();
+    fs::write(dir.join("assets/sprite.png"), SQUARE).unwrap();
+
+    let settings = AssetServerSettings {
+        asset_folder: dir.join("assets").to_string_lossy().into_owned(),
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(dir.join("assets.zip")),
+        prefer_filesystem: true,
+        ..Default::default()
+    };
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder.insert_resource(settings).insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder.add_resource(settings).add_resource(config);
+    builder
+        .add_plugin(ReflectPlugin)
+        .add_plugin(CorePlugin)
+        .add_plugin(AssetIoZipPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Texture>()
+        .init_asset_loader::<ImageTextureLoader>();
+
+    let asset_server = (*builder.resources().get::<AssetServer>().unwrap()).clone();
+    asset_server.watch_for_changes().unwrap();
+    let handle = asset_server.load("sprite.png");
+    wait_for_width(&mut builder, &handle, 1);
+
+    fs::write(dir.join("assets/sprite.png"), WIDE).unwrap();
+    wait_for_width(&mut builder, &handle, 2);
+
+    // Editors often replace the file instead of writing to it
+    fs::write(dir.join("assets/sprite.png.tmp"), SQUARE).unwrap();
+    fs::rename(
+        dir.join("assets/sprite.png.tmp"),
+        dir.join("assets/sprite.png"),
+    )
+    .unwrap();
+    wait_for_width(&mut builder, &handle, 1);
+
+    fs::remove_dir_all(&dir).ok();
+}
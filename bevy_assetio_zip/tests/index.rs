@@ -0,0 +1,105 @@
+//! Sidecar indexes are not used in browser builds
+#![cfg(not(target_arch = "wasm32"))]
+
+mod common;
+
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, CompressionMethod};
+use zip::{write::FileOptions, ZipArchive, ZipWriter};
+
+use common::{asset_io, build_app, load};
+
+/// Write a bundle with a single stored entry next to the test executable
+fn write_stored_bundle(file_name: &str, contents: &str) -> std::path::PathBuf {
+    let exe = std::env::current_exe().unwrap();
+    let path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("a.txt", stored).unwrap();
+    zip.write_all(contents.as_bytes()).unwrap();
+    zip.finish().unwrap();
+    path
+}
+
+/// Write an index for the bundle that lists the data of its `a.txt` entry under the given name
+fn write_index(bundle: &Path, name: &str) {
+    let bytes = fs::read(bundle).unwrap();
+    let eocd = &bytes[bytes.len() - 22..];
+    let mut zip = ZipArchive::new(File::open(bundle).unwrap()).unwrap();
+    let entry = zip.by_name("a.txt").unwrap();
+    let data_start = entry.data_start() as usize;
+    let data = &bytes[data_start..data_start + entry.size() as usize];
+
+    let index = format!(
+        "bevy_assetio_zip-index\t1\t{}\t{}\n{}\t{}\t{}\t{}\tstored\t{}\n",
+        bytes.len(),
+        crc32fast::hash(eocd),
+        data_start,
+        data.len(),
+        data.len(),
+        crc32fast::hash(data),
+        name
+    );
+    fs::write(bundle.with_extension("idx"), index).unwrap();
+}
+
+fn config(file_name: &str) -> AssetIoZipConfig {
+    AssetIoZipConfig {
+        file_names: vec![file_name.to_owned()],
+        ..Default::default()
+    }
+}
+
+#[test]
+fn entries_are_found_with_the_sidecar_index() {
+    let file_name = format!("index-{}", std::process::id());
+    let bundle = write_stored_bundle(&file_name, "indexed");
+    write_index(&bundle, "renamed.txt");
+
+    // Only the entries listed in the index are found, proving the central directory isn't read
+    let asset_io = asset_io(&build_app(config(&file_name), TaskPool::new()));
+    assert_eq!(load(&asset_io, "renamed.txt").as_deref(), Some("indexed"));
+    assert_eq!(load(&asset_io, "a.txt"), None);
+
+    fs::remove_file(&bundle).unwrap();
+    fs::remove_file(bundle.with_extension("idx")).unwrap();
+}
+
+#[test]
+fn indexes_that_do_not_match_the_bundle_are_ignored() {
+    let file_name = format!("index-mismatch-{}", std::process::id());
+    let bundle = write_stored_bundle(&file_name, "old");
+    write_index(&bundle, "renamed.txt");
+    // Rebuild the bundle, leaving the old index behind
+    write_stored_bundle(&file_name, "rebuilt");
+
+    let asset_io = asset_io(&build_app(config(&file_name), TaskPool::new()));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("rebuilt"));
+    assert_eq!(load(&asset_io, "renamed.txt"), None);
+
+    fs::remove_file(&bundle).unwrap();
+    fs::remove_file(bundle.with_extension("idx")).unwrap();
+}
+
+#[test]
+fn unsupported_index_versions_are_ignored() {
+    let file_name = format!("index-version-{}", std::process::id());
+    let bundle = write_stored_bundle(&file_name, "plain");
+    write_index(&bundle, "renamed.txt");
+    let index_path = bundle.with_extension("idx");
+    let index = fs::read_to_string(&index_path).unwrap();
+    fs::write(&index_path, index.replacen("\t1\t", "\t2\t", 1)).unwrap();
+
+    let asset_io = asset_io(&build_app(config(&file_name), TaskPool::new()));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("plain"));
+    assert_eq!(load(&asset_io, "renamed.txt"), None);
+
+    fs::remove_file(&bundle).unwrap();
+    fs::remove_file(&index_path).unwrap();
+}
@@ -0,0 +1,39 @@
+//! iOS tests, run in a simulator or on a device with e.g. `cargo dinghy test`, which runs the test
+//! executable from inside of an application bundle
+#![cfg(target_os = "ios")]
+
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::AssetIoZipConfig;
+
+use common::{asset_io, build_app, load, write_zip};
+
+#[test]
+fn app_bundle_resources_are_searched() {
+    let exe = std::env::current_exe().unwrap().canonicalize().unwrap();
+    let app_dir = exe
+        .ancestors()
+        .find(|dir| dir.extension().map(|x| x == "app").unwrap_or(false))
+        .expect("Tests must be run from inside of an application bundle")
+        .to_owned();
+    let bundle = app_dir.join("assets/ios-resources.zip");
+    write_zip(&bundle, &[("a.txt", "resources")]);
+
+    let build = |search_app_bundle| {
+        let config = AssetIoZipConfig {
+            file_names: vec!["ios-resources".into()],
+            search_asset_dir: false,
+            search_app_bundle,
+            ..Default::default()
+        };
+        asset_io(&build_app(config, TaskPool::new()))
+    };
+
+    assert_eq!(load(&build(true), "a.txt").as_deref(), Some("resources"));
+    assert_eq!(load(&build(false), "a.txt"), None);
+
+    fs::remove_file(&bundle).unwrap();
+}
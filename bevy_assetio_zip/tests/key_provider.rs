@@ -0,0 +1,83 @@
+use std::{path::Path, sync::Arc};
+
+use bevy::{asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{
+    test_util::{entry_obfuscated_bundle, key_slot_bundle},
+    AssetIoZipConfig, BundleSources, KeyProvider, ZipAssetIo,
+};
+
+fn load_bytes(zip_io: &ZipAssetIo, path: &str) -> Vec<u8> {
+    TaskPool::new()
+        .scope(|scope| scope.spawn(zip_io.load_path(Path::new(path))))
+        .pop()
+        .unwrap()
+        .unwrap()
+}
+
+/// Supplies the salt of the `external` bundle and overrides the salt of the `overridden` bundle
+struct TestKeys;
+
+impl KeyProvider for TestKeys {
+    fn salt(&self, bundle: &str, stored: &[u8]) -> Option<Vec<u8>> {
+        match bundle {
+            "external" => {
+                assert!(stored.is_empty());
+                Some(b"external salt".to_vec())
+            }
+            "overridden" => Some(b"other salt".to_vec()),
+            _ => None,
+        }
+    }
+}
+
+#[test]
+fn key_providers_supply_and_override_entry_salts() {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "external",
+            entry_obfuscated_bundle(&[("a.txt", "a")], b"external salt", false),
+        )
+        .add(
+            "overridden",
+            entry_obfuscated_bundle(&[("b.txt", "b")], b"stored salt", true),
+        )
+        .add(
+            "stored",
+            entry_obfuscated_bundle(&[("c.txt", "c")], b"stored salt", true),
+        );
+    let config = AssetIoZipConfig {
+        key_provider: Some(Arc::new(TestKeys)),
+        ..Default::default()
+    };
+
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+
+    assert_eq!(load_bytes(&zip_io, "a.txt"), b"a");
+    // The salt from the provider is used instead of the one in the bundle
+    assert_ne!(load_bytes(&zip_io, "b.txt"), b"b");
+    assert_eq!(load_bytes(&zip_io, "c.txt"), b"c");
+}
+
+#[test]
+fn bundles_without_their_salt_need_a_key_provider() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "external",
+        entry_obfuscated_bundle(&[("a.txt", "a")], b"external salt", false),
+    );
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    assert_ne!(load_bytes(&zip_io, "a.txt"), b"a");
+}
+
+#[test]
+fn bundles_describe_their_obfuscation_in_the_header_key_slot() {
+    let mut sources = BundleSources::default();
+    sources.add("slotted", key_slot_bundle(&[("a.txt", "a")], b"slot salt"));
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    assert_eq!(load_bytes(&zip_io, "a.txt"), b"a");
+}
@@ -0,0 +1,42 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy::{app::Events, tasks::TaskPool};
+use bevy_assetio_zip::{AssetIoZipConfig, AssetLoadEvent, AssetSource};
+
+use common::{asset_io, build_app, load, write_bundle};
+
+#[test]
+fn loaded_assets_send_load_events() {
+    let file_name = format!("load_events-{}", std::process::id());
+    let bundle = write_bundle(&file_name, &[("levels/1.txt", "level one")]);
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name.clone()],
+        ..Default::default()
+    };
+    let builder = build_app(config, TaskPool::new());
+    let asset_io = asset_io(&builder);
+
+    assert_eq!(
+        load(&asset_io, "levels/1.txt").as_deref(),
+        Some("level one")
+    );
+    // Assets that fail to load don't send events
+    assert_eq!(load(&asset_io, "levels/missing.txt"), None);
+    fs::remove_file(&bundle).unwrap();
+
+    let mut app = builder.app;
+    app.update();
+    let events = app.resources.get::<Events<AssetLoadEvent>>().unwrap();
+    let loaded = events
+        .get_reader()
+        .iter(&events)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(loaded.len(), 1);
+    assert_eq!(loaded[0].path, Path::new("levels/1.txt"));
+    assert_eq!(loaded[0].source, AssetSource::Bundle(file_name));
+    assert_eq!(loaded[0].bytes, "level one".len());
+}
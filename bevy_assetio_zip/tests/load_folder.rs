@@ -0,0 +1,146 @@
+use std::{
+    fs::{self, File},
+    io::Write,
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use bevy::{
+    asset::{AssetPlugin, AssetServerSettings, LoadState},
+    core::CorePlugin,
+    prelude::*,
+    reflect::ReflectPlugin,
+    render::texture::ImageTextureLoader,
+};
+use bevy_assetio_zip::{AssetIoZipConfig, AssetIoZipPlugin, BundleAssetServerExt};
+use zip::{write::FileOptions, ZipWriter};
+
+/// A 1x1 white PNG image
+const SPRITE: &[u8] = &[
+    0x89, 0x50, 0x4e, 0x47, 0x0d, 0x0a, 0x1a, 0x0a, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x48, 0x44, 0x52,
+    0x00, 0x00, 0x00, 0x01, 0x00, 0x00, 0x00, 0x01, 0x08, 0x06, 0x00, 0x00, 0x00, 0x1f, 0x15, 0xc4,
+    0x89, 0x00, 0x00, 0x00, 0x0d, 0x49, 0x44, 0x41, 0x54, 0x78, 0x9c, 0x63, 0xf8, 0xcf, 0xc0, 0xf0,
+    0x1f, 0x00, 0x05, 0x00, 0x01, 0xff, 0x89, 0x99, 0x3d, 0x1d, 0x00, 0x00, 0x00, 0x00, 0x49, 0x45,
+    0x4e, 0x44, 0xae, 0x42, 0x60, 0x82,
+];
+
+fn write_bundle(path: &Path, files: &[&str]) {
+    let mut zip = ZipWriter::new(File::create(path).unwrap());
+    for file in files {
+        zip.start_file(*file, FileOptions::default()).unwrap();
+        zip.write_all(SPRITE).unwrap();
+    }
+    zip.finish().unwrap();
+}
+
+#[test]
+fn load_folder_from_bundle() {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_load_folder_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let bundle_path = dir.join("assets.zip");
+    write_bundle(
+        &bundle_path,
+        &[
+            "sprites/player.png",
+            "sprites/enemy.png",
+            "sprites/ui/button.png",
+            "music/theme.png",
+        ],
+    );
+
+    // Point the asset folder at a directory that doesn't exist so that there are no loose assets
+    let settings = AssetServerSettings {
+        asset_folder: dir.join("assets").to_string_lossy().into_owned(),
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(bundle_path),
+        ..Default::default()
+    };
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder.insert_resource(settings).insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder.add_resource(settings).add_resource(config);
+    builder
+        .add_plugin(ReflectPlugin)
+        .add_plugin(CorePlugin)
+        .add_plugin(AssetIoZipPlugin)
+        .add_plugin(AssetPlugin)
+        .add_asset::<Texture>()
+        .init_asset_loader::<ImageTextureLoader>();
+
+    let asset_server = (*builder.resources().get::<AssetServer>().unwrap()).clone();
+    let handles = asset_server.load_folder("sprites").unwrap();
+    assert_eq!(handles.len(), 3);
+
+    let start = Instant::now();
+    loop {
+        match asset_server.get_group_load_state(handles.iter().map(|x| x.id)) {
+            LoadState::Loaded => break,
+            LoadState::Failed => panic!("Failed to load sprites from the bundle"),
+            _ if start.elapsed() > Duration::from_secs(10) => {
+                panic!("Timed out loading sprites from the bundle")
+            }
+            // Loaded assets are only committed when the app updates
+            _ => {
+                builder.app.update();
+                std::thread::sleep(Duration::from_millis(10));
+            }
+        }
+    }
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn bundles_are_queried_through_the_asset_server() {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_server_ext_{}",
+        std::process::id()
+    ));
+    fs::create_dir_all(&dir).unwrap();
+    let bundle_path = dir.join("assets.zip");
+    write_bundle(
+        &bundle_path,
+        &["levels/1.png", "levels/2.png", "levels_extra/3.png"],
+    );
+
+    let settings = AssetServerSettings {
+        asset_folder: dir.join("assets").to_string_lossy().into_owned(),
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(bundle_path),
+        ..Default::default()
+    };
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder.insert_resource(settings).insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder.add_resource(settings).add_resource(config);
+    builder
+        .add_plugin(ReflectPlugin)
+        .add_plugin(CorePlugin)
+        .add_plugin(AssetIoZipPlugin)
+        .add_plugin(AssetPlugin);
+
+    let asset_server = builder.resources().get::<AssetServer>().unwrap();
+    assert!(asset_server.bundle_contains("levels/1.png"));
+    assert!(!asset_server.bundle_contains("levels/4.png"));
+    assert_eq!(
+        asset_server.bundle_metadata("levels/2.png").unwrap().bundle,
+        "assets"
+    );
+    assert_eq!(asset_server.bundle_metadata("levels/4.png"), None);
+    assert_eq!(
+        asset_server.bundle_list("levels/"),
+        vec![Path::new("levels/1.png"), Path::new("levels/2.png")]
+    );
+    assert_eq!(asset_server.bundle_list("").len(), 3);
+
+    fs::remove_dir_all(&dir).ok();
+}
@@ -0,0 +1,153 @@
+mod common;
+
+use std::{
+    io::{Cursor, Write},
+    path::Path,
+    time::{Duration, UNIX_EPOCH},
+};
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin, BundleSources, EntryMetadataCache,
+    MemoryBundleSource, ZipAssetIo,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::{bundle, load};
+
+#[test]
+fn asset_metadata_is_read_from_the_loading_bundle() {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "dlc",
+            bundle(&[
+                ("levels/1.txt", "dlc"),
+                (".metadata", "1600000000\t0000beef\tlevels/1.txt\n"),
+            ]),
+        )
+        .add("base", bundle(&[("levels/2.txt", "base")]));
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    let metadata = zip_io.asset_metadata("levels/1.txt").unwrap();
+    assert_eq!(metadata.bundle, "dlc");
+    assert_eq!(
+        metadata.modified,
+        Some(UNIX_EPOCH + Duration::from_secs(1_600_000_000))
+    );
+    assert_eq!(metadata.hash, Some(0xbeef));
+
+    let metadata = zip_io.asset_metadata("levels/2.txt").unwrap();
+    assert_eq!(metadata.bundle, "base");
+    assert_eq!(metadata.modified, None);
+
+    assert!(zip_io.asset_metadata("levels/3.txt").is_none());
+}
+
+#[test]
+fn bundle_comments_are_read_by_bundle_name() {
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("levels/1.txt", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"dlc").unwrap();
+    zip.set_comment("build 1234");
+    let commented = MemoryBundleSource::new(zip.finish().unwrap().into_inner());
+
+    let mut sources = BundleSources::default();
+    sources
+        .add("dlc", commented)
+        .add("base", bundle(&[("levels/1.txt", "base")]));
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    assert_eq!(zip_io.bundle_comment("dlc").as_deref(), Some("build 1234"));
+    assert_eq!(zip_io.bundle_comment("base"), None);
+    assert_eq!(zip_io.bundle_comment("missing"), None);
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("dlc"));
+}
+
+#[test]
+fn asset_fingerprints_come_from_the_loading_bundle() {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "dlc",
+            bundle(&[
+                ("levels/1.txt", "dlc"),
+                ("levels/2.txt", "dlc"),
+                (".metadata", "1600000000\t0000beef\tlevels/1.txt\n"),
+            ]),
+        )
+        .add(
+            "base",
+            bundle(&[
+                ("levels/1.txt", "base"),
+                ("levels/2.txt", "base"),
+                ("levels/3.txt", "base"),
+                (
+                    ".metadata",
+                    "0\t00000001\tlevels/1.txt\n\
+                     0\t00000002\tlevels/2.txt\n\
+                     0\t00000003\tlevels/3.txt\n",
+                ),
+            ]),
+        );
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    let fingerprints = zip_io.asset_fingerprints();
+    assert_eq!(fingerprints.len(), 2);
+    assert_eq!(fingerprints[Path::new("levels/1.txt")], 0xbeef);
+    assert_eq!(fingerprints[Path::new("levels/3.txt")], 3);
+}
+
+#[test]
+fn entry_metadata_cache_updates_on_unmount() {
+    let mut sources = BundleSources::default();
+    sources
+        .add(
+            "dlc",
+            bundle(&[
+                ("levels/1.txt", "dlc level"),
+                (".metadata", "0\t0000beef\tlevels/1.txt\n"),
+            ]),
+        )
+        .add(
+            "base",
+            bundle(&[("levels/1.txt", "base"), ("levels/2.txt", "base")]),
+        );
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(sources);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(TaskPool::new()))
+        .add_resource(sources);
+    builder.add_plugin(AssetIoZipPlugin);
+    builder.app.update();
+
+    {
+        let cache = builder.resources().get::<EntryMetadataCache>().unwrap();
+        let level = cache.get("levels/1.txt").unwrap();
+        assert_eq!(level.bundle, "dlc");
+        assert_eq!(level.size, 9);
+        assert_eq!(level.hash, Some(0xbeef));
+        assert_eq!(cache.bundle_size("base"), 4);
+        assert_eq!(cache.total_size(), 13);
+    }
+
+    let asset_io = (*builder.resources().get::<AssetIoZip>().unwrap()).clone();
+    assert!(asset_io.unmount("dlc"));
+    builder.app.update();
+
+    let cache = builder.resources().get::<EntryMetadataCache>().unwrap();
+    let level = cache.get("levels/1.txt").unwrap();
+    assert_eq!(level.bundle, "base");
+    assert_eq!(level.hash, None);
+    assert_eq!(cache.total_size(), 8);
+}
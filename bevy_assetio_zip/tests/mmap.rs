@@ -0,0 +1,98 @@
+mod common;
+
+use std::{fs::File, io::Write};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, CompressionMethod};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::{asset_io, build_app, load};
+
+#[test]
+fn stored_and_compressed_entries_are_read_with_mmap() {
+    let file_name = format!("mmap-{}", std::process::id());
+    let exe = std::env::current_exe().unwrap();
+    let path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file_aligned("aligned.txt", stored, 64).unwrap();
+    zip.write_all(b"aligned").unwrap();
+    zip.start_file("stored.txt", stored).unwrap();
+    zip.write_all(b"stored").unwrap();
+    let deflated = FileOptions::default().compression_method(CompressionMethod::Deflated);
+    zip.start_file("deflated.txt", deflated).unwrap();
+    zip.write_all(b"deflated").unwrap();
+    zip.finish().unwrap();
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+
+    // Stored entries are served from the memory map and compressed ones are still decompressed
+    assert_eq!(load(&asset_io, "aligned.txt").as_deref(), Some("aligned"));
+    assert_eq!(load(&asset_io, "stored.txt").as_deref(), Some("stored"));
+    assert_eq!(load(&asset_io, "deflated.txt").as_deref(), Some("deflated"));
+    assert_eq!(load(&asset_io, "missing.txt"), None);
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[cfg(feature = "obfuscation")]
+#[test]
+fn mapped_obfuscated_entries_are_deobfuscated() {
+    let file_name = format!("mmap-obfuscated-{}", std::process::id());
+    let exe = std::env::current_exe().unwrap();
+    let path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+
+    let mut zip = ZipWriter::new(File::create(&path).unwrap());
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("a.txt", stored).unwrap();
+    zip.write_all(&[b'a' ^ 0b0101_0101]).unwrap();
+    zip.start_file(".obfuscated-entries", stored).unwrap();
+    zip.finish().unwrap();
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("a"));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn v2_bundle_entries_are_found_after_the_header() {
+    let file_name = format!("mmap-v2-{}", std::process::id());
+    let exe = std::env::current_exe().unwrap();
+    let path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+
+    let mut zip = ZipWriter::new(std::io::Cursor::new(Vec::new()));
+    let stored = FileOptions::default().compression_method(CompressionMethod::Stored);
+    zip.start_file("stored.txt", stored).unwrap();
+    zip.write_all(b"stored after a header").unwrap();
+    let zip_data = zip.finish().unwrap().into_inner();
+
+    let mut bundle = b"BAZB".to_vec();
+    bundle.extend_from_slice(&2u16.to_le_bytes());
+    bundle.extend_from_slice(&0u16.to_le_bytes());
+    bundle.extend_from_slice(&20u32.to_le_bytes());
+    bundle.extend_from_slice(&0u64.to_le_bytes());
+    bundle.extend(zip_data);
+    std::fs::write(&path, bundle).unwrap();
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(
+        load(&asset_io, "stored.txt").as_deref(),
+        Some("stored after a header")
+    );
+
+    std::fs::remove_file(&path).unwrap();
+}
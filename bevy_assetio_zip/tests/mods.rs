@@ -0,0 +1,194 @@
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZipConfig, BundleSources, ModConflict, ModConflictPolicy, ModConflictReport, ZipAssetIo,
+};
+
+use common::{asset_io, build_app, load, write_zip};
+
+#[test]
+fn mods_override_the_main_bundle_in_priority_order() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_mods-{}", std::process::id()));
+    write_zip(
+        &dir.join("assets.zip"),
+        &[("a.txt", "main"), ("b.txt", "main"), ("c.txt", "main")],
+    );
+    // Workshop items are sub-directories of the content dir
+    let workshop = dir.join("workshop");
+    write_zip(
+        &workshop.join("123/hd.zip"),
+        &[("a.txt", "hd"), ("b.txt", "hd")],
+    );
+    write_zip(&workshop.join("456/extra.zip"), &[("a.txt", "extra")]);
+    write_zip(&dir.join("mods/local.zip"), &[("a.txt", "local")]);
+    fs::write(dir.join("mods/readme.txt"), "not a mod").unwrap();
+
+    let config = AssetIoZipConfig {
+        bundle_path: Some(dir.join("assets.zip")),
+        mod_dirs: vec![dir.join("mods"), workshop],
+        mod_order: vec!["123".into()],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+
+    // Listed mods come first, then the rest by name, then the main bundle
+    let mounted = asset_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| (x.name, x.is_mod))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        mounted,
+        [
+            ("123/hd".to_owned(), true),
+            ("456/extra".to_owned(), true),
+            ("local".to_owned(), true),
+            ("assets".to_owned(), false),
+        ]
+    );
+
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("hd"));
+    assert_eq!(load(&asset_io, "b.txt").as_deref(), Some("hd"));
+    assert_eq!(load(&asset_io, "c.txt").as_deref(), Some("main"));
+
+    let chain = asset_io
+        .override_chain("b.txt")
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(chain, ["123/hd", "assets"]);
+    assert!(asset_io.override_chain("missing.txt").is_empty());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn mod_conflicts_are_reported_and_resolved_by_the_policy() {
+    let dir =
+        std::env::temp_dir().join(format!("bevy_assetio_zip_conflicts-{}", std::process::id()));
+    write_zip(&dir.join("a.zip"), &[("x.txt", "a"), ("y.txt", "a")]);
+    write_zip(&dir.join("b.zip"), &[("x.txt", "b")]);
+
+    let build = |mod_conflict_policy| {
+        let config = AssetIoZipConfig {
+            file_names: vec!["missing".into()],
+            mod_dirs: vec![dir.clone()],
+            mod_order: vec!["b".into()],
+            mod_conflict_policy,
+            ..Default::default()
+        };
+        build_app(config, TaskPool::new())
+    };
+    let conflict = |winner: Option<&str>, providers: &[&str]| ModConflict {
+        path: "x.txt".into(),
+        providers: providers.iter().map(|x| x.to_string()).collect(),
+        winner: winner.map(Into::into),
+    };
+
+    let builder = build(ModConflictPolicy::Priority);
+    let report = builder.resources().get::<ModConflictReport>().unwrap();
+    assert_eq!(report.conflicts, [conflict(Some("b"), &["b", "a"])]);
+    assert!(report.get("y.txt").is_none());
+    let priority_io = asset_io(&builder);
+    assert_eq!(load(&priority_io, "x.txt").as_deref(), Some("b"));
+    assert_eq!(load(&priority_io, "y.txt").as_deref(), Some("a"));
+
+    // The mod order is ignored and the mods are used in the order they are found
+    let first_io = asset_io(&build(ModConflictPolicy::FirstWins));
+    assert_eq!(
+        first_io.mod_conflicts().conflicts,
+        [conflict(Some("a"), &["a", "b"])]
+    );
+    assert_eq!(load(&first_io, "x.txt").as_deref(), Some("a"));
+
+    // Only the conflicting asset fails to load
+    let error_io = asset_io(&build(ModConflictPolicy::Error));
+    assert_eq!(
+        error_io.mod_conflicts().conflicts,
+        [conflict(None, &["a", "b"])]
+    );
+    assert_eq!(load(&error_io, "x.txt"), None);
+    assert_eq!(load(&error_io, "y.txt").as_deref(), Some("a"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn mods_only_provide_allowed_assets() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_filter-{}", std::process::id()));
+    let files = [
+        ("textures/grass.png", "main"),
+        ("textures/trees/oak.png", "main"),
+        ("textures/ui.png", "main"),
+        ("scripts/ai.lua", "main"),
+    ];
+    write_zip(&dir.join("assets.zip"), &files);
+    let mod_files = files
+        .iter()
+        .map(|(path, _)| (*path, "mod"))
+        .collect::<Vec<_>>();
+    write_zip(&dir.join("mods/hd.zip"), &mod_files);
+
+    let config = AssetIoZipConfig {
+        bundle_path: Some(dir.join("assets.zip")),
+        mod_dirs: vec![dir.join("mods")],
+        // `*` doesn't match across directories, and invalid patterns are ignored
+        mod_allow: vec!["textures/*".into(), "[".into()],
+        mod_deny: vec!["textures/ui.png".into()],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+
+    assert_eq!(
+        load(&asset_io, "textures/grass.png").as_deref(),
+        Some("mod")
+    );
+    assert_eq!(
+        load(&asset_io, "textures/trees/oak.png").as_deref(),
+        Some("main")
+    );
+    assert_eq!(load(&asset_io, "textures/ui.png").as_deref(), Some("main"));
+    assert_eq!(load(&asset_io, "scripts/ai.lua").as_deref(), Some("main"));
+    let chain = asset_io
+        .override_chain("scripts/ai.lua")
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(chain, ["assets"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn mods_over_the_limits_are_not_mounted() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_limits-{}", std::process::id()));
+    let bomb = "0".repeat(10_000);
+    write_zip(&dir.join("small.zip"), &[("levels/1.txt", "small")]);
+    write_zip(
+        &dir.join("many.zip"),
+        &[("a.txt", "many"), ("b.txt", "many"), ("c.txt", "many")],
+    );
+    write_zip(&dir.join("bomb.zip"), &[("levels/2.txt", &bomb)]);
+
+    let config = AssetIoZipConfig {
+        mod_dirs: vec![dir.clone()],
+        mod_max_entries: Some(2),
+        mod_max_uncompressed_size: Some(1024),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+
+    let mounted = zip_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(mounted, ["small"]);
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("small"));
+    assert_eq!(load(&zip_io, "levels/2.txt"), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
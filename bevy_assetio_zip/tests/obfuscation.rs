@@ -0,0 +1,169 @@
+mod common;
+
+use std::{
+    fs,
+    io::{Cursor, Read, Seek, SeekFrom, Write},
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZipConfig, BundleSource, BundleSources, FileBundleSource, MemoryBundleSource,
+};
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, CompressionMethod};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::{asset_io, build_app, build_app_with_sources, load, write_bundle};
+
+#[test]
+fn obfuscated_bundles_are_preferred_and_deobfuscated() {
+    let file_name = format!("obfuscation-{}", std::process::id());
+    let zip_path = write_bundle(&file_name, &[("a.txt", "plain")]);
+
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("a.txt", FileOptions::default()).unwrap();
+    zip.write_all(b"obfuscated").unwrap();
+    let obfuscated = zip
+        .finish()
+        .unwrap()
+        .into_inner()
+        .into_iter()
+        .map(|x| x ^ 0b0101_0101)
+        .collect::<Vec<_>>();
+    let bin_path = zip_path.with_extension("bin");
+    fs::write(&bin_path, obfuscated).unwrap();
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let obfuscated_io = asset_io(&build_app(config.clone(), TaskPool::new()));
+    assert_eq!(load(&obfuscated_io, "a.txt").as_deref(), Some("obfuscated"));
+
+    fs::remove_file(&bin_path).unwrap();
+    let plain_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(load(&plain_io, "a.txt").as_deref(), Some("plain"));
+    fs::remove_file(&zip_path).unwrap();
+}
+
+#[test]
+fn obfuscated_entries_are_deobfuscated() {
+    let file_name = format!("obfuscated-entries-{}", std::process::id());
+    let obfuscated = b"entry".iter().map(|x| x ^ 0b0101_0101).collect::<Vec<_>>();
+    let exe = std::env::current_exe().unwrap();
+    let zip_path = exe.parent().unwrap().join(format!("{}.zip", file_name));
+    let mut zip = ZipWriter::new(fs::File::create(&zip_path).unwrap());
+    zip.start_file("a.txt", FileOptions::default()).unwrap();
+    zip.write_all(&obfuscated).unwrap();
+    zip.start_file(".obfuscated-entries", FileOptions::default())
+        .unwrap();
+    zip.finish().unwrap();
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("entry"));
+    fs::remove_file(&zip_path).unwrap();
+}
+
+#[test]
+fn obfuscation_is_detected_from_the_first_bytes() {
+    let file_name = format!("obfuscation-detect-{}", std::process::id());
+    let plain_path = write_bundle(&file_name, &[("a.txt", "plain")]);
+    let plain = fs::read(&plain_path).unwrap();
+    let obfuscated = plain.iter().map(|x| x ^ 0b0101_0101).collect::<Vec<_>>();
+
+    // An obfuscated bundle with a `.zip` extension
+    fs::write(&plain_path, &obfuscated).unwrap();
+    assert!(FileBundleSource::detect(&plain_path).obfuscated());
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let mislabeled_zip_io = asset_io(&build_app(config.clone(), TaskPool::new()));
+    assert_eq!(load(&mislabeled_zip_io, "a.txt").as_deref(), Some("plain"));
+    fs::remove_file(&plain_path).unwrap();
+
+    // A plain bundle with a `.bin` extension
+    let bin_path = plain_path.with_extension("bin");
+    fs::write(&bin_path, &plain).unwrap();
+    assert!(!FileBundleSource::detect(&bin_path).obfuscated());
+    let mislabeled_bin_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(load(&mislabeled_bin_io, "a.txt").as_deref(), Some("plain"));
+
+    // Files that don't look like zips fall back to their extension
+    fs::write(&bin_path, "not a zip").unwrap();
+    assert!(FileBundleSource::detect(&bin_path).obfuscated());
+    assert!(!FileBundleSource::detect(&plain_path).obfuscated());
+    fs::remove_file(&bin_path).unwrap();
+}
+
+#[test]
+fn obfuscated_v2_bundles_are_read_whatever_their_extension() {
+    let file_name = format!("obfuscation-v2-{}", std::process::id());
+    let zip_path = write_bundle(&file_name, &[("a.txt", "obfuscated v2")]);
+
+    // A v2 header with the obfuscation flag set, followed by the obfuscated zip data
+    let mut bundle = b"BAZB".to_vec();
+    bundle.extend_from_slice(&2u16.to_le_bytes());
+    bundle.extend_from_slice(&1u16.to_le_bytes());
+    bundle.extend_from_slice(&20u32.to_le_bytes());
+    bundle.extend_from_slice(&0u64.to_le_bytes());
+    bundle.extend(fs::read(&zip_path).unwrap().iter().map(|x| x ^ 0b0101_0101));
+    fs::write(&zip_path, bundle).unwrap();
+
+    let source = FileBundleSource::detect(&zip_path);
+    assert!(source.obfuscated());
+    assert_eq!(source.data_offset(), 20);
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("obfuscated v2"));
+
+    fs::remove_file(&zip_path).unwrap();
+}
+
+#[test]
+fn entries_are_deobfuscated_with_their_own_keys() {
+    let dir = std::env::temp_dir().join(format!("obfuscation-keys-{}", std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    let contents = "0123456789abcdefghijklmnopqrstuvwxyz";
+    for name in ["a.txt", "sub/b.txt"].iter() {
+        let path = dir.join("assets").join(name);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &dir.join("assets"),
+        &bundle,
+        &BundleOptions {
+            obfuscate_entries: true,
+            compression: CompressionMethod::Stored,
+            ..Default::default()
+        },
+    );
+
+    let mut sources = BundleSources::default();
+    sources.add("keyed", MemoryBundleSource::new(fs::read(&bundle).unwrap()));
+    let asset_io = asset_io(&build_app_with_sources(
+        AssetIoZipConfig::default(),
+        TaskPool::new(),
+        sources,
+    ));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some(contents));
+    assert_eq!(load(&asset_io, "sub/b.txt").as_deref(), Some(contents));
+
+    // Streamed entries are decoded from wherever they are read
+    let mut reader = asset_io.open_entry("sub/b.txt").unwrap();
+    let mut rest = String::new();
+    reader.seek(SeekFrom::Start(11)).unwrap();
+    reader.read_to_string(&mut rest).unwrap();
+    assert_eq!(rest, &contents[11..]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,39 @@
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::{bundle, load};
+
+#[test]
+fn overlay_assets_override_the_bundles() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_overlay-{}", std::process::id()));
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+    let config = AssetIoZipConfig {
+        overlay_bundle: Some(dir.join("user_assets.zip")),
+        ..Default::default()
+    };
+
+    let zip_io = ZipAssetIo::new(config.clone(), sources.clone(), None, TaskPool::new());
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+
+    zip_io.write_overlay_asset("levels/1.txt", b"user").unwrap();
+    zip_io.write_overlay_asset("levels/2.txt", b"new").unwrap();
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("user"));
+    assert_eq!(load(&zip_io, "levels/2.txt").as_deref(), Some("new"));
+
+    zip_io
+        .write_overlay_asset("levels/1.txt", b"user 2")
+        .unwrap();
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("user 2"));
+
+    // The overlay is kept between runs
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("user 2"));
+    assert_eq!(zip_io.mounted_bundles()[0].name, "user_assets");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
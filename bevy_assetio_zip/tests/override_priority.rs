@@ -0,0 +1,88 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy::{asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, BundleSources, FallbackAssetIo, MemoryAssetIo, ZipAssetIo,
+};
+
+use common::{asset_io, build_app_with_sources, bundle, load};
+
+#[test]
+fn bundles_override_in_priority_order() {
+    let mut sources = BundleSources::default();
+    sources.add("dlc", bundle(&[("levels/1.txt", "dlc")])).add(
+        "base",
+        bundle(&[("levels/1.txt", "base"), ("levels/2.txt", "base")]),
+    );
+
+    let config = AssetIoZipConfig {
+        fallback: FallbackAssetIo::Memory(
+            vec![("levels/2.txt", "loose"), ("levels/3.txt", "loose")]
+                .into_iter()
+                .collect::<MemoryAssetIo>(),
+        ),
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app_with_sources(config, TaskPool::new(), sources));
+
+    assert_eq!(load(&asset_io, "levels/1.txt").as_deref(), Some("dlc"));
+    assert_eq!(load(&asset_io, "levels/2.txt").as_deref(), Some("base"));
+    assert_eq!(load(&asset_io, "levels/3.txt").as_deref(), Some("loose"));
+    assert_eq!(load(&asset_io, "levels/4.txt"), None);
+
+    let chain = asset_io
+        .override_chain("levels/1.txt")
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(chain, vec!["dlc", "base"]);
+}
+
+#[test]
+fn zip_asset_io_has_no_fallback() {
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&[("levels/1.txt", "base")]));
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+    assert_eq!(load(&zip_io, "levels/2.txt"), None);
+    assert!(zip_io.is_directory(Path::new("levels")));
+    assert!(!zip_io.is_directory(Path::new("textures")));
+}
+
+#[test]
+fn user_overrides_are_loaded_before_the_bundles() {
+    let dir =
+        std::env::temp_dir().join(format!("bevy_assetio_zip_overrides-{}", std::process::id()));
+    fs::create_dir_all(dir.join("levels")).unwrap();
+    fs::write(dir.join("levels/1.txt"), "override").unwrap();
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[("levels/1.txt", "base"), ("levels/2.txt", "base")]),
+    );
+    let mut config = AssetIoZipConfig {
+        override_dir: Some(dir.clone()),
+        ..Default::default()
+    };
+
+    let zip_io = ZipAssetIo::new(config.clone(), sources.clone(), None, TaskPool::new());
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("override"));
+    assert_eq!(load(&zip_io, "levels/2.txt").as_deref(), Some("base"));
+
+    config.use_override_dir = false;
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn asset_io_is_send_and_sync() {
+    fn assert_send_sync<T: Send + Sync>() {}
+    assert_send_sync::<ZipAssetIo>();
+    assert_send_sync::<AssetIoZip>();
+}
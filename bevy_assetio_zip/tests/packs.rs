@@ -0,0 +1,92 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy::{
+    asset::{AssetIo, AssetIoError},
+    tasks::TaskPool,
+};
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::{asset_io, build_app, bundle, load, write_bundle};
+
+#[test]
+fn packed_files_are_read_from_their_blocks() {
+    let file_name = format!("packs-{}", std::process::id());
+    let index = "0\t0\t5\tpacked/hello.txt\n\
+                 0\t5\t5\tpacked/world.txt\n\
+                 1\t0\t3\tpacked/other.txt\n\
+                 1\t2\t9\tpacked/truncated.txt\n\
+                 not an index line\n";
+    let bundle = write_bundle(
+        &file_name,
+        &[
+            ("big.txt", "not packed"),
+            (".packs/0", "helloworld"),
+            (".packs/1", "abc"),
+            (".packs/index", index),
+        ],
+    );
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app(config, TaskPool::new()));
+
+    assert_eq!(load(&asset_io, "big.txt").as_deref(), Some("not packed"));
+    assert_eq!(
+        load(&asset_io, "packed/hello.txt").as_deref(),
+        Some("hello")
+    );
+    assert_eq!(
+        load(&asset_io, "packed/world.txt").as_deref(),
+        Some("world")
+    );
+    assert_eq!(load(&asset_io, "packed/other.txt").as_deref(), Some("abc"));
+    assert_eq!(load(&asset_io, "packed/missing.txt"), None);
+
+    // Index entries that point past the end of their block are an error, not a missing file
+    let truncated = TaskPool::new()
+        .scope(|scope| scope.spawn(asset_io.load_path(Path::new("packed/truncated.txt"))))
+        .pop()
+        .unwrap();
+    assert!(matches!(truncated, Err(AssetIoError::Io(_))));
+
+    fs::remove_file(&bundle).unwrap();
+}
+
+#[test]
+fn packed_blocks_are_read_again_after_they_are_dropped() {
+    // Six blocks with two files each, more than the number of blocks kept in memory
+    let mut files = Vec::new();
+    let mut index = String::new();
+    for block in 0..6 {
+        files.push((format!(".packs/{}", block), format!("{}a{}b", block, block)));
+        index.push_str(&format!("{}\t0\t2\tpacked/{}a.txt\n", block, block));
+        index.push_str(&format!("{}\t2\t2\tpacked/{}b.txt\n", block, block));
+    }
+    files.push((".packs/index".to_owned(), index));
+    let files = files
+        .iter()
+        .map(|(path, contents)| (path.as_str(), contents.as_str()))
+        .collect::<Vec<_>>();
+
+    let mut sources = BundleSources::default();
+    sources.add("base", bundle(&files));
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+
+    // Start reading every block so that the first ones are dropped before they are read fully
+    for block in 0..6 {
+        let path = format!("packed/{}a.txt", block);
+        assert_eq!(load(&zip_io, &path), Some(format!("{}a", block)));
+    }
+    for block in 0..6 {
+        let path = format!("packed/{}b.txt", block);
+        assert_eq!(load(&zip_io, &path), Some(format!("{}b", block)));
+    }
+
+    // Fully read blocks are decompressed again when one of their files is loaded again
+    assert_eq!(load(&zip_io, "packed/5a.txt").as_deref(), Some("5a"));
+    assert_eq!(load(&zip_io, "packed/5b.txt").as_deref(), Some("5b"));
+}
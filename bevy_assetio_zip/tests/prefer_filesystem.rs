@@ -0,0 +1,51 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, BundleSources, FallbackAssetIo, MemoryAssetIo, ZipAssetIo,
+};
+
+use common::{bundle, load};
+
+#[test]
+fn loose_assets_shadow_the_bundles_when_the_filesystem_is_preferred() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "assets",
+        bundle(&[("levels/1.txt", "bundled"), ("levels/2.txt", "bundled 2")]),
+    );
+    let asset_io = |prefer_filesystem| {
+        let config = AssetIoZipConfig {
+            prefer_filesystem,
+            ..Default::default()
+        };
+        let zip_io = ZipAssetIo::new(config, sources.clone(), None, TaskPool::new());
+        let mut memory_io = MemoryAssetIo::new();
+        memory_io.insert("levels/1.txt", "loose");
+        AssetIoZip::new(zip_io, Box::new(memory_io))
+    };
+
+    let preferred = asset_io(true);
+    assert_eq!(load(&preferred, "levels/1.txt").as_deref(), Some("loose"));
+    assert_eq!(
+        load(&preferred, "levels/2.txt").as_deref(),
+        Some("bundled 2")
+    );
+    assert_eq!(load(&preferred, "levels/3.txt"), None);
+
+    let bundled = asset_io(false);
+    assert_eq!(load(&bundled, "levels/1.txt").as_deref(), Some("bundled"));
+}
+
+#[test]
+fn preferring_the_filesystem_without_a_fallback_is_reported() {
+    let config = AssetIoZipConfig {
+        prefer_filesystem: true,
+        fallback: FallbackAssetIo::Noop,
+        ..Default::default()
+    };
+    let problems = config.validate();
+
+    assert_eq!(problems.len(), 1, "{:#?}", problems);
+    assert!(problems[0].contains("`prefer_filesystem` is set"));
+}
@@ -0,0 +1,96 @@
+mod common;
+
+use std::{
+    fs,
+    sync::mpsc,
+    time::{Duration, Instant},
+};
+
+use bevy::tasks::{TaskPool, TaskPoolBuilder};
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::{asset_io, build_app, bundle, load, write_bundle};
+
+#[test]
+fn prefetched_assets_are_served_from_memory() {
+    let file_name = format!("prefetch-{}", std::process::id());
+    let bundle = write_bundle(&file_name, &[("a.txt", "a"), ("b.txt", "b")]);
+
+    // A single thread runs the prefetch before any task spawned after it
+    let task_pool = TaskPoolBuilder::new().num_threads(1).build();
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        // Don't keep the bundle open, so that it can't be read once it is removed
+        max_concurrent_reads: Some(0),
+        ..Default::default()
+    };
+    let builder = build_app(config, task_pool.clone());
+    let asset_io = asset_io(&builder);
+
+    asset_io.prefetch(vec!["a.txt"]);
+    let (sender, receiver) = mpsc::channel();
+    task_pool
+        .spawn(async move { sender.send(()).unwrap() })
+        .detach();
+    receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    // Only the prefetched asset can still be loaded once the bundle is gone
+    fs::remove_file(&bundle).unwrap();
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("a"));
+    assert_eq!(load(&asset_io, "b.txt"), None);
+}
+
+/// Wait until the prefetched assets of the given asset IO take up the given number of bytes
+fn wait_for_prefetched_bytes(zip_io: &ZipAssetIo, bytes: u64) {
+    let start = Instant::now();
+    while zip_io.prefetched_bytes() != bytes {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "Prefetching timed out with {} bytes prefetched",
+            zip_io.prefetched_bytes()
+        );
+        std::thread::sleep(Duration::from_millis(1));
+    }
+}
+
+#[test]
+fn prefetched_assets_are_bounded_and_can_be_evicted() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("a.txt", "aaaa"),
+            ("b.txt", "bbbb"),
+            ("c.txt", "cccccc"),
+            ("big.txt", "bbbbbbbbbbb"),
+        ]),
+    );
+    let config = AssetIoZipConfig {
+        max_prefetched_bytes: Some(10),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+
+    // Assets larger than the limit aren't kept
+    zip_io.prefetch(vec!["big.txt", "a.txt"]);
+    wait_for_prefetched_bytes(&zip_io, 4);
+    zip_io.prefetch(vec!["b.txt"]);
+    wait_for_prefetched_bytes(&zip_io, 8);
+
+    // The oldest asset is dropped to make room for a new one
+    zip_io.prefetch(vec!["c.txt"]);
+    wait_for_prefetched_bytes(&zip_io, 10);
+    assert_eq!(load(&zip_io, "b.txt").as_deref(), Some("bbbb"));
+    assert_eq!(zip_io.prefetched_bytes(), 6);
+
+    zip_io.prefetch(vec!["a.txt"]);
+    wait_for_prefetched_bytes(&zip_io, 10);
+    zip_io.evict_prefetched(vec!["c.txt"]);
+    assert_eq!(zip_io.prefetched_bytes(), 4);
+    zip_io.clear_prefetched();
+    assert_eq!(zip_io.prefetched_bytes(), 0);
+
+    // Evicted assets are read from the bundles again
+    assert_eq!(load(&zip_io, "a.txt").as_deref(), Some("aaaa"));
+    assert_eq!(load(&zip_io, "c.txt").as_deref(), Some("cccccc"));
+}
@@ -0,0 +1,90 @@
+mod common;
+
+use std::{path::Path, sync::mpsc, time::Duration};
+
+use bevy::{
+    app::Events,
+    prelude::*,
+    tasks::{IoTaskPool, TaskPoolBuilder},
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin, AssetLoadProgress, BundleSources, LoadPriority,
+};
+
+use common::{bundle, load};
+
+#[test]
+fn prefetches_are_read_highest_priority_first() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("lods/1.txt", "far"),
+            ("lods/2.txt", "far"),
+            ("props.txt", "near"),
+            ("hero.txt", "hero"),
+        ]),
+    );
+    let config = AssetIoZipConfig {
+        progress_event_size: Some(0),
+        ..Default::default()
+    };
+    let pool = TaskPoolBuilder::new().num_threads(1).build();
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(pool.clone()))
+        .insert_resource(config)
+        .insert_resource(sources);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(pool.clone()))
+        .add_resource(config)
+        .add_resource(sources);
+    builder.add_plugin(AssetIoZipPlugin);
+    let asset_io = (*builder.resources().get::<AssetIoZip>().unwrap()).clone();
+
+    // Keep the only IO thread busy until every prefetch has been queued
+    let (release, blocked) = mpsc::channel::<()>();
+    pool.spawn(async move { blocked.recv().ok() }).detach();
+    asset_io.prefetch_with_priority(vec!["lods/1.txt", "lods/2.txt"], LoadPriority::Background);
+    asset_io.prefetch(vec!["props.txt"]);
+    asset_io.prefetch_with_priority(vec!["hero.txt"], LoadPriority::Critical);
+    release.send(()).unwrap();
+
+    let mut reader = builder
+        .resources()
+        .get::<Events<AssetLoadProgress>>()
+        .unwrap()
+        .get_reader();
+    let mut order = Vec::new();
+    for _ in 0..500 {
+        builder.app.update();
+        let events = builder
+            .resources()
+            .get::<Events<AssetLoadProgress>>()
+            .unwrap();
+        order.extend(
+            reader
+                .iter(&events)
+                .filter(|x| x.bytes == x.total)
+                .map(|x| x.path.clone()),
+        );
+        if order.len() == 4 {
+            break;
+        }
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert_eq!(
+        order,
+        [
+            Path::new("hero.txt"),
+            Path::new("props.txt"),
+            Path::new("lods/1.txt"),
+            Path::new("lods/2.txt")
+        ]
+    );
+    assert_eq!(load(&asset_io, "hero.txt").as_deref(), Some("hero"));
+    assert_eq!(load(&asset_io, "lods/2.txt").as_deref(), Some("far"));
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::path::Path;
+
+use bevy::{
+    app::Events,
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin, AssetLoadProgress, BundleSources,
+};
+
+use common::{bundle, load};
+
+#[test]
+fn large_assets_report_progress() {
+    let level = "level ".repeat(500_000);
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[("levels/1.txt", &level), ("levels/2.txt", "small")]),
+    );
+    let config = AssetIoZipConfig {
+        progress_event_size: Some(1024 * 1024),
+        ..Default::default()
+    };
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(config)
+        .insert_resource(sources);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(TaskPool::new()))
+        .add_resource(config)
+        .add_resource(sources);
+    builder.add_plugin(AssetIoZipPlugin);
+
+    let asset_io = (*builder.resources().get::<AssetIoZip>().unwrap()).clone();
+    assert_eq!(
+        load(&asset_io, "levels/1.txt").map(|x| x.len()),
+        Some(level.len())
+    );
+    assert_eq!(load(&asset_io, "levels/2.txt").as_deref(), Some("small"));
+    builder.app.update();
+
+    let events = builder
+        .resources()
+        .get::<Events<AssetLoadProgress>>()
+        .unwrap();
+    let progress = events
+        .get_reader()
+        .iter(&events)
+        .cloned()
+        .collect::<Vec<_>>();
+    assert!(progress.iter().all(|x| x.path == Path::new("levels/1.txt")));
+    assert!(progress.windows(2).all(|x| x[0].bytes <= x[1].bytes));
+    let total = level.len() as u64;
+    assert_eq!(
+        progress.last().map(|x| (x.bytes, x.total)),
+        Some((total, total))
+    );
+}
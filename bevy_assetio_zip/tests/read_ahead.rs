@@ -0,0 +1,32 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, ZipAssetIo};
+
+use common::{bundle, load};
+
+#[test]
+fn read_ahead_serves_adjacent_entries() {
+    let large = "x".repeat(3000);
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("levels/1.txt", "one"),
+            ("levels/2.txt", &large),
+            ("levels/3.txt", "three"),
+        ]),
+    );
+    let config = AssetIoZipConfig {
+        read_ahead: 1024,
+        max_concurrent_reads: Some(2),
+        ..Default::default()
+    };
+
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    for _ in 0..2 {
+        assert_eq!(load(&zip_io, "levels/3.txt").as_deref(), Some("three"));
+        assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("one"));
+        assert_eq!(load(&zip_io, "levels/2.txt"), Some(large.clone()));
+    }
+}
@@ -0,0 +1,62 @@
+mod common;
+
+use std::{
+    fs,
+    io::{Cursor, Write},
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleRegion, BundleSources, ZipAssetIo};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::load;
+
+#[test]
+fn bundles_are_read_from_a_region_of_a_larger_file() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_region-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    zip.start_file("levels/1.txt", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"appended").unwrap();
+    let bundle = zip.finish().unwrap().into_inner();
+
+    // The bundle is appended to an executable and followed by more data
+    let prefix = b"executable code";
+    let mut file = prefix.to_vec();
+    file.extend(&bundle);
+    file.extend(b"trailing data");
+    fs::write(dir.join("game.exe"), &file).unwrap();
+
+    let region = BundleRegion {
+        offset: prefix.len() as u64,
+        length: bundle.len() as u64,
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(dir.join("game.exe")),
+        bundle_region: Some(region),
+        ..Default::default()
+    };
+    assert!(config.validate().is_empty(), "{:#?}", config.validate());
+
+    let zip_io = ZipAssetIo::new(
+        config.clone(),
+        BundleSources::default(),
+        None,
+        TaskPool::new(),
+    );
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("appended"));
+
+    let config = AssetIoZipConfig {
+        bundle_region: Some(BundleRegion {
+            length: file.len() as u64,
+            ..region
+        }),
+        ..config
+    };
+    let problems = config.validate();
+    assert_eq!(problems.len(), 1, "{:#?}", problems);
+    assert!(problems[0].contains("`bundle_region` ends at byte"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,69 @@
+mod common;
+
+use std::{
+    sync::atomic::{AtomicUsize, Ordering},
+    time::Duration,
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZipConfig, BundleSource, BundleSources, MemoryBundleSource, ReadSeekSend, RetryPolicy,
+    ZipAssetIo,
+};
+
+use common::{bundle, load};
+
+/// A bundle source that fails to open a number of times before it succeeds, like a bundle on a
+/// flaky network drive
+struct FlakySource {
+    source: MemoryBundleSource,
+    failures: AtomicUsize,
+}
+
+impl BundleSource for FlakySource {
+    fn open(&self) -> std::io::Result<Box<dyn ReadSeekSend>> {
+        let remaining = self.failures.load(Ordering::SeqCst);
+        if remaining > 0 {
+            self.failures.store(remaining - 1, Ordering::SeqCst);
+            return Err(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "network drive timed out",
+            ));
+        }
+        self.source.open()
+    }
+}
+
+#[test]
+fn transient_open_errors_are_retried() {
+    let retry = RetryPolicy {
+        attempts: 3,
+        backoff: Duration::from_millis(1),
+        backoff_multiplier: 2,
+    };
+    let mount = |failures, io_retry| {
+        let mut sources = BundleSources::default();
+        sources.add(
+            "flaky",
+            FlakySource {
+                source: bundle(&[("levels/1.txt", "flaky")]),
+                failures: AtomicUsize::new(failures),
+            },
+        );
+        let config = AssetIoZipConfig {
+            io_retry,
+            ..Default::default()
+        };
+        ZipAssetIo::new(config, sources, None, TaskPool::new())
+    };
+
+    let zip_io = mount(2, retry);
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("flaky"));
+
+    // The bundle is dropped when it still can't be opened after the last attempt
+    let zip_io = mount(3, retry);
+    assert!(zip_io.mounted_bundles().is_empty());
+
+    let zip_io = mount(1, RetryPolicy::none());
+    assert!(zip_io.mounted_bundles().is_empty());
+}
@@ -0,0 +1,49 @@
+use std::path::Path;
+
+use bevy_assetio_zip::AssetIoZipConfig;
+
+#[test]
+fn runtime_config_overrides_the_config() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_runtime-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let path = dir.join(bevy_assetio_zip::RUNTIME_CONFIG_FILE);
+    let mut config = AssetIoZipConfig::default();
+
+    assert_eq!(config.apply_runtime_config(&path), Ok(false));
+
+    std::fs::write(
+        &path,
+        "bundle-path = \"packs/assets.zip\"\nmod-dirs = [\"mods\"]\nmax-concurrent-reads = 2",
+    )
+    .unwrap();
+    assert_eq!(config.apply_runtime_config(&path), Ok(true));
+    assert_eq!(config.bundle_path, Some(dir.join("packs/assets.zip")));
+    assert_eq!(config.mod_dirs, vec![dir.join("mods")]);
+    assert_eq!(config.file_names, vec!["assets".to_owned()]);
+    assert_eq!(config.max_concurrent_reads, Some(2));
+
+    // Only the variants of the platform that the game runs on are used
+    std::fs::write(
+        &path,
+        format!(
+            "[variants.all]\n\"a.txt\" = \"a.all.txt\"\n\
+             [variants.{}]\n\"b.txt\" = \"b.platform.txt\"\n\
+             [variants.not-a-platform]\n\"c.txt\" = \"c.other.txt\"\n",
+            std::env::consts::OS
+        ),
+    )
+    .unwrap();
+    assert_eq!(config.apply_runtime_config(&path), Ok(true));
+    let variants = &config.asset_variants;
+    assert_eq!(variants.resolve(Path::new("a.txt")), Path::new("a.all.txt"));
+    assert_eq!(
+        variants.resolve(Path::new("b.txt")),
+        Path::new("b.platform.txt")
+    );
+    assert_eq!(variants.resolve(Path::new("c.txt")), Path::new("c.txt"));
+
+    std::fs::write(&path, "bundle-name = \"assets\"").unwrap();
+    assert!(config.apply_runtime_config(&path).is_err());
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,77 @@
+mod common;
+
+use std::fs;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, MemoryBundleSource};
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{asset_io, build_app_with_sources, load};
+
+/// Bundle the given files with solid compression in frames of the given size
+fn solid_bundle(name: &str, files: &[(&str, &str)], options: BundleOptions) -> Vec<u8> {
+    let dir =
+        std::env::temp_dir().join(format!("bevy_assetio_zip_{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    for (path, contents) in files {
+        let path = dir.join("assets").join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(
+        &dir.join("assets"),
+        &bundle,
+        &BundleOptions {
+            solid: true,
+            ..options
+        },
+    );
+    let bytes = fs::read(&bundle).unwrap();
+    fs::remove_dir_all(&dir).unwrap();
+    bytes
+}
+
+#[test]
+fn assets_are_read_from_the_frames_of_solid_bundles() {
+    let levels = (0..20)
+        .map(|i| {
+            (
+                format!("levels/{}.txt", i),
+                format!("level {} ", i).repeat(20),
+            )
+        })
+        .collect::<Vec<_>>();
+    let files = levels
+        .iter()
+        .map(|(path, contents)| (path.as_str(), contents.as_str()))
+        .collect::<Vec<_>>();
+
+    // Small frames spread the assets, and the zip central directory, over many frames
+    for obfuscate in [false, true].iter().copied() {
+        let bundle = solid_bundle(
+            "solid",
+            &files,
+            BundleOptions {
+                solid_frame_size: 100,
+                obfuscate,
+                ..Default::default()
+            },
+        );
+
+        let mut sources = BundleSources::default();
+        sources.add("solid", MemoryBundleSource::new(bundle));
+        let asset_io = asset_io(&build_app_with_sources(
+            AssetIoZipConfig::default(),
+            TaskPool::new(),
+            sources,
+        ));
+
+        // Read the assets out of order so that earlier frames have to be decompressed again
+        for (path, contents) in levels.iter().rev() {
+            assert_eq!(load(&asset_io, path).as_ref(), Some(contents));
+        }
+        assert_eq!(load(&asset_io, "levels/missing.txt"), None);
+    }
+}
@@ -0,0 +1,24 @@
+mod common;
+
+use bevy_assetio_zip::{AssetLoadEvent, AssetSource, AssetSourceLog};
+
+#[test]
+fn asset_sources_are_logged_by_path() {
+    let mut log = AssetSourceLog::default();
+    for (path, source) in &[
+        ("b.png", AssetSource::Fallback),
+        ("a.png", AssetSource::Bundle("base".into())),
+        ("b.png", AssetSource::Override),
+    ] {
+        log.record(&AssetLoadEvent {
+            path: (*path).into(),
+            source: source.clone(),
+            bytes: 100,
+            micros: 10,
+        });
+    }
+
+    assert_eq!(log.get("b.png"), Some(&AssetSource::Override));
+    assert_eq!(log.get("c.png"), None);
+    assert_eq!(log.to_string(), "a.png: bundle base\nb.png: override dir\n");
+}
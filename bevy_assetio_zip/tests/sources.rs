@@ -0,0 +1,112 @@
+mod common;
+
+use std::{
+    fs,
+    io::{self, Cursor},
+};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZipConfig, BundleFileSharing, BundleSource, BundleSources, ReadSeekSend, ZipAssetIo,
+};
+
+use common::{asset_io, build_app_with_sources, bundle, load, write_bundle, write_zip, zip_bytes};
+
+/// A source that serves a copy of the same bundle bytes every time it is opened
+struct VecSource(Vec<u8>);
+
+impl BundleSource for VecSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        Ok(Box::new(Cursor::new(self.0.clone())))
+    }
+}
+
+#[test]
+fn custom_sources_are_mounted() {
+    let mut sources = BundleSources::default();
+    sources.add("memory", bundle(&[("memory.txt", "from memory")]));
+
+    let config = AssetIoZipConfig {
+        file_names: vec![format!("sources-missing-{}", std::process::id())],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app_with_sources(config, TaskPool::new(), sources));
+
+    assert_eq!(
+        load(&asset_io, "memory.txt").as_deref(),
+        Some("from memory")
+    );
+    assert_eq!(load(&asset_io, "missing.txt"), None);
+}
+
+#[test]
+fn sources_are_mounted_in_order_before_the_asset_bundle() {
+    let file_name = format!("sources-{}", std::process::id());
+    let bundle_path = write_bundle(
+        &file_name,
+        &[
+            ("a.txt", "bundle a"),
+            ("b.txt", "bundle b"),
+            ("c.txt", "bundle c"),
+        ],
+    );
+
+    let second = zip_bytes(&[("b.txt", "second b"), ("c.txt", "second c")]);
+    let mut sources = BundleSources::default();
+    sources
+        .add("first", bundle(&[("c.txt", "first c")]))
+        .add("second", VecSource(second));
+
+    let config = AssetIoZipConfig {
+        file_names: vec![file_name],
+        ..Default::default()
+    };
+    let asset_io = asset_io(&build_app_with_sources(config, TaskPool::new(), sources));
+
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("bundle a"));
+    assert_eq!(load(&asset_io, "b.txt").as_deref(), Some("second b"));
+    assert_eq!(load(&asset_io, "c.txt").as_deref(), Some("first c"));
+
+    fs::remove_file(&bundle_path).unwrap();
+}
+
+#[test]
+fn bundle_files_can_be_replaced_while_they_are_mounted() {
+    let file_name = format!("sources-sharing-{}", std::process::id());
+    let path = write_bundle(&file_name, &[("a.txt", "old")]);
+    let mount = |bundle_sharing| {
+        let config = AssetIoZipConfig {
+            file_names: vec![file_name.clone()],
+            bundle_sharing,
+            ..Default::default()
+        };
+        ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new())
+    };
+    let replace = || {
+        // Patchers write the new bundle next to the old one and move it over the old one
+        let new_path = path.with_extension("zip.new");
+        write_zip(&new_path, &[("a.txt", "new")]);
+        fs::rename(&new_path, &path)
+    };
+
+    // The mounted bundle keeps its file open
+    let zip_io = mount(BundleFileSharing::AllowReplace);
+    assert_eq!(load(&zip_io, "a.txt").as_deref(), Some("old"));
+    replace().unwrap();
+    assert_eq!(load(&zip_io, "a.txt").as_deref(), Some("old"));
+    assert_eq!(
+        load(&mount(BundleFileSharing::AllowReplace), "a.txt").as_deref(),
+        Some("new")
+    );
+
+    // Only Windows can keep other processes from replacing open files
+    #[cfg(windows)]
+    {
+        let zip_io = mount(BundleFileSharing::ReadOnly);
+        assert_eq!(load(&zip_io, "a.txt").as_deref(), Some("new"));
+        assert!(replace().is_err());
+        fs::remove_file(path.with_extension("zip.new")).unwrap();
+    }
+
+    fs::remove_file(&path).unwrap();
+}
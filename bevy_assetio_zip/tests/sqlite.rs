@@ -0,0 +1,179 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{
+    AssetIoZipConfig, BundleSources, FileBundleSource, MemoryBundleSource, ZipAssetIo,
+};
+use rusqlite::{params, Connection};
+
+use common::load;
+
+/// Get a fresh directory for the files of a test
+fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_sqlite-{}-{}",
+        name,
+        std::process::id()
+    ));
+    let _ = fs::remove_dir_all(&dir);
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write an SQLite bundle containing the given assets with the given flags
+fn write_sqlite_bundle(path: &Path, assets: &[(&str, &[u8], i64)]) {
+    let connection = Connection::open(path).unwrap();
+    connection
+        .execute_batch(
+            "CREATE TABLE assets (
+                path TEXT PRIMARY KEY,
+                blob BLOB NOT NULL,
+                flags INTEGER NOT NULL DEFAULT 0
+            )",
+        )
+        .unwrap();
+    for (asset_path, data, flags) in assets {
+        connection
+            .execute(
+                "INSERT INTO assets (flags, blob, path) VALUES (?, ?, ?)",
+                params![flags, data, asset_path],
+            )
+            .unwrap();
+    }
+}
+
+/// Data that is spread across several pages of the database
+fn large_asset() -> Vec<u8> {
+    (0..20000).map(|i| (i * 7 % 251) as u8).collect()
+}
+
+fn sqlite_zip_io(path: &Path) -> ZipAssetIo {
+    let mut sources = BundleSources::default();
+    sources.add("base", FileBundleSource::new(path, false));
+    ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new())
+}
+
+#[test]
+fn sqlite_bundles_are_read_like_zip_bundles() {
+    let dir = test_dir("read");
+    let bundle = dir.join("assets.sqlite");
+    let large = large_asset();
+    write_sqlite_bundle(
+        &bundle,
+        &[
+            ("levels/1.txt", b"sqlite", 0),
+            ("large.bin", &large, 0),
+            ("flagged.txt", b"flagged", 1),
+        ],
+    );
+    let zip_io = sqlite_zip_io(&bundle);
+
+    assert_eq!(zip_io.mounted_bundles()[0].name, "base");
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("sqlite"));
+    assert_eq!(load(&zip_io, "levels/2.txt"), None);
+    assert!(zip_io.is_directory(Path::new("levels")));
+
+    let data = TaskPool::new()
+        .scope(|scope| scope.spawn(zip_io.load_path(Path::new("large.bin"))))
+        .pop()
+        .unwrap()
+        .unwrap();
+    assert_eq!(data, large);
+
+    // Assets with flags that we don't know are listed, but can't be read
+    assert_eq!(zip_io.override_chain("flagged.txt").len(), 1);
+    let flagged = TaskPool::new()
+        .scope(|scope| scope.spawn(zip_io.load_path(Path::new("flagged.txt"))))
+        .pop()
+        .unwrap();
+    assert!(flagged.is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sqlite_assets_are_passed_through_uncompressed_but_not_streamed() {
+    let dir = test_dir("raw");
+    let bundle = dir.join("assets.sqlite");
+    write_sqlite_bundle(&bundle, &[("large.bin", &large_asset(), 0)]);
+    let zip_io = sqlite_zip_io(&bundle);
+
+    assert!(zip_io.open_entry("large.bin").is_err());
+    let raw = zip_io.raw_entry("large.bin").unwrap();
+    assert_eq!(raw.decompress().unwrap(), large_asset());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sqlite_bundles_are_found_by_their_extension() {
+    let dir = test_dir("search");
+    fs::create_dir_all(dir.join("mods")).unwrap();
+    write_sqlite_bundle(
+        &dir.join("sqlite-test-assets.sqlite"),
+        &[("levels/1.txt", b"base", 0)],
+    );
+    write_sqlite_bundle(&dir.join("mods/dlc.sqlite"), &[("levels/2.txt", b"dlc", 0)]);
+    assert!(AssetIoZipConfig {
+        bundle_path: Some(dir.join("mods/dlc.sqlite")),
+        ..Default::default()
+    }
+    .validate()
+    .is_empty());
+
+    let config = AssetIoZipConfig {
+        file_names: vec!["sqlite-test-assets".into()],
+        search_asset_dir: true,
+        mod_dirs: vec![dir.join("mods")],
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(
+        config,
+        BundleSources::default(),
+        Some(dir.clone()),
+        TaskPool::new(),
+    );
+    let mounted = zip_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| x.name)
+        .collect::<Vec<_>>();
+    assert_eq!(mounted.len(), 2, "{:?}", mounted);
+    assert!(mounted.contains(&"dlc".to_owned()));
+    assert_eq!(load(&zip_io, "levels/1.txt").as_deref(), Some("base"));
+    assert_eq!(load(&zip_io, "levels/2.txt").as_deref(), Some("dlc"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn sqlite_bundles_that_cannot_be_opened_are_not_mounted() {
+    let dir = test_dir("unmountable");
+    let bundle = dir.join("assets.sqlite");
+    write_sqlite_bundle(&bundle, &[("levels/1.txt", b"sqlite", 0)]);
+
+    // SQLite can only open databases that are files
+    let mut sources = BundleSources::default();
+    sources.add(
+        "memory",
+        MemoryBundleSource::new(fs::read(&bundle).unwrap()),
+    );
+    // A database without the assets table
+    let other = dir.join("other.sqlite");
+    Connection::open(&other)
+        .unwrap()
+        .execute_batch("CREATE TABLE other (id INTEGER PRIMARY KEY)")
+        .unwrap();
+    sources.add("other", FileBundleSource::new(&other, false));
+
+    let zip_io = ZipAssetIo::new(AssetIoZipConfig::default(), sources, None, TaskPool::new());
+    assert!(zip_io.mounted_bundles().is_empty());
+    assert_eq!(load(&zip_io, "levels/1.txt"), None);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
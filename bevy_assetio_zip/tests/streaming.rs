@@ -0,0 +1,90 @@
+mod common;
+
+use std::time::{Duration, Instant};
+
+use bevy::{
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipPlugin, BundleSources, LevelStreamer};
+
+use common::{bundle, load};
+
+#[test]
+fn streamed_groups_are_decompressed_in_the_background_within_the_frame_budget() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("levels/1.txt", "aaaa"),
+            ("levels/2.txt", "bbbb"),
+            ("levels/3.txt", "cccc"),
+            ("menu.txt", "menu"),
+            (".groups", "level\tlevels/*\n"),
+        ]),
+    );
+
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(sources);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(TaskPool::new()))
+        .add_resource(sources);
+    builder.add_plugin(AssetIoZipPlugin);
+
+    {
+        let mut streamer = builder.resources().get_mut::<LevelStreamer>().unwrap();
+        streamer.max_in_flight = 2;
+        streamer.bytes_per_frame = Some(4);
+        streamer.stream_group("level");
+        assert!(!streamer.is_done());
+    }
+    let streamed = |builder: &AppBuilder| {
+        let streamer = builder.resources().get::<LevelStreamer>().unwrap();
+        (streamer.remaining(), streamer.in_flight())
+    };
+
+    // The first frame starts as many reads as may be in flight without waiting for them
+    builder.app.update();
+    assert_eq!(streamed(&builder), (1, 2));
+
+    // Frames that collect a read have used up their budget and don't start another one
+    let start = Instant::now();
+    while streamed(&builder).1 > 0 {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "Streaming timed out"
+        );
+        builder.app.update();
+        assert_eq!(streamed(&builder).0, 1);
+    }
+    builder.app.update();
+    assert_eq!(streamed(&builder), (0, 1));
+
+    // Assets that can't be read finish streaming too
+    builder
+        .resources()
+        .get_mut::<LevelStreamer>()
+        .unwrap()
+        .stream(vec!["menu.txt", "missing.txt"]);
+    while !builder
+        .resources()
+        .get::<LevelStreamer>()
+        .unwrap()
+        .is_done()
+    {
+        assert!(
+            start.elapsed() < Duration::from_secs(5),
+            "Streaming timed out"
+        );
+        builder.app.update();
+        assert!(streamed(&builder).1 <= 2);
+    }
+
+    let asset_io = (*builder.resources().get::<AssetIoZip>().unwrap()).clone();
+    assert_eq!(load(&asset_io, "levels/2.txt").as_deref(), Some("bbbb"));
+    assert_eq!(load(&asset_io, "menu.txt").as_deref(), Some("menu"));
+}
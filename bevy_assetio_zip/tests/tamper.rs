@@ -0,0 +1,72 @@
+mod common;
+
+use std::{fs, panic::AssertUnwindSafe};
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleSources, TamperResponse, ZipAssetIo};
+
+#[test]
+fn tampered_bundles_are_detected() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_tamper-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("assets.zip"), "abc").unwrap();
+    let zip_io = |hash: &str| {
+        let config = AssetIoZipConfig {
+            bundle_path: Some(dir.join("assets.zip")),
+            expected_bundle_hash: Some(hash.into()),
+            tamper_response: TamperResponse::Error,
+            ..Default::default()
+        };
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+        }))
+    };
+
+    // The BLAKE3 hash of "abc"
+    assert!(zip_io("6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85").is_ok());
+    assert!(zip_io("af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262").is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bundle_hashes_are_cached_across_runs() {
+    let dir = std::env::temp_dir().join(format!("bevy_assetio_zip_hash-{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    fs::write(dir.join("assets.zip"), "abc").unwrap();
+    let zip_io = |hash: &str| {
+        let config = AssetIoZipConfig {
+            bundle_path: Some(dir.join("assets.zip")),
+            expected_bundle_hash: Some(hash.into()),
+            tamper_response: TamperResponse::Error,
+            hash_cache_dir: Some(dir.join("cache")),
+            ..Default::default()
+        };
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            ZipAssetIo::new(config, BundleSources::default(), None, TaskPool::new());
+        }))
+    };
+
+    // The BLAKE3 hash of "abc"
+    let hash = "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85";
+    assert!(zip_io(hash).is_ok());
+    let cache = fs::read_dir(dir.join("cache"))
+        .unwrap()
+        .next()
+        .unwrap()
+        .unwrap()
+        .path();
+    assert!(fs::read_to_string(&cache).unwrap().contains(hash));
+
+    // The bundle isn't hashed again while its size and modification time stay the same
+    let other = "af1349b9f5f9a1a6a0404dea36dcc9499bcb25c9adc112b7cc9a93cae41f3262";
+    fs::write(
+        &cache,
+        fs::read_to_string(&cache).unwrap().replace(hash, other),
+    )
+    .unwrap();
+    assert!(zip_io(other).is_ok());
+    assert!(zip_io(hash).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
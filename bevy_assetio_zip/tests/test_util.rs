@@ -0,0 +1,56 @@
+mod common;
+
+use std::collections::HashMap;
+
+use bevy_assetio_zip::test_util::{app_with_bundle, TempBundle};
+
+use common::{asset_io, load};
+
+fn files() -> HashMap<&'static str, &'static [u8]> {
+    let mut files = HashMap::new();
+    files.insert("levels/1.txt", &b"level one"[..]);
+    files.insert("levels/2.txt", &b"level two"[..]);
+    files
+}
+
+#[test]
+fn temp_bundles_are_loaded_by_the_app() {
+    let bundle = TempBundle::new(&files(), false);
+    assert_eq!(bundle.path().file_name().unwrap(), "assets.zip");
+
+    let builder = app_with_bundle(&bundle);
+    let asset_io = asset_io(&builder);
+    assert_eq!(
+        load(&asset_io, "levels/1.txt").as_deref(),
+        Some("level one")
+    );
+    assert_eq!(
+        load(&asset_io, "levels/2.txt").as_deref(),
+        Some("level two")
+    );
+    // Only the bundle is read, with no loose asset fallback
+    assert_eq!(load(&asset_io, "Cargo.toml"), None);
+}
+
+#[test]
+#[cfg(feature = "obfuscation")]
+fn obfuscated_temp_bundles_are_loaded_by_the_app() {
+    let bundle = TempBundle::new(&files(), true);
+    assert_eq!(bundle.path().file_name().unwrap(), "assets.bin");
+
+    let asset_io = asset_io(&app_with_bundle(&bundle));
+    assert_eq!(
+        load(&asset_io, "levels/1.txt").as_deref(),
+        Some("level one")
+    );
+}
+
+#[test]
+fn temp_bundles_are_deleted_when_dropped() {
+    let bundle = TempBundle::new(&files(), false);
+    let dir = bundle.path().parent().unwrap().to_owned();
+    assert!(bundle.path().is_file());
+
+    drop(bundle);
+    assert!(!dir.exists());
+}
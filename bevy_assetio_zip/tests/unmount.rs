@@ -0,0 +1,166 @@
+mod common;
+
+use std::{
+    io::{self, Cursor, Read, Seek, SeekFrom},
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc::{self, Receiver, Sender},
+        Arc, Mutex,
+    },
+    thread,
+    time::Duration,
+};
+
+use bevy::tasks::{TaskPool, TaskPoolBuilder};
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, BundleSource, BundleSources, FallbackAssetIo, MemoryAssetIo,
+    ReadSeekSend,
+};
+
+use common::{asset_io, build_app_with_sources, bundle, load, zip_bytes};
+
+fn build(sources: BundleSources, task_pool: TaskPool) -> AssetIoZip {
+    let config = AssetIoZipConfig {
+        file_names: vec![format!("unmount-missing-{}", std::process::id())],
+        fallback: FallbackAssetIo::Memory(
+            vec![("a.txt", "fallback")]
+                .into_iter()
+                .collect::<MemoryAssetIo>(),
+        ),
+        ..Default::default()
+    };
+    asset_io(&build_app_with_sources(config, task_pool, sources))
+}
+
+fn mounted(asset_io: &AssetIoZip) -> Vec<String> {
+    asset_io
+        .mounted_bundles()
+        .into_iter()
+        .map(|x| x.name)
+        .collect()
+}
+
+#[test]
+fn unmounted_bundles_are_no_longer_read() {
+    let mut sources = BundleSources::default();
+    sources
+        .add("dlc", bundle(&[("a.txt", "dlc"), ("b.txt", "dlc")]))
+        .add("base", bundle(&[("a.txt", "base")]));
+    let asset_io = build(sources, TaskPool::new());
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("dlc"));
+
+    assert!(asset_io.unmount("dlc"));
+    assert_eq!(mounted(&asset_io), vec!["base"]);
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("base"));
+    assert_eq!(load(&asset_io, "b.txt"), None);
+
+    assert!(asset_io.unmount("base"));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("fallback"));
+
+    // Unmounting an unknown bundle does nothing
+    assert!(!asset_io.unmount("base"));
+}
+
+#[test]
+fn assets_prefetched_from_unmounted_bundles_are_dropped() {
+    let mut sources = BundleSources::default();
+    sources.add("dlc", bundle(&[("a.txt", "dlc")]));
+    // A single thread runs the prefetch before any task spawned after it
+    let task_pool = TaskPoolBuilder::new().num_threads(1).build();
+    let asset_io = build(sources, task_pool.clone());
+
+    asset_io.prefetch(vec!["a.txt"]);
+    let (sender, receiver) = mpsc::channel();
+    task_pool
+        .spawn(async move { sender.send(()).unwrap() })
+        .detach();
+    receiver.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert!(asset_io.unmount("dlc"));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("fallback"));
+}
+
+/// A bundle source whose readers block on their next read while it is armed, until it is released
+struct GatedSource {
+    bytes: Vec<u8>,
+    gate: Arc<Gate>,
+    dropped: Arc<AtomicBool>,
+}
+
+struct Gate {
+    armed: AtomicBool,
+    opened: Mutex<Sender<()>>,
+    release: Mutex<Receiver<()>>,
+}
+
+struct GatedReader {
+    inner: Cursor<Vec<u8>>,
+    gate: Arc<Gate>,
+}
+
+impl Read for GatedReader {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        if self.gate.armed.swap(false, Ordering::SeqCst) {
+            self.gate.opened.lock().unwrap().send(()).unwrap();
+            self.gate.release.lock().unwrap().recv().unwrap();
+        }
+        self.inner.read(buf)
+    }
+}
+
+impl Seek for GatedReader {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        self.inner.seek(pos)
+    }
+}
+
+impl BundleSource for GatedSource {
+    fn open(&self) -> io::Result<Box<dyn ReadSeekSend>> {
+        Ok(Box::new(GatedReader {
+            inner: Cursor::new(self.bytes.clone()),
+            gate: self.gate.clone(),
+        }))
+    }
+}
+
+impl Drop for GatedSource {
+    fn drop(&mut self) {
+        self.dropped.store(true, Ordering::SeqCst);
+    }
+}
+
+#[test]
+fn reads_in_progress_finish_before_the_bundle_is_closed() {
+    let dropped = Arc::new(AtomicBool::new(false));
+    let (opened_sender, opened) = mpsc::channel();
+    let (release, release_receiver) = mpsc::channel();
+    let gate = Arc::new(Gate {
+        armed: AtomicBool::new(false),
+        opened: Mutex::new(opened_sender),
+        release: Mutex::new(release_receiver),
+    });
+    let mut sources = BundleSources::default();
+    sources.add(
+        "gated",
+        GatedSource {
+            bytes: zip_bytes(&[("a.txt", "gated")]),
+            gate: gate.clone(),
+            dropped: dropped.clone(),
+        },
+    );
+    let asset_io = build(sources, TaskPool::new());
+
+    // Start a read and unmount the bundle while the read has it open
+    gate.armed.store(true, Ordering::SeqCst);
+    let reader_io = asset_io.clone();
+    let read = thread::spawn(move || load(&reader_io, "a.txt"));
+    opened.recv_timeout(Duration::from_secs(5)).unwrap();
+
+    assert!(asset_io.unmount("gated"));
+    assert!(!dropped.load(Ordering::SeqCst));
+    assert_eq!(load(&asset_io, "a.txt").as_deref(), Some("fallback"));
+
+    release.send(()).unwrap();
+    assert_eq!(read.join().unwrap().as_deref(), Some("gated"));
+    assert!(dropped.load(Ordering::SeqCst));
+}
@@ -0,0 +1,88 @@
+mod common;
+
+#[cfg(feature = "runtime-config")]
+use std::path::Path;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{
+    AssetIoZip, AssetIoZipConfig, AssetVariants, BundleSources, MemoryAssetIo, ZipAssetIo,
+};
+
+use common::{bundle, load};
+
+#[test]
+fn asset_variants_are_loaded_in_place_of_the_asset() {
+    let mut sources = BundleSources::default();
+    sources.add(
+        "base",
+        bundle(&[
+            ("shaders/foo.wgsl", "common"),
+            ("shaders/foo.metal.wgsl", "metal"),
+            ("shaders/foo.vulkan.wgsl", "vulkan"),
+        ]),
+    );
+    let config = AssetIoZipConfig {
+        asset_variants: vec![
+            ("shaders/foo.wgsl", "shaders/foo.metal.wgsl"),
+            ("shaders/bar.wgsl", "shaders/bar.metal.wgsl"),
+        ]
+        .into_iter()
+        .collect(),
+        ..Default::default()
+    };
+    let zip_io = ZipAssetIo::new(config, sources, None, TaskPool::new());
+    let fallback = vec![("shaders/bar.metal.wgsl", "loose metal")]
+        .into_iter()
+        .collect::<MemoryAssetIo>();
+    let asset_io = AssetIoZip::new(zip_io.clone(), Box::new(fallback));
+
+    assert_eq!(
+        load(&asset_io, "shaders/foo.wgsl").as_deref(),
+        Some("metal")
+    );
+    assert_eq!(
+        load(&asset_io, "shaders/bar.wgsl").as_deref(),
+        Some("loose metal")
+    );
+
+    // Replacing the table at runtime affects the loads that start afterwards
+    let mut variants = AssetVariants::new();
+    variants.insert("shaders/foo.wgsl", "shaders/foo.vulkan.wgsl");
+    zip_io.set_asset_variants(variants);
+    assert_eq!(
+        load(&asset_io, "shaders/foo.wgsl").as_deref(),
+        Some("vulkan")
+    );
+    assert_eq!(load(&asset_io, "shaders/bar.wgsl"), None);
+
+    zip_io.set_asset_variants(AssetVariants::new());
+    assert_eq!(load(&zip_io, "shaders/foo.wgsl").as_deref(), Some("common"));
+}
+
+#[test]
+#[cfg(feature = "runtime-config")]
+fn asset_variants_are_read_for_the_platform() {
+    let variants = AssetVariants::from_toml(
+        r#"
+        [all]
+        "a.txt" = "a.all.txt"
+        "b.txt" = "b.all.txt"
+
+        [macos]
+        "b.txt" = "b.macos.txt"
+
+        [windows]
+        "c.txt" = "c.windows.txt"
+        "#,
+        "macos",
+    )
+    .unwrap();
+
+    assert_eq!(variants.resolve(Path::new("a.txt")), Path::new("a.all.txt"));
+    assert_eq!(
+        variants.resolve(Path::new("b.txt")),
+        Path::new("b.macos.txt")
+    );
+    assert_eq!(variants.resolve(Path::new("c.txt")), Path::new("c.txt"));
+    assert!(AssetVariants::from_toml("[macos]\n\"a.txt\" = 1", "macos").is_err());
+}
@@ -0,0 +1,120 @@
+mod common;
+
+use std::{
+    fmt::Debug,
+    fs,
+    sync::{Arc, Mutex},
+};
+
+use bevy::{
+    tasks::TaskPool,
+    utils::tracing::{
+        field::{Field, Visit},
+        span, subscriber, Event, Metadata, Subscriber,
+    },
+};
+use bevy_assetio_zip::AssetIoZipConfig;
+
+use common::{build_app, write_bundle};
+
+/// A subscriber that keeps the messages of every event
+#[derive(Default, Clone)]
+struct Messages(Arc<Mutex<Vec<String>>>);
+
+impl Visit for Messages {
+    fn record_debug(&mut self, field: &Field, value: &dyn Debug) {
+        if field.name() == "message" {
+            self.0.lock().unwrap().push(format!("{:?}", value));
+        }
+    }
+}
+
+impl Subscriber for Messages {
+    fn enabled(&self, _: &Metadata) -> bool {
+        true
+    }
+    fn new_span(&self, _: &span::Attributes) -> span::Id {
+        span::Id::from_u64(1)
+    }
+    fn record(&self, _: &span::Id, _: &span::Record) {}
+    fn record_follows_from(&self, _: &span::Id, _: &span::Id) {}
+    fn event(&self, event: &Event) {
+        event.record(&mut self.clone());
+    }
+    fn enter(&self, _: &span::Id) {}
+    fn exit(&self, _: &span::Id) {}
+}
+
+/// Build an app with the plugin and get the messages that were logged
+fn build_messages(config: AssetIoZipConfig) -> Vec<String> {
+    let messages = Messages::default();
+    subscriber::with_default(messages.clone(), || build_app(config, TaskPool::new()));
+    let messages = messages.0.lock().unwrap();
+    messages.clone()
+}
+
+#[test]
+fn bundle_probing_is_logged_when_verbose() {
+    let found = format!("verbose-found-{}", std::process::id());
+    let bundle = write_bundle(&found, &[("a.txt", "a"), ("b.txt", "b")]);
+
+    let config = AssetIoZipConfig {
+        file_names: vec!["verbose-missing".into(), found.clone()],
+        verbose: true,
+        ..Default::default()
+    };
+    let messages = build_messages(config.clone());
+
+    // The executable is checked for an appended bundle first
+    let first_probe = messages.iter().find(|x| x.starts_with("Probed")).unwrap();
+    assert!(
+        first_probe.starts_with("Probed for asset bundle appended to"),
+        "{:#?}",
+        messages
+    );
+    let probed = messages
+        .iter()
+        .filter(|x| x.starts_with("Probed for asset bundle at"))
+        .collect::<Vec<_>>();
+    // Both names are probed with each enabled extension next to the executable and in the asset
+    // folder
+    let extensions = 1 + cfg!(feature = "obfuscation") as usize + cfg!(feature = "sqlite") as usize;
+    assert_eq!(probed.len(), 4 * extensions, "{:#?}", messages);
+    assert!(probed[0].contains("verbose-missing") && probed[0].ends_with(": not found"));
+    assert!(probed
+        .iter()
+        .any(|x| x.contains(&found) && x.ends_with(".zip: found")));
+    let opened = messages.last().unwrap();
+    assert!(opened.contains("with 2 entries"), "{}", opened);
+    assert!(opened.contains("obfuscated: false"), "{}", opened);
+
+    // Nothing is logged unless the config asks for it
+    let quiet = build_messages(AssetIoZipConfig {
+        verbose: false,
+        ..config
+    });
+    assert!(
+        quiet.iter().all(|x| !x.contains("asset bundle")),
+        "{:#?}",
+        quiet
+    );
+
+    fs::remove_file(&bundle).unwrap();
+}
+
+#[test]
+fn missing_bundles_are_logged_when_verbose() {
+    let messages = build_messages(AssetIoZipConfig {
+        file_names: vec!["verbose-none".into()],
+        verbose: true,
+        ..Default::default()
+    });
+    assert!(
+        messages
+            .last()
+            .unwrap()
+            .starts_with("No asset bundle found"),
+        "{:#?}",
+        messages
+    );
+}
@@ -0,0 +1,49 @@
+mod common;
+
+use bevy::tasks::TaskPool;
+use bevy_assetio_zip::{AssetIoZipConfig, BundleFileSystem, BundleSources};
+use vfs::{VfsFileType, VfsPath};
+
+use common::{asset_io, build_app_with_sources, bundle};
+
+#[test]
+fn mounted_bundles_can_be_browsed_as_a_file_system() {
+    let mut sources = BundleSources::default();
+    sources.add("mod", bundle(&[("textures/grass.png", "modded")]));
+    sources.add(
+        "base",
+        bundle(&[
+            ("textures/grass.png", "base grass"),
+            ("textures/stone.png", "stone"),
+            ("music.ogg", "music"),
+        ]),
+    );
+    let asset_io = asset_io(&build_app_with_sources(
+        AssetIoZipConfig::default(),
+        TaskPool::new(),
+        sources,
+    ));
+    let root = VfsPath::new(BundleFileSystem::new((*asset_io).clone()));
+
+    let mut textures = root
+        .join("textures")
+        .unwrap()
+        .read_dir()
+        .unwrap()
+        .map(|path| path.filename())
+        .collect::<Vec<_>>();
+    textures.sort();
+    assert_eq!(textures, ["grass.png", "stone.png"]);
+
+    // Assets resolve to the bundle that the game would load them from
+    let grass = root.join("textures/grass.png").unwrap();
+    assert_eq!(grass.read_to_string().unwrap(), "modded");
+    let metadata = grass.metadata().unwrap();
+    assert_eq!(metadata.file_type, VfsFileType::File);
+    assert_eq!(metadata.len, "modded".len() as u64);
+
+    assert!(root.join("textures").unwrap().is_dir().unwrap());
+    assert!(!root.join("missing.ogg").unwrap().exists().unwrap());
+    assert!(root.join("missing.ogg").unwrap().open_file().is_err());
+    assert!(root.join("new.txt").unwrap().create_file().is_err());
+}
@@ -0,0 +1,82 @@
+mod common;
+
+use std::{
+    fs,
+    path::{Path, PathBuf},
+};
+
+use bevy::{
+    asset::{AssetIo, AssetServerSettings},
+    prelude::*,
+    tasks::{IoTaskPool, TaskPool},
+};
+use bevy_assetio_zip::{AssetIoZip, AssetIoZipConfig, AssetIoZipPlugin};
+
+use common::{asset_io, write_zip};
+
+/// Build the plugin's asset IO with the given asset folder and bundle
+fn build_asset_io(asset_folder: &Path, bundle_path: PathBuf) -> AssetIoZip {
+    let settings = AssetServerSettings {
+        asset_folder: asset_folder.to_string_lossy().into_owned(),
+    };
+    let config = AssetIoZipConfig {
+        bundle_path: Some(bundle_path),
+        ..Default::default()
+    };
+    let mut builder = App::build();
+    #[cfg(feature = "bevy-unstable")]
+    builder
+        .insert_resource(IoTaskPool(TaskPool::new()))
+        .insert_resource(settings)
+        .insert_resource(config);
+    #[cfg(not(feature = "bevy-unstable"))]
+    builder
+        .add_resource(IoTaskPool(TaskPool::new()))
+        .add_resource(settings)
+        .add_resource(config);
+    builder.add_plugin(AssetIoZipPlugin);
+    asset_io(&builder)
+}
+
+#[test]
+fn assets_only_in_the_bundle_are_not_watched() {
+    let dir =
+        std::env::temp_dir().join(format!("bevy_assetio_zip_watching_{}", std::process::id()));
+    let asset_dir = dir.join("assets");
+    fs::create_dir_all(&asset_dir).unwrap();
+    fs::write(asset_dir.join("loose.txt"), "loose").unwrap();
+    let bundle_path = dir.join("assets.zip");
+    write_zip(&bundle_path, &[("bundled.txt", "bundled")]);
+
+    let asset_io = build_asset_io(&asset_dir, bundle_path);
+
+    asset_io.watch_for_changes().unwrap();
+    asset_io
+        .watch_path_for_changes(Path::new("loose.txt"))
+        .unwrap();
+    // The filesystem watcher fails to watch files that don't exist
+    asset_io
+        .watch_path_for_changes(Path::new("bundled.txt"))
+        .unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn watching_is_a_no_op_without_an_asset_folder() {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_watching_no_folder_{}",
+        std::process::id()
+    ));
+    let bundle_path = dir.join("assets.zip");
+    write_zip(&bundle_path, &[("bundled.txt", "bundled")]);
+
+    let asset_io = build_asset_io(&dir.join("assets"), bundle_path);
+
+    asset_io.watch_for_changes().unwrap();
+    asset_io
+        .watch_path_for_changes(Path::new("bundled.txt"))
+        .unwrap();
+
+    fs::remove_dir_all(&dir).unwrap();
+}
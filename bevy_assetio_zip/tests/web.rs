@@ -0,0 +1,31 @@
+//! Browser build tests, run with `wasm-pack test --headless --firefox bevy_assetio_zip`
+#![cfg(target_arch = "wasm32")]
+
+mod common;
+
+use std::path::Path;
+
+use bevy::{app::Events, asset::AssetIo, tasks::TaskPool};
+use bevy_assetio_zip::{AssetIoZipConfig, BundleDownloadProgress};
+use wasm_bindgen_test::{wasm_bindgen_test, wasm_bindgen_test_configure};
+
+use common::{asset_io, build_app};
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn loads_wait_for_the_download_and_fall_back_without_a_bundle() {
+    let config = AssetIoZipConfig {
+        file_names: vec!["missing".into()],
+        ..Default::default()
+    };
+    let builder = build_app(config, TaskPool::new());
+    assert!(builder
+        .resources()
+        .get::<Events<BundleDownloadProgress>>()
+        .is_some());
+
+    // The test server has no bundle, so once the download fails the fallback asset IO is used
+    let asset_io = asset_io(&builder);
+    assert!(asset_io.load_path(Path::new("missing.txt")).await.is_err());
+}
@@ -0,0 +1,91 @@
+//! Attaching bundles to executables for single-file distributions
+//!
+//! The bundle is appended to a copy of the executable, followed by a footer that locates it. All
+//! values in the footer are little-endian:
+//!
+//! | Offset from the end | Size | Field                                  |
+//! | ------------------- | ---- | -------------------------------------- |
+//! | 24                  | 8    | Offset of the bundle in the file       |
+//! | 16                  | 8    | Length of the bundle                   |
+//! | 8                   | 8    | Magic bytes, [`APPENDED_BUNDLE_MAGIC`] |
+
+use std::{
+    convert::TryInto,
+    fs::File,
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::Path,
+};
+
+/// The magic bytes at the end of the footer of executables with an appended bundle
+pub const APPENDED_BUNDLE_MAGIC: &[u8; 8] = b"BAZBAPND";
+
+/// The length of the footer of executables with an appended bundle
+pub const APPENDED_FOOTER_LEN: u64 = 24;
+
+/// Write a copy of the executable at `executable` to `output` with the bundle at `bundle` appended
+/// to it, followed by a footer that locates the bundle
+///
+/// The game finds the appended bundle when it runs from `output`, so the game and its assets can
+/// be distributed as a single file. The bundle is copied as is, so it can be any kind of bundle
+/// written by the bundler. If the executable already has an appended bundle, such as when the
+/// assets are bundled again, it is replaced. The permissions of the executable are copied to
+/// `output`, so it stays executable.
+///
+/// Code signatures of the executable are not preserved, so sign `output` after appending the
+/// bundle if needed.
+///
+/// An error is returned if the files can't be read or written, or if `output` is the executable.
+pub fn append_to_executable<P: AsRef<Path>, Q: AsRef<Path>, R: AsRef<Path>>(
+    executable: P,
+    bundle: Q,
+    output: R,
+) -> io::Result<()> {
+    let (executable, output) = (executable.as_ref(), output.as_ref());
+    if output.canonicalize().ok() == Some(executable.canonicalize()?) {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "The output can't be the executable that the bundle is appended to",
+        ));
+    }
+
+    let mut exe_file = File::open(executable)?;
+    let exe_len = match appended_bundle(&mut exe_file)? {
+        Some((offset, _)) => offset,
+        None => exe_file.metadata()?.len(),
+    };
+    exe_file.seek(SeekFrom::Start(0))?;
+
+    let mut out = BufWriter::new(File::create(output)?);
+    io::copy(&mut (&mut exe_file).take(exe_len), &mut out)?;
+    let bundle_len = io::copy(&mut File::open(bundle)?, &mut out)?;
+    out.write_all(&exe_len.to_le_bytes())?;
+    out.write_all(&bundle_len.to_le_bytes())?;
+    out.write_all(APPENDED_BUNDLE_MAGIC)?;
+    out.into_inner()?.sync_all()?;
+
+    std::fs::set_permissions(output, exe_file.metadata()?.permissions())
+}
+
+/// Read the offset and length of the bundle appended to the given file, returning `None` if it
+/// doesn't end with a footer
+fn appended_bundle(file: &mut File) -> io::Result<Option<(u64, u64)>> {
+    let len = file.metadata()?.len();
+    if len < APPENDED_FOOTER_LEN {
+        return Ok(None);
+    }
+
+    let mut footer = [0; APPENDED_FOOTER_LEN as usize];
+    file.seek(SeekFrom::End(-(APPENDED_FOOTER_LEN as i64)))?;
+    file.read_exact(&mut footer)?;
+    if &footer[16..] != APPENDED_BUNDLE_MAGIC {
+        return Ok(None);
+    }
+
+    let offset = u64::from_le_bytes(footer[..8].try_into().unwrap());
+    let length = u64::from_le_bytes(footer[8..16].try_into().unwrap());
+    if offset.checked_add(length) != Some(len - APPENDED_FOOTER_LEN) {
+        return Ok(None);
+    }
+
+    Ok(Some((offset, length)))
+}
@@ -0,0 +1,244 @@
+//! Comparing the size and speed of the compression codecs on a sample of the assets
+
+use std::{
+    collections::BTreeMap,
+    fmt,
+    io::{self, Cursor, Write},
+    path::Path,
+    time::{Duration, Instant},
+};
+
+use zip::{write::FileOptions, CompressionMethod, ZipArchive, ZipWriter};
+
+use crate::{entry_name, ignore, stats::format_size};
+#[cfg(feature = "solid")]
+use crate::{DEFAULT_SOLID_FRAME_SIZE, DEFAULT_SOLID_LEVEL};
+
+/// The default number of bytes of assets that [`benchmark`] compresses with each codec
+pub const DEFAULT_BENCHMARK_SAMPLE_SIZE: u64 = 64 * 1024 * 1024;
+
+/// The zstd levels of solid compression that are benchmarked
+#[cfg(feature = "solid")]
+const SOLID_BENCHMARK_LEVELS: &[i32] = &[3, 9, DEFAULT_SOLID_LEVEL];
+
+/// How well one codec compressed the sample of assets in a [`BenchmarkReport`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodecBenchmark {
+    /// The name of the codec, which is the `compression` setting of the `asset_config.toml` for
+    /// codecs that compress each entry, or `solid` for solid compression
+    pub codec: String,
+    /// The compression level, which is the `solid-level` for solid compression
+    pub level: Option<i32>,
+    /// The size of the compressed sample
+    pub compressed_bytes: u64,
+    /// How long it took to compress the sample
+    pub compress_duration: Duration,
+    /// How long it took to decompress the sample again
+    pub decompress_duration: Duration,
+}
+
+/// The result of comparing the compression codecs with [`benchmark`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BenchmarkReport {
+    /// The number of asset files in the sample
+    pub sample_files: u64,
+    /// The total size of the asset files in the sample
+    pub sample_bytes: u64,
+    /// The results of each codec enabled by the features of the bundler, in the order they were
+    /// benchmarked
+    pub codecs: Vec<CodecBenchmark>,
+}
+
+impl fmt::Display for BenchmarkReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Compressed a sample of {} files, {}",
+            self.sample_files,
+            format_size(self.sample_bytes)
+        )?;
+        writeln!(
+            f,
+            "{:<8} {:>5} {:>12} {:>7} {:>14} {:>14}",
+            "codec", "level", "size", "ratio", "compress", "decompress"
+        )?;
+        for codec in &self.codecs {
+            writeln!(
+                f,
+                "{:<8} {:>5} {:>12} {:>6.1}% {:>14} {:>14}",
+                codec.codec,
+                codec.level.map(|x| x.to_string()).unwrap_or_default(),
+                format_size(codec.compressed_bytes),
+                codec.compressed_bytes as f64 * 100.0 / self.sample_bytes.max(1) as f64,
+                throughput(self.sample_bytes, codec.compress_duration),
+                throughput(self.sample_bytes, codec.decompress_duration),
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compress a sample of up to `sample_size` bytes of the assets in the given asset dir with each
+/// codec enabled by the features of the bundler, and report the size of the compressed sample and
+/// how long it took to compress and decompress
+///
+/// The sample takes files of every extension in turn, so that it is representative of the mix of
+/// assets even when it is much smaller than the asset dir. This helps pick the `compression` and
+/// `solid-level` settings based on the actual assets.
+///
+/// An error is returned if the asset files can't be read.
+pub fn benchmark<P: AsRef<Path>>(asset_dir: P, sample_size: u64) -> io::Result<BenchmarkReport> {
+    let files = sample(asset_dir.as_ref(), sample_size)?;
+    let mut report = BenchmarkReport {
+        sample_files: files.len() as u64,
+        sample_bytes: files.iter().map(|x| x.1.len() as u64).sum(),
+        codecs: Vec::new(),
+    };
+
+    report.codecs.push(benchmark_entries(
+        &files,
+        "none",
+        CompressionMethod::Stored,
+    )?);
+    #[cfg(feature = "deflate")]
+    report.codecs.push(benchmark_entries(
+        &files,
+        "deflate",
+        CompressionMethod::Deflated,
+    )?);
+    #[cfg(feature = "bzip2")]
+    report.codecs.push(benchmark_entries(
+        &files,
+        "bzip2",
+        CompressionMethod::Bzip2,
+    )?);
+    #[cfg(feature = "solid")]
+    for level in SOLID_BENCHMARK_LEVELS {
+        report.codecs.push(benchmark_solid(&files, *level)?);
+    }
+
+    Ok(report)
+}
+
+/// Read a sample of up to `sample_size` bytes of the asset files in the given dir, taking the
+/// next file of each extension in turn
+fn sample(asset_dir: &Path, sample_size: u64) -> io::Result<Vec<(String, Vec<u8>)>> {
+    let mut extensions = BTreeMap::<String, Vec<_>>::new();
+    for entry in ignore::walk_assets(asset_dir) {
+        let entry = entry?;
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let extension = entry
+            .path()
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let size = entry.metadata()?.len();
+        extensions
+            .entry(extension)
+            .or_default()
+            .push((entry.into_path(), size));
+    }
+    let mut extensions = extensions
+        .into_iter()
+        .map(|(_, mut files)| {
+            files.sort();
+            files.into_iter()
+        })
+        .collect::<Vec<_>>();
+
+    let mut files = Vec::new();
+    let mut sample_bytes = 0;
+    loop {
+        let mut added = false;
+        for extension in &mut extensions {
+            let (path, size) = match extension.next() {
+                Some(file) => file,
+                None => continue,
+            };
+            // Always take at least one file, even if it is larger than the sample
+            if sample_bytes + size > sample_size && !files.is_empty() {
+                continue;
+            }
+
+            let name = entry_name(path.strip_prefix(asset_dir).unwrap());
+            files.push((name, std::fs::read(&path)?));
+            sample_bytes += size;
+            added = true;
+        }
+
+        if !added {
+            return Ok(files);
+        }
+    }
+}
+
+/// Benchmark compressing each file in its own zip entry with the given compression method
+fn benchmark_entries(
+    files: &[(String, Vec<u8>)],
+    codec: &str,
+    method: CompressionMethod,
+) -> io::Result<CodecBenchmark> {
+    let start = Instant::now();
+    let mut zip = ZipWriter::new(Cursor::new(Vec::new()));
+    let options = FileOptions::default().compression_method(method);
+    for (name, data) in files {
+        zip.start_file(name, options)?;
+        zip.write_all(data)?;
+    }
+    let bytes = zip.finish()?.into_inner();
+    let compress_duration = start.elapsed();
+
+    let start = Instant::now();
+    let mut archive = ZipArchive::new(Cursor::new(&bytes))?;
+    for i in 0..archive.len() {
+        io::copy(&mut archive.by_index(i)?, &mut io::sink())?;
+    }
+    let decompress_duration = start.elapsed();
+
+    Ok(CodecBenchmark {
+        codec: codec.to_owned(),
+        level: None,
+        compressed_bytes: bytes.len() as u64,
+        compress_duration,
+        decompress_duration,
+    })
+}
+
+/// Benchmark compressing all of the files together in frames like solid bundles, with the given
+/// zstd level
+#[cfg(feature = "solid")]
+fn benchmark_solid(files: &[(String, Vec<u8>)], level: i32) -> io::Result<CodecBenchmark> {
+    let data = files.iter().flat_map(|x| &x.1).copied().collect::<Vec<_>>();
+
+    let start = Instant::now();
+    let frames = data
+        .chunks(DEFAULT_SOLID_FRAME_SIZE as usize)
+        .map(|chunk| zstd::bulk::compress(chunk, level).map(|frame| (frame, chunk.len())))
+        .collect::<io::Result<Vec<_>>>()?;
+    let compress_duration = start.elapsed();
+
+    let start = Instant::now();
+    for (frame, len) in &frames {
+        zstd::bulk::decompress(frame, *len)?;
+    }
+    let decompress_duration = start.elapsed();
+
+    Ok(CodecBenchmark {
+        codec: "solid".to_owned(),
+        level: Some(level),
+        compressed_bytes: frames.iter().map(|x| x.0.len() as u64).sum(),
+        compress_duration,
+        decompress_duration,
+    })
+}
+
+/// Format the speed of processing the given number of bytes in the given time, such as
+/// `120.50 MiB/s`
+fn throughput(bytes: u64, duration: Duration) -> String {
+    let seconds = duration.as_secs_f64().max(1e-9);
+    format!("{}/s", format_size((bytes as f64 / seconds) as u64))
+}
@@ -0,0 +1,140 @@
+//! Comparing the assets in two bundles
+
+use std::{collections::BTreeMap, fmt, path::Path};
+
+use serde_json::json;
+
+use crate::reader::BundleReader;
+
+/// An asset that was added, removed, or changed between two bundles
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AssetChange {
+    /// The path of the asset in the bundles
+    pub path: String,
+    /// The size of the asset in the old bundle, or `None` if it was added
+    pub old_size: Option<u64>,
+    /// The size of the asset in the new bundle, or `None` if it was removed
+    pub new_size: Option<u64>,
+}
+
+/// The assets that differ between two bundles, as returned by [`diff`]
+///
+/// Each list is sorted by path. The [`Display`][fmt::Display] implementation renders the diff with
+/// one asset per line, prefixed by `+`, `-`, or `~` for added, removed, and changed assets.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct BundleDiff {
+    pub added: Vec<AssetChange>,
+    pub removed: Vec<AssetChange>,
+    pub changed: Vec<AssetChange>,
+}
+
+impl BundleDiff {
+    /// Whether or not the bundles contain the same assets
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+impl fmt::Display for BundleDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.added {
+            writeln!(f, "+ {} ({} bytes)", change.path, change.new_size.unwrap())?;
+        }
+        for change in &self.removed {
+            writeln!(f, "- {} ({} bytes)", change.path, change.old_size.unwrap())?;
+        }
+        for change in &self.changed {
+            writeln!(
+                f,
+                "~ {} ({} -> {} bytes)",
+                change.path,
+                change.old_size.unwrap(),
+                change.new_size.unwrap()
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Compare the assets in two bundles, listing the assets that were added, removed, or changed in
+/// the `new` bundle
+///
+/// Assets are compared by the size and CRC-32 of their data after undoing the compression,
+/// obfuscation, and packing of the bundles, so bundles written with different options can be
+/// compared. The entries used internally by the bundler are not compared.
+pub fn diff<P: AsRef<Path>>(old: P, new: P) -> BundleDiff {
+    diff_hashes(asset_hashes(old.as_ref()), asset_hashes(new.as_ref()))
+}
+
+/// Compare the sizes and CRC-32s of the assets of two bundles
+pub(crate) fn diff_hashes(
+    old: BTreeMap<String, (u64, u32)>,
+    mut new: BTreeMap<String, (u64, u32)>,
+) -> BundleDiff {
+    let mut diff = BundleDiff::default();
+
+    for (path, (old_size, old_hash)) in old {
+        let change = |new_size| AssetChange {
+            path: path.clone(),
+            old_size: Some(old_size),
+            new_size,
+        };
+        match new.remove(&path) {
+            None => diff.removed.push(change(None)),
+            Some((new_size, new_hash)) if (new_size, new_hash) != (old_size, old_hash) => {
+                diff.changed.push(change(Some(new_size)))
+            }
+            Some(_) => (),
+        }
+    }
+    diff.added = new
+        .into_iter()
+        .map(|(path, (new_size, _))| AssetChange {
+            path,
+            old_size: None,
+            new_size: Some(new_size),
+        })
+        .collect();
+
+    diff
+}
+
+/// Render the diff as the JSON of the [`CHANGELOG_ENTRY`][crate::CHANGELOG_ENTRY]
+pub(crate) fn changelog_json(diff: &BundleDiff) -> String {
+    let sized = |changes: &[AssetChange], size: fn(&AssetChange) -> Option<u64>| {
+        changes
+            .iter()
+            .map(|x| json!({ "path": x.path, "size": size(x) }))
+            .collect::<Vec<_>>()
+    };
+    let changed = diff
+        .changed
+        .iter()
+        .map(|x| json!({ "path": x.path, "old_size": x.old_size, "new_size": x.new_size }))
+        .collect::<Vec<_>>();
+
+    serde_json::to_string_pretty(&json!({
+        "added": sized(&diff.added, |x| x.new_size),
+        "removed": sized(&diff.removed, |x| x.old_size),
+        "changed": changed,
+    }))
+    .unwrap()
+}
+
+/// Get the size and CRC-32 of every asset in the bundle at the given path
+pub(crate) fn asset_hashes(path: &Path) -> BTreeMap<String, (u64, u32)> {
+    let mut bundle = BundleReader::open(path)
+        .unwrap_or_else(|e| panic!("Could not open bundle {}: {}", path.display(), e));
+
+    bundle
+        .asset_paths()
+        .into_iter()
+        .map(|name| {
+            let data = bundle.read_asset(&name).unwrap_or_else(|e| {
+                panic!("Could not read {} from {}: {}", name, path.display(), e)
+            });
+            (name, (data.len() as u64, crc32fast::hash(&data)))
+        })
+        .collect()
+}
@@ -0,0 +1,59 @@
+//! Unpacking the assets of bundles
+
+use std::{
+    io,
+    path::{Component, Path},
+};
+
+use filetime::FileTime;
+
+use crate::reader::BundleReader;
+
+/// Extract the assets in the bundle at `bundle_path` into `out_dir`
+///
+/// The bundle is decoded however it was written, including obfuscated `.bin` bundles, bundles with
+/// obfuscated entries, and assets in packed blocks, so the extracted dir matches the asset dir that
+/// the bundle was built from. The entries used internally by the bundler are not extracted. If the
+/// bundle was built with `asset_metadata`, the modification times of the extracted files are
+/// restored, and on unix the permissions recorded for each asset, such as the executable bits
+/// recorded by `unix_permissions`, are restored as well.
+///
+/// An error is returned if the bundle can't be read, or if it contains an asset path that would be
+/// written outside of `out_dir`.
+pub fn extract<P: AsRef<Path>, Q: AsRef<Path>>(bundle_path: P, out_dir: Q) -> io::Result<()> {
+    let out_dir = out_dir.as_ref();
+    let mut bundle = BundleReader::open(bundle_path.as_ref())?;
+    let metadata = bundle.asset_metadata().unwrap_or_default();
+
+    for path in bundle.asset_paths() {
+        let relative = Path::new(&path);
+        if !relative
+            .components()
+            .all(|x| matches!(x, Component::Normal(_)))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("Asset path {} is outside of the bundle", path),
+            ));
+        }
+
+        let data = bundle.read_asset(&path)?;
+        let out_file = out_dir.join(relative);
+        if let Some(parent) = out_file.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        std::fs::write(&out_file, data)?;
+
+        #[cfg(unix)]
+        if let Some(mode) = bundle.asset_mode(&path) {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&out_file, std::fs::Permissions::from_mode(mode & 0o777))?;
+        }
+
+        if let Some(metadata) = metadata.get(&path) {
+            filetime::set_file_mtime(&out_file, FileTime::from_system_time(metadata.modified))?;
+        }
+    }
+
+    Ok(())
+}
@@ -0,0 +1,152 @@
+//! Excluding assets from the bundle with `.assetignore` files
+
+use std::path::{Path, PathBuf};
+
+use glob::{MatchOptions, Pattern};
+use walkdir::{DirEntry, WalkDir};
+
+use crate::{entry_name, warn, ASSET_IGNORE_FILE};
+
+/// The options used to match ignore patterns, where wildcards don't cross directories like in
+/// `.gitignore` files
+const IGNORE_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A pattern from one line of an `.assetignore` file
+struct Rule {
+    /// The directory containing the `.assetignore` file, relative to the asset dir
+    base: PathBuf,
+    pattern: Pattern,
+    /// Whether the pattern re-includes the paths it matches, from a leading `!`
+    negate: bool,
+    /// Whether the pattern only matches directories, from a trailing `/`
+    dir_only: bool,
+}
+
+/// The rules of every `.assetignore` file in an asset dir
+struct AssetIgnore {
+    /// The rules in the order they are checked, with the rules of parent directories first so that
+    /// the rules of subdirectories take precedence
+    rules: Vec<Rule>,
+}
+
+impl AssetIgnore {
+    /// Read the `.assetignore` files in the given asset dir
+    ///
+    /// Dirs that can't be read are skipped, and left to be reported by the walk over the assets.
+    fn new(source_dir: &Path) -> Self {
+        let mut files = WalkDir::new(source_dir)
+            .into_iter()
+            .filter_map(Result::ok)
+            .filter(|entry| entry.file_type().is_file() && entry.file_name() == ASSET_IGNORE_FILE)
+            .map(|entry| entry.into_path())
+            .collect::<Vec<_>>();
+        files.sort_by_key(|x| x.components().count());
+
+        let mut rules = Vec::new();
+        for file in files {
+            let base = file
+                .parent()
+                .unwrap()
+                .strip_prefix(source_dir)
+                .unwrap()
+                .to_owned();
+            let text = std::fs::read_to_string(&file).expect("Could not read .assetignore file");
+            for line in text.lines() {
+                match parse_rule(&base, line) {
+                    Some(Ok(rule)) => rules.push(rule),
+                    Some(Err(e)) => warn(&format!(
+                        "Invalid pattern `{}` in {}: {}",
+                        line,
+                        file.display(),
+                        e
+                    )),
+                    None => (),
+                }
+            }
+        }
+
+        Self { rules }
+    }
+
+    /// Check whether the given path, relative to the asset dir, is ignored
+    ///
+    /// Like in `.gitignore` files, the last pattern that matches the path decides whether it is
+    /// ignored.
+    fn is_ignored(&self, name: &Path, is_dir: bool) -> bool {
+        let mut ignored = false;
+        for rule in &self.rules {
+            if rule.dir_only && !is_dir {
+                continue;
+            }
+            let relative = match name.strip_prefix(&rule.base) {
+                Ok(relative) if !relative.as_os_str().is_empty() => relative,
+                _ => continue,
+            };
+
+            if rule
+                .pattern
+                .matches_with(&entry_name(relative), IGNORE_MATCH_OPTIONS)
+            {
+                ignored = !rule.negate;
+            }
+        }
+
+        ignored
+    }
+}
+
+/// Parse one line of an `.assetignore` file in the directory `base`, returning `None` for blank
+/// lines and comments
+fn parse_rule(base: &Path, line: &str) -> Option<Result<Rule, glob::PatternError>> {
+    let mut line = line.trim_end();
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+
+    // A leading backslash escapes a `!` or `#` at the start of a pattern
+    let negate = line.starts_with('!');
+    if negate || line.starts_with("\\!") || line.starts_with("\\#") {
+        line = &line[1..];
+    }
+
+    let dir_only = line.ends_with('/');
+    let line = line.trim_end_matches('/');
+    if line.is_empty() {
+        return None;
+    }
+
+    // Patterns with a slash are relative to the directory of the `.assetignore` file, and patterns
+    // without one match at any depth
+    let pattern = if line.contains('/') {
+        line.trim_start_matches('/').to_owned()
+    } else {
+        format!("**/{}", line)
+    };
+
+    Some(Pattern::new(&pattern).map(|pattern| Rule {
+        base: base.to_owned(),
+        pattern,
+        negate,
+        dir_only,
+    }))
+}
+
+/// Walk the files and directories in the asset dir, skipping the `.assetignore` files and the paths
+/// that they ignore
+pub(crate) fn walk_assets(source_dir: &Path) -> impl Iterator<Item = walkdir::Result<DirEntry>> {
+    let ignore = AssetIgnore::new(source_dir);
+    let source_dir = source_dir.to_owned();
+
+    WalkDir::new(&source_dir)
+        .into_iter()
+        .filter_entry(move |entry| {
+            let name = entry.path().strip_prefix(&source_dir).unwrap();
+            let is_ignore_file =
+                entry.file_type().is_file() && entry.file_name() == ASSET_IGNORE_FILE;
+            !is_ignore_file && !ignore.is_ignored(name, entry.file_type().is_dir())
+        })
+}
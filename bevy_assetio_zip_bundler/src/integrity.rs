@@ -0,0 +1,14 @@
+//! Hashing bundles so that games can check that they haven't been tampered with
+
+use std::{fs::File, io, path::Path};
+
+/// The environment variable that the bundle hash is passed to the game crate in, to be embedded
+/// with the `bundle_hash!` macro of `bevy_assetio_zip`
+pub const BUNDLE_HASH_ENV_VAR: &str = "ASSETIO_ZIP_BUNDLE_HASH";
+
+/// Get the hex BLAKE3 hash of the bundle file at the given path
+pub fn bundle_hash<P: AsRef<Path>>(path: P) -> io::Result<String> {
+    let mut hasher = blake3::Hasher::new();
+    io::copy(&mut File::open(path)?, &mut hasher)?;
+    Ok(hasher.finalize().to_hex().to_string())
+}
@@ -0,0 +1,70 @@
+//! Keys that the data of the entries in obfuscated bundles is scrambled with
+//!
+//! The data of each entry is XOR-ed with a keystream seeded from a random bundle salt and the name
+//! of the entry, so recovering the keystream of one file does not decode the other files in the
+//! bundle. The salt is stored in the obfuscated entries marker so that the reader can derive the
+//! same keystreams.
+
+use std::{
+    collections::hash_map::RandomState,
+    hash::{BuildHasher, Hasher},
+    time::SystemTime,
+};
+
+/// The length of the bundle salt in bytes
+const SALT_LEN: usize = 16;
+/// The FNV-1a offset basis used to hash the salt and entry name into a seed
+const FNV_OFFSET_BASIS: u64 = 0xcbf2_9ce4_8422_2325;
+/// The FNV-1a prime used to hash the salt and entry name into a seed
+const FNV_PRIME: u64 = 0x0000_0100_0000_01b3;
+/// The increment of the SplitMix64 generator that produces the keystream
+const SPLITMIX_GAMMA: u64 = 0x9e37_79b9_7f4a_7c15;
+
+/// Generate a new random bundle salt
+pub(crate) fn bundle_salt() -> Vec<u8> {
+    let time = SystemTime::now()
+        .duration_since(SystemTime::UNIX_EPOCH)
+        .unwrap_or_default();
+
+    (0..SALT_LEN / 8)
+        .flat_map(|i| {
+            let mut hasher = RandomState::new().build_hasher();
+            hasher.write_u128(time.as_nanos());
+            hasher.write_u32(std::process::id());
+            hasher.write_usize(i);
+            hasher.finish().to_le_bytes()
+        })
+        .collect()
+}
+
+/// Obfuscate the data of the entry with the given name using the keystream derived from the salt,
+/// where `data` starts at `offset` in the entry data
+pub(crate) fn obfuscate(salt: &[u8], name: &str, offset: u64, data: &mut [u8]) {
+    let seed = splitmix(
+        salt.iter()
+            .chain(&[0])
+            .chain(name.as_bytes())
+            .fold(FNV_OFFSET_BASIS, |hash, byte| {
+                (hash ^ *byte as u64).wrapping_mul(FNV_PRIME)
+            }),
+    );
+
+    // Each word of the keystream is computed from its position, so we can start anywhere in the
+    // entry
+    let mut word = 0;
+    for (pos, byte) in (offset..).zip(data.iter_mut()) {
+        if pos == offset || pos % 8 == 0 {
+            let counter = pos / 8 + 1;
+            word = splitmix(seed.wrapping_add(counter.wrapping_mul(SPLITMIX_GAMMA)));
+        }
+        *byte ^= word.to_le_bytes()[(pos % 8) as usize];
+    }
+}
+
+/// The SplitMix64 output function
+fn splitmix(value: u64) -> u64 {
+    let mut z = value;
+    z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+    z ^ (z >> 31)
+}
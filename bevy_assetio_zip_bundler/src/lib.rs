@@ -10,20 +10,59 @@
 //!
 //! [k_license]: https://github.com/katharostech/katharos-license
 
+mod append;
+mod benchmark;
+mod diff;
+mod extract;
+mod ignore;
+mod integrity;
+mod keys;
+mod merge;
+mod obfuscation;
+mod pipeline;
+mod reader;
+mod repack;
+mod stats;
+mod steps;
+mod verify;
 #[cfg(feature = "bundle-crate-assets")]
-use std::path::PathBuf;
+mod workspace;
+
+pub use append::{append_to_executable, APPENDED_BUNDLE_MAGIC, APPENDED_FOOTER_LEN};
+pub use benchmark::{benchmark, BenchmarkReport, CodecBenchmark, DEFAULT_BENCHMARK_SAMPLE_SIZE};
+pub use diff::{diff, AssetChange, BundleDiff};
+pub use extract::extract;
+pub use integrity::{bundle_hash, BUNDLE_HASH_ENV_VAR};
+pub use merge::ConflictPolicy;
+pub use obfuscation::{
+    wrap_reader, wrap_reader_with_key, wrap_writer, wrap_writer_with_key, Xor, OBFUSCATION_KEY,
+};
+pub use pipeline::{Converter, PipelineStep};
+pub use reader::comment;
+pub use repack::repack;
+pub use stats::{stats, BundleStats, ExtensionStats};
+#[cfg(feature = "bundle-crate-assets")]
+pub use steps::register_step;
+pub use steps::{BundleFile, BundleStep};
+pub use verify::{verify, VerifyError, VerifyReport};
+
 use std::{
-    fs::File,
-    io::{BufWriter, Read, Seek, Write},
-    path::Path,
+    collections::{BTreeMap, HashMap, HashSet},
+    fs::{File, OpenOptions},
+    io::{self, BufWriter, Read, Seek, SeekFrom, Write},
+    path::{Component, Path, PathBuf},
+    sync::Arc,
+    time::{Instant, SystemTime},
 };
 
+use glob::{MatchOptions, Pattern};
 #[cfg(feature = "bundle-crate-assets")]
 use serde::Deserialize;
-use walkdir::WalkDir;
-use xorio::Xor;
 pub use zip::CompressionMethod;
-use zip::{write::FileOptions, ZipWriter};
+use zip::{write::FileOptions, DateTime, ZipArchive, ZipWriter};
+
+use pipeline::Pipeline;
+use reader::BundleReader;
 
 /// Compression mode to use for asset bundle
 #[cfg(feature = "bundle-crate-assets")]
@@ -36,16 +75,37 @@ enum Compression {
 }
 
 #[cfg(feature = "bundle-crate-assets")]
-impl Into<CompressionMethod> for Compression {
-    fn into(self) -> CompressionMethod {
-        match self {
+impl From<Compression> for CompressionMethod {
+    fn from(compression: Compression) -> Self {
+        match compression {
             Compression::None => CompressionMethod::Stored,
+            #[cfg(feature = "bzip2")]
             Compression::Bzip2 => CompressionMethod::Bzip2,
+            #[cfg(not(feature = "bzip2"))]
+            Compression::Bzip2 => panic!(
+                "Bzip2 compression requires the `bzip2` feature of `bevy_assetio_zip_bundler`"
+            ),
+            #[cfg(feature = "deflate")]
             Compression::Deflate => CompressionMethod::Deflated,
+            #[cfg(not(feature = "deflate"))]
+            Compression::Deflate => panic!(
+                "Deflate compression requires the `deflate` feature of `bevy_assetio_zip_bundler`"
+            ),
         }
     }
 }
 
+/// The dir that the asset dirs and the output dir in the `asset_config.toml` are relative to
+#[cfg(feature = "bundle-crate-assets")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "kebab-case")]
+enum PathBase {
+    /// The root dir of the crate being built
+    Crate,
+    /// The root dir of the workspace that the crate being built belongs to
+    Workspace,
+}
+
 /// Configuration options for the `asset_config.toml` file
 #[cfg(feature = "bundle-crate-assets")]
 #[derive(Debug, Deserialize)]
@@ -54,10 +114,41 @@ impl Into<CompressionMethod> for Compression {
 #[serde(rename_all = "kebab-case")]
 struct AssetBundlerConfig {
     file_name: String,
-    compression: Compression,
+    compression: Option<Compression>,
     obfuscate: bool,
+    obfuscate_entries: bool,
+    entry_salt: Option<String>,
     bundle_for_debug_builds: bool,
-    out_dir: String,
+    asset_dir: PathBuf,
+    paths_relative_to: PathBase,
+    out_dir: Option<PathBuf>,
+    pack_threshold: u64,
+    pack_block_size: u64,
+    align: u16,
+    format: BundleFormat,
+    write_index: bool,
+    collect_credits: bool,
+    credits_patterns: Vec<String>,
+    asset_paths: bool,
+    validate_references: bool,
+    gltf_dependencies: bool,
+    pipeline: Vec<PipelineStep>,
+    solid: bool,
+    solid_frame_size: u64,
+    solid_level: i32,
+    asset_metadata: bool,
+    unix_permissions: bool,
+    timestamps: TimestampPolicy,
+    comment: Option<String>,
+    rename_file: Option<PathBuf>,
+    extra_asset_dirs: Vec<PathBuf>,
+    merge_conflicts: ConflictPolicy,
+    previous_bundle: Option<PathBuf>,
+    keep_assets: Vec<String>,
+    embed_bundle_hash: bool,
+    stats_file: Option<PathBuf>,
+    max_bundle_size: Option<String>,
+    max_bundle_size_action: SizeLimitAction,
 }
 
 #[cfg(feature = "bundle-crate-assets")]
@@ -65,10 +156,41 @@ impl Default for AssetBundlerConfig {
     fn default() -> Self {
         Self {
             file_name: "assets".into(),
-            compression: Compression::Bzip2,
+            compression: None,
             obfuscate: false,
+            obfuscate_entries: false,
+            entry_salt: None,
             bundle_for_debug_builds: false,
-            out_dir: "./target".into(),
+            asset_dir: "assets".into(),
+            paths_relative_to: PathBase::Crate,
+            out_dir: None,
+            pack_threshold: 0,
+            pack_block_size: DEFAULT_PACK_BLOCK_SIZE,
+            align: 0,
+            format: BundleFormat::Zip,
+            write_index: false,
+            collect_credits: false,
+            credits_patterns: default_credits_patterns(),
+            asset_paths: false,
+            validate_references: false,
+            gltf_dependencies: true,
+            pipeline: Vec::new(),
+            solid: false,
+            solid_frame_size: DEFAULT_SOLID_FRAME_SIZE,
+            solid_level: DEFAULT_SOLID_LEVEL,
+            asset_metadata: false,
+            unix_permissions: false,
+            timestamps: TimestampPolicy::BuildTime,
+            comment: None,
+            rename_file: None,
+            extra_asset_dirs: Vec::new(),
+            merge_conflicts: ConflictPolicy::Error,
+            previous_bundle: None,
+            keep_assets: Vec::new(),
+            embed_bundle_hash: false,
+            stats_file: None,
+            max_bundle_size: None,
+            max_bundle_size_action: SizeLimitAction::Fail,
         }
     }
 }
@@ -76,7 +198,12 @@ impl Default for AssetBundlerConfig {
 /// Automatically bundle the assets from this crate's `assets` dir and parse the bundler config from
 /// the optional `asset_config.toml` file.
 ///
-/// This function is meant to be used in your crates `build.rs` file.
+/// This function is meant to be used in your crates `build.rs` file. In a workspace the asset dirs
+/// can be given relative to the workspace root instead.
+///
+/// Unless an `out-dir` is configured, the bundle is written to the build script's `OUT_DIR` and
+/// then hard linked, or copied if it can't be linked, into the profile dir of the target dir next
+/// to the executable.
 #[cfg(feature = "bundle-crate-assets")]
 pub fn bundle_crate_assets() {
     let cargo_dir = std::env::var("CARGO_MANIFEST_DIR").unwrap();
@@ -84,25 +211,620 @@ pub fn bundle_crate_assets() {
 
     // Load bundler config file
     let config: AssetBundlerConfig = std::fs::read(config_path)
-        .and_then(
-            |x| Ok(toml::from_slice(x.as_slice()).expect("Could not parse asset_config.toml")),
-        )
+        .map(|x| toml::from_slice(x.as_slice()).expect("Could not parse asset_config.toml"))
+        .unwrap_or_default();
+    let groups: BTreeMap<String, Vec<String>> =
+        std::fs::read(PathBuf::from(cargo_dir.clone()).join(GROUPS_FILE))
+            .map(|x| toml::from_slice(x.as_slice()).expect("Could not parse groups.toml"))
+            .unwrap_or_default();
+    let renames: BTreeMap<String, String> = config
+        .rename_file
+        .as_ref()
+        .map(|x| {
+            let path = PathBuf::from(cargo_dir.clone()).join(x);
+            let renames = std::fs::read(&path)
+                .unwrap_or_else(|e| panic!("Could not read {}: {}", path.display(), e));
+            toml::from_slice(&renames)
+                .unwrap_or_else(|e| panic!("Could not parse {}: {}", path.display(), e))
+        })
         .unwrap_or_default();
 
+    let workspace_root = workspace::workspace_root(Path::new(&cargo_dir));
+    let base_dir = match config.paths_relative_to {
+        PathBase::Crate => PathBuf::from(cargo_dir),
+        PathBase::Workspace => workspace_root.clone(),
+    };
+    let extra_asset_dirs = config
+        .extra_asset_dirs
+        .iter()
+        .map(|x| base_dir.join(x))
+        .collect();
+
     let profile = std::env::var("PROFILE").unwrap();
     let file_extension = if config.obfuscate { "bin" } else { "zip" };
-    let asset_dir = PathBuf::from(cargo_dir).join("assets");
-    let bundle_file = format!("{}/{}.{}", config.out_dir, config.file_name, file_extension).into();
-    std::fs::create_dir_all(config.out_dir).unwrap();
+    let asset_dir = base_dir.join(&config.asset_dir);
+    // By default the bundle is written to the build script's `OUT_DIR`, which is the only dir that
+    // build scripts should write to, and is linked next to the executable once it has been built
+    let build_out_dir = PathBuf::from(std::env::var("OUT_DIR").unwrap());
+    let out_dir = match &config.out_dir {
+        Some(out_dir) => base_dir.join(out_dir),
+        None => build_out_dir.clone(),
+    };
+    let bundle_file_name = format!("{}.{}", config.file_name, file_extension);
+    let bundle_file = out_dir.join(&bundle_file_name);
+    std::fs::create_dir_all(&out_dir).unwrap();
+
+    // The constants are needed to compile the crate, so they are generated even when the assets
+    // aren't bundled
+    if config.asset_paths {
+        write_asset_paths(&asset_dir, &build_out_dir.join(ASSET_PATHS_FILE));
+    }
+
+    if profile == "release" || config.bundle_for_debug_builds {
+        // Unlink the bundle from the last build first, so that the bundle next to the executable
+        // isn't overwritten while the new one is being written
+        if config.out_dir.is_none() {
+            std::fs::remove_file(&bundle_file).ok();
+        }
 
-    if profile == "release" || config.bundle_for_debug_builds == true {
-        bundle_assets(
+        bundle_assets_with_options(
             asset_dir,
-            bundle_file,
-            config.obfuscate,
-            config.compression.into(),
+            bundle_file.clone(),
+            &BundleOptions {
+                obfuscate: config.obfuscate,
+                obfuscate_entries: config.obfuscate_entries,
+                entry_salt: config.entry_salt.map(String::into_bytes),
+                compression: config
+                    .compression
+                    .map(Into::into)
+                    .unwrap_or(DEFAULT_COMPRESSION),
+                pack_threshold: config.pack_threshold,
+                pack_block_size: config.pack_block_size,
+                align: config.align,
+                format: config.format,
+                write_index: config.write_index,
+                collect_credits: config.collect_credits,
+                credits_patterns: config.credits_patterns,
+                validate_references: config.validate_references,
+                gltf_dependencies: config.gltf_dependencies,
+                pipeline: config.pipeline,
+                steps: steps::registered_steps(),
+                solid: config.solid,
+                solid_frame_size: config.solid_frame_size,
+                solid_level: config.solid_level,
+                asset_metadata: config.asset_metadata,
+                unix_permissions: config.unix_permissions,
+                timestamps: config.timestamps,
+                comment: config.comment,
+                previous_bundle: config.previous_bundle,
+                keep_assets: config.keep_assets,
+                renames,
+                extra_asset_dirs,
+                merge_conflicts: config.merge_conflicts,
+                groups,
+                stats_file: config.stats_file,
+                max_bundle_size: config.max_bundle_size.as_deref().map(|x| {
+                    stats::parse_size(x)
+                        .unwrap_or_else(|| panic!("Invalid max-bundle-size `{}`", x))
+                }),
+                max_bundle_size_action: config.max_bundle_size_action,
+            },
         );
+
+        // Pass the hash to the game crate so that the plugin can check the bundle at startup
+        if config.embed_bundle_hash {
+            let hash = bundle_hash(&bundle_file).unwrap();
+            println!("cargo:rustc-env={}={}", BUNDLE_HASH_ENV_VAR, hash);
+        }
+
+        if config.out_dir.is_none() {
+            let profile_dir = workspace::profile_dir(&build_out_dir)
+                .expect("Could not find the profile dir from the build script's OUT_DIR");
+            let mut files = vec![bundle_file_name];
+            if config.write_index {
+                files.push(format!("{}.idx", config.file_name));
+            }
+            for file in files {
+                workspace::link_or_copy(&out_dir.join(&file), &profile_dir.join(&file))
+                    .unwrap_or_else(|e| {
+                        panic!(
+                            "Could not copy {} to {}: {}",
+                            file,
+                            profile_dir.display(),
+                            e
+                        )
+                    });
+            }
+        }
+    }
+}
+
+/// The default target size of the packed blocks that small files are aggregated into
+pub const DEFAULT_PACK_BLOCK_SIZE: u64 = 1024 * 1024;
+
+/// The zip entry that contains the index of the files in the packed blocks
+///
+/// Each line of the index has the form `<block>\t<offset>\t<length>\t<path>`, where `block` is the
+/// number of the block entry, named `.packs/<block>`, that contains the file data.
+pub const PACK_INDEX_ENTRY: &str = ".packs/index";
+
+/// A zip entry that marks that the data of every other entry in the bundle is obfuscated
+///
+/// The entry contains the random bundle salt that the key of each entry is derived from, along with
+/// the entry name. Bundles where this entry is empty XOR the data of every entry with the same
+/// byte.
+pub const OBFUSCATED_ENTRIES_MARKER: &str = ".obfuscated-entries";
+
+/// The zip entry that contains the modification time and content hash of each bundled asset
+///
+/// Each line of the entry has the form `<modified>\t<hash>\t<path>`, where `modified` is the
+/// modification time of the asset file in seconds since the Unix epoch and `hash` is the hex CRC-32
+/// of the bundled asset data.
+pub const METADATA_ENTRY: &str = ".metadata";
+
+/// The zip entry that summarizes the assets added, removed, and changed since the
+/// [`previous_bundle`][BundleOptions::previous_bundle]
+///
+/// The entry is a JSON object with `added`, `removed`, and `changed` arrays, sorted by path. Added
+/// and removed assets have a `path` and a `size`, and changed assets have a `path`, an `old_size`,
+/// and a `new_size`, with the sizes in bytes.
+pub const CHANGELOG_ENTRY: &str = "changelog.json";
+
+/// The file in the crate root that [`bundle_crate_assets`] reads the asset groups from
+///
+/// The file maps each group name to a list of glob patterns of the assets in the group, such as
+/// `level_2 = ["levels/2/**", "music/boss.ogg"]`.
+pub const GROUPS_FILE: &str = "groups.toml";
+
+/// The zip entry that contains the [`groups`][BundleOptions::groups] of the bundle
+///
+/// Each line of the entry has the form `<group>\t<pattern>`, with one line for every glob pattern
+/// of the group.
+pub const GROUPS_ENTRY: &str = ".groups";
+
+/// The name of the files that exclude assets from the bundle
+///
+/// The files use the `.gitignore` syntax and can be placed in the asset dir or any of its
+/// subdirectories, where their patterns are relative to the directory they are in. Patterns
+/// without a slash match files and directories at any depth, a trailing slash only matches
+/// directories, and a leading `!` includes paths excluded by an earlier pattern again. The files
+/// themselves are never bundled.
+pub const ASSET_IGNORE_FILE: &str = ".assetignore";
+
+/// The magic bytes at the start of [`BundleFormat::V2`] bundles
+pub const HEADER_MAGIC: &[u8; 4] = b"BAZB";
+
+/// The format version written in the header of [`BundleFormat::V2`] bundles
+pub const FORMAT_VERSION: u16 = 2;
+
+/// The length of the header of [`BundleFormat::V2`] bundles, before any alignment padding
+pub const HEADER_LEN: usize = 20;
+
+/// Header flag set when the zip data is obfuscated
+pub const FLAG_OBFUSCATED: u16 = 1;
+/// Header flag set when the zip data is encrypted
+pub const FLAG_ENCRYPTED: u16 = 1 << 1;
+/// Header flag set when the entries in the zip data are compressed
+pub const FLAG_COMPRESSED: u16 = 1 << 2;
+/// Header flag set when the zip data is wrapped in a seekable zstd stream
+pub const FLAG_SOLID: u16 = 1 << 3;
+/// Header flag set when the header is followed by a key slot describing how the data of the
+/// entries is obfuscated
+pub const FLAG_KEY_SLOT: u16 = 1 << 4;
+
+/// Key slot algorithm id of entries that are XOR-ed with the same byte
+pub const KEY_ALGORITHM_XOR: u8 = 1;
+/// Key slot algorithm id of entries that are XOR-ed with a keystream derived from the salt and
+/// the entry name
+pub const KEY_ALGORITHM_KEYSTREAM: u8 = 2;
+/// Key slot id of the key derivation that hashes the salt and entry name with FNV-1a and expands
+/// the hash with SplitMix64
+pub const KDF_FNV1A_SPLITMIX: u8 = 1;
+
+/// The default size in bytes of the uncompressed data in each frame of solid bundles
+pub const DEFAULT_SOLID_FRAME_SIZE: u64 = 4 * 1024 * 1024;
+/// The compression used when none is set, which is the best of the enabled compression features:
+/// bzip2, then deflate, then none
+#[cfg(feature = "bzip2")]
+pub const DEFAULT_COMPRESSION: CompressionMethod = CompressionMethod::Bzip2;
+/// The compression used when none is set, which is the best of the enabled compression features:
+/// bzip2, then deflate, then none
+#[cfg(all(not(feature = "bzip2"), feature = "deflate"))]
+pub const DEFAULT_COMPRESSION: CompressionMethod = CompressionMethod::Deflated;
+/// The compression used when none is set, which is the best of the enabled compression features:
+/// bzip2, then deflate, then none
+#[cfg(not(any(feature = "bzip2", feature = "deflate")))]
+pub const DEFAULT_COMPRESSION: CompressionMethod = CompressionMethod::Stored;
+
+/// The default zstd compression level of solid bundles
+pub const DEFAULT_SOLID_LEVEL: i32 = 19;
+
+/// The magic number of the zstd skippable frame that contains the seek table of solid bundles
+#[cfg(feature = "solid")]
+const SKIPPABLE_FRAME_MAGIC: u32 = 0x184D_2A5E;
+/// The magic number at the end of the seek table of solid bundles
+#[cfg(feature = "solid")]
+const SEEKABLE_MAGIC: u32 = 0x8F92_EAB1;
+
+/// The size of the chunks that asset files are copied into the bundle in
+const COPY_CHUNK_SIZE: usize = 64 * 1024;
+
+/// The file format to write the bundle in
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(feature = "bundle-crate-assets", serde(rename_all = "lowercase"))]
+pub enum BundleFormat {
+    /// A plain zip file, or an obfuscated zip file when obfuscation is enabled
+    Zip,
+    /// A zip file preceded by a header with the magic bytes [`HEADER_MAGIC`], the
+    /// [`FORMAT_VERSION`], flags describing how the zip data is encoded, the length of the header,
+    /// and the offset of the zip central directory
+    ///
+    /// When the [entries are obfuscated][BundleOptions::obfuscate_entries], the header also has a
+    /// key slot, flagged with [`FLAG_KEY_SLOT`], so that the runtime can configure itself from the
+    /// bundle. The key slot has the algorithm id, the key derivation id, the length of the salt as
+    /// a little-endian `u16`, and the salt, which is empty when the
+    /// [`entry_salt`][BundleOptions::entry_salt] is supplied by the game.
+    ///
+    /// The header is never obfuscated, so the runtime can always tell how to read the bundle.
+    V2,
+}
+
+impl Default for BundleFormat {
+    fn default() -> Self {
+        Self::Zip
+    }
+}
+
+/// The modification times to record for the entries of a bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(feature = "bundle-crate-assets", serde(rename_all = "kebab-case"))]
+pub enum TimestampPolicy {
+    /// Record 1980-01-01, the earliest time that zip files can store, for every entry, and record
+    /// zero for the modification times in the [`METADATA_ENTRY`], so that bundling the same assets
+    /// always produces the same bundle
+    Zero,
+    /// Record the modification time of each asset file for its entry, which is also recorded in
+    /// the [`METADATA_ENTRY`] when [`asset_metadata`][BundleOptions::asset_metadata] is enabled
+    Preserve,
+    /// Record the time that the bundle was built for every entry
+    BuildTime,
+}
+
+impl Default for TimestampPolicy {
+    fn default() -> Self {
+        Self::BuildTime
+    }
+}
+
+/// What to do when a bundle is larger than the
+/// [`max_bundle_size`][BundleOptions::max_bundle_size]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(feature = "bundle-crate-assets", serde(rename_all = "lowercase"))]
+pub enum SizeLimitAction {
+    /// Panic, failing the build script
+    Fail,
+    /// Report the size as a warning
+    Warn,
+}
+
+impl Default for SizeLimitAction {
+    fn default() -> Self {
+        Self::Fail
+    }
+}
+
+/// The number of the largest entries listed when a bundle is larger than the
+/// [`max_bundle_size`][BundleOptions::max_bundle_size]
+const LARGEST_ENTRIES_LISTED: usize = 10;
+
+/// The identifier at the start of the first line of the sidecar index file
+///
+/// The first line of the index has the form `bevy_assetio_zip-index\t<version>\t<size>\t<crc32>`,
+/// where `size` is the size of the zip data and `crc32` is the checksum of its last 22 bytes, which
+/// are its end of central directory record unless the bundle has a comment. Each following line
+/// has the form
+/// `<data start>\t<compressed size>\t<size>\t<crc32>\t<compression>\t<name>`.
+pub const INDEX_MAGIC: &str = "bevy_assetio_zip-index";
+
+/// The version of the sidecar index file format
+pub const INDEX_VERSION: u32 = 1;
+
+/// The zip entry that the collected license and credits files are written to
+pub const CREDITS_ENTRY: &str = "credits.txt";
+
+/// The glob patterns of the license and credits files collected by default
+pub const DEFAULT_CREDITS_PATTERNS: &[&str] = &["**/LICENSE*", "**/CREDITS*"];
+
+/// The options used to match the credits patterns, which ignore case so that both `LICENSE` and
+/// `License.txt` are found
+const CREDITS_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: false,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// The options used to match the patterns of the asset groups and of the kept assets, where `*`
+/// does not match across directories but `**` does, the same as at runtime
+const GROUP_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// Options used when bundling assets
+#[derive(Debug, Clone)]
+pub struct BundleOptions {
+    /// Whether or not to obfuscate the bundle
+    pub obfuscate: bool,
+    /// Whether or not to obfuscate the data of each entry while leaving the zip structure intact
+    ///
+    /// This allows standard zip tools to list the contents of the bundle for debugging while the
+    /// contents of the files remain scrambled. Each file is scrambled with its own key, derived
+    /// from its name and a random salt for the bundle.
+    pub obfuscate_entries: bool,
+    /// The salt to derive the keys of [obfuscated entries][Self::obfuscate_entries] from instead
+    /// of a random one
+    ///
+    /// The salt is left out of the bundle, so the game has to supply it with a `KeyProvider`
+    /// to read the bundle. Tools that read bundles, such as `verify` and `extract`, can't decode
+    /// these bundles.
+    pub entry_salt: Option<Vec<u8>>,
+    /// The compression method to use for the bundle entries
+    pub compression: CompressionMethod,
+    /// Files smaller than this number of bytes will be aggregated into packed blocks that are
+    /// compressed as a unit. Set to `0` to disable packing.
+    pub pack_threshold: u64,
+    /// The target size in bytes of each packed block
+    pub pack_block_size: u64,
+    /// When the compression method is [`CompressionMethod::Stored`], pad the entries so that the
+    /// data of each file starts at an offset that is a multiple of this alignment, allowing the
+    /// runtime to serve them directly from a memory map. Values of `0` or `1` disable alignment.
+    pub align: u16,
+    /// The file format to write the bundle in
+    pub format: BundleFormat,
+    /// Whether or not to write a sidecar index of the bundle entries to a file next to the bundle
+    /// with an `.idx` extension
+    ///
+    /// The runtime reads this small file instead of parsing the zip central directory, which makes
+    /// startup instant even for bundles with a huge number of files.
+    pub write_index: bool,
+    /// Whether or not to collect the license and credits files in the asset dir into a single
+    /// [`CREDITS_ENTRY`] in the bundle
+    ///
+    /// This makes it easy to ship the attribution required by third-party assets along with the
+    /// assets themselves. The collected files are still bundled as regular assets too.
+    pub collect_credits: bool,
+    /// Glob patterns of the paths, relative to the asset dir, of the files to collect when
+    /// [`collect_credits`][Self::collect_credits] is enabled
+    ///
+    /// Patterns are matched case-insensitively, `*` matches within a single directory, and `**`
+    /// matches across directories. Defaults to [`DEFAULT_CREDITS_PATTERNS`].
+    pub credits_patterns: Vec<String>,
+    /// Whether or not to check that the asset paths referenced by the `.scn` and `.ron` files in
+    /// the asset dir exist, panicking with a list of the missing assets if they don't
+    ///
+    /// Any string in those files without whitespace that ends in the extension of one of the
+    /// bundled files is treated as an asset path, relative to the asset dir. Labels such as the
+    /// `#Mesh0` in `models/tree.gltf#Mesh0` are ignored.
+    pub validate_references: bool,
+    /// Whether or not to make sure that the buffers and images referenced by the `.gltf` files in
+    /// the asset dir are bundled along with them
+    ///
+    /// References to files outside of the asset dir can't be bundled and are reported as warnings,
+    /// as are references to missing files.
+    pub gltf_dependencies: bool,
+    /// The conversion steps to run over the files before they are bundled, such as converting
+    /// textures to a GPU compressed format or audio to a compressed format
+    ///
+    /// Converted files are never written to packed blocks.
+    pub pipeline: Vec<PipelineStep>,
+    /// The custom [`BundleStep`]s to run over the files before they are bundled, in order, after
+    /// the [`pipeline`][Self::pipeline]
+    ///
+    /// [`bundle_crate_assets`] uses the steps registered with [`register_step`].
+    pub steps: Vec<Arc<dyn BundleStep>>,
+    /// Whether or not to store the entries uncompressed and compress the whole zip data with zstd
+    ///
+    /// Solid compression shrinks bundles of many similar files far more than compressing each
+    /// entry on its own. The data is compressed as a sequence of independent frames followed by a
+    /// seek table in the zstd seekable format, so that the runtime only has to decompress the
+    /// frames containing the assets that are loaded. Solid bundles are always written in the
+    /// [`BundleFormat::V2`] format and require the `solid` feature of `bevy_assetio_zip`.
+    pub solid: bool,
+    /// The size in bytes of the uncompressed data in each frame of solid bundles
+    ///
+    /// Larger frames compress better, but the runtime decompresses a whole frame to read any part
+    /// of it.
+    pub solid_frame_size: u64,
+    /// The zstd compression level of solid bundles, from 1 to 22
+    pub solid_level: i32,
+    /// Whether or not to record the modification time and content hash of each asset in the
+    /// [`METADATA_ENTRY`], so that games can tell when a cached asset is out of date
+    pub asset_metadata: bool,
+    /// Whether or not to record the unix permissions of each asset file in its entry, so that the
+    /// executable bits of scripts and tool payloads are restored by [`extract`]
+    ///
+    /// Only applies when bundling on unix. Files with an executable bit are never packed, since
+    /// the assets in packed blocks have no entries of their own to record permissions in.
+    pub unix_permissions: bool,
+    /// The modification times recorded for the entries of the bundle
+    pub timestamps: TimestampPolicy,
+    /// The archive comment to store in the bundle, such as a build id or git commit, which can be
+    /// read with [`comment`] or at runtime to include in crash reports
+    pub comment: Option<String>,
+    /// The path to the previous version of the bundle, which is compared with the new bundle to
+    /// write the [`CHANGELOG_ENTRY`]
+    ///
+    /// No changelog is written if there is no bundle at the path, such as for the first release.
+    /// The path may be the same as the path of the new bundle, in which case it is read before it
+    /// is overwritten.
+    pub previous_bundle: Option<PathBuf>,
+    /// Glob patterns of the assets that must not be removed, such as assets referenced by save
+    /// games, which are checked against the assets in the
+    /// [`previous_bundle`][Self::previous_bundle]
+    ///
+    /// Bundling panics with a list of the removed assets if any asset in the previous bundle that
+    /// matches one of the patterns is missing from the new bundle. Use `**` to keep every asset.
+    pub keep_assets: Vec<String>,
+    /// The paths in the bundle of assets whose paths differ from their paths in the asset dir,
+    /// mapped from their paths in the asset dir
+    ///
+    /// A path in the asset dir renames either the asset at that path or the directory at that path,
+    /// along with everything in it, and the longest matching path is used. Renaming a directory to
+    /// an empty path moves its contents to the root of the bundle. Every other option that matches
+    /// asset paths, except for `.assetignore` files, is matched against the renamed paths.
+    /// References between assets are not rewritten. Assets renamed to the same path are resolved
+    /// with the [`merge_conflicts`][Self::merge_conflicts] policy.
+    pub renames: BTreeMap<String, String>,
+    /// More asset dirs whose files are bundled along with the files of the asset dir, such as
+    /// assets shared between several games
+    ///
+    /// Directories found in several asset dirs are merged, and files at the same path with the
+    /// same content are only bundled once.
+    pub extra_asset_dirs: Vec<PathBuf>,
+    /// What to do when the asset dirs have different files at the same path in the bundle, where
+    /// the asset dir comes first and the [`extra_asset_dirs`][Self::extra_asset_dirs] follow in
+    /// order
+    pub merge_conflicts: ConflictPolicy,
+    /// Named groups of assets, such as the assets needed by each level, mapped to glob patterns of
+    /// the paths of the assets in each group
+    ///
+    /// The groups are written to the [`GROUPS_ENTRY`] so that the game can preload every asset in a
+    /// group at once. Patterns are matched against the paths of the bundled assets, after they
+    /// have been converted by the [`pipeline`][Self::pipeline]. Groups that don't match any asset
+    /// are reported as warnings.
+    pub groups: BTreeMap<String, Vec<String>>,
+    /// The path to write [`BundleStats`] about the new bundle to as JSON, including how long it
+    /// took to build, so that CI can graph the growth of the asset size over time
+    pub stats_file: Option<PathBuf>,
+    /// The maximum size in bytes of the bundle file, to catch assets such as uncompressed source
+    /// art that were bundled by accident
+    ///
+    /// When the bundle is larger, the largest entries in it are listed and the
+    /// [`max_bundle_size_action`][Self::max_bundle_size_action] is taken.
+    pub max_bundle_size: Option<u64>,
+    /// What to do when the bundle is larger than the [`max_bundle_size`][Self::max_bundle_size]
+    pub max_bundle_size_action: SizeLimitAction,
+}
+
+impl Default for BundleOptions {
+    fn default() -> Self {
+        Self {
+            obfuscate: false,
+            obfuscate_entries: false,
+            entry_salt: None,
+            compression: DEFAULT_COMPRESSION,
+            pack_threshold: 0,
+            pack_block_size: DEFAULT_PACK_BLOCK_SIZE,
+            align: 0,
+            format: BundleFormat::Zip,
+            write_index: false,
+            collect_credits: false,
+            credits_patterns: default_credits_patterns(),
+            validate_references: false,
+            gltf_dependencies: true,
+            pipeline: Vec::new(),
+            steps: Vec::new(),
+            solid: false,
+            solid_frame_size: DEFAULT_SOLID_FRAME_SIZE,
+            solid_level: DEFAULT_SOLID_LEVEL,
+            asset_metadata: false,
+            unix_permissions: false,
+            timestamps: TimestampPolicy::BuildTime,
+            comment: None,
+            previous_bundle: None,
+            keep_assets: Vec::new(),
+            renames: BTreeMap::new(),
+            extra_asset_dirs: Vec::new(),
+            merge_conflicts: ConflictPolicy::Error,
+            groups: BTreeMap::new(),
+            stats_file: None,
+            max_bundle_size: None,
+            max_bundle_size_action: SizeLimitAction::Fail,
+        }
+    }
+}
+
+/// The name of the file in the build script's `OUT_DIR` that [`bundle_crate_assets`] writes the
+/// asset path constants to when `asset-paths` is enabled in the `asset_config.toml`
+pub const ASSET_PATHS_FILE: &str = "asset_paths.rs";
+
+/// Write a Rust source file to `out_file` with a `pub const` for the path of every asset in
+/// `asset_dir`
+///
+/// The name of each constant is the path of the asset in upper snake case, so `textures/grass.png`
+/// becomes `TEXTURES_GRASS_PNG`. Include the file in a module to have asset paths checked at
+/// compile time:
+///
+/// ```ignore
+/// mod asset_paths {
+///     include!(concat!(env!("OUT_DIR"), "/asset_paths.rs"));
+/// }
+///
+/// let texture = asset_server.load(asset_paths::TEXTURES_GRASS_PNG);
+/// ```
+///
+/// Panics if two assets have paths that map to the same constant name.
+pub fn write_asset_paths<P: AsRef<Path>>(asset_dir: P, out_file: P) {
+    let asset_dir = asset_dir.as_ref();
+    let mut names = ignore::walk_assets(asset_dir)
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| entry_name(entry.path().strip_prefix(asset_dir).unwrap()))
+        .collect::<Vec<_>>();
+    names.sort();
+
+    let mut source = String::from("// Generated by bevy_assetio_zip_bundler. Do not edit.\n");
+    let mut consts = HashMap::new();
+    for name in &names {
+        let const_name = asset_path_const_name(name);
+        if let Some(other) = consts.insert(const_name.clone(), name) {
+            panic!(
+                "Assets {} and {} both have the asset path constant name {}",
+                other, name, const_name
+            );
+        }
+
+        source.push_str(&format!(
+            "\n/// `{}`\n#[allow(dead_code)]\npub const {}: &str = {:?};\n",
+            name, const_name, name
+        ));
+    }
+
+    std::fs::write(out_file, source).expect("Could not write asset paths file");
+}
+
+/// Get the name of the constant for the given asset path, such as `TEXTURES_GRASS_PNG` for
+/// `textures/grass.png`
+fn asset_path_const_name(name: &str) -> String {
+    let mut const_name = name
+        .chars()
+        .map(|x| {
+            if x.is_ascii_alphanumeric() {
+                x.to_ascii_uppercase()
+            } else {
+                '_'
+            }
+        })
+        .collect::<String>();
+
+    // Identifiers can't start with a digit
+    if const_name.starts_with(|x: char| x.is_ascii_digit()) {
+        const_name.insert(0, '_');
     }
+
+    const_name
+}
+
+/// Get the [`DEFAULT_CREDITS_PATTERNS`] as owned strings
+fn default_credits_patterns() -> Vec<String> {
+    DEFAULT_CREDITS_PATTERNS
+        .iter()
+        .map(|&x| x.to_owned())
+        .collect()
 }
 
 /// Bundle the assets in the given `asset_dir` and write the result to `bundle_file`.
@@ -112,60 +834,1089 @@ pub fn bundle_assets<P: AsRef<Path>>(
     obfuscate: bool,
     compression: CompressionMethod,
 ) {
+    bundle_assets_with_options(
+        asset_dir,
+        bundle_file,
+        &BundleOptions {
+            obfuscate,
+            compression,
+            ..Default::default()
+        },
+    );
+}
+
+/// Bundle the assets in the given `asset_dir` and write the result to `bundle_file` using the given
+/// [`BundleOptions`].
+pub fn bundle_assets_with_options<P: AsRef<Path>>(
+    asset_dir: P,
+    bundle_file: P,
+    options: &BundleOptions,
+) {
+    let start = Instant::now();
+
     // Bundle assets
-    zip_dir(
-        asset_dir.as_ref(),
-        bundle_file.as_ref(),
-        compression.into(),
-        obfuscate,
+    zip_dir(asset_dir.as_ref(), bundle_file.as_ref(), options);
+
+    if let Some(stats_file) = &options.stats_file {
+        let mut stats = stats(bundle_file.as_ref()).expect("Could not read the new bundle");
+        stats.build_duration = Some(start.elapsed());
+        std::fs::write(stats_file, stats.to_json()).expect("Could not write the bundle stats");
+    }
+
+    if let Some(max_size) = options.max_bundle_size {
+        check_bundle_size(
+            bundle_file.as_ref(),
+            max_size,
+            options.max_bundle_size_action,
+        );
+    }
+}
+
+/// Take the given action if the bundle is larger than `max_size`, listing its largest entries
+fn check_bundle_size(bundle_file: &Path, max_size: u64, action: SizeLimitAction) {
+    let size = std::fs::metadata(bundle_file).unwrap().len();
+    if size <= max_size {
+        return;
+    }
+
+    let mut entries = BundleReader::open(bundle_file)
+        .and_then(|mut x| x.entry_sizes())
+        .expect("Could not read the new bundle");
+    entries.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+
+    let mut message = format!(
+        "Asset bundle {} is {}, which is larger than the maximum of {}. The largest entries are:",
+        bundle_file.display(),
+        stats::format_size(size),
+        stats::format_size(max_size)
     );
+    for (name, compressed_size, _) in entries.iter().take(LARGEST_ENTRIES_LISTED) {
+        message.push_str(&format!(
+            "\n  {} ({})",
+            name,
+            stats::format_size(*compressed_size)
+        ));
+    }
+
+    match action {
+        SizeLimitAction::Fail => panic!("{}", message),
+        // Each line of a cargo warning needs its own prefix
+        SizeLimitAction::Warn => message.lines().for_each(warn),
+    }
 }
 
 trait WriteSeek: Seek + Write {}
 impl<T: Seek + Write> WriteSeek for T {}
 
-fn zip_dir<P: AsRef<Path>>(
-    source_dir: P,
-    target_file: P,
-    compression: CompressionMethod,
-    obfuscate: bool,
-) {
+trait ReadSeek: Seek + Read {}
+impl<T: Seek + Read> ReadSeek for T {}
+
+fn zip_dir<P: AsRef<Path>>(source_dir: P, target_file: P, options: &BundleOptions) {
     let source_dir = source_dir.as_ref();
-    let walkdir = WalkDir::new(source_dir);
-    let archive_file = File::create(target_file.as_ref()).expect("Could not create archive file");
-    let writer: Box<dyn WriteSeek> = if obfuscate {
-        Box::new(Xor::new(archive_file))
+
+    if options.solid && !cfg!(feature = "solid") {
+        panic!("Solid bundles require the `solid` feature of `bevy_assetio_zip_bundler`");
+    }
+
+    // Solid bundles store the entries uncompressed and need the v2 header to be recognized
+    let solid_options;
+    let options = if options.solid {
+        solid_options = BundleOptions {
+            compression: CompressionMethod::Stored,
+            format: BundleFormat::V2,
+            ..options.clone()
+        };
+        &solid_options
     } else {
-        Box::new(archive_file)
+        options
+    };
+
+    let roots = std::iter::once(source_dir)
+        .chain(options.extra_asset_dirs.iter().map(PathBuf::as_path))
+        .collect::<Vec<_>>();
+
+    if options.validate_references {
+        validate_references(&roots, &options.renames);
+    }
+
+    // The previous bundle is read before the new one is written, since it may be at the same path
+    let previous_assets = options.previous_bundle.as_ref().and_then(|path| {
+        if path.exists() {
+            Some(diff::asset_hashes(path))
+        } else {
+            warn(&format!(
+                "Previous bundle {} doesn't exist, no changelog will be written",
+                path.display()
+            ));
+            None
+        }
+    });
+
+    let overridden = merge::overridden_files(&roots, options);
+    let walkdir = roots
+        .iter()
+        .flat_map(|&root| ignore::walk_assets(root).map(move |entry| (root, entry)));
+    let mut archive_file = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(target_file.as_ref())
+        .expect("Could not create archive file");
+
+    let salt = match (options.obfuscate_entries, &options.entry_salt) {
+        (true, Some(salt)) => Some(salt.clone()),
+        (true, None) => Some(keys::bundle_salt()),
+        (false, _) => None,
+    };
+    let salt = salt.as_deref();
+    let key_slot = key_slot(salt, options);
+
+    // Reserve space for the header, which is written once the zip data is finished
+    let header_len = match options.format {
+        BundleFormat::Zip => 0,
+        BundleFormat::V2 => v2_header_len(options, key_slot.len()),
+    };
+    archive_file.write_all(&vec![0; header_len]).unwrap();
+
+    let zip_file = OffsetFile::new(&mut archive_file, header_len as u64);
+    let writer: Box<dyn WriteSeek + '_> = if options.obfuscate {
+        Box::new(wrap_writer(zip_file))
+    } else {
+        Box::new(zip_file)
     };
     let buf_writer = BufWriter::new(writer);
 
     let mut zip = ZipWriter::new(buf_writer);
-    let options = FileOptions::default().compression_method(compression);
+    let file_options = FileOptions::default().compression_method(options.compression);
+    let file_options = match options.timestamps {
+        TimestampPolicy::Zero => file_options.last_modified_time(DateTime::default()),
+        TimestampPolicy::Preserve | TimestampPolicy::BuildTime => file_options,
+    };
+
+    let credits_patterns = options
+        .credits_patterns
+        .iter()
+        .map(|x| Pattern::new(x).expect("Invalid credits pattern"))
+        .collect::<Vec<_>>();
+    let mut credits = String::new();
+    let mut metadata = String::new();
+    // The size and CRC-32 of every bundled asset, which are compared with the previous bundle
+    let mut assets = BTreeMap::new();
+    let mut record = |path: &Path, name: &str, size: u64, hash: u32| {
+        if options.asset_metadata {
+            metadata.push_str(&metadata_line(path, name, hash, options.timestamps));
+        }
+        assets.insert(name.to_owned(), (size, hash));
+    };
 
-    let mut buffer = Vec::new();
-    for entry in walkdir {
+    let pipeline = Pipeline::new(&options.pipeline);
+    let mut packed_files = Vec::new();
+    let mut bundled_files = HashSet::new();
+    // The directories already written, which may be found in several asset dirs
+    let mut dirs = HashSet::new();
+    let mut gltf_files = Vec::new();
+    for (root, entry) in walkdir {
         let entry = entry.unwrap();
         let path = entry.path();
-        let name = path.strip_prefix(source_dir).unwrap();
+        let name = path.strip_prefix(root).unwrap();
 
         // Write file or directory explicitly
         // Some unzip tools unzip files with directory paths correctly, some do not!
         if path.is_file() {
-            #[allow(deprecated)]
-            zip.start_file_from_path(name, options).unwrap();
-            let mut f = File::open(path).unwrap();
-
-            f.read_to_end(&mut buffer).unwrap();
-            zip.write_all(&*buffer).unwrap();
-            buffer.clear();
-        } else if name.as_os_str().len() != 0 {
+            if overridden.contains(path) {
+                continue;
+            }
+
+            let entry_name = renamed(&entry_name(name), &options.renames);
+            if entry_name.is_empty() {
+                panic!("Asset {} is renamed to an empty path", name.display());
+            }
+
+            if options.collect_credits {
+                if credits_patterns
+                    .iter()
+                    .any(|x| x.matches_with(&entry_name, CREDITS_MATCH_OPTIONS))
+                {
+                    credits.push_str(&format!("== {} ==\n\n", entry_name));
+                    credits.push_str(std::fs::read_to_string(path).unwrap().trim_end());
+                    credits.push_str("\n\n");
+                }
+
+                // The collected credits take the place of a top-level credits file
+                if entry_name == CREDITS_ENTRY {
+                    continue;
+                }
+            }
+
+            // The generated changelog takes the place of a top-level changelog file
+            if previous_assets.is_some() && entry_name == CHANGELOG_ENTRY {
+                continue;
+            }
+
+            bundled_files.insert(name.to_owned());
+            if path.extension().map(|x| x == "gltf").unwrap_or(false) {
+                gltf_files.push((root, path.to_owned()));
+            }
+
+            let file_options = asset_file_options(path, options, file_options);
+
+            let file = match pipeline.run(path, &entry_name) {
+                Some((name, data)) => BundleFile::from_path(name, path, Some(data)),
+                None => BundleFile::from_path(entry_name, path, None),
+            };
+            let mut file = match steps::run(&options.steps, file) {
+                Some(file) => file,
+                None => continue,
+            };
+
+            // Converted files are written directly since packed blocks are read from the asset dir
+            if let Some(data) = file.take_data() {
+                record(path, &file.name, data.len() as u64, crc32fast::hash(&data));
+                write_file(&mut zip, &file.name, &data, salt, options, file_options);
+                continue;
+            }
+            let entry_name = file.name;
+
+            // Small files are collected to be written to packed blocks later, except for
+            // executables whose permissions need an entry of their own
+            let executable = unix_mode(path, options)
+                .map(|x| x & 0o111 != 0)
+                .unwrap_or(false);
+            if entry.metadata().unwrap().len() < options.pack_threshold && !executable {
+                let data = std::fs::read(path).unwrap();
+                record(path, &entry_name, data.len() as u64, crc32fast::hash(&data));
+                packed_files.push((path.to_owned(), entry_name));
+                continue;
+            }
+
+            let (size, hash) = copy_file(&mut zip, &entry_name, path, salt, options, file_options);
+            record(path, &entry_name, size, hash);
+        } else if !name.as_os_str().is_empty() {
             // Only if not root! Avoids path spec / warning
             // and mapname conversion failed error on unzip
-            #[allow(deprecated)]
-            zip.add_directory_from_path(name, options).unwrap();
+            let dir_name = renamed(&entry_name(name), &options.renames);
+            if !dir_name.is_empty() && dirs.insert(dir_name.clone()) {
+                zip.add_directory(dir_name, file_options).unwrap();
+            }
         }
     }
 
-    zip.finish().unwrap();
+    // Bundle the glTF dependencies that weren't found while walking the asset dir
+    if options.gltf_dependencies {
+        for (root, gltf_file) in &gltf_files {
+            for name in gltf_dependencies(root, gltf_file) {
+                if bundled_files.insert(name.clone()) {
+                    let path = root.join(&name);
+                    let file_options = asset_file_options(&path, options, file_options);
+                    let name = renamed(&entry_name(&name), &options.renames);
+                    let file = match pipeline.run(&path, &name) {
+                        Some((name, data)) => BundleFile::from_path(name, &path, Some(data)),
+                        None => BundleFile::from_path(name, &path, None),
+                    };
+                    let mut file = match steps::run(&options.steps, file) {
+                        Some(file) => file,
+                        None => continue,
+                    };
+                    let (size, hash) = match file.take_data() {
+                        Some(data) => {
+                            write_file(&mut zip, &file.name, &data, salt, options, file_options);
+                            (data.len() as u64, crc32fast::hash(&data))
+                        }
+                        None => copy_file(&mut zip, &file.name, &path, salt, options, file_options),
+                    };
+                    record(&path, &file.name, size, hash);
+                }
+            }
+        }
+    }
+
+    // Bundle the files added by the custom steps
+    for step in &options.steps {
+        for mut file in step.added_files() {
+            let data = match file.take_data() {
+                Some(data) => data,
+                None => std::fs::read(file.path().unwrap()).unwrap(),
+            };
+            let path = file.path().unwrap_or_else(|| Path::new(""));
+            record(path, &file.name, data.len() as u64, crc32fast::hash(&data));
+            write_file(&mut zip, &file.name, &data, salt, options, file_options);
+        }
+    }
+
+    if !packed_files.is_empty() {
+        write_packed_blocks(&mut zip, &packed_files, salt, options, file_options);
+    }
+
+    if !credits.is_empty() {
+        zip.start_file(CREDITS_ENTRY, file_options).unwrap();
+        write_entry_data(&mut zip, CREDITS_ENTRY, credits.as_bytes(), salt);
+        let hash = (credits.len() as u64, crc32fast::hash(credits.as_bytes()));
+        assets.insert(CREDITS_ENTRY.to_owned(), hash);
+    }
+
+    if !options.groups.is_empty() {
+        let groups = groups_entry(&options.groups, assets.keys());
+        zip.start_file(GROUPS_ENTRY, file_options).unwrap();
+        write_entry_data(&mut zip, GROUPS_ENTRY, groups.as_bytes(), salt);
+    }
+
+    if let Some(mut previous_assets) = previous_assets {
+        previous_assets.remove(CHANGELOG_ENTRY);
+        check_kept_assets(&previous_assets, &assets, &options.keep_assets);
+        let changelog = diff::changelog_json(&diff::diff_hashes(previous_assets, assets));
+        zip.start_file(CHANGELOG_ENTRY, file_options).unwrap();
+        write_entry_data(&mut zip, CHANGELOG_ENTRY, changelog.as_bytes(), salt);
+    }
+
+    if options.asset_metadata {
+        zip.start_file(METADATA_ENTRY, file_options).unwrap();
+        write_entry_data(&mut zip, METADATA_ENTRY, metadata.as_bytes(), salt);
+    }
+
+    if let Some(salt) = stored_salt(salt, options) {
+        zip.start_file(OBFUSCATED_ENTRIES_MARKER, file_options)
+            .unwrap();
+        zip.write_all(salt).unwrap();
+    }
+
+    if let Some(comment) = &options.comment {
+        zip.set_comment(comment.clone());
+    }
+
+    zip.finish().unwrap().flush().unwrap();
+    drop(zip);
+
+    if options.format == BundleFormat::V2 {
+        write_v2_header(&mut archive_file, header_len, &key_slot, options);
+    }
+
+    if options.write_index {
+        let index_file = target_file.as_ref().with_extension("idx");
+        write_index(&mut archive_file, header_len, &index_file, options);
+    }
+
+    #[cfg(feature = "solid")]
+    if options.solid {
+        let temp_file = target_file.as_ref().with_extension("solid-tmp");
+        compress_solid(&mut archive_file, header_len, &temp_file, options);
+    }
+}
+
+/// Panic if any of the previous assets that match the `keep_assets` patterns are missing from the
+/// new assets
+fn check_kept_assets<T>(
+    previous_assets: &BTreeMap<String, T>,
+    assets: &BTreeMap<String, T>,
+    keep_assets: &[String],
+) {
+    let patterns = keep_assets
+        .iter()
+        .map(|x| Pattern::new(x).expect("Invalid keep-assets pattern"))
+        .collect::<Vec<_>>();
+
+    let removed = previous_assets
+        .keys()
+        .filter(|name| !assets.contains_key(*name))
+        .filter(|name| {
+            patterns
+                .iter()
+                .any(|x| x.matches_with(name, GROUP_MATCH_OPTIONS))
+        })
+        .map(|name| format!("\n  {}", name))
+        .collect::<String>();
+    if !removed.is_empty() {
+        panic!(
+            "Assets that must be kept were removed since the previous bundle:{}",
+            removed
+        );
+    }
+}
+
+/// The extensions of the files whose asset references are checked by [`validate_references`]
+const SCENE_EXTENSIONS: &[&str] = &["scn", "ron"];
+
+/// Panic if any of the `.scn` or `.ron` files in the asset dirs reference an asset that doesn't
+/// exist, where the scenes reference assets by their renamed paths
+fn validate_references(roots: &[&Path], renames: &BTreeMap<String, String>) {
+    let mut names = HashSet::new();
+    let mut scenes = Vec::new();
+    let walk = roots
+        .iter()
+        .flat_map(|&root| ignore::walk_assets(root).map(move |entry| (root, entry)));
+    for (root, entry) in walk {
+        let entry = entry.unwrap();
+        if !entry.file_type().is_file() {
+            continue;
+        }
+
+        let path = entry.path();
+        let is_scene = path
+            .extension()
+            .map(|x| SCENE_EXTENSIONS.iter().any(|ext| x == *ext))
+            .unwrap_or(false);
+        if is_scene {
+            scenes.push((root, path.to_owned()));
+        }
+        names.insert(renamed(
+            &entry_name(path.strip_prefix(root).unwrap()),
+            renames,
+        ));
+    }
+
+    let extensions = names
+        .iter()
+        .filter_map(|name| extension(name))
+        .collect::<HashSet<_>>();
+
+    let mut missing = Vec::new();
+    for (root, scene) in scenes {
+        let text = std::fs::read_to_string(&scene).unwrap();
+        for string in string_literals(&text) {
+            let path = string.split('#').next().unwrap_or(&string);
+            let is_asset_path = !path.contains(char::is_whitespace)
+                && extension(path).map_or(false, |ext| extensions.contains(ext));
+            if is_asset_path && !names.contains(path) {
+                missing.push(format!(
+                    "{} references missing asset {}",
+                    entry_name(scene.strip_prefix(root).unwrap()),
+                    path
+                ));
+            }
+        }
+    }
+
+    if !missing.is_empty() {
+        panic!("Found missing asset references:\n{}", missing.join("\n"));
+    }
+}
+
+/// Get the extension of the file name at the end of an entry name, if it has one
+fn extension(name: &str) -> Option<&str> {
+    let file_name = name.rsplit('/').next()?;
+    let mut parts = file_name.rsplitn(2, '.');
+    let extension = parts.next()?;
+    parts.next().map(|_| extension)
+}
+
+/// Get the contents of the double-quoted string literals in RON text, with escapes resolved
+fn string_literals(text: &str) -> Vec<String> {
+    let mut strings = Vec::new();
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            // Skip character literals so that `'"'` doesn't start a string
+            '\'' => {
+                if chars.next() == Some('\\') {
+                    chars.next();
+                }
+                chars.next();
+            }
+            '"' => {
+                let mut string = String::new();
+                while let Some(c) = chars.next() {
+                    match c {
+                        '"' => break,
+                        '\\' => string.extend(chars.next()),
+                        c => string.push(c),
+                    }
+                }
+                strings.push(string);
+            }
+            _ => (),
+        }
+    }
+
+    strings
+}
+
+/// Get the length of the v2 header with a key slot of the given length, padded so that entry
+/// alignment is preserved in the bundle file
+fn v2_header_len(options: &BundleOptions, key_slot_len: usize) -> usize {
+    let align = options.align.max(1) as usize;
+    (HEADER_LEN + key_slot_len + align - 1) / align * align
+}
+
+/// Get the key slot of the v2 header for the given entry salt, which is empty when the entries
+/// aren't obfuscated
+fn key_slot(salt: Option<&[u8]>, options: &BundleOptions) -> Vec<u8> {
+    let salt = match stored_salt(salt, options) {
+        Some(salt) => salt,
+        None => return Vec::new(),
+    };
+
+    let mut key_slot = vec![KEY_ALGORITHM_KEYSTREAM, KDF_FNV1A_SPLITMIX];
+    key_slot.extend_from_slice(&(salt.len() as u16).to_le_bytes());
+    key_slot.extend_from_slice(salt);
+    key_slot
+}
+
+/// Get the salt of the entry keystreams to store in the bundle, which is empty when the salt is
+/// supplied by the game instead
+fn stored_salt<'a>(salt: Option<&'a [u8]>, options: &BundleOptions) -> Option<&'a [u8]> {
+    if options.entry_salt.is_some() {
+        salt.map(|_| &[][..])
+    } else {
+        salt
+    }
+}
+
+/// Write the v2 header, followed by the given key slot, to the start of the finished bundle file
+fn write_v2_header(file: &mut File, header_len: usize, key_slot: &[u8], options: &BundleOptions) {
+    // The end of central directory record is the last 22 bytes of the zip data before the archive
+    // comment
+    let comment_len = options.comment.as_ref().map(String::len).unwrap_or(0);
+    let mut eocd = [0; 22];
+    file.seek(SeekFrom::End(-((eocd.len() + comment_len) as i64)))
+        .unwrap();
+    if options.obfuscate {
+        wrap_reader(&mut *file).read_exact(&mut eocd).unwrap();
+    } else {
+        file.read_exact(&mut eocd).unwrap();
+    }
+    let index_offset = u32::from_le_bytes([eocd[16], eocd[17], eocd[18], eocd[19]]) as u64;
+
+    let mut flags = 0;
+    if options.obfuscate {
+        flags |= FLAG_OBFUSCATED;
+    }
+    if options.compression != CompressionMethod::Stored {
+        flags |= FLAG_COMPRESSED;
+    }
+    if options.solid {
+        flags |= FLAG_SOLID;
+    }
+    if !key_slot.is_empty() {
+        flags |= FLAG_KEY_SLOT;
+    }
+
+    let mut header = Vec::with_capacity(HEADER_LEN + key_slot.len());
+    header.extend_from_slice(HEADER_MAGIC);
+    header.extend_from_slice(&FORMAT_VERSION.to_le_bytes());
+    header.extend_from_slice(&flags.to_le_bytes());
+    header.extend_from_slice(&(header_len as u32).to_le_bytes());
+    header.extend_from_slice(&index_offset.to_le_bytes());
+    header.extend_from_slice(key_slot);
+
+    file.seek(SeekFrom::Start(0)).unwrap();
+    file.write_all(&header).unwrap();
+}
+
+/// Replace the zip data of the finished bundle file with a seekable zstd stream of it
+///
+/// The zip data is compressed one frame at a time into `temp_file`, which is then copied over the
+/// zip data, so that only a frame of the bundle is held in memory at once.
+#[cfg(feature = "solid")]
+fn compress_solid(file: &mut File, header_len: usize, temp_file: &Path, options: &BundleOptions) {
+    let mut temp = OpenOptions::new()
+        .read(true)
+        .write(true)
+        .create(true)
+        .truncate(true)
+        .open(temp_file)
+        .expect("Could not create temporary solid compression file");
+
+    // Compress each chunk as an independent frame and record its sizes in the seek table
+    let mut seek_table = Vec::new();
+    let mut frame_count = 0u32;
+    {
+        file.seek(SeekFrom::Start(header_len as u64)).unwrap();
+        let mut reader: Box<dyn Read> = if options.obfuscate {
+            Box::new(wrap_reader(&mut *file))
+        } else {
+            Box::new(&mut *file)
+        };
+        let mut writer: Box<dyn Write> = if options.obfuscate {
+            Box::new(wrap_writer(&mut temp))
+        } else {
+            Box::new(&mut temp)
+        };
+
+        let frame_size = options.solid_frame_size.max(1);
+        let mut chunk = Vec::with_capacity(frame_size as usize);
+        loop {
+            chunk.clear();
+            (&mut reader)
+                .take(frame_size)
+                .read_to_end(&mut chunk)
+                .unwrap();
+            if chunk.is_empty() {
+                break;
+            }
+
+            let frame = zstd::bulk::compress(&chunk, options.solid_level).unwrap();
+            seek_table.extend_from_slice(&(frame.len() as u32).to_le_bytes());
+            seek_table.extend_from_slice(&(chunk.len() as u32).to_le_bytes());
+            writer.write_all(&frame).unwrap();
+            frame_count += 1;
+        }
+
+        // The seek table is stored in a skippable frame so that the data is still a valid zstd
+        // stream
+        let footer_len = 9;
+        let mut footer = Vec::new();
+        footer.extend_from_slice(&SKIPPABLE_FRAME_MAGIC.to_le_bytes());
+        footer.extend_from_slice(&((seek_table.len() + footer_len) as u32).to_le_bytes());
+        footer.extend_from_slice(&seek_table);
+        footer.extend_from_slice(&frame_count.to_le_bytes());
+        footer.push(0);
+        footer.extend_from_slice(&SEEKABLE_MAGIC.to_le_bytes());
+        writer.write_all(&footer).unwrap();
+    }
+
+    file.set_len(header_len as u64).unwrap();
+    file.seek(SeekFrom::Start(header_len as u64)).unwrap();
+    temp.seek(SeekFrom::Start(0)).unwrap();
+    io::copy(&mut temp, file).unwrap();
+
+    drop(temp);
+    std::fs::remove_file(temp_file).ok();
+}
+
+/// Write the sidecar index of the entries in the finished bundle file to `index_file`
+fn write_index(file: &mut File, header_len: usize, index_file: &Path, options: &BundleOptions) {
+    let zip_file = OffsetFile::new(file, header_len as u64);
+    let mut reader: Box<dyn ReadSeek + '_> = if options.obfuscate {
+        Box::new(wrap_reader(zip_file))
+    } else {
+        Box::new(zip_file)
+    };
+
+    // Identify the bundle by the size of its zip data and the checksum of its last 22 bytes, which
+    // hold the end of the central directory record and which the runtime can check without
+    // reading the rest of the bundle
+    let size = reader.seek(SeekFrom::End(0)).unwrap();
+    let mut eocd = [0; 22];
+    reader.seek(SeekFrom::End(-(eocd.len() as i64))).unwrap();
+    reader.read_exact(&mut eocd).unwrap();
+    let mut index = format!(
+        "{}\t{}\t{}\t{}\n",
+        INDEX_MAGIC,
+        INDEX_VERSION,
+        size,
+        crc32fast::hash(&eocd)
+    );
+
+    let mut zip = ZipArchive::new(reader).expect("Could not read back the asset bundle");
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).unwrap();
+        let compression = match entry.compression() {
+            CompressionMethod::Stored => "stored",
+            #[cfg(feature = "deflate")]
+            CompressionMethod::Deflated => "deflated",
+            #[cfg(feature = "bzip2")]
+            CompressionMethod::Bzip2 => "bzip2",
+            other => panic!("Unsupported compression method: {:?}", other),
+        };
+        index.push_str(&format!(
+            "{}\t{}\t{}\t{}\t{}\t{}\n",
+            entry.data_start(),
+            entry.compressed_size(),
+            entry.size(),
+            entry.crc32(),
+            compression,
+            entry.name()
+        ));
+    }
+
+    std::fs::write(index_file, index).expect("Could not write asset index file");
+}
+
+/// A reader and writer over the data in the inner file after the given offset, leaving room for a
+/// header
+struct OffsetFile<W> {
+    inner: W,
+    offset: u64,
+}
+
+impl<W: Seek> OffsetFile<W> {
+    fn new(mut inner: W, offset: u64) -> Self {
+        inner.seek(SeekFrom::Start(offset)).unwrap();
+        Self { inner, offset }
+    }
+}
+
+impl<W: Read> Read for OffsetFile<W> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        self.inner.read(buf)
+    }
+}
+
+impl<W: Write> Write for OffsetFile<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        self.inner.write(buf)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+impl<W: Seek> Seek for OffsetFile<W> {
+    fn seek(&mut self, pos: SeekFrom) -> io::Result<u64> {
+        let pos = match pos {
+            SeekFrom::Start(pos) => SeekFrom::Start(pos + self.offset),
+            pos => pos,
+        };
+        Ok(self.inner.seek(pos)? - self.offset)
+    }
+}
+
+/// Start a new zip entry with the given name for an asset
+fn start_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    options: &BundleOptions,
+    file_options: FileOptions,
+) {
+    if options.compression == CompressionMethod::Stored && options.align > 1 {
+        zip.start_file_aligned(name, file_options, options.align)
+            .unwrap();
+    } else {
+        zip.start_file(name, file_options).unwrap();
+    }
+}
+
+/// Write a new zip entry with the given name and data for an asset
+fn write_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    data: &[u8],
+    salt: Option<&[u8]>,
+    options: &BundleOptions,
+    file_options: FileOptions,
+) {
+    start_file(zip, name, options, file_options);
+    write_entry_data(zip, name, data, salt);
+}
+
+/// Write a new zip entry with the given name for the asset file at `path`, copying the file in
+/// chunks of [`COPY_CHUNK_SIZE`] so that huge assets are never loaded into memory whole, and return
+/// the size and CRC-32 of the file data
+fn copy_file<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    path: &Path,
+    salt: Option<&[u8]>,
+    options: &BundleOptions,
+    file_options: FileOptions,
+) -> (u64, u32) {
+    start_file(zip, name, options, file_options);
+
+    let mut file = File::open(path).unwrap();
+    let mut hasher = crc32fast::Hasher::new();
+    let mut buf = vec![0; COPY_CHUNK_SIZE];
+    let mut offset = 0;
+    loop {
+        let read = file.read(&mut buf).unwrap();
+        if read == 0 {
+            break;
+        }
+
+        let chunk = &mut buf[..read];
+        hasher.update(chunk);
+        if let Some(salt) = salt {
+            keys::obfuscate(salt, name, offset, chunk);
+        }
+        zip.write_all(chunk).unwrap();
+        offset += read as u64;
+    }
+
+    (offset, hasher.finalize())
+}
+
+/// Get the contents of the [`GROUPS_ENTRY`] for the given groups, warning about the groups that
+/// don't match any of the given asset names
+///
+/// Panics if any of the patterns are invalid.
+fn groups_entry<'a, I>(groups: &BTreeMap<String, Vec<String>>, names: I) -> String
+where
+    I: Iterator<Item = &'a String> + Clone,
+{
+    let mut entry = String::new();
+    for (group, patterns) in groups {
+        let mut matched = false;
+        for pattern in patterns {
+            let compiled = Pattern::new(pattern).unwrap_or_else(|e| {
+                panic!("Invalid pattern {} in group {}: {}", pattern, group, e)
+            });
+            matched |= names
+                .clone()
+                .any(|x| compiled.matches_with(x, GROUP_MATCH_OPTIONS));
+            entry.push_str(&format!("{}\t{}\n", group, pattern));
+        }
+
+        if !matched {
+            warn(&format!("Asset group {} doesn't match any assets", group));
+        }
+    }
+
+    entry
+}
+
+/// Get the line of the [`METADATA_ENTRY`] for an asset with the given name and bundled data hash,
+/// read from the file at `path`, where the modification time is zero for the
+/// [`TimestampPolicy::Zero`] policy
+fn metadata_line(path: &Path, name: &str, hash: u32, timestamps: TimestampPolicy) -> String {
+    if timestamps == TimestampPolicy::Zero {
+        return format!("0\t{:08x}\t{}\n", hash, name);
+    }
+
+    let modified = std::fs::metadata(path)
+        .and_then(|x| x.modified())
+        .ok()
+        .and_then(|x| x.duration_since(SystemTime::UNIX_EPOCH).ok())
+        .map(|x| x.as_secs())
+        .unwrap_or(0);
+
+    format!("{}\t{:08x}\t{}\n", modified, hash, name)
+}
+
+/// Get the paths, relative to the asset dir, of the buffers and images referenced by a glTF file
+///
+/// Embedded data URIs are skipped, and references to files that are missing or outside of the
+/// asset dir are reported as warnings.
+fn gltf_dependencies(source_dir: &Path, gltf_file: &Path) -> Vec<PathBuf> {
+    let gltf_name = gltf_file.strip_prefix(source_dir).unwrap().display();
+    let gltf: serde_json::Value = match std::fs::read(gltf_file)
+        .map_err(|e| e.to_string())
+        .and_then(|x| serde_json::from_slice(&x).map_err(|e| e.to_string()))
+    {
+        Ok(gltf) => gltf,
+        Err(e) => {
+            warn(&format!("Could not parse glTF file {}: {}", gltf_name, e));
+            return Vec::new();
+        }
+    };
+
+    let source_dir = normalize_path(source_dir);
+    let gltf_dir = gltf_file.parent().unwrap();
+    let uris = ["buffers", "images"]
+        .iter()
+        .filter_map(|key| gltf.get(key)?.as_array())
+        .flatten()
+        .filter_map(|x| x.get("uri")?.as_str())
+        .filter(|uri| !uri.starts_with("data:"));
+
+    let mut dependencies = Vec::new();
+    for uri in uris {
+        let path = normalize_path(&gltf_dir.join(percent_decode(uri)));
+        match path.strip_prefix(&source_dir) {
+            Ok(name) if path.is_file() => dependencies.push(name.to_owned()),
+            Ok(_) => warn(&format!(
+                "glTF file {} references missing file {}",
+                gltf_name, uri
+            )),
+            Err(_) => warn(&format!(
+                "glTF file {} references {}, which is outside of the asset dir and won't be \
+                 bundled",
+                gltf_name, uri
+            )),
+        }
+    }
+
+    dependencies
+}
+
+/// Decode the percent-encoded characters in a URI
+fn percent_decode(uri: &str) -> String {
+    let mut bytes = Vec::with_capacity(uri.len());
+    let mut rest = uri.as_bytes();
+    while let Some((&byte, tail)) = rest.split_first() {
+        let decoded = tail
+            .get(..2)
+            .filter(|_| byte == b'%')
+            .and_then(|hex| u8::from_str_radix(std::str::from_utf8(hex).ok()?, 16).ok());
+        match decoded {
+            Some(decoded) => {
+                bytes.push(decoded);
+                rest = &tail[2..];
+            }
+            None => {
+                bytes.push(byte);
+                rest = tail;
+            }
+        }
+    }
+
+    String::from_utf8_lossy(&bytes).into_owned()
+}
+
+/// Resolve the `.` and `..` components of a path without touching the filesystem
+fn normalize_path(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => (),
+            Component::ParentDir if normalized.file_name().is_some() => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+
+    normalized
+}
+
+/// Report a warning, which Cargo shows in the build output when bundling from a build script
+fn warn(message: &str) {
+    println!("cargo:warning={}", message);
+}
+
+/// Get the file options for the entry of the given asset file, recording its unix permissions and
+/// modification time if the bundle options call for them
+fn asset_file_options(
+    path: &Path,
+    options: &BundleOptions,
+    file_options: FileOptions,
+) -> FileOptions {
+    let file_options = match unix_mode(path, options) {
+        Some(mode) => file_options.unix_permissions(mode),
+        None => file_options,
+    };
+
+    match options.timestamps {
+        TimestampPolicy::Preserve => {
+            let modified = std::fs::metadata(path).unwrap().modified().unwrap();
+            file_options.last_modified_time(zip_date_time(modified))
+        }
+        TimestampPolicy::Zero | TimestampPolicy::BuildTime => file_options,
+    }
+}
+
+/// Convert a time to the UTC date and time stored in zip entries, returning 1980-01-01 for times
+/// that zip files can't store
+fn zip_date_time(time: SystemTime) -> DateTime {
+    let secs = match time.duration_since(SystemTime::UNIX_EPOCH) {
+        Ok(x) => x.as_secs(),
+        Err(_) => return DateTime::default(),
+    };
+    let (days, secs) = ((secs / 86400) as i64, secs % 86400);
+
+    // Convert the days since the epoch to a civil date, from Howard Hinnant's `civil_from_days`
+    let z = days + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1460 + doe / 36524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + if month <= 2 { 1 } else { 0 };
+
+    DateTime::from_date_and_time(
+        year.max(0).min(u16::MAX as i64) as u16,
+        month as u8,
+        day as u8,
+        (secs / 3600) as u8,
+        (secs / 60 % 60) as u8,
+        (secs % 60) as u8,
+    )
+    .unwrap_or_default()
+}
+
+/// Get the unix permissions of the given file if they should be recorded in its entry
+#[cfg_attr(not(unix), allow(unused_variables))]
+fn unix_mode(path: &Path, options: &BundleOptions) -> Option<u32> {
+    #[cfg(unix)]
+    if options.unix_permissions {
+        use std::os::unix::fs::PermissionsExt;
+        return Some(std::fs::metadata(path).unwrap().permissions().mode());
+    }
+
+    None
+}
+
+/// Write the given files into packed block entries along with the pack index entry
+fn write_packed_blocks<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    files: &[(PathBuf, String)],
+    salt: Option<&[u8]>,
+    options: &BundleOptions,
+    file_options: FileOptions,
+) {
+    let mut index = String::new();
+    let mut block = Vec::new();
+    let mut block_number = 0;
+
+    for (path, name) in files {
+        let offset = block.len();
+        File::open(path).unwrap().read_to_end(&mut block).unwrap();
+
+        index.push_str(&format!(
+            "{}\t{}\t{}\t{}\n",
+            block_number,
+            offset,
+            block.len() - offset,
+            name
+        ));
+
+        if block.len() as u64 >= options.pack_block_size {
+            let block_name = format!(".packs/{}", block_number);
+            zip.start_file(&block_name, file_options).unwrap();
+            write_entry_data(zip, &block_name, &block, salt);
+            block.clear();
+            block_number += 1;
+        }
+    }
+
+    if !block.is_empty() {
+        let block_name = format!(".packs/{}", block_number);
+        zip.start_file(&block_name, file_options).unwrap();
+        write_entry_data(zip, &block_name, &block, salt);
+    }
+
+    zip.start_file(PACK_INDEX_ENTRY, file_options).unwrap();
+    write_entry_data(zip, PACK_INDEX_ENTRY, index.as_bytes(), salt);
+}
+
+/// Write the data for the current zip entry, obfuscating it with the key derived from the entry
+/// name if a bundle salt is given
+fn write_entry_data<W: Write + Seek>(
+    zip: &mut ZipWriter<W>,
+    name: &str,
+    data: &[u8],
+    salt: Option<&[u8]>,
+) {
+    match salt {
+        Some(salt) => {
+            let mut data = data.to_vec();
+            keys::obfuscate(salt, name, 0, &mut data);
+            zip.write_all(&data).unwrap();
+        }
+        None => zip.write_all(data).unwrap(),
+    }
+}
+
+/// Get the path in the bundle of the asset with the given path in the asset dir, using the longest
+/// of the `renames` that matches the path or one of its parent directories
+fn renamed(name: &str, renames: &BTreeMap<String, String>) -> String {
+    let renamed = renames
+        .iter()
+        .filter_map(|(from, to)| {
+            let from = from.trim_matches('/');
+            let rest = if name == from {
+                ""
+            } else {
+                name.strip_prefix(from)?.strip_prefix('/')?
+            };
+            Some((from.len(), to.trim_matches('/'), rest))
+        })
+        .max_by_key(|(len, ..)| *len);
+
+    match renamed {
+        Some((_, to, "")) => to.to_owned(),
+        Some((_, "", rest)) => rest.to_owned(),
+        Some((_, to, rest)) => format!("{}/{}", to, rest),
+        None => name.to_owned(),
+    }
+}
+
+/// Get the zip entry name for the given path relative to the asset dir, using forward slashes as
+/// the path separator
+fn entry_name(path: &Path) -> String {
+    path.components()
+        .map(|x| x.as_os_str().to_str().expect("Non-unicode asset path"))
+        .collect::<Vec<_>>()
+        .join("/")
 }
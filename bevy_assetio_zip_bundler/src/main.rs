@@ -0,0 +1,65 @@
+//! Command line tool for inspecting asset bundles
+
+use std::process::exit;
+
+const USAGE: &str = "\
+Usage: bevy_assetio_zip_bundler <command> [args]
+
+Commands:
+    append <exe> <bundle> <out exe>   Write a copy of the executable with the bundle attached to it
+    benchmark <asset dir>             Compare the compression codecs on a sample of the assets
+    diff <old bundle> <new bundle>    List the assets added, removed, and changed in the new bundle
+    extract <bundle> <out dir>        Unpack the assets in the bundle, including obfuscated bundles
+    verify <bundle>                   Check the integrity of every entry and asset in the bundle";
+
+fn main() {
+    let args = std::env::args().skip(1).collect::<Vec<_>>();
+    let args = args.iter().map(String::as_str).collect::<Vec<_>>();
+
+    match args.as_slice() {
+        ["append", executable, bundle, output] => {
+            if let Err(e) =
+                bevy_assetio_zip_bundler::append_to_executable(executable, bundle, output)
+            {
+                eprintln!(
+                    "Could not append bundle {} to {}: {}",
+                    bundle, executable, e
+                );
+                exit(1);
+            }
+        }
+        ["benchmark", asset_dir] => {
+            let sample_size = bevy_assetio_zip_bundler::DEFAULT_BENCHMARK_SAMPLE_SIZE;
+            match bevy_assetio_zip_bundler::benchmark(asset_dir, sample_size) {
+                Ok(report) => print!("{}", report),
+                Err(e) => {
+                    eprintln!("Could not benchmark assets in {}: {}", asset_dir, e);
+                    exit(1);
+                }
+            }
+        }
+        ["diff", old, new] => print!("{}", bevy_assetio_zip_bundler::diff(old, new)),
+        ["extract", bundle, out_dir] => {
+            if let Err(e) = bevy_assetio_zip_bundler::extract(bundle, out_dir) {
+                eprintln!("Could not extract bundle {}: {}", bundle, e);
+                exit(1);
+            }
+        }
+        ["verify", bundle] => match bevy_assetio_zip_bundler::verify(bundle) {
+            Ok(report) => {
+                print!("{}", report);
+                if !report.is_ok() {
+                    exit(1);
+                }
+            }
+            Err(e) => {
+                eprintln!("Could not open bundle {}: {}", bundle, e);
+                exit(1);
+            }
+        },
+        _ => {
+            eprintln!("{}", USAGE);
+            exit(2);
+        }
+    }
+}
@@ -0,0 +1,94 @@
+//! Merging the files of several asset dirs into one bundle
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    path::{Path, PathBuf},
+};
+
+#[cfg(feature = "bundle-crate-assets")]
+use serde::Deserialize;
+
+use crate::{entry_name, ignore, renamed, warn, BundleOptions};
+
+/// What to do when the merged asset dirs have different files at the same path in the bundle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(feature = "bundle-crate-assets", serde(rename_all = "kebab-case"))]
+pub enum ConflictPolicy {
+    /// Panic with a report of the conflicting files, failing the build script
+    Error,
+    /// Bundle the file from the first asset dir that has one, reporting the conflicts as warnings
+    FirstWins,
+    /// Bundle the file from the last asset dir that has one, reporting the conflicts as warnings
+    LastWins,
+}
+
+impl Default for ConflictPolicy {
+    fn default() -> Self {
+        Self::Error
+    }
+}
+
+/// Find the files in the given asset dirs that are left out of the bundle because another file is
+/// bundled at the same path
+///
+/// Files with the same content are merged silently, keeping the first one, and files with
+/// different content are resolved with the [`merge_conflicts`][BundleOptions::merge_conflicts]
+/// policy.
+pub(crate) fn overridden_files(roots: &[&Path], options: &BundleOptions) -> HashSet<PathBuf> {
+    // The files bundled at each path, in the order of the asset dirs
+    let mut files = BTreeMap::<_, Vec<_>>::new();
+    for root in roots {
+        for entry in ignore::walk_assets(root) {
+            let entry = entry.unwrap();
+            if entry.file_type().is_file() {
+                let name = entry.path().strip_prefix(root).unwrap();
+                let name = renamed(&entry_name(name), &options.renames);
+                files.entry(name).or_default().push(entry.into_path());
+            }
+        }
+    }
+
+    let mut overridden = HashSet::new();
+    let mut report = String::new();
+    for (name, mut paths) in files {
+        if paths.len() < 2 {
+            continue;
+        }
+
+        let first = std::fs::read(&paths[0]).unwrap();
+        let conflicting = paths[1..]
+            .iter()
+            .any(|x| std::fs::read(x).unwrap() != first);
+        if conflicting {
+            let winner = match options.merge_conflicts {
+                ConflictPolicy::Error => None,
+                ConflictPolicy::FirstWins => Some(0),
+                ConflictPolicy::LastWins => Some(paths.len() - 1),
+            };
+            report.push_str(&format!("\n  {}: ", name));
+            for (i, path) in paths.iter().enumerate() {
+                let marker = if Some(i) == winner { " (bundled)" } else { "" };
+                let separator = if i == 0 { "" } else { ", " };
+                report.push_str(&format!("{}{}{}", separator, path.display(), marker));
+            }
+            paths.swap(0, winner.unwrap_or(0));
+        }
+
+        overridden.extend(paths.into_iter().skip(1));
+    }
+
+    if !report.is_empty() {
+        let message = format!(
+            "Different files are bundled at the same paths in the merged asset dirs:{}",
+            report
+        );
+        match options.merge_conflicts {
+            ConflictPolicy::Error => panic!("{}", message),
+            // Each line of a cargo warning needs its own prefix
+            ConflictPolicy::FirstWins | ConflictPolicy::LastWins => message.lines().for_each(warn),
+        }
+    }
+
+    overridden
+}
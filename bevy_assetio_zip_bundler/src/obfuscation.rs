@@ -0,0 +1,58 @@
+//! Reading and writing the obfuscated data of `.bin` bundles
+//!
+//! Obfuscated bundles are XOR-ed byte by byte with [`OBFUSCATION_KEY`]. In the v2 format only the
+//! zip data after the header is obfuscated, but because every byte is XOR-ed with the same key, the
+//! whole file can be read through [`wrap_reader`] and the header skipped afterwards.
+
+use std::io::{Read, Write};
+
+pub use xorio::Xor;
+
+/// The byte that obfuscated bundles, and the entries of bundles without an entry salt, are XOR-ed
+/// with
+pub const OBFUSCATION_KEY: u8 = 0b0101_0101;
+
+/// Wrap a reader so that the data read through it is de-obfuscated
+///
+/// This lets companion tools, such as localization extractors and QA scripts, open obfuscated
+/// bundles with standard zip libraries without duplicating the obfuscation logic:
+///
+/// ```no_run
+/// # use std::fs::File;
+/// use bevy_assetio_zip_bundler::wrap_reader;
+/// use zip::ZipArchive;
+///
+/// let bundle = wrap_reader(File::open("assets.bin").unwrap());
+/// let mut archive = ZipArchive::new(bundle).unwrap();
+/// for i in 0..archive.len() {
+///     println!("{}", archive.by_index(i).unwrap().name());
+/// }
+/// ```
+///
+/// The returned reader also implements [`Seek`][std::io::Seek] when the wrapped reader does.
+/// Entries that were obfuscated with a per-entry key by `obfuscate-entries` are still scrambled
+/// after reading them through this, use [`extract`][crate::extract] to read those.
+pub fn wrap_reader<R: Read>(reader: R) -> Xor<R> {
+    wrap_reader_with_key(reader, &[OBFUSCATION_KEY])
+}
+
+/// Wrap a writer so that the data written through it is obfuscated the way the bundler obfuscates
+/// `.bin` bundles
+pub fn wrap_writer<W: Write>(writer: W) -> Xor<W> {
+    wrap_writer_with_key(writer, &[OBFUSCATION_KEY])
+}
+
+/// Wrap a reader so that the data read through it is XOR-ed with the given key
+///
+/// Every byte is XOR-ed with each byte of the key in turn, so the key has the same effect as the
+/// single byte that all of its bytes XOR to.
+pub fn wrap_reader_with_key<R: Read>(reader: R, key: &[u8]) -> Xor<R> {
+    Xor::new_with_xor_bytes(reader, key.to_vec())
+}
+
+/// Wrap a writer so that the data written through it is XOR-ed with the given key
+///
+/// Data written with a key can be read back with [`wrap_reader_with_key`] and the same key.
+pub fn wrap_writer_with_key<W: Write>(writer: W, key: &[u8]) -> Xor<W> {
+    Xor::new_with_xor_bytes(writer, key.to_vec())
+}
@@ -0,0 +1,174 @@
+//! The asset conversion pipeline that is run over the files before they are bundled
+
+use std::{
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+use glob::{MatchOptions, Pattern};
+#[cfg(feature = "bundle-crate-assets")]
+use serde::Deserialize;
+
+/// The options used to match the pipeline patterns, where `*` does not match across directories but
+/// `**` does
+const PIPELINE_MATCH_OPTIONS: MatchOptions = MatchOptions {
+    case_sensitive: true,
+    require_literal_separator: true,
+    require_literal_leading_dot: false,
+};
+
+/// A step of the asset conversion pipeline, which converts the files matching a pattern with either
+/// an external command or a built-in converter
+///
+/// Every step that matches a file is run in order, each one converting the output of the previous
+/// one, so a step can match the extension given to the file by an earlier step.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(
+    feature = "bundle-crate-assets",
+    serde(deny_unknown_fields, rename_all = "kebab-case")
+)]
+pub struct PipelineStep {
+    /// Glob pattern of the asset paths that the step converts, such as `**/*.png`
+    ///
+    /// In patterns `*` matches within a single directory and `**` matches across directories.
+    #[cfg_attr(feature = "bundle-crate-assets", serde(rename = "match"))]
+    pub pattern: String,
+    /// The command to convert the file with, such as `toktx --t2 {output} {input}`
+    ///
+    /// The command is split on whitespace and is not run through a shell. `{input}` is replaced
+    /// with the path to a temporary copy of the file and `{output}` with the path that the command
+    /// must write the converted file to.
+    pub command: Option<String>,
+    /// The built-in converter to convert the file with, used instead of a command
+    pub converter: Option<Converter>,
+    /// The extension to give the converted file in the bundle, such as `ktx2`, replacing its
+    /// current extension
+    pub extension: Option<String>,
+}
+
+/// A converter that is built into the bundler
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "bundle-crate-assets", derive(Deserialize))]
+#[cfg_attr(feature = "bundle-crate-assets", serde(rename_all = "kebab-case"))]
+pub enum Converter {
+    /// Remove the whitespace from JSON files, such as `.gltf` files
+    MinifyJson,
+}
+
+/// The pipeline steps with their patterns parsed
+pub(crate) struct Pipeline<'a> {
+    steps: Vec<(Pattern, &'a PipelineStep)>,
+    /// The directory that the files are copied to for conversion commands
+    temp_dir: PathBuf,
+}
+
+impl<'a> Pipeline<'a> {
+    pub fn new(steps: &'a [PipelineStep]) -> Self {
+        let steps = steps
+            .iter()
+            .map(|step| {
+                if step.command.is_some() == step.converter.is_some() {
+                    panic!(
+                        "Pipeline step for {} must have exactly one of `command` or `converter`",
+                        step.pattern
+                    );
+                }
+                let pattern = Pattern::new(&step.pattern).expect("Invalid pipeline pattern");
+                (pattern, step)
+            })
+            .collect();
+
+        Self {
+            steps,
+            temp_dir: std::env::temp_dir()
+                .join(format!("bevy_assetio_zip_pipeline-{}", std::process::id())),
+        }
+    }
+
+    /// Run the matching steps over the file at `path`, returning the name and data of the converted
+    /// file, or `None` if no step matches the file
+    pub fn run(&self, path: &Path, name: &str) -> Option<(String, Vec<u8>)> {
+        let mut converted: Option<(String, Vec<u8>)> = None;
+        for (pattern, step) in &self.steps {
+            let current_name = converted.as_ref().map_or(name, |(name, _)| name.as_str());
+            if !pattern.matches_with(current_name, PIPELINE_MATCH_OPTIONS) {
+                continue;
+            }
+
+            let (current_name, data) = match converted.take() {
+                Some(converted) => converted,
+                None => (name.to_owned(), std::fs::read(path).unwrap()),
+            };
+            let new_name = match &step.extension {
+                Some(extension) => Path::new(&current_name)
+                    .with_extension(extension)
+                    .to_str()
+                    .unwrap()
+                    .to_owned(),
+                None => current_name.clone(),
+            };
+
+            let data = match (&step.command, step.converter) {
+                (Some(command), _) => self.run_command(command, &current_name, &new_name, &data),
+                (None, Some(Converter::MinifyJson)) => {
+                    let json: serde_json::Value = serde_json::from_slice(&data)
+                        .unwrap_or_else(|e| panic!("Could not parse {} as JSON: {}", name, e));
+                    serde_json::to_vec(&json).unwrap()
+                }
+                (None, None) => unreachable!(),
+            };
+
+            converted = Some((new_name, data));
+        }
+
+        converted
+    }
+
+    /// Convert the data of a file with an external command
+    fn run_command(&self, command: &str, name: &str, new_name: &str, data: &[u8]) -> Vec<u8> {
+        std::fs::create_dir_all(&self.temp_dir).unwrap();
+        let input = self.temp_dir.join(format!("input-{}", file_name(name)));
+        let output = self
+            .temp_dir
+            .join(format!("output-{}", file_name(new_name)));
+        std::fs::write(&input, data).unwrap();
+
+        let mut args = command.split_whitespace().map(|arg| {
+            arg.replace("{input}", input.to_str().unwrap())
+                .replace("{output}", output.to_str().unwrap())
+        });
+        let program = args.next().expect("Empty pipeline command");
+        let status = Command::new(&program)
+            .args(args)
+            .status()
+            .unwrap_or_else(|e| panic!("Could not run pipeline command {}: {}", program, e));
+        if !status.success() {
+            panic!("Pipeline command `{}` failed for {}", command, name);
+        }
+
+        let converted = std::fs::read(&output).unwrap_or_else(|e| {
+            panic!(
+                "Pipeline command `{}` didn't write {}: {}",
+                command,
+                output.display(),
+                e
+            )
+        });
+        std::fs::remove_file(&input).ok();
+        std::fs::remove_file(&output).ok();
+
+        converted
+    }
+}
+
+impl<'a> Drop for Pipeline<'a> {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.temp_dir).ok();
+    }
+}
+
+/// Get the file name of an asset path
+fn file_name(name: &str) -> &str {
+    name.rsplit('/').next().unwrap_or(name)
+}
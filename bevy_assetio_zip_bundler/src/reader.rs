@@ -0,0 +1,283 @@
+//! Reading the assets back out of existing bundles
+
+use std::{
+    collections::{BTreeMap, HashMap},
+    convert::TryInto,
+    fs::File,
+    io::{self, Read},
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use zip::ZipArchive;
+
+use crate::{
+    keys, wrap_reader, OffsetFile, ReadSeek, FLAG_ENCRYPTED, FLAG_OBFUSCATED, FLAG_SOLID,
+    FORMAT_VERSION, GROUPS_ENTRY, HEADER_LEN, HEADER_MAGIC, METADATA_ENTRY,
+    OBFUSCATED_ENTRIES_MARKER, OBFUSCATION_KEY, PACK_INDEX_ENTRY,
+};
+
+/// The location of an asset inside of a packed block
+#[derive(Debug, Clone, Copy)]
+struct PackedFile {
+    block: usize,
+    offset: usize,
+    length: usize,
+}
+
+/// An existing asset bundle opened to read its assets
+pub(crate) struct BundleReader {
+    archive: ZipArchive<Box<dyn ReadSeek>>,
+    /// The salt that the entry keys are derived from if the entries are obfuscated, which is empty
+    /// for bundles that XOR every entry with the same byte
+    entry_salt: Option<Vec<u8>>,
+    packed: BTreeMap<String, PackedFile>,
+    /// The most recently read packed block
+    block: Option<(usize, Vec<u8>)>,
+}
+
+impl BundleReader {
+    /// Open the bundle at the given path, detecting its format
+    pub fn open(path: &Path) -> io::Result<Self> {
+        let mut file = File::open(path)?;
+        let mut magic = Vec::with_capacity(HEADER_LEN);
+        (&mut file)
+            .take(HEADER_LEN as u64)
+            .read_to_end(&mut magic)?;
+
+        let (offset, obfuscated, solid) =
+            if magic.len() == HEADER_LEN && magic[..4] == HEADER_MAGIC[..] {
+                let version = u16::from_le_bytes(magic[4..6].try_into().unwrap());
+                let flags = u16::from_le_bytes(magic[6..8].try_into().unwrap());
+                let header_len = u32::from_le_bytes(magic[8..12].try_into().unwrap());
+                if version > FORMAT_VERSION {
+                    return Err(invalid(format!(
+                        "Bundle format version {} is newer than the supported version {}",
+                        version, FORMAT_VERSION
+                    )));
+                }
+                if flags & FLAG_ENCRYPTED != 0 {
+                    return Err(invalid("Encrypted bundles are not supported".into()));
+                }
+                (
+                    header_len as u64,
+                    flags & FLAG_OBFUSCATED != 0,
+                    flags & FLAG_SOLID != 0,
+                )
+            } else if magic.starts_with(b"PK") {
+                (0, false, false)
+            } else if magic
+                .iter()
+                .take(2)
+                .map(|x| x ^ OBFUSCATION_KEY)
+                .eq(b"PK".iter().copied())
+            {
+                (0, true, false)
+            } else {
+                return Err(invalid("Not a zip file or asset bundle".into()));
+            };
+
+        let zip_file = OffsetFile::new(file, offset);
+        let reader: Box<dyn ReadSeek> = if obfuscated {
+            Box::new(wrap_reader(zip_file))
+        } else {
+            Box::new(zip_file)
+        };
+        // The seek table of solid bundles is in a skippable frame, so the whole zip data can be
+        // decompressed as a regular zstd stream
+        #[cfg(feature = "solid")]
+        let reader: Box<dyn ReadSeek> = if solid {
+            Box::new(std::io::Cursor::new(zstd::stream::decode_all(reader)?))
+        } else {
+            reader
+        };
+        #[cfg(not(feature = "solid"))]
+        if solid {
+            return Err(invalid(
+                "Solid bundles require the `solid` feature of `bevy_assetio_zip_bundler`".into(),
+            ));
+        }
+
+        let mut bundle = Self {
+            archive: ZipArchive::new(reader)?,
+            entry_salt: None,
+            packed: BTreeMap::new(),
+            block: None,
+        };
+
+        if bundle.has_entry(OBFUSCATED_ENTRIES_MARKER) {
+            bundle.entry_salt = Some(bundle.read_entry(OBFUSCATED_ENTRIES_MARKER)?);
+        }
+        if bundle.has_entry(PACK_INDEX_ENTRY) {
+            let index = bundle.read_entry(PACK_INDEX_ENTRY)?;
+            bundle.packed = String::from_utf8_lossy(&index)
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(4, '\t');
+                    let packed = PackedFile {
+                        block: fields.next()?.parse().ok()?,
+                        offset: fields.next()?.parse().ok()?,
+                        length: fields.next()?.parse().ok()?,
+                    };
+                    Some((fields.next()?.to_owned(), packed))
+                })
+                .collect();
+        }
+
+        Ok(bundle)
+    }
+
+    /// Whether or not the bundle has a zip entry with the given name
+    fn has_entry(&mut self, name: &str) -> bool {
+        self.archive.by_name(name).is_ok()
+    }
+
+    /// Get the names of the files in the bundle, including the entries used internally by the
+    /// bundler but excluding directories
+    pub fn entry_names(&self) -> Vec<String> {
+        let mut names = self
+            .archive
+            .file_names()
+            .filter(|name| !name.ends_with('/'))
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        names.sort();
+        names
+    }
+
+    /// Get the name, compressed size, and uncompressed size of every file in the bundle, including
+    /// the entries used internally by the bundler but excluding directories
+    pub fn entry_sizes(&mut self) -> io::Result<Vec<(String, u64, u64)>> {
+        let mut sizes = Vec::new();
+        for i in 0..self.archive.len() {
+            let file = self.archive.by_index(i)?;
+            if !file.name().ends_with('/') {
+                sizes.push((file.name().to_owned(), file.compressed_size(), file.size()));
+            }
+        }
+        Ok(sizes)
+    }
+
+    /// Read and decompress the data of a zip entry, undoing the obfuscation of its data
+    ///
+    /// Reading the whole entry checks its CRC-32.
+    pub fn read_entry(&mut self, name: &str) -> io::Result<Vec<u8>> {
+        let mut data = Vec::new();
+        self.archive.by_name(name)?.read_to_end(&mut data)?;
+
+        match &self.entry_salt {
+            Some(salt) if name != OBFUSCATED_ENTRIES_MARKER => {
+                if salt.is_empty() {
+                    data.iter_mut().for_each(|x| *x ^= OBFUSCATION_KEY);
+                } else {
+                    keys::obfuscate(salt, name, 0, &mut data);
+                }
+            }
+            _ => (),
+        }
+
+        Ok(data)
+    }
+
+    /// Get the paths of the assets in the bundle, including packed assets but excluding directories
+    /// and the entries used internally by the bundler
+    pub fn asset_paths(&self) -> Vec<String> {
+        let mut paths = self
+            .archive
+            .file_names()
+            .filter(|name| !name.ends_with('/') && !is_internal_entry(name))
+            .chain(self.packed.keys().map(String::as_str))
+            .map(ToOwned::to_owned)
+            .collect::<Vec<_>>();
+        paths.sort();
+        paths
+    }
+
+    /// Get the modification times and content hashes recorded for the assets, returning `None` if
+    /// the bundle has no [`METADATA_ENTRY`] or it can't be read
+    ///
+    /// Each line of the metadata has the form `<modified>\t<hash>\t<path>`.
+    pub fn asset_metadata(&mut self) -> Option<HashMap<String, RecordedMetadata>> {
+        let metadata = self.read_entry(METADATA_ENTRY).ok()?;
+
+        Some(
+            String::from_utf8_lossy(&metadata)
+                .lines()
+                .filter_map(|line| {
+                    let mut fields = line.splitn(3, '\t');
+                    let modified = fields.next()?.parse().ok()?;
+                    let hash = u32::from_str_radix(fields.next()?, 16).ok()?;
+                    let metadata = RecordedMetadata {
+                        modified: SystemTime::UNIX_EPOCH + Duration::from_secs(modified),
+                        hash,
+                    };
+                    Some((fields.next()?.to_owned(), metadata))
+                })
+                .collect(),
+        )
+    }
+
+    /// Get the unix permissions of an asset, returning `None` for packed assets and for entries
+    /// that don't record them
+    pub fn asset_mode(&mut self, path: &str) -> Option<u32> {
+        if self.packed.contains_key(path) {
+            return None;
+        }
+        self.archive.by_name(path).ok()?.unix_mode()
+    }
+
+    /// Get the archive comment of the bundle
+    pub fn comment(&self) -> &[u8] {
+        self.archive.comment()
+    }
+
+    /// Read an asset from its own entry or from the packed block containing it
+    pub fn read_asset(&mut self, path: &str) -> io::Result<Vec<u8>> {
+        let packed = match self.packed.get(path) {
+            Some(packed) => *packed,
+            None => return self.read_entry(path),
+        };
+
+        if self.block.as_ref().map(|(block, _)| *block) != Some(packed.block) {
+            let block = self.read_entry(&format!(".packs/{}", packed.block))?;
+            self.block = Some((packed.block, block));
+        }
+
+        self.block
+            .as_ref()
+            .unwrap()
+            .1
+            .get(packed.offset..packed.offset + packed.length)
+            .map(ToOwned::to_owned)
+            .ok_or_else(|| invalid(format!("Packed asset {} is out of its block", path)))
+    }
+}
+
+/// Read the archive comment of the bundle at the given path, which is empty if the bundle was built
+/// without a [`comment`][crate::BundleOptions::comment]
+///
+/// An error is returned if the bundle can't be read.
+pub fn comment<P: AsRef<Path>>(bundle_path: P) -> io::Result<String> {
+    let bundle = BundleReader::open(bundle_path.as_ref())?;
+    Ok(String::from_utf8_lossy(bundle.comment()).into_owned())
+}
+
+/// The modification time and content hash of an asset recorded in the [`METADATA_ENTRY`]
+#[derive(Debug, Clone, Copy)]
+pub(crate) struct RecordedMetadata {
+    pub modified: SystemTime,
+    pub hash: u32,
+}
+
+/// Whether or not the given entry is used internally by the bundler rather than being an asset
+fn is_internal_entry(name: &str) -> bool {
+    name == OBFUSCATED_ENTRIES_MARKER
+        || name == METADATA_ENTRY
+        || name == GROUPS_ENTRY
+        || name.starts_with(".packs/")
+}
+
+/// Create an error for a bundle that can't be read
+fn invalid(message: String) -> io::Error {
+    io::Error::new(io::ErrorKind::InvalidData, message)
+}
@@ -0,0 +1,43 @@
+//! Rewriting existing bundles with different options
+
+use std::{
+    io,
+    path::{Path, PathBuf},
+};
+
+use crate::{bundle_assets_with_options, extract, BundleOptions};
+
+/// A temporary directory that is removed when dropped, even if bundling panics
+struct TempDir(PathBuf);
+
+impl Drop for TempDir {
+    fn drop(&mut self) {
+        std::fs::remove_dir_all(&self.0).ok();
+    }
+}
+
+/// Rewrite the bundle at `input` to `output` with different options, such as a different
+/// compression or obfuscation, without needing the asset dir that it was built from
+///
+/// The assets are extracted to a temporary directory with [`extract`] and bundled again with
+/// `new_options`, so every option applies as it does when bundling an asset dir. Assets that were
+/// converted by a pipeline when the bundle was built are already converted, so `new_options`
+/// should usually not have any pipeline steps. Modification times are carried over when the
+/// bundle was built with `asset_metadata`.
+///
+/// An error is returned if the input bundle can't be read.
+pub fn repack<P: AsRef<Path>, Q: AsRef<Path>>(
+    input: P,
+    output: Q,
+    new_options: &BundleOptions,
+) -> io::Result<()> {
+    let temp_dir = TempDir(
+        std::env::temp_dir().join(format!("bevy_assetio_zip_repack-{}", std::process::id())),
+    );
+    std::fs::remove_dir_all(&temp_dir.0).ok();
+
+    extract(input, &temp_dir.0)?;
+    bundle_assets_with_options(temp_dir.0.as_path(), output.as_ref(), new_options);
+
+    Ok(())
+}
@@ -0,0 +1,145 @@
+//! Statistics about the size of bundles, for graphing asset size growth in CI
+
+use std::{collections::BTreeMap, io, path::Path, time::Duration};
+
+use serde_json::json;
+
+use crate::reader::BundleReader;
+
+/// The sizes of the entries of a bundle with one file extension
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ExtensionStats {
+    /// The number of entries with the extension
+    pub entries: u64,
+    /// The total size of the entries in the bundle, after compression
+    pub compressed_bytes: u64,
+    /// The total size of the entries before compression
+    pub uncompressed_bytes: u64,
+}
+
+/// Statistics about the size of a bundle, returned by [`stats`]
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct BundleStats {
+    /// The size of the bundle file
+    pub bundle_bytes: u64,
+    /// The number of files in the bundle, including the entries used internally by the bundler
+    pub entries: u64,
+    /// The total size of the entries in the bundle, after compression
+    ///
+    /// The entries of solid bundles are stored uncompressed, so only the
+    /// [`bundle_bytes`][Self::bundle_bytes] reflect the zstd compression of those bundles.
+    pub compressed_bytes: u64,
+    /// The total size of the entries before compression
+    pub uncompressed_bytes: u64,
+    /// The sizes of the entries for each lowercase file extension, where entries without an
+    /// extension, such as packed blocks, are listed under an empty extension
+    pub extensions: BTreeMap<String, ExtensionStats>,
+    /// How long it took to build the bundle, if it was just built
+    pub build_duration: Option<Duration>,
+}
+
+impl BundleStats {
+    /// Render the statistics as pretty-printed JSON
+    ///
+    /// The JSON object has the `bundle_bytes`, `entries`, `compressed_bytes`, and
+    /// `uncompressed_bytes` of the bundle, an `extensions` object with the `entries`,
+    /// `compressed_bytes`, and `uncompressed_bytes` of each extension, and the
+    /// `build_seconds`, which is `null` if the bundle wasn't just built.
+    pub fn to_json(&self) -> String {
+        let extensions = self
+            .extensions
+            .iter()
+            .map(|(extension, stats)| {
+                let stats = json!({
+                    "entries": stats.entries,
+                    "compressed_bytes": stats.compressed_bytes,
+                    "uncompressed_bytes": stats.uncompressed_bytes,
+                });
+                (extension.clone(), stats)
+            })
+            .collect::<serde_json::Map<_, _>>();
+
+        serde_json::to_string_pretty(&json!({
+            "bundle_bytes": self.bundle_bytes,
+            "entries": self.entries,
+            "compressed_bytes": self.compressed_bytes,
+            "uncompressed_bytes": self.uncompressed_bytes,
+            "extensions": extensions,
+            "build_seconds": self.build_duration.map(|x| x.as_secs_f64()),
+        }))
+        .unwrap()
+    }
+}
+
+/// The units accepted by [`parse_size`], with the number of bytes in each
+const SIZE_UNITS: &[(&str, u64)] = &[
+    ("B", 1),
+    ("KB", 1000),
+    ("MB", 1000 * 1000),
+    ("GB", 1000 * 1000 * 1000),
+    ("KIB", 1 << 10),
+    ("MIB", 1 << 20),
+    ("GIB", 1 << 30),
+];
+
+/// Parse a size such as `2GiB`, `500 MB`, or `1024` into a number of bytes, returning `None` if it
+/// isn't a valid size
+///
+/// Units are case-insensitive, and a number without a unit is a number of bytes.
+#[cfg(feature = "bundle-crate-assets")]
+pub(crate) fn parse_size(size: &str) -> Option<u64> {
+    let size = size.trim();
+    let split = size
+        .find(|x: char| !x.is_ascii_digit() && x != '.')
+        .unwrap_or(size.len());
+    let (number, unit) = size.split_at(split);
+    let number = number.parse::<f64>().ok()?;
+    let unit = unit.trim().to_uppercase();
+
+    let bytes = if unit.is_empty() {
+        1
+    } else {
+        SIZE_UNITS.iter().find(|x| x.0 == unit)?.1
+    };
+    Some((number * bytes as f64).round() as u64)
+}
+
+/// Format a number of bytes with the largest binary unit it has at least one of, such as `1.50 MiB`
+pub(crate) fn format_size(bytes: u64) -> String {
+    match SIZE_UNITS[4..].iter().rev().find(|x| bytes >= x.1) {
+        Some((unit, size)) => {
+            let unit = unit.replace('I', "i");
+            format!("{:.2} {}", bytes as f64 / *size as f64, unit)
+        }
+        None => format!("{} B", bytes),
+    }
+}
+
+/// Collect statistics about the size of the bundle at the given path
+///
+/// An error is returned if the bundle can't be read.
+pub fn stats<P: AsRef<Path>>(bundle_path: P) -> io::Result<BundleStats> {
+    let bundle_path = bundle_path.as_ref();
+    let mut bundle = BundleReader::open(bundle_path)?;
+
+    let mut stats = BundleStats {
+        bundle_bytes: std::fs::metadata(bundle_path)?.len(),
+        ..Default::default()
+    };
+    for (name, compressed_size, size) in bundle.entry_sizes()? {
+        let extension = Path::new(&name)
+            .extension()
+            .map(|x| x.to_string_lossy().to_lowercase())
+            .unwrap_or_default();
+        let extension = stats.extensions.entry(extension).or_default();
+        extension.entries += 1;
+        extension.compressed_bytes += compressed_size;
+        extension.uncompressed_bytes += size;
+
+        stats.entries += 1;
+        stats.compressed_bytes += compressed_size;
+        stats.uncompressed_bytes += size;
+    }
+
+    Ok(stats)
+}
@@ -0,0 +1,146 @@
+//! Custom steps that are run over the files before they are bundled
+
+#[cfg(feature = "bundle-crate-assets")]
+use std::sync::Mutex;
+use std::{
+    io,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+#[cfg(feature = "bundle-crate-assets")]
+lazy_static::lazy_static! {
+    /// The steps registered with [`register_step`], which [`bundle_crate_assets`] runs
+    ///
+    /// [`bundle_crate_assets`]: crate::bundle_crate_assets
+    static ref REGISTERED_STEPS: Mutex<Vec<Arc<dyn BundleStep>>> = Mutex::new(Vec::new());
+}
+
+/// A file that is about to be bundled, which is passed through each [`BundleStep`] in turn
+#[derive(Debug, Clone)]
+pub struct BundleFile {
+    /// The path of the file in the bundle, which steps may change to rename the file
+    pub name: String,
+    path: Option<PathBuf>,
+    data: Option<Vec<u8>>,
+}
+
+impl BundleFile {
+    /// Create a file with the given path in the bundle and data, such as a file added by
+    /// [`BundleStep::added_files`]
+    pub fn new<N: Into<String>>(name: N, data: Vec<u8>) -> Self {
+        Self {
+            name: name.into(),
+            path: None,
+            data: Some(data),
+        }
+    }
+
+    /// Create a file for the asset file at `path`, with its data if it has already been converted
+    pub(crate) fn from_path(name: String, path: &Path, data: Option<Vec<u8>>) -> Self {
+        Self {
+            name,
+            path: Some(path.to_owned()),
+            data,
+        }
+    }
+
+    /// The path of the asset file that the file is read from, or `None` if it was added by a step
+    pub fn path(&self) -> Option<&Path> {
+        self.path.as_deref()
+    }
+
+    /// Get the data of the file, reading it from the asset file if it hasn't been read yet
+    pub fn data(&mut self) -> io::Result<&[u8]> {
+        if self.data.is_none() {
+            let path = self
+                .path
+                .as_ref()
+                .expect("Bundle file has neither data nor a path");
+            self.data = Some(std::fs::read(path)?);
+        }
+
+        Ok(self.data.as_deref().unwrap())
+    }
+
+    /// Replace the data of the file
+    pub fn set_data(&mut self, data: Vec<u8>) {
+        self.data = Some(data);
+    }
+
+    /// Take the data of the file if it has been read or changed by a step
+    pub(crate) fn take_data(&mut self) -> Option<Vec<u8>> {
+        self.data.take()
+    }
+}
+
+/// A custom step of the bundler, which can filter, transform, and add to the bundled files
+///
+/// Steps run in order over every file after the [`pipeline`][crate::BundleOptions::pipeline], each
+/// one getting the output of the previous one. Files whose data has been read or changed by a step
+/// are never written to packed blocks.
+///
+/// Steps are passed to [`bundle_assets_with_options`][crate::bundle_assets_with_options] in the
+/// [`steps`][crate::BundleOptions::steps] option, or registered with [`register_step`] before
+/// calling [`bundle_crate_assets`][crate::bundle_crate_assets] in a build script.
+///
+/// ```no_run
+/// use bevy_assetio_zip_bundler::{register_step, BundleFile, BundleStep};
+///
+/// /// Leaves the editor-only files out of the bundle
+/// struct SkipEditorFiles;
+///
+/// impl BundleStep for SkipEditorFiles {
+///     fn process(&self, file: BundleFile) -> Option<BundleFile> {
+///         if file.name.starts_with("editor/") {
+///             None
+///         } else {
+///             Some(file)
+///         }
+///     }
+/// }
+///
+/// // In `build.rs`
+/// register_step(SkipEditorFiles);
+/// bevy_assetio_zip_bundler::bundle_crate_assets();
+/// ```
+pub trait BundleStep: Send + Sync + 'static {
+    /// Process a file that is about to be bundled, returning `None` to leave it out of the bundle
+    ///
+    /// By default the file is bundled unchanged.
+    fn process(&self, file: BundleFile) -> Option<BundleFile> {
+        Some(file)
+    }
+
+    /// Get the files to add to the bundle once every asset file has been processed
+    ///
+    /// The added files are not passed through the steps.
+    fn added_files(&self) -> Vec<BundleFile> {
+        Vec::new()
+    }
+}
+
+impl std::fmt::Debug for dyn BundleStep {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("BundleStep")
+    }
+}
+
+/// Register a step to be run by [`bundle_crate_assets`], after the steps registered before it
+///
+/// [`bundle_crate_assets`]: crate::bundle_crate_assets
+#[cfg(feature = "bundle-crate-assets")]
+pub fn register_step<S: BundleStep>(step: S) {
+    REGISTERED_STEPS.lock().unwrap().push(Arc::new(step));
+}
+
+/// Get the steps registered with [`register_step`]
+#[cfg(feature = "bundle-crate-assets")]
+pub(crate) fn registered_steps() -> Vec<Arc<dyn BundleStep>> {
+    REGISTERED_STEPS.lock().unwrap().clone()
+}
+
+/// Run the given steps over a file, returning `None` if one of them leaves it out of the bundle
+pub(crate) fn run(steps: &[Arc<dyn BundleStep>], file: BundleFile) -> Option<BundleFile> {
+    steps.iter().try_fold(file, |file, step| step.process(file))
+}
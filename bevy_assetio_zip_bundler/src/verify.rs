@@ -0,0 +1,136 @@
+//! Checking the integrity of bundles
+
+use std::{
+    collections::{HashMap, HashSet},
+    fmt, io,
+    path::Path,
+};
+
+use crate::reader::BundleReader;
+
+/// A problem found in a bundle by [`verify`]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VerifyError {
+    /// The zip entry or asset path that the problem was found in
+    pub path: String,
+    /// A description of the problem
+    pub message: String,
+}
+
+/// The result of checking a bundle with [`verify`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct VerifyReport {
+    /// The number of zip entries that were read
+    pub entries: usize,
+    /// The number of assets that were read, including packed assets
+    pub assets: usize,
+    /// Whether or not the asset hashes were checked against the
+    /// [`METADATA_ENTRY`][crate::METADATA_ENTRY]
+    pub checked_metadata: bool,
+    /// The problems found in the bundle
+    pub errors: Vec<VerifyError>,
+}
+
+impl VerifyReport {
+    /// Whether or not the bundle passed verification
+    pub fn is_ok(&self) -> bool {
+        self.errors.is_empty()
+    }
+}
+
+impl fmt::Display for VerifyReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for error in &self.errors {
+            writeln!(f, "{}: {}", error.path, error.message)?;
+        }
+        writeln!(
+            f,
+            "Checked {} entries and {} assets{}: {}",
+            self.entries,
+            self.assets,
+            if self.checked_metadata {
+                " against the asset metadata"
+            } else {
+                ""
+            },
+            if self.is_ok() {
+                "OK".to_owned()
+            } else {
+                format!("{} errors", self.errors.len())
+            }
+        )
+    }
+}
+
+/// Check the integrity of the bundle at the given path
+///
+/// Every entry is read and decompressed, which checks its CRC-32, and every asset is read out of
+/// its entry or packed block. If the bundle has a [`METADATA_ENTRY`][crate::METADATA_ENTRY] the
+/// hash of every asset is also checked against it. This can be used to gate releases on bundles
+/// that have been uploaded or downloaded intact.
+///
+/// An error is returned if the bundle can't be opened at all, such as when its zip central
+/// directory is corrupt. Problems with individual entries are collected in the report.
+pub fn verify<P: AsRef<Path>>(bundle_path: P) -> io::Result<VerifyReport> {
+    let mut bundle = BundleReader::open(bundle_path.as_ref())?;
+    let mut report = VerifyReport::default();
+
+    let mut failed_entries = HashSet::new();
+    for name in bundle.entry_names() {
+        report.entries += 1;
+        if let Err(e) = bundle.read_entry(&name) {
+            failed_entries.insert(name.clone());
+            report.errors.push(VerifyError {
+                path: name,
+                message: e.to_string(),
+            });
+        }
+    }
+
+    let metadata = bundle.asset_metadata();
+    report.checked_metadata = metadata.is_some();
+    let mut hashes = metadata
+        .unwrap_or_default()
+        .into_iter()
+        .map(|(path, metadata)| (path, metadata.hash))
+        .collect::<HashMap<_, _>>();
+
+    for path in bundle.asset_paths() {
+        report.assets += 1;
+        // Assets stored in their own entry have already been reported if the entry is broken
+        if failed_entries.contains(&path) {
+            continue;
+        }
+
+        let data = match bundle.read_asset(&path) {
+            Ok(data) => data,
+            Err(e) => {
+                report.errors.push(VerifyError {
+                    path,
+                    message: e.to_string(),
+                });
+                continue;
+            }
+        };
+
+        if let Some(hash) = hashes.remove(&path) {
+            if crc32fast::hash(&data) != hash {
+                report.errors.push(VerifyError {
+                    path,
+                    message: "Hash doesn't match the asset metadata".into(),
+                });
+            }
+        }
+    }
+
+    let mut missing = hashes.into_iter().map(|(name, _)| name).collect::<Vec<_>>();
+    missing.sort();
+    report
+        .errors
+        .extend(missing.into_iter().map(|path| VerifyError {
+            path,
+            message: "Asset is listed in the asset metadata but is missing".into(),
+        }));
+
+    Ok(report)
+}
@@ -0,0 +1,43 @@
+//! Discovery of the cargo workspace that the crate being built belongs to and of its target dirs
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+/// Find the root dir of the workspace that the crate in the given dir belongs to, which is the
+/// nearest dir with a `Cargo.toml` that has a `[workspace]` table, or the crate dir itself when it
+/// isn't in a workspace
+pub(crate) fn workspace_root(crate_dir: &Path) -> PathBuf {
+    crate_dir
+        .ancestors()
+        .find(|dir| is_workspace_root(dir))
+        .unwrap_or(crate_dir)
+        .to_owned()
+}
+
+/// Get the profile dir that the executables are built in from the `OUT_DIR` of a build script,
+/// which is `<profile dir>/build/<package>-<hash>/out`
+pub(crate) fn profile_dir(out_dir: &Path) -> Option<PathBuf> {
+    out_dir.ancestors().nth(3).map(Path::to_owned)
+}
+
+/// Hard link the file at `from` to `to`, replacing any file already there, or copy it if it can't
+/// be linked, such as when the paths are on different filesystems
+pub(crate) fn link_or_copy(from: &Path, to: &Path) -> io::Result<()> {
+    match fs::remove_file(to) {
+        Err(e) if e.kind() != io::ErrorKind::NotFound => return Err(e),
+        _ => (),
+    }
+
+    fs::hard_link(from, to).or_else(|_| fs::copy(from, to).map(|_| ()))
+}
+
+/// Whether or not the manifest in the given dir defines a workspace
+fn is_workspace_root(dir: &Path) -> bool {
+    std::fs::read(dir.join("Cargo.toml"))
+        .ok()
+        .and_then(|x| toml::from_slice::<toml::Value>(&x).ok())
+        .map(|x| x.get("workspace").is_some())
+        .unwrap_or(false)
+}
@@ -0,0 +1,47 @@
+mod common;
+
+use std::fs::{self, File};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, CompressionMethod};
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn stored_entries_are_aligned() {
+    let dir = test_dir("align");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("a.txt", "a"), ("sub/bb.txt", "bb"), ("sub/ccc.txt", "ccc")],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            compression: CompressionMethod::Stored,
+            align: 64,
+            ..Default::default()
+        },
+    );
+
+    let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+    let mut files = 0;
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).unwrap();
+        if entry.is_file() {
+            assert_eq!(
+                entry.data_start() % 64,
+                0,
+                "{} is not aligned",
+                entry.name()
+            );
+            files += 1;
+        }
+    }
+    assert_eq!(files, 3);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
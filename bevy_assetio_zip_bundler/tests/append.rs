@@ -0,0 +1,103 @@
+mod common;
+
+use std::{convert::TryInto, fs};
+
+use bevy_assetio_zip_bundler::{
+    append_to_executable, bundle_assets_with_options, BundleOptions, APPENDED_BUNDLE_MAGIC,
+    APPENDED_FOOTER_LEN,
+};
+
+use common::{test_dir, write_files};
+
+/// Read the offset and length of the appended bundle from the footer of a file
+fn footer(bytes: &[u8]) -> (usize, usize) {
+    let footer = &bytes[bytes.len() - APPENDED_FOOTER_LEN as usize..];
+    assert_eq!(&footer[16..], APPENDED_BUNDLE_MAGIC);
+    let offset = u64::from_le_bytes(footer[..8].try_into().unwrap());
+    let length = u64::from_le_bytes(footer[8..16].try_into().unwrap());
+    (offset as usize, length as usize)
+}
+
+#[test]
+fn bundles_are_appended_to_a_copy_of_the_executable() {
+    let dir = test_dir("append");
+    fs::write(dir.join("game"), "executable").unwrap();
+    write_files(&dir.join("assets"), &[("a.txt", "a")]);
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions::default(),
+    );
+    let bundle = fs::read(dir.join("assets.zip")).unwrap();
+
+    append_to_executable(
+        dir.join("game"),
+        dir.join("assets.zip"),
+        dir.join("bundled"),
+    )
+    .unwrap();
+    let bytes = fs::read(dir.join("bundled")).unwrap();
+    let (offset, length) = footer(&bytes);
+    assert_eq!(&bytes[..offset], b"executable");
+    assert_eq!(&bytes[offset..offset + length], &bundle[..]);
+    assert_eq!(fs::read(dir.join("game")).unwrap(), b"executable");
+
+    // Appending to an executable that already has a bundle replaces the bundle
+    fs::write(dir.join("other.zip"), "other bundle").unwrap();
+    append_to_executable(
+        dir.join("bundled"),
+        dir.join("other.zip"),
+        dir.join("rebundled"),
+    )
+    .unwrap();
+    let bytes = fs::read(dir.join("rebundled")).unwrap();
+    let (offset, length) = footer(&bytes);
+    assert_eq!(&bytes[..offset], b"executable");
+    assert_eq!(&bytes[offset..offset + length], b"other bundle");
+    assert_eq!(bytes.len(), offset + length + APPENDED_FOOTER_LEN as usize);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn appended_executables_stay_executable() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = test_dir("append_permissions");
+    fs::write(dir.join("game"), "executable").unwrap();
+    fs::set_permissions(dir.join("game"), fs::Permissions::from_mode(0o755)).unwrap();
+    fs::write(dir.join("assets.zip"), "bundle").unwrap();
+
+    append_to_executable(
+        dir.join("game"),
+        dir.join("assets.zip"),
+        dir.join("bundled"),
+    )
+    .unwrap();
+    let mode = fs::metadata(dir.join("bundled"))
+        .unwrap()
+        .permissions()
+        .mode();
+    assert_eq!(mode & 0o777, 0o755);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bundles_are_not_appended_to_the_executable_itself() {
+    let dir = test_dir("append_in_place");
+    fs::write(dir.join("game"), "executable").unwrap();
+    fs::write(dir.join("assets.zip"), "bundle").unwrap();
+
+    let error = append_to_executable(dir.join("game"), dir.join("assets.zip"), dir.join("game"))
+        .unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidInput);
+    assert_eq!(fs::read(dir.join("game")).unwrap(), b"executable");
+
+    assert!(
+        append_to_executable(dir.join("missing"), dir.join("assets.zip"), dir.join("out")).is_err()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,51 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::write_asset_paths;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn asset_paths_are_written_as_constants() {
+    let dir = test_dir("asset_paths");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("textures/grass.png", ""),
+            ("2d/sprite-sheet.png", ""),
+            ("music.ogg", ""),
+        ],
+    );
+
+    let out_file = dir.join("asset_paths.rs");
+    write_asset_paths(&asset_dir, &out_file);
+
+    let source = fs::read_to_string(&out_file).unwrap();
+    assert!(source.contains("pub const TEXTURES_GRASS_PNG: &str = \"textures/grass.png\";"));
+    assert!(source.contains("pub const MUSIC_OGG: &str = \"music.ogg\";"));
+    // Identifiers can't start with a digit
+    assert!(source.contains("pub const _2D_SPRITE_SHEET_PNG: &str = \"2d/sprite-sheet.png\";"));
+
+    // Constants are sorted by path so that the file is stable across builds
+    let music = source.find("MUSIC_OGG").unwrap();
+    let grass = source.find("TEXTURES_GRASS_PNG").unwrap();
+    assert!(source.find("_2D_SPRITE_SHEET_PNG").unwrap() < music);
+    assert!(music < grass);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "both have the asset path constant name TEXTURES_GRASS_PNG")]
+fn clashing_constant_names_panic() {
+    let dir = test_dir("asset_paths-clash");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("textures/grass.png", ""), ("textures/grass-png", "")],
+    );
+
+    write_asset_paths(&asset_dir, &dir.join("asset_paths.rs"));
+}
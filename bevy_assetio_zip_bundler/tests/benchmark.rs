@@ -0,0 +1,81 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::benchmark;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn the_sample_takes_files_of_every_extension_in_turn() {
+    let dir = test_dir("benchmark_sample");
+    let text = "compressible ".repeat(80);
+    write_files(
+        &dir,
+        &[
+            ("a.txt", &text),
+            ("b.txt", &text),
+            ("c.txt", &text),
+            ("levels/1.level", &text),
+            ("music.ogg", &text),
+        ],
+    );
+
+    // Room for one file of each extension
+    let report = benchmark(&dir, 3 * text.len() as u64).unwrap();
+    assert_eq!(report.sample_files, 3);
+    assert_eq!(report.sample_bytes, 3 * text.len() as u64);
+
+    // At least one file is taken even if it is larger than the sample
+    let report = benchmark(&dir, 1).unwrap();
+    assert_eq!(report.sample_files, 1);
+
+    let report = benchmark(&dir, u64::MAX).unwrap();
+    assert_eq!(report.sample_files, 5);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn every_enabled_codec_is_benchmarked() {
+    let dir = test_dir("benchmark_codecs");
+    write_files(&dir, &[("a.txt", &"compressible ".repeat(1000))]);
+
+    let report = benchmark(&dir, u64::MAX).unwrap();
+    let codecs = report
+        .codecs
+        .iter()
+        .map(|x| (x.codec.as_str(), x.level))
+        .collect::<Vec<_>>();
+    let mut expected = vec![("none", None)];
+    if cfg!(feature = "deflate") {
+        expected.push(("deflate", None));
+    }
+    if cfg!(feature = "bzip2") {
+        expected.push(("bzip2", None));
+    }
+    assert_eq!(codecs[..expected.len()], expected[..]);
+    if cfg!(feature = "solid") {
+        assert!(codecs[expected.len()..]
+            .iter()
+            .all(|(codec, level)| *codec == "solid" && level.is_some()));
+    }
+
+    // Storing the files takes more space than the files themselves, and compressing them less
+    let none = &report.codecs[0];
+    assert!(none.compressed_bytes > report.sample_bytes);
+    assert!(report.codecs[1..]
+        .iter()
+        .all(|x| x.compressed_bytes < report.sample_bytes));
+    assert!(report.to_string().contains("none"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_asset_dirs_cannot_be_benchmarked() {
+    let dir = test_dir("benchmark_missing");
+    assert!(benchmark(dir.join("assets"), u64::MAX).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
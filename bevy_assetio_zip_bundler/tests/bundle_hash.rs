@@ -0,0 +1,22 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::bundle_hash;
+
+use common::test_dir;
+
+#[test]
+fn bundles_are_hashed_with_blake3() {
+    let dir = test_dir("bundle_hash");
+    let bundle = dir.join("assets.zip");
+    fs::write(&bundle, "abc").unwrap();
+
+    assert_eq!(
+        bundle_hash(&bundle).unwrap(),
+        "6437b3ac38465133ffb63b75273a8db548c558465d79db03fd359c6cd5bd9d85"
+    );
+    assert!(bundle_hash(dir.join("missing.zip")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,59 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, CHANGELOG_ENTRY};
+use serde_json::json;
+
+use common::{test_dir, write_files, zip_entries};
+
+#[test]
+fn changes_since_the_previous_bundle_are_written_to_the_changelog() {
+    let dir = test_dir("changelog");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("kept.txt", "kept"),
+            ("changed.txt", "old"),
+            ("removed.txt", "gone"),
+        ],
+    );
+    let bundle = dir.join("assets.zip");
+    let options = BundleOptions {
+        pack_threshold: 0,
+        previous_bundle: Some(bundle.clone()),
+        ..Default::default()
+    };
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+    // There is nothing to compare the first release with
+    assert!(!zip_entries(&bundle).contains_key(CHANGELOG_ENTRY));
+
+    fs::remove_file(asset_dir.join("removed.txt")).unwrap();
+    write_files(
+        &asset_dir,
+        &[("changed.txt", "newer"), ("added.txt", "new")],
+    );
+    // The previous bundle is read before it is overwritten by the new one
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+
+    let changelog = zip_entries(&bundle).remove(CHANGELOG_ENTRY).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&changelog).unwrap(),
+        json!({
+            "added": [{ "path": "added.txt", "size": 3 }],
+            "removed": [{ "path": "removed.txt", "size": 4 }],
+            "changed": [{ "path": "changed.txt", "old_size": 3, "new_size": 5 }],
+        })
+    );
+
+    // The changelog of the previous release isn't listed as a change in the next one
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+    let changelog = zip_entries(&bundle).remove(CHANGELOG_ENTRY).unwrap();
+    assert_eq!(
+        serde_json::from_str::<serde_json::Value>(&changelog).unwrap(),
+        json!({ "added": [], "removed": [], "changed": [] })
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,50 @@
+mod common;
+
+use std::fs::{self, File};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, CompressionMethod, DEFAULT_COMPRESSION,
+};
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn bundles_default_to_the_best_enabled_codec() {
+    #[cfg(feature = "bzip2")]
+    let expected = CompressionMethod::Bzip2;
+    #[cfg(all(not(feature = "bzip2"), feature = "deflate"))]
+    let expected = CompressionMethod::Deflated;
+    #[cfg(not(any(feature = "bzip2", feature = "deflate")))]
+    let expected = CompressionMethod::Stored;
+    assert_eq!(DEFAULT_COMPRESSION, expected);
+
+    let dir = test_dir("codecs");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(&asset_dir, &bundle, &BundleOptions::default());
+
+    let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+    assert_eq!(zip.by_name("a.txt").unwrap().compression(), expected);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(not(feature = "solid"))]
+#[should_panic(expected = "Solid bundles require the `solid` feature")]
+fn solid_bundles_need_the_solid_feature() {
+    let dir = test_dir("codecs-solid");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    bundle_assets_with_options(
+        &asset_dir,
+        &dir.join("assets.bin"),
+        &BundleOptions {
+            solid: true,
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,37 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, comment, BundleFormat, BundleOptions};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+#[test]
+fn comments_are_read_back_from_every_format() {
+    let dir = test_dir("comment");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    for (i, format) in [BundleFormat::Zip, BundleFormat::V2].iter().enumerate() {
+        let bundle = dir.join(format!("{}.zip", i));
+        bundle_assets_with_options(
+            &asset_dir,
+            &bundle,
+            &BundleOptions {
+                format: *format,
+                comment: Some("build 1234".to_owned()),
+                ..Default::default()
+            },
+        );
+
+        assert_eq!(comment(&bundle).unwrap(), "build 1234");
+        assert_eq!(bundled_assets(&bundle), files(&[("a.txt", "a")]));
+    }
+
+    // Bundles built without a comment have an empty one
+    let bundle = dir.join("plain.zip");
+    bundle_assets_with_options(&asset_dir, &bundle, &BundleOptions::default());
+    assert_eq!(comment(&bundle).unwrap(), "");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,83 @@
+//! Helpers shared by the integration tests
+//!
+//! Every test file compiles its own copy of this module, and not every file uses every helper.
+#![allow(dead_code)]
+
+use std::{
+    collections::BTreeMap,
+    fs::{self, File},
+    io::Read,
+    path::{Path, PathBuf},
+};
+
+use bevy_assetio_zip_bundler::extract;
+use walkdir::WalkDir;
+use zip::ZipArchive;
+
+/// Create an empty dir for a test, removing what an earlier run left behind
+pub fn test_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!(
+        "bevy_assetio_zip_bundler_{}-{}",
+        name,
+        std::process::id()
+    ));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+/// Write the given files to the dir, creating their parent dirs
+pub fn write_files(dir: &Path, files: &[(&str, &str)]) {
+    for (path, contents) in files {
+        let path = dir.join(path);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(path, contents).unwrap();
+    }
+}
+
+/// Read every file in the dir, keyed by its path relative to the dir
+pub fn read_files(dir: &Path) -> BTreeMap<String, String> {
+    WalkDir::new(dir)
+        .into_iter()
+        .map(|entry| entry.unwrap())
+        .filter(|entry| entry.file_type().is_file())
+        .map(|entry| {
+            let name = entry.path().strip_prefix(dir).unwrap();
+            let name = name.to_str().unwrap().replace('\\', "/");
+            (name, fs::read_to_string(entry.path()).unwrap())
+        })
+        .collect()
+}
+
+/// Get the assets in the bundle at the given path, keyed by their paths
+pub fn bundled_assets(bundle: &Path) -> BTreeMap<String, String> {
+    let out_dir = bundle.with_extension("extracted");
+    fs::remove_dir_all(&out_dir).ok();
+    extract(bundle, &out_dir).unwrap();
+    let assets = read_files(&out_dir);
+    fs::remove_dir_all(&out_dir).unwrap();
+    assets
+}
+
+/// Read every file entry in the zip at the given path, keyed by its entry name
+pub fn zip_entries(bundle: &Path) -> BTreeMap<String, String> {
+    let mut zip = ZipArchive::new(File::open(bundle).unwrap()).unwrap();
+    let mut entries = BTreeMap::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).unwrap();
+        if entry.is_file() {
+            let mut contents = String::new();
+            entry.read_to_string(&mut contents).unwrap();
+            entries.insert(entry.name().to_owned(), contents);
+        }
+    }
+    entries
+}
+
+/// Get a map of the given paths to contents
+pub fn files(files: &[(&str, &str)]) -> BTreeMap<String, String> {
+    files
+        .iter()
+        .map(|(path, contents)| (path.to_string(), contents.to_string()))
+        .collect()
+}
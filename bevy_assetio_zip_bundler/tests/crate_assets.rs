@@ -0,0 +1,51 @@
+//! Bundling the assets of a crate from a build script, which reads its settings from the
+//! environment variables that cargo sets for build scripts
+
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_crate_assets, register_step, BundleFile, BundleStep};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+/// Renames the levels to `.level` files
+struct RenameLevels;
+
+impl BundleStep for RenameLevels {
+    fn process(&self, mut file: BundleFile) -> Option<BundleFile> {
+        if file.name.starts_with("levels/") {
+            file.name = file.name.replace(".txt", ".level");
+        }
+        Some(file)
+    }
+}
+
+#[test]
+fn registered_steps_are_run_on_the_crate_assets() {
+    let dir = test_dir("crate_assets");
+    let out_dir = dir.join("target");
+    write_files(
+        &dir,
+        &[
+            (
+                "asset_config.toml",
+                &format!("out-dir = '{}'\n", out_dir.display()),
+            ),
+            ("assets/levels/1.txt", "1"),
+            ("assets/a.txt", "a"),
+        ],
+    );
+    std::env::set_var("CARGO_MANIFEST_DIR", &dir);
+    std::env::set_var("OUT_DIR", &out_dir);
+    std::env::set_var("PROFILE", "release");
+    register_step(RenameLevels);
+
+    bundle_crate_assets();
+    assert_eq!(
+        bundled_assets(&out_dir.join("assets.zip")),
+        files(&[("levels/1.level", "1"), ("a.txt", "a")])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
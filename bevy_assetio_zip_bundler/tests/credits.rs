@@ -0,0 +1,73 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, CREDITS_ENTRY};
+
+use common::{files, test_dir, write_files, zip_entries};
+
+#[test]
+fn license_files_are_collected_into_the_credits_entry() {
+    let dir = test_dir("credits");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("credits.txt", "Made by us"),
+            ("fonts/LICENSE", "OFL\n"),
+            ("music/Credits.md", "Music by someone"),
+            ("music/theme.ogg", "theme"),
+        ],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            collect_credits: true,
+            ..Default::default()
+        },
+    );
+
+    let mut entries = zip_entries(&bundle);
+    let credits = entries.remove(CREDITS_ENTRY).unwrap();
+    assert!(credits.contains("== fonts/LICENSE ==\n\nOFL\n\n"));
+    assert!(credits.contains("== music/Credits.md ==\n\nMusic by someone\n\n"));
+    // A top-level credits file is merged into the collected credits instead of overwriting them
+    assert!(credits.contains("== credits.txt ==\n\nMade by us\n\n"));
+
+    // The collected files are still bundled as regular assets
+    assert_eq!(
+        entries,
+        files(&[
+            ("fonts/LICENSE", "OFL\n"),
+            ("music/Credits.md", "Music by someone"),
+            ("music/theme.ogg", "theme"),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn credits_are_not_collected_by_default() {
+    let dir = test_dir("credits-default");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("LICENSE", "MIT")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(zip_entries(&bundle), files(&[("LICENSE", "MIT")]));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
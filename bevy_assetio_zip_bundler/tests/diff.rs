@@ -0,0 +1,89 @@
+mod common;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, diff, AssetChange, BundleOptions, CompressionMethod,
+};
+
+use common::{test_dir, write_files};
+
+#[test]
+fn bundles_are_compared_by_their_assets() {
+    let dir = test_dir("diff");
+    write_files(
+        &dir.join("old"),
+        &[
+            ("kept.txt", "kept"),
+            ("changed.txt", "old"),
+            ("removed.txt", "gone"),
+        ],
+    );
+    write_files(
+        &dir.join("new"),
+        &[
+            ("kept.txt", "kept"),
+            ("changed.txt", "newer"),
+            ("added.txt", "new"),
+        ],
+    );
+    bundle_assets_with_options(
+        dir.join("old"),
+        dir.join("old.zip"),
+        &BundleOptions::default(),
+    );
+    bundle_assets_with_options(
+        dir.join("new"),
+        dir.join("new.zip"),
+        &BundleOptions::default(),
+    );
+
+    let changes = diff(dir.join("old.zip"), dir.join("new.zip"));
+    let change = |path: &str, old_size, new_size| AssetChange {
+        path: path.to_owned(),
+        old_size,
+        new_size,
+    };
+    assert_eq!(changes.added, vec![change("added.txt", None, Some(3))]);
+    assert_eq!(changes.removed, vec![change("removed.txt", Some(4), None)]);
+    assert_eq!(
+        changes.changed,
+        vec![change("changed.txt", Some(3), Some(5))]
+    );
+    assert_eq!(
+        changes.to_string(),
+        "+ added.txt (3 bytes)\n- removed.txt (4 bytes)\n~ changed.txt (3 -> 5 bytes)\n"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bundles_written_with_different_options_have_no_differences() {
+    let dir = test_dir("diff_options");
+    write_files(
+        &dir.join("assets"),
+        &[("a.txt", "a"), ("b/c.txt", "c"), ("d.txt", "d")],
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("plain.zip"),
+        &BundleOptions {
+            compression: CompressionMethod::Stored,
+            ..Default::default()
+        },
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("packed.bin"),
+        &BundleOptions {
+            obfuscate: true,
+            pack_threshold: 1024,
+            asset_metadata: true,
+            ..Default::default()
+        },
+    );
+
+    let changes = diff(dir.join("plain.zip"), dir.join("packed.bin"));
+    assert!(changes.is_empty(), "{}", changes);
+
+    std::fs::remove_dir_all(&dir).unwrap();
+}
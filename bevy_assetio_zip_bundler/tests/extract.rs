@@ -0,0 +1,129 @@
+mod common;
+
+use std::{fs, io::Write};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, extract, BundleOptions, TimestampPolicy,
+};
+use zip::{write::FileOptions, ZipWriter};
+
+use common::{read_files, test_dir, write_files};
+
+#[test]
+fn extracted_bundles_match_their_asset_dir() {
+    let dir = test_dir("extract");
+    let assets = [
+        ("a.txt", "a"),
+        ("nested/b.txt", "b"),
+        ("large.txt", "large large large large large"),
+    ];
+    write_files(&dir.join("assets"), &assets);
+
+    let options = [
+        BundleOptions::default(),
+        BundleOptions {
+            obfuscate: true,
+            ..Default::default()
+        },
+        BundleOptions {
+            obfuscate_entries: true,
+            pack_threshold: 16,
+            ..Default::default()
+        },
+    ];
+    for (i, options) in options.iter().enumerate() {
+        let bundle = dir.join(format!("{}.zip", i));
+        bundle_assets_with_options(dir.join("assets"), bundle.clone(), options);
+        extract(&bundle, dir.join(i.to_string())).unwrap();
+        assert_eq!(
+            read_files(&dir.join(i.to_string())),
+            read_files(&dir.join("assets")),
+            "{:?}",
+            options
+        );
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn modification_times_are_restored_from_the_asset_metadata() {
+    let dir = test_dir("extract_times");
+    write_files(&dir.join("assets"), &[("a.txt", "a")]);
+    let modified = std::time::UNIX_EPOCH + std::time::Duration::from_secs(1_000_000_000);
+    filetime::set_file_mtime(
+        dir.join("assets/a.txt"),
+        filetime::FileTime::from_system_time(modified),
+    )
+    .unwrap();
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            asset_metadata: true,
+            timestamps: TimestampPolicy::Preserve,
+            ..Default::default()
+        },
+    );
+
+    extract(dir.join("assets.zip"), dir.join("out")).unwrap();
+    let extracted = fs::metadata(dir.join("out/a.txt")).unwrap();
+    assert_eq!(extracted.modified().unwrap(), modified);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn unix_permissions_are_restored() {
+    use std::os::unix::fs::PermissionsExt;
+
+    let dir = test_dir("extract_permissions");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("tools/run.sh", "#!/bin/sh"), ("a.txt", "a")]);
+    let set_mode = |path: &str, mode: u32| {
+        fs::set_permissions(asset_dir.join(path), fs::Permissions::from_mode(mode)).unwrap();
+    };
+    set_mode("tools/run.sh", 0o755);
+    set_mode("a.txt", 0o640);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            // Only the file that isn't executable is packed
+            pack_threshold: 1024,
+            unix_permissions: true,
+            ..Default::default()
+        },
+    );
+    extract(&bundle, dir.join("out")).unwrap();
+
+    let mode = |path: &str| {
+        let metadata = fs::metadata(dir.join("out").join(path)).unwrap();
+        metadata.permissions().mode() & 0o777
+    };
+    assert_eq!(mode("tools/run.sh"), 0o755);
+    assert_eq!(read_files(&dir.join("out")), read_files(&asset_dir));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn assets_outside_of_the_out_dir_are_not_extracted() {
+    let dir = test_dir("extract_outside");
+    let mut zip = ZipWriter::new(fs::File::create(dir.join("evil.zip")).unwrap());
+    zip.start_file("../escaped.txt", FileOptions::default())
+        .unwrap();
+    zip.write_all(b"escaped").unwrap();
+    zip.finish().unwrap();
+
+    let error = extract(dir.join("evil.zip"), dir.join("out")).unwrap_err();
+    assert_eq!(error.kind(), std::io::ErrorKind::InvalidData);
+    assert!(!dir.join("escaped.txt").exists());
+
+    assert!(extract(dir.join("missing.zip"), dir.join("out")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,184 @@
+mod common;
+
+use std::{
+    convert::TryInto,
+    fs,
+    io::{Cursor, Read},
+    path::Path,
+};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleFormat, BundleOptions, CompressionMethod, FLAG_COMPRESSED,
+    FLAG_KEY_SLOT, FLAG_OBFUSCATED, FORMAT_VERSION, HEADER_LEN, HEADER_MAGIC, KDF_FNV1A_SPLITMIX,
+    KEY_ALGORITHM_KEYSTREAM,
+};
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+/// The fields of a v2 header
+struct Header {
+    version: u16,
+    flags: u16,
+    header_len: usize,
+    index_offset: u64,
+}
+
+fn read_header(bundle: &[u8]) -> Header {
+    assert_eq!(&bundle[..4], HEADER_MAGIC);
+    Header {
+        version: u16::from_le_bytes(bundle[4..6].try_into().unwrap()),
+        flags: u16::from_le_bytes(bundle[6..8].try_into().unwrap()),
+        header_len: u32::from_le_bytes(bundle[8..12].try_into().unwrap()) as usize,
+        index_offset: u64::from_le_bytes(bundle[12..20].try_into().unwrap()),
+    }
+}
+
+/// Read the file entries of the given zip data
+fn entries(zip_data: Vec<u8>) -> Vec<(String, String)> {
+    let mut zip = ZipArchive::new(Cursor::new(zip_data)).unwrap();
+    let mut entries = Vec::new();
+    for i in 0..zip.len() {
+        let mut entry = zip.by_index(i).unwrap();
+        if !entry.is_file() {
+            continue;
+        }
+        let mut contents = String::new();
+        entry.read_to_string(&mut contents).unwrap();
+        entries.push((entry.name().to_owned(), contents));
+    }
+    entries.sort();
+    entries
+}
+
+fn bundle(dir: &Path, options: BundleOptions) -> Vec<u8> {
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a"), ("sub/b.txt", "bb")]);
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+    fs::read(bundle).unwrap()
+}
+
+#[test]
+fn v2_bundles_start_with_a_header() {
+    let dir = test_dir("format_v2");
+    let bundle = bundle(
+        &dir,
+        BundleOptions {
+            format: BundleFormat::V2,
+            ..Default::default()
+        },
+    );
+
+    let header = read_header(&bundle);
+    assert_eq!(header.version, FORMAT_VERSION);
+    assert_eq!(header.flags, FLAG_COMPRESSED);
+    assert_eq!(header.header_len, HEADER_LEN);
+
+    let zip_data = bundle[header.header_len..].to_vec();
+    // The end of central directory record points at the index
+    let eocd = &zip_data[zip_data.len() - 22..];
+    assert_eq!(
+        u32::from_le_bytes(eocd[16..20].try_into().unwrap()) as u64,
+        header.index_offset
+    );
+    assert_eq!(
+        entries(zip_data),
+        vec![
+            ("a.txt".to_owned(), "a".to_owned()),
+            ("sub/b.txt".to_owned(), "bb".to_owned())
+        ]
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn v2_headers_are_not_obfuscated() {
+    let dir = test_dir("format_v2_obfuscated");
+    let bundle = bundle(
+        &dir,
+        BundleOptions {
+            obfuscate: true,
+            compression: CompressionMethod::Stored,
+            format: BundleFormat::V2,
+            ..Default::default()
+        },
+    );
+
+    let header = read_header(&bundle);
+    assert_eq!(header.flags, FLAG_OBFUSCATED);
+    let zip_data = bundle[header.header_len..]
+        .iter()
+        .map(|x| x ^ 0b0101_0101)
+        .collect::<Vec<_>>();
+    assert_eq!(entries(zip_data).len(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn v2_headers_describe_obfuscated_entries_in_a_key_slot() {
+    let dir = test_dir("format_v2_key_slot");
+    let bundle = bundle(
+        &dir,
+        BundleOptions {
+            obfuscate_entries: true,
+            entry_salt: Some(b"game salt".to_vec()),
+            format: BundleFormat::V2,
+            ..Default::default()
+        },
+    );
+
+    let header = read_header(&bundle);
+    assert_eq!(header.flags, FLAG_COMPRESSED | FLAG_KEY_SLOT);
+    assert_eq!(header.header_len, HEADER_LEN + 4);
+    // The salt supplied by the game isn't stored in the bundle
+    assert_eq!(
+        &bundle[HEADER_LEN..header.header_len],
+        &[KEY_ALGORITHM_KEYSTREAM, KDF_FNV1A_SPLITMIX, 0, 0]
+    );
+    let zip = ZipArchive::new(Cursor::new(bundle[header.header_len..].to_vec())).unwrap();
+    assert!(zip.file_names().any(|name| name == "sub/b.txt"));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn v2_headers_are_padded_to_the_alignment() {
+    let dir = test_dir("format_v2_align");
+    let bundle = bundle(
+        &dir,
+        BundleOptions {
+            compression: CompressionMethod::Stored,
+            align: 64,
+            format: BundleFormat::V2,
+            ..Default::default()
+        },
+    );
+
+    let header = read_header(&bundle);
+    assert_eq!(header.header_len, 64);
+    // Entries are aligned in the bundle file, not just in the zip data
+    let mut zip = ZipArchive::new(Cursor::new(bundle[header.header_len..].to_vec())).unwrap();
+    for i in 0..zip.len() {
+        let entry = zip.by_index(i).unwrap();
+        if !entry.is_file() {
+            continue;
+        }
+        assert_eq!((entry.data_start() + 64) % 64, 0, "{}", entry.name());
+    }
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn zip_bundles_have_no_header() {
+    let dir = test_dir("format_zip");
+    let bundle = bundle(&dir, BundleOptions::default());
+
+    assert_eq!(&bundle[..2], b"PK");
+    assert_eq!(entries(bundle).len(), 2);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{files, test_dir, write_files, zip_entries};
+
+const GLTF: &str = r#"{
+    "buffers": [
+        { "uri": "../buffers/tree%20mesh.bin" },
+        { "uri": "data:application/octet-stream;base64,AAAA" }
+    ],
+    "images": [
+        { "uri": "./bark.png" },
+        { "uri": "../../outside.png" },
+        { "uri": "missing.png" }
+    ]
+}"#;
+
+#[test]
+fn gltf_dependencies_are_bundled_once() {
+    let dir = test_dir("gltf");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("models/tree.gltf", GLTF),
+            ("models/bark.png", "bark"),
+            ("buffers/tree mesh.bin", "mesh"),
+        ],
+    );
+    write_files(&dir, &[("outside.png", "outside")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            ..Default::default()
+        },
+    );
+
+    // Files outside of the asset dir and missing files are only warned about
+    assert_eq!(
+        zip_entries(&bundle),
+        files(&[
+            ("buffers/tree mesh.bin", "mesh"),
+            ("models/bark.png", "bark"),
+            ("models/tree.gltf", GLTF),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn invalid_gltf_files_are_still_bundled() {
+    let dir = test_dir("gltf-invalid");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("models/broken.gltf", "not json")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        zip_entries(&bundle),
+        files(&[("models/broken.gltf", "not json")])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
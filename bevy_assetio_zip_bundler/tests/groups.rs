@@ -0,0 +1,66 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, GROUPS_ENTRY};
+
+use common::{bundled_assets, files, test_dir, write_files, zip_entries};
+
+#[test]
+fn asset_groups_are_written_to_the_bundle() {
+    let dir = test_dir("groups");
+    let asset_dir = dir.join("assets");
+    let assets = [
+        ("levels/1/map.txt", "1"),
+        ("levels/2/map.txt", "2"),
+        ("music/boss.ogg", "boss"),
+    ];
+    write_files(&asset_dir, &assets);
+
+    let bundle = dir.join("assets.zip");
+    let groups = vec![
+        ("level_1".to_owned(), vec!["levels/1/*".to_owned()]),
+        (
+            "level_2".to_owned(),
+            vec!["levels/2/**".to_owned(), "music/boss.ogg".to_owned()],
+        ),
+        // Groups that match nothing are only warned about
+        ("level_3".to_owned(), vec!["levels/3/**".to_owned()]),
+    ];
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            groups: groups.into_iter().collect(),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        zip_entries(&bundle).remove(GROUPS_ENTRY).unwrap(),
+        "level_1\tlevels/1/*\nlevel_2\tlevels/2/**\nlevel_2\tmusic/boss.ogg\nlevel_3\tlevels/3/**\n"
+    );
+    // The groups entry is not an asset
+    assert_eq!(bundled_assets(&bundle), files(&assets));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "Invalid pattern levels/[ in group broken")]
+fn invalid_group_patterns_panic() {
+    let dir = test_dir("groups-invalid");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    bundle_assets_with_options(
+        &asset_dir,
+        &dir.join("assets.zip"),
+        &BundleOptions {
+            groups: vec![("broken".to_owned(), vec!["levels/[".to_owned()])]
+                .into_iter()
+                .collect(),
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,54 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+#[test]
+fn ignored_assets_are_left_out_of_the_bundle() {
+    let dir = test_dir("ignore");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            (
+                ".assetignore",
+                "# Work in progress\n*.psd\nwip/\n!keep.psd\n",
+            ),
+            ("a.txt", "a"),
+            ("art/hero.psd", "layers"),
+            ("art/keep.psd", "kept"),
+            ("wip/level.txt", "unfinished"),
+            ("levels/.assetignore", "/draft.txt\n"),
+            ("levels/draft.txt", "draft"),
+            ("levels/1.txt", "1"),
+            ("levels/old/draft.txt", "old"),
+        ],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            ..Default::default()
+        },
+    );
+
+    // Patterns with a slash are relative to their `.assetignore` file, and the ignore files
+    // themselves are never bundled
+    assert_eq!(
+        bundled_assets(&bundle),
+        files(&[
+            ("a.txt", "a"),
+            ("art/keep.psd", "kept"),
+            ("levels/1.txt", "1"),
+            ("levels/old/draft.txt", "old"),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,99 @@
+mod common;
+
+use std::fs::{self, File};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleFormat, BundleOptions, CompressionMethod, INDEX_MAGIC,
+    INDEX_VERSION,
+};
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn the_index_lists_every_entry() {
+    let dir = test_dir("index");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a"), ("sub/b.txt", "bb")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            write_index: true,
+            ..Default::default()
+        },
+    );
+
+    let index = fs::read_to_string(dir.join("assets.idx")).unwrap();
+    let mut lines = index.lines();
+    let header = lines.next().unwrap().split('\t').collect::<Vec<_>>();
+    assert_eq!(header[0], INDEX_MAGIC);
+    assert_eq!(header[1], INDEX_VERSION.to_string());
+    assert_eq!(header[2], fs::metadata(&bundle).unwrap().len().to_string());
+
+    let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+    let mut names = Vec::new();
+    for line in lines {
+        let fields = line.split('\t').collect::<Vec<_>>();
+        let entry = zip.by_name(fields[5]).unwrap();
+        assert_eq!(fields[0], entry.data_start().to_string());
+        assert_eq!(fields[1], entry.compressed_size().to_string());
+        assert_eq!(fields[2], entry.size().to_string());
+        assert_eq!(fields[3], entry.crc32().to_string());
+        let compression = match entry.compression() {
+            CompressionMethod::Stored => "stored",
+            CompressionMethod::Deflated => "deflated",
+            CompressionMethod::Bzip2 => "bzip2",
+            other => panic!("Unexpected compression method {:?}", other),
+        };
+        assert_eq!(fields[4], compression);
+        names.push(fields[5].to_owned());
+    }
+    names.sort();
+    // Directory entries are listed just like in the zip central directory
+    assert_eq!(names, vec!["a.txt", "sub/", "sub/b.txt"]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn v2_index_offsets_are_relative_to_the_zip_data() {
+    let dir = test_dir("index_v2");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            format: BundleFormat::V2,
+            write_index: true,
+            ..Default::default()
+        },
+    );
+
+    // The size in the index is of the zip data after the header
+    let index = fs::read_to_string(dir.join("assets.idx")).unwrap();
+    let size = index.lines().next().unwrap().split('\t').nth(2).unwrap();
+    assert_eq!(
+        size,
+        (fs::metadata(&bundle).unwrap().len() - 20).to_string()
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn no_index_is_written_by_default() {
+    let dir = test_dir("index_default");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    bundle_assets_with_options(&asset_dir, &dir.join("assets.zip"), &Default::default());
+    assert!(!dir.join("assets.idx").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
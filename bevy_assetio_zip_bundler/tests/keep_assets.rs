@@ -0,0 +1,40 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{test_dir, write_files};
+
+fn rebundle_without(name: &str, removed: &str) {
+    let dir = test_dir(name);
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("saves/items/sword.ron", "sword"), ("levels/1.txt", "1")],
+    );
+    let bundle = dir.join("assets.zip");
+    let options = BundleOptions {
+        pack_threshold: 0,
+        previous_bundle: Some(bundle.clone()),
+        keep_assets: vec!["saves/**".to_owned()],
+        ..Default::default()
+    };
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+
+    fs::remove_file(asset_dir.join(removed)).unwrap();
+    bundle_assets_with_options(&asset_dir, &bundle, &options);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn assets_that_are_not_kept_can_be_removed() {
+    rebundle_without("keep_assets-other", "levels/1.txt");
+}
+
+#[test]
+#[should_panic(expected = "removed since the previous bundle:\n  saves/items/sword.ron")]
+fn removing_kept_assets_fails_the_build() {
+    rebundle_without("keep_assets-removed", "saves/items/sword.ron");
+}
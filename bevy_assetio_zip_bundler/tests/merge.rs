@@ -0,0 +1,66 @@
+mod common;
+
+use std::{fs, path::Path};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, ConflictPolicy};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+/// Bundle an asset dir merged with a shared asset dir that has a different `logo.png`
+fn bundle_merged(dir: &Path, merge_conflicts: ConflictPolicy) {
+    write_files(
+        &dir.join("assets"),
+        &[("logo.png", "game"), ("levels/1.txt", "1"), ("a.txt", "a")],
+    );
+    write_files(
+        &dir.join("shared"),
+        &[
+            ("logo.png", "studio"),
+            ("levels/shared.txt", "s"),
+            ("a.txt", "a"),
+        ],
+    );
+
+    bundle_assets_with_options(
+        &dir.join("assets"),
+        &dir.join("assets.zip"),
+        &BundleOptions {
+            extra_asset_dirs: vec![dir.join("shared")],
+            merge_conflicts,
+            ..Default::default()
+        },
+    );
+}
+
+#[test]
+fn conflicts_are_resolved_with_the_policy() {
+    for (i, (policy, logo)) in [
+        (ConflictPolicy::FirstWins, "game"),
+        (ConflictPolicy::LastWins, "studio"),
+    ]
+    .iter()
+    .enumerate()
+    {
+        let dir = test_dir(&format!("merge-{}", i));
+        bundle_merged(&dir, *policy);
+
+        // Directories are merged and identical files are only bundled once
+        assert_eq!(
+            bundled_assets(&dir.join("assets.zip")),
+            files(&[
+                ("a.txt", "a"),
+                ("levels/1.txt", "1"),
+                ("levels/shared.txt", "s"),
+                ("logo.png", logo),
+            ])
+        );
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
+
+#[test]
+#[should_panic(expected = "Different files are bundled at the same paths in the merged asset dirs")]
+fn conflicts_fail_the_build_by_default() {
+    bundle_merged(&test_dir("merge-error"), ConflictPolicy::default());
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::{
+    fs,
+    time::{SystemTime, UNIX_EPOCH},
+};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, METADATA_ENTRY};
+
+use common::{test_dir, write_files, zip_entries};
+
+#[test]
+fn asset_metadata_is_recorded_for_every_asset() {
+    let dir = test_dir("metadata");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("big.txt", "bigger than the threshold"), ("small.txt", "s")],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 10,
+            asset_metadata: true,
+            ..Default::default()
+        },
+    );
+
+    // Packed files get metadata too
+    let now = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let mut lines = zip_entries(&bundle)[METADATA_ENTRY]
+        .lines()
+        .map(|line| {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let modified = fields[0].parse::<u64>().unwrap();
+            assert!(now - modified < 60 * 60);
+            (fields[2].to_owned(), fields[1].to_owned())
+        })
+        .collect::<Vec<_>>();
+    lines.sort();
+    assert_eq!(
+        lines,
+        [
+            (
+                "big.txt".to_owned(),
+                format!("{:08x}", crc32fast::hash(b"bigger than the threshold"))
+            ),
+            (
+                "small.txt".to_owned(),
+                format!("{:08x}", crc32fast::hash(b"s"))
+            ),
+        ]
+    );
+
+    // No metadata is recorded by default
+    bundle_assets_with_options(&asset_dir, &bundle, &BundleOptions::default());
+    assert!(!zip_entries(&bundle).contains_key(METADATA_ENTRY));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,163 @@
+mod common;
+
+use std::{
+    fs::{self, File},
+    io::{Cursor, Read, Write},
+};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, wrap_reader, wrap_reader_with_key, wrap_writer,
+    wrap_writer_with_key, BundleOptions, OBFUSCATED_ENTRIES_MARKER, OBFUSCATION_KEY,
+};
+use xorio::Xor;
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn obfuscated_bundles_are_xored() {
+    let dir = test_dir("obfuscation");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            obfuscate: true,
+            ..Default::default()
+        },
+    );
+
+    // The whole file is scrambled, so it only reads as a zip once it is XOR-ed again
+    assert!(ZipArchive::new(File::open(&bundle).unwrap()).is_err());
+    let mut zip = ZipArchive::new(Xor::new(File::open(&bundle).unwrap())).unwrap();
+    assert!(zip.by_name("a.txt").is_ok());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn obfuscated_entries_keep_the_zip_structure() {
+    let dir = test_dir("obfuscated_entries");
+    let asset_dir = dir.join("assets");
+    let contents = "the same contents in both files";
+    write_files(&asset_dir, &[("a.txt", contents), ("sub/b.txt", contents)]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            obfuscate_entries: true,
+            ..Default::default()
+        },
+    );
+
+    // Every file is listed, but its data is scrambled with a key of its own
+    let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+    let mut entry_data = |name: &str| {
+        let mut data = Vec::new();
+        zip.by_name(name).unwrap().read_to_end(&mut data).unwrap();
+        data
+    };
+    let a = entry_data("a.txt");
+    let b = entry_data("sub/b.txt");
+    assert_eq!(a.len(), contents.len());
+    assert_ne!(a, contents.as_bytes());
+    assert_ne!(a, b);
+
+    // The marker holds the salt that the keys are derived from
+    assert_eq!(entry_data(OBFUSCATED_ENTRIES_MARKER).len(), 16);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bundles_get_a_new_salt_every_time() {
+    let dir = test_dir("obfuscated_entries-salt");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "abc")]);
+
+    let options = BundleOptions {
+        obfuscate_entries: true,
+        ..Default::default()
+    };
+    let salts = (0..2)
+        .map(|i| {
+            let bundle = dir.join(format!("assets-{}.zip", i));
+            bundle_assets_with_options(&asset_dir, &bundle, &options);
+            let mut zip = ZipArchive::new(File::open(&bundle).unwrap()).unwrap();
+            let mut salt = Vec::new();
+            zip.by_name(OBFUSCATED_ENTRIES_MARKER)
+                .unwrap()
+                .read_to_end(&mut salt)
+                .unwrap();
+            salt
+        })
+        .collect::<Vec<_>>();
+    assert_ne!(salts[0], salts[1]);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn obfuscated_bundles_are_read_through_the_wrapped_reader() {
+    let dir = test_dir("obfuscation_wrap");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            obfuscate: true,
+            ..Default::default()
+        },
+    );
+
+    let mut zip = ZipArchive::new(wrap_reader(File::open(&bundle).unwrap())).unwrap();
+    let mut contents = String::new();
+    zip.by_name("a.txt")
+        .unwrap()
+        .read_to_string(&mut contents)
+        .unwrap();
+    assert_eq!(contents, "a");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn wrapped_writers_obfuscate_like_the_bundler() {
+    let mut obfuscated = Vec::new();
+    wrap_writer(&mut obfuscated).write_all(b"data").unwrap();
+    assert_eq!(
+        obfuscated,
+        b"data"
+            .iter()
+            .map(|x| x ^ OBFUSCATION_KEY)
+            .collect::<Vec<_>>()
+    );
+
+    let mut data = Vec::new();
+    wrap_reader(Cursor::new(obfuscated))
+        .read_to_end(&mut data)
+        .unwrap();
+    assert_eq!(data, b"data");
+
+    // Custom keys are read back with the same key
+    let mut obfuscated = Vec::new();
+    wrap_writer_with_key(&mut obfuscated, &[1, 2])
+        .write_all(b"data")
+        .unwrap();
+    assert_eq!(
+        obfuscated,
+        b"data".iter().map(|x| x ^ 3).collect::<Vec<_>>()
+    );
+    let mut data = Vec::new();
+    wrap_reader_with_key(Cursor::new(obfuscated), &[1, 2])
+        .read_to_end(&mut data)
+        .unwrap();
+    assert_eq!(data, b"data");
+}
@@ -0,0 +1,68 @@
+mod common;
+
+use std::{collections::BTreeMap, fs};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions, PACK_INDEX_ENTRY};
+
+use common::{files, test_dir, write_files, zip_entries};
+
+#[test]
+fn small_files_are_packed_into_blocks() {
+    let dir = test_dir("packs");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("big.txt", "bigger than the threshold"),
+            ("small/a.txt", "aaaa"),
+            ("small/b.txt", "bbbb"),
+            ("small/c.txt", "cccc"),
+        ],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 10,
+            pack_block_size: 8,
+            ..Default::default()
+        },
+    );
+
+    let mut entries = zip_entries(&bundle);
+    let index = entries.remove(PACK_INDEX_ENTRY).unwrap();
+    // Blocks are written once they reach the block size, so the last one may be smaller
+    assert_eq!(entries[".packs/0"].len(), 8);
+    assert_eq!(entries[".packs/1"].len(), 4);
+
+    let mut packed = index
+        .lines()
+        .map(|line| {
+            let fields = line.split('\t').collect::<Vec<_>>();
+            let block = &entries[&format!(".packs/{}", fields[0])];
+            let offset = fields[1].parse::<usize>().unwrap();
+            let length = fields[2].parse::<usize>().unwrap();
+            (fields[3], &block[offset..offset + length])
+        })
+        .collect::<Vec<_>>();
+    packed.sort_unstable();
+    assert_eq!(
+        packed,
+        [
+            ("small/a.txt", "aaaa"),
+            ("small/b.txt", "bbbb"),
+            ("small/c.txt", "cccc")
+        ]
+    );
+
+    // Only large files get their own entries
+    let unpacked = entries
+        .into_iter()
+        .filter(|(name, _)| !name.starts_with(".packs/"))
+        .collect::<BTreeMap<_, _>>();
+    assert_eq!(unpacked, files(&[("big.txt", "bigger than the threshold")]));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
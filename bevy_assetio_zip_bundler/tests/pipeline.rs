@@ -0,0 +1,104 @@
+mod common;
+
+use std::{fs, panic::AssertUnwindSafe};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, Converter, PipelineStep,
+};
+
+use common::{files, test_dir, write_files, zip_entries};
+
+#[test]
+fn pipeline_steps_convert_the_matching_files() {
+    let dir = test_dir("pipeline");
+    write_files(
+        &dir.join("assets"),
+        &[
+            (
+                "models/tree.gltf",
+                "{ \"asset\": { \"version\": \"2.0\" } }",
+            ),
+            ("data.json", "{ \"kept\": true }"),
+        ],
+    );
+    let step = |pattern: &str, extension: Option<&str>| PipelineStep {
+        pattern: pattern.to_owned(),
+        command: None,
+        converter: Some(Converter::MinifyJson),
+        extension: extension.map(str::to_owned),
+    };
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            // The second step matches the extension given by the first one
+            pipeline: vec![step("**/*.gltf", Some("min")), step("**/*.min", None)],
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        zip_entries(&dir.join("assets.zip")),
+        files(&[
+            ("data.json", "{ \"kept\": true }"),
+            ("models/tree.min", "{\"asset\":{\"version\":\"2.0\"}}"),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[cfg(unix)]
+fn pipeline_commands_write_the_converted_file() {
+    let dir = test_dir("pipeline_command");
+    write_files(&dir.join("assets"), &[("a.txt", "a")]);
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            pipeline: vec![PipelineStep {
+                pattern: "*.txt".to_owned(),
+                command: Some("cp {input} {output}".to_owned()),
+                converter: None,
+                extension: Some("copy".to_owned()),
+            }],
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        zip_entries(&dir.join("assets.zip")),
+        files(&[("a.copy", "a")])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn pipeline_steps_need_exactly_one_converter() {
+    let dir = test_dir("pipeline_invalid");
+    write_files(&dir.join("assets"), &[("a.json", "{}")]);
+    let bundle = |command: Option<&str>, converter| {
+        std::panic::catch_unwind(AssertUnwindSafe(|| {
+            bundle_assets_with_options(
+                dir.join("assets"),
+                dir.join("assets.zip"),
+                &BundleOptions {
+                    pipeline: vec![PipelineStep {
+                        pattern: "*.json".to_owned(),
+                        command: command.map(str::to_owned),
+                        converter,
+                        extension: None,
+                    }],
+                    ..Default::default()
+                },
+            )
+        }))
+    };
+
+    assert!(bundle(None, None).is_err());
+    assert!(bundle(Some("cp {input} {output}"), Some(Converter::MinifyJson)).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
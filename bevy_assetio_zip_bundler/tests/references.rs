@@ -0,0 +1,78 @@
+mod common;
+
+use std::{
+    fs,
+    panic::{self, AssertUnwindSafe},
+};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{test_dir, write_files};
+
+const SCENE: &str = r#"[
+    (
+        entity: 0,
+        components: [
+            { "type": "Sprite", "map": { "texture": "textures/grass.png" } },
+            { "type": "Model", "map": { "mesh": "models/tree.gltf#Mesh0" } },
+            { "type": "Name", "map": { "name": "the tree.png" } },
+        ],
+    ),
+]"#;
+
+#[test]
+fn scenes_referencing_existing_assets_are_bundled() {
+    let dir = test_dir("references");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("scenes/level.scn", SCENE),
+            ("textures/grass.png", "grass"),
+            ("models/tree.gltf", "tree"),
+        ],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            validate_references: true,
+            ..Default::default()
+        },
+    );
+    assert!(bundle.exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_references_fail_the_build() {
+    let dir = test_dir("references-missing");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("scenes/level.scn", SCENE), ("textures/stone.png", "stone")],
+    );
+
+    let bundle = dir.join("assets.zip");
+    let options = BundleOptions {
+        validate_references: true,
+        ..Default::default()
+    };
+    let error = panic::catch_unwind(AssertUnwindSafe(|| {
+        bundle_assets_with_options(&asset_dir, &bundle, &options)
+    }))
+    .unwrap_err();
+    let message = error.downcast_ref::<String>().unwrap();
+    assert!(message.contains("scenes/level.scn references missing asset textures/grass.png"));
+    // Only strings ending in the extension of an asset are checked
+    assert!(!message.contains("tree.gltf"));
+    assert!(!message.contains("the tree.png"));
+
+    // References aren't checked unless enabled
+    bundle_assets_with_options(&asset_dir, &bundle, &BundleOptions::default());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,80 @@
+mod common;
+
+use std::{collections::BTreeMap, fs};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleOptions};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+fn renames(renames: &[(&str, &str)]) -> BTreeMap<String, String> {
+    renames
+        .iter()
+        .map(|(from, to)| (from.to_string(), to.to_string()))
+        .collect()
+}
+
+#[test]
+fn assets_are_bundled_at_their_renamed_paths() {
+    let dir = test_dir("renames");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[
+            ("old.txt", "old"),
+            ("raw/textures/grass.png", "grass"),
+            ("raw/textures/ui/button.png", "button"),
+            ("raw/music.ogg", "music"),
+            ("root/a.txt", "a"),
+            ("small.txt", "s"),
+        ],
+    );
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            // Packed assets are renamed too
+            pack_threshold: 2,
+            renames: renames(&[
+                ("old.txt", "new.txt"),
+                ("raw", "source"),
+                // The longest matching path wins
+                ("raw/textures/", "textures"),
+                ("root", ""),
+            ]),
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        bundled_assets(&bundle),
+        files(&[
+            ("a.txt", "a"),
+            ("new.txt", "old"),
+            ("small.txt", "s"),
+            ("source/music.ogg", "music"),
+            ("textures/grass.png", "grass"),
+            ("textures/ui/button.png", "button"),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+#[should_panic(expected = "bundled at the same paths in the merged asset dirs:\n  a.txt: ")]
+fn assets_renamed_to_the_same_path_conflict() {
+    let dir = test_dir("renames-clash");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a"), ("b.txt", "b")]);
+
+    bundle_assets_with_options(
+        &asset_dir,
+        &dir.join("assets.zip"),
+        &BundleOptions {
+            renames: renames(&[("b.txt", "a.txt")]),
+            ..Default::default()
+        },
+    );
+}
@@ -0,0 +1,66 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, diff, repack, BundleOptions, CompressionMethod,
+};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+#[test]
+fn repacked_bundles_keep_their_assets() {
+    let dir = test_dir("repack");
+    write_files(
+        &dir.join("assets"),
+        &[("a.txt", "a"), ("nested/b.txt", "b")],
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.bin"),
+        &BundleOptions {
+            obfuscate: true,
+            pack_threshold: 16,
+            ..Default::default()
+        },
+    );
+
+    repack(
+        dir.join("assets.bin"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            compression: CompressionMethod::Stored,
+            ..Default::default()
+        },
+    )
+    .unwrap();
+
+    assert!(diff(dir.join("assets.bin"), dir.join("assets.zip")).is_empty());
+    assert_eq!(
+        bundled_assets(&dir.join("assets.zip")),
+        files(&[("a.txt", "a"), ("nested/b.txt", "b")])
+    );
+    // The repacked bundle is a plain zip file
+    let mut zip = zip::ZipArchive::new(fs::File::open(dir.join("assets.zip")).unwrap()).unwrap();
+    assert_eq!(
+        zip.by_name("a.txt").unwrap().compression(),
+        CompressionMethod::Stored
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn missing_bundles_cannot_be_repacked() {
+    let dir = test_dir("repack_missing");
+
+    assert!(repack(
+        dir.join("missing.zip"),
+        dir.join("assets.zip"),
+        &BundleOptions::default()
+    )
+    .is_err());
+    assert!(!dir.join("assets.zip").exists());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
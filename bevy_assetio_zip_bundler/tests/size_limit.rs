@@ -0,0 +1,46 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, CompressionMethod, SizeLimitAction,
+};
+
+use common::{test_dir, write_files};
+
+fn bundle_with_limit(name: &str, max_bundle_size: u64, action: SizeLimitAction) {
+    let dir = test_dir(name);
+    let asset_dir = dir.join("assets");
+    let large = "x".repeat(4096);
+    write_files(&asset_dir, &[("small.txt", "small"), ("large.txt", &large)]);
+
+    bundle_assets_with_options(
+        &asset_dir,
+        &dir.join("assets.zip"),
+        &BundleOptions {
+            pack_threshold: 0,
+            compression: CompressionMethod::Stored,
+            max_bundle_size: Some(max_bundle_size),
+            max_bundle_size_action: action,
+            ..Default::default()
+        },
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn bundles_within_the_limit_are_accepted() {
+    bundle_with_limit("size_limit-ok", 1 << 20, SizeLimitAction::Fail);
+}
+
+#[test]
+#[should_panic(expected = "The largest entries are:\n  large.txt (4.00 KiB)\n  small.txt (5 B)")]
+fn bundles_over_the_limit_fail_the_build() {
+    bundle_with_limit("size_limit-fail", 1024, SizeLimitAction::Fail);
+}
+
+#[test]
+fn bundles_over_the_limit_can_only_warn() {
+    bundle_with_limit("size_limit-warn", 1024, SizeLimitAction::Warn);
+}
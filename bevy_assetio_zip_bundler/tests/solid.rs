@@ -0,0 +1,59 @@
+mod common;
+
+use std::{convert::TryInto, fs, io::Cursor};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, CompressionMethod, FLAG_SOLID, FORMAT_VERSION,
+    HEADER_MAGIC,
+};
+use zip::ZipArchive;
+
+use common::{test_dir, write_files};
+
+#[test]
+fn solid_bundles_are_seekable_zstd_streams_of_stored_entries() {
+    let dir = test_dir("solid");
+    let asset_dir = dir.join("assets");
+    let level = "wall ".repeat(200);
+    write_files(&asset_dir, &[("a.txt", "a"), ("levels/1.txt", &level)]);
+
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            solid: true,
+            solid_frame_size: 256,
+            ..Default::default()
+        },
+    );
+    let bundle = fs::read(bundle).unwrap();
+
+    // Solid bundles are always written with a v2 header
+    assert_eq!(&bundle[..4], HEADER_MAGIC);
+    assert_eq!(
+        u16::from_le_bytes(bundle[4..6].try_into().unwrap()),
+        FORMAT_VERSION
+    );
+    let flags = u16::from_le_bytes(bundle[6..8].try_into().unwrap());
+    assert_eq!(flags, FLAG_SOLID);
+    let header_len = u32::from_le_bytes(bundle[8..12].try_into().unwrap()) as usize;
+
+    // The seek table footer records a frame for every started frame size of zip data
+    let data = &bundle[header_len..];
+    let footer = &data[data.len() - 9..];
+    assert_eq!(footer[5..], 0x8F92_EAB1u32.to_le_bytes());
+    let frame_count = u32::from_le_bytes(footer[..4].try_into().unwrap()) as usize;
+
+    let zip_data = zstd::stream::decode_all(data).unwrap();
+    assert!(frame_count > 1);
+    assert!((frame_count - 1) * 256 < zip_data.len() && zip_data.len() <= frame_count * 256);
+    assert!(data.len() < zip_data.len());
+
+    let mut zip = ZipArchive::new(Cursor::new(zip_data)).unwrap();
+    let entry = zip.by_name("levels/1.txt").unwrap();
+    assert_eq!(entry.compression(), CompressionMethod::Stored);
+    assert_eq!(entry.size(), level.len() as u64);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, stats, BundleOptions, CompressionMethod, ExtensionStats,
+};
+
+use common::{test_dir, write_files};
+
+#[test]
+fn bundle_sizes_are_collected_by_extension() {
+    let dir = test_dir("stats");
+    let asset_dir = dir.join("assets");
+    write_files(
+        &asset_dir,
+        &[("a.txt", "aaaa"), ("levels/b.TXT", "bb"), ("c.png", "ccc")],
+    );
+    let bundle = dir.join("assets.zip");
+    let stats_file = dir.join("stats.json");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            compression: CompressionMethod::Stored,
+            stats_file: Some(stats_file.clone()),
+            ..Default::default()
+        },
+    );
+
+    let stats = stats(&bundle).unwrap();
+    assert_eq!(stats.bundle_bytes, fs::metadata(&bundle).unwrap().len());
+    // Extensions are grouped case-insensitively
+    assert_eq!(
+        stats.extensions["txt"],
+        ExtensionStats {
+            entries: 2,
+            compressed_bytes: 6,
+            uncompressed_bytes: 6,
+        }
+    );
+    assert_eq!(stats.extensions["png"].entries, 1);
+    assert_eq!(
+        stats.entries,
+        stats.extensions.values().map(|x| x.entries).sum::<u64>()
+    );
+    // The bundle wasn't just built by this call
+    assert_eq!(stats.build_duration, None);
+
+    let json = serde_json::from_str::<serde_json::Value>(&fs::read_to_string(&stats_file).unwrap())
+        .unwrap();
+    assert_eq!(json["bundle_bytes"], stats.bundle_bytes);
+    assert_eq!(json["extensions"]["txt"]["uncompressed_bytes"], 6);
+    assert!(json["build_seconds"].is_f64());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn stats_of_new_bundles_include_the_build_time() {
+    let dir = test_dir("stats_file");
+    write_files(&dir.join("assets"), &[("a.txt", "a")]);
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            stats_file: Some(dir.join("stats.json")),
+            ..Default::default()
+        },
+    );
+
+    let json: serde_json::Value =
+        serde_json::from_slice(&fs::read(dir.join("stats.json")).unwrap()).unwrap();
+    assert_eq!(json["entries"], 1);
+    assert!(json["build_seconds"].is_number());
+
+    assert!(stats(dir.join("missing.zip")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,72 @@
+mod common;
+
+use std::{fs, sync::Arc};
+
+use bevy_assetio_zip_bundler::{bundle_assets_with_options, BundleFile, BundleOptions, BundleStep};
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+/// Leaves the editor files out and adds a list of the levels
+struct SkipEditorFiles;
+
+impl BundleStep for SkipEditorFiles {
+    fn process(&self, file: BundleFile) -> Option<BundleFile> {
+        if file.name.starts_with("editor/") {
+            None
+        } else {
+            Some(file)
+        }
+    }
+
+    fn added_files(&self) -> Vec<BundleFile> {
+        vec![BundleFile::new("levels.txt", b"1".to_vec())]
+    }
+}
+
+/// Moves the levels into a dir and uppercases them
+struct ShoutLevels;
+
+impl BundleStep for ShoutLevels {
+    fn process(&self, mut file: BundleFile) -> Option<BundleFile> {
+        if file.name.starts_with("levels/") {
+            assert!(file.path().is_some());
+            let data = file.data().unwrap().to_ascii_uppercase();
+            file.set_data(data);
+            file.name = format!("loud/{}", file.name);
+        }
+        Some(file)
+    }
+}
+
+#[test]
+fn bundle_steps_filter_transform_and_add_files() {
+    let dir = test_dir("steps");
+    write_files(
+        &dir.join("assets"),
+        &[
+            ("editor/gizmo.txt", "gizmo"),
+            ("levels/1.txt", "level one"),
+            ("a.txt", "a"),
+        ],
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            steps: vec![Arc::new(SkipEditorFiles), Arc::new(ShoutLevels)],
+            pack_threshold: 1024,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        bundled_assets(&dir.join("assets.zip")),
+        files(&[
+            ("a.txt", "a"),
+            ("levels.txt", "1"),
+            ("loud/levels/1.txt", "LEVEL ONE"),
+        ])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
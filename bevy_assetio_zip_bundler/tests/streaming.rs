@@ -0,0 +1,97 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, CompressionMethod, METADATA_ENTRY,
+};
+
+use common::{bundled_assets, files, test_dir, write_files, zip_entries};
+
+/// Text that is larger than the chunks that files are copied in and doesn't repeat, so that a
+/// chunk copied with the wrong offset can't go unnoticed
+fn large_text() -> String {
+    (0..40_000).map(|i| format!("{} ", i)).collect()
+}
+
+#[test]
+fn large_files_are_copied_with_per_entry_obfuscation() {
+    let dir = test_dir("streaming");
+    let asset_dir = dir.join("assets");
+    let level = large_text();
+    assert!(level.len() > 3 * 64 * 1024);
+    write_files(&asset_dir, &[("levels/1.txt", &level), ("a.txt", "a")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            obfuscate_entries: true,
+            compression: CompressionMethod::Stored,
+            asset_metadata: true,
+            ..Default::default()
+        },
+    );
+
+    assert_eq!(
+        bundled_assets(&bundle),
+        files(&[("a.txt", "a"), ("levels/1.txt", &level)])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn copied_files_are_hashed_while_they_are_streamed() {
+    let dir = test_dir("streaming-metadata");
+    let asset_dir = dir.join("assets");
+    let level = large_text();
+    write_files(&asset_dir, &[("levels/1.txt", &level)]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            asset_metadata: true,
+            ..Default::default()
+        },
+    );
+
+    let metadata = zip_entries(&bundle).remove(METADATA_ENTRY).unwrap();
+    let hash = format!("\t{:08x}\tlevels/1.txt", crc32fast::hash(level.as_bytes()));
+    assert!(metadata.lines().any(|line| line.ends_with(&hash)));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn solid_bundles_are_compressed_without_leaving_temporary_files() {
+    let dir = test_dir("streaming-solid");
+    let asset_dir = dir.join("assets");
+    let level = large_text();
+    write_files(&asset_dir, &[("levels/1.txt", &level), ("a.txt", "a")]);
+
+    let bundle = dir.join("assets.bin");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            solid: true,
+            solid_frame_size: 16 * 1024,
+            obfuscate: true,
+            ..Default::default()
+        },
+    );
+
+    assert!(!bundle.with_extension("solid-tmp").exists());
+    assert_eq!(
+        bundled_assets(&bundle),
+        files(&[("a.txt", "a"), ("levels/1.txt", &level)])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}
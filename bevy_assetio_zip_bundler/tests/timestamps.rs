@@ -0,0 +1,95 @@
+mod common;
+
+use std::{
+    fs::{self, File},
+    path::Path,
+    time::UNIX_EPOCH,
+};
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, BundleOptions, TimestampPolicy, METADATA_ENTRY,
+};
+use zip::{DateTime, ZipArchive};
+
+use common::{test_dir, write_files, zip_entries};
+
+/// Get the modification time of the zip entry with the given name
+fn entry_time(bundle: &Path, name: &str) -> DateTime {
+    let mut zip = ZipArchive::new(File::open(bundle).unwrap()).unwrap();
+    let entry = zip.by_name(name).unwrap();
+    entry.last_modified()
+}
+
+/// Convert a zip date and time to seconds since the Unix epoch, using Howard Hinnant's
+/// `days_from_civil`
+fn unix_secs(time: DateTime) -> i64 {
+    let (month, day) = (time.month() as i64, time.day() as i64);
+    let year = time.year() as i64 - if month <= 2 { 1 } else { 0 };
+    let era = year.div_euclid(400);
+    let yoe = year - era * 400;
+    let doy = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day - 1;
+    let doe = yoe * 365 + yoe / 4 - yoe / 100 + doy;
+    let days = era * 146_097 + doe - 719_468;
+
+    days * 86400 + time.hour() as i64 * 3600 + time.minute() as i64 * 60 + time.second() as i64
+}
+
+#[test]
+fn zero_timestamps_make_bundles_reproducible() {
+    let dir = test_dir("timestamps-zero");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a"), ("levels/1.txt", "1")]);
+
+    let options = BundleOptions {
+        pack_threshold: 0,
+        asset_metadata: true,
+        timestamps: TimestampPolicy::Zero,
+        ..Default::default()
+    };
+    bundle_assets_with_options(&asset_dir, &dir.join("1.zip"), &options);
+    bundle_assets_with_options(&asset_dir, &dir.join("2.zip"), &options);
+
+    let bundle = dir.join("1.zip");
+    assert_eq!(
+        fs::read(&bundle).unwrap(),
+        fs::read(dir.join("2.zip")).unwrap()
+    );
+    let time = entry_time(&bundle, "levels/1.txt");
+    assert_eq!((time.year(), time.month(), time.day()), (1980, 1, 1));
+    assert!(zip_entries(&bundle)[METADATA_ENTRY]
+        .lines()
+        .all(|line| line.starts_with("0\t")));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn modification_times_can_be_preserved() {
+    let dir = test_dir("timestamps-preserve");
+    let asset_dir = dir.join("assets");
+    write_files(&asset_dir, &[("a.txt", "a")]);
+
+    let bundle = dir.join("assets.zip");
+    bundle_assets_with_options(
+        &asset_dir,
+        &bundle,
+        &BundleOptions {
+            pack_threshold: 0,
+            timestamps: TimestampPolicy::Preserve,
+            ..Default::default()
+        },
+    );
+
+    let modified = fs::metadata(asset_dir.join("a.txt"))
+        .unwrap()
+        .modified()
+        .unwrap()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs() as i64;
+    // Zip files store times with a resolution of two seconds
+    let recorded = unix_secs(entry_time(&bundle, "a.txt"));
+    assert!((0..2).contains(&(modified - recorded)));
+
+    fs::remove_dir_all(&dir).unwrap();
+}
@@ -0,0 +1,92 @@
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::{
+    bundle_assets_with_options, verify, BundleOptions, CompressionMethod,
+};
+
+use common::{test_dir, write_files};
+
+#[test]
+fn intact_bundles_pass_verification() {
+    let dir = test_dir("verify");
+    write_files(
+        &dir.join("assets"),
+        &[
+            ("a.txt", "a"),
+            ("b/c.txt", "c"),
+            ("large.txt", &"large ".repeat(100)),
+        ],
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            pack_threshold: 16,
+            asset_metadata: true,
+            ..Default::default()
+        },
+    );
+
+    let report = verify(dir.join("assets.zip")).unwrap();
+    assert!(report.is_ok(), "{}", report);
+    assert_eq!(report.assets, 3);
+    assert!(report.checked_metadata);
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn corrupt_entries_fail_verification() {
+    let dir = test_dir("verify_corrupt");
+    write_files(
+        &dir.join("assets"),
+        &[
+            ("broken.txt", "broken contents"),
+            ("fine.txt", "fine contents"),
+        ],
+    );
+    bundle_assets_with_options(
+        dir.join("assets"),
+        dir.join("assets.zip"),
+        &BundleOptions {
+            compression: CompressionMethod::Stored,
+            ..Default::default()
+        },
+    );
+
+    // Flip a byte of the stored data, which the CRC-32 of the entry no longer matches
+    let mut bytes = fs::read(dir.join("assets.zip")).unwrap();
+    let start = bytes
+        .windows(6)
+        .position(|x| x == b"broken")
+        .map(|x| x + "broken.txt".len())
+        .and_then(|name_end| {
+            bytes[name_end..]
+                .windows(6)
+                .position(|x| x == b"broken")
+                .map(|x| name_end + x)
+        })
+        .unwrap();
+    bytes[start] ^= 0xff;
+    fs::write(dir.join("assets.zip"), bytes).unwrap();
+
+    let report = verify(dir.join("assets.zip")).unwrap();
+    assert!(!report.is_ok());
+    assert_eq!(report.errors.len(), 1, "{}", report);
+    assert_eq!(report.errors[0].path, "broken.txt");
+
+    fs::remove_dir_all(&dir).unwrap();
+}
+
+#[test]
+fn files_that_are_not_bundles_cannot_be_verified() {
+    let dir = test_dir("verify_garbage");
+    fs::write(dir.join("assets.zip"), "not a zip file").unwrap();
+
+    assert!(verify(dir.join("assets.zip")).is_err());
+    assert!(verify(dir.join("missing.zip")).is_err());
+
+    fs::remove_dir_all(&dir).unwrap();
+}
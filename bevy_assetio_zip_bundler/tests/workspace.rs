@@ -0,0 +1,53 @@
+//! Bundling the assets of a crate that is one of the crates of a workspace
+
+mod common;
+
+use std::fs;
+
+use bevy_assetio_zip_bundler::bundle_crate_assets;
+
+use common::{bundled_assets, files, test_dir, write_files};
+
+#[test]
+fn asset_dirs_can_be_relative_to_the_workspace_root() {
+    let dir = test_dir("workspace");
+    write_files(
+        &dir,
+        &[
+            ("Cargo.toml", "[workspace]\nmembers = ['game']\n"),
+            ("game/Cargo.toml", "[package]\nname = 'game'\n"),
+            (
+                "game/asset_config.toml",
+                "paths-relative-to = 'workspace'\n\
+                 asset-dir = 'game/assets'\n\
+                 extra-asset-dirs = ['shared']\n\
+                 write-index = true\n",
+            ),
+            ("game/assets/a.txt", "a"),
+            ("shared/b.txt", "b"),
+        ],
+    );
+    let profile_dir = dir.join("target/release");
+    let out_dir = profile_dir.join("build/game-0123456789abcdef/out");
+    std::env::set_var("CARGO_MANIFEST_DIR", dir.join("game"));
+    std::env::set_var("OUT_DIR", &out_dir);
+    std::env::set_var("PROFILE", "release");
+
+    bundle_crate_assets();
+    // The bundle is written to the `OUT_DIR` and linked next to the executable
+    let expected = files(&[("a.txt", "a"), ("b.txt", "b")]);
+    assert_eq!(bundled_assets(&out_dir.join("assets.zip")), expected);
+    assert_eq!(bundled_assets(&profile_dir.join("assets.zip")), expected);
+    assert!(profile_dir.join("assets.idx").exists());
+    assert!(!dir.join("game/target").exists());
+
+    // Building again replaces the bundle next to the executable
+    write_files(&dir.join("shared"), &[("c.txt", "c")]);
+    bundle_crate_assets();
+    assert_eq!(
+        bundled_assets(&profile_dir.join("assets.zip")),
+        files(&[("a.txt", "a"), ("b.txt", "b"), ("c.txt", "c")])
+    );
+
+    fs::remove_dir_all(&dir).unwrap();
+}